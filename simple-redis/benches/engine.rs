@@ -0,0 +1,73 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use futures::future::join_all;
+use simple_redis::client::Client;
+use simple_redis::engine::Engine;
+use simple_redis::resp::frame::Frame;
+use simple_redis::server::Server;
+use tokio::runtime::Runtime;
+
+// concurrent clients, each sending the same mix of single-key and multi-key requests
+const CLIENTS: usize = 32;
+const ROUNDS: usize = 16;
+
+async fn workload(client: &mut Client, id: usize) {
+    for round in 0..ROUNDS {
+        let key = format!("key:{}:{}", id, round);
+        client.set(&key, b"value").await.unwrap();
+        client.get(&key).await.unwrap();
+    }
+
+    // keys spread over the partitions, so the thread-per-core engine coordinates them
+    let keys: Vec<Frame> = (0..ROUNDS)
+        .map(|round| format!("key:{}:{}", id, round).as_bytes().into())
+        .collect();
+    let mut mget = vec![b"MGET".into()];
+    mget.extend(keys);
+    client.execute(mget).await.unwrap();
+}
+
+fn bench_engine(c: &mut Criterion, name: &str, engine: Engine) {
+    let rt = Runtime::new().unwrap();
+    let server = rt
+        .block_on(
+            Server::builder()
+                .bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+                .engine(engine)
+                .start(),
+        )
+        .unwrap();
+    let mut clients: Vec<Client> = rt.block_on(async {
+        let mut clients = Vec::with_capacity(CLIENTS);
+        for _ in 0..CLIENTS {
+            clients.push(Client::connect(server.local_addr()).await.unwrap());
+        }
+        clients
+    });
+
+    let mut group = c.benchmark_group("engine");
+    group.throughput(Throughput::Elements((CLIENTS * (ROUNDS * 2 + 1)) as u64));
+    group.bench_function(name, |b| {
+        b.iter(|| {
+            rt.block_on(join_all(
+                clients
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(id, client)| workload(client, id)),
+            ))
+        })
+    });
+    group.finish();
+
+    drop(clients);
+    rt.block_on(server.shutdown()).unwrap();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_engine(c, "shared", Engine::Shared);
+    bench_engine(c, "thread-per-core", Engine::ThreadPerCore(0));
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
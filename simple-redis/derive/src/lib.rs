@@ -0,0 +1,14 @@
+mod redis_command;
+
+use proc_macro::TokenStream;
+use redis_command::process_redis_command;
+use syn::DeriveInput;
+
+/// generates the `TryFrom<Frame>` parser and the `RedisCommand` metadata (name, arity and key
+/// positions) of a command from its struct definition
+#[proc_macro_derive(RedisCommand, attributes(command, arg))]
+pub fn derive_redis_command(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    process_redis_command(input).into()
+}
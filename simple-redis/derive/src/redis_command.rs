@@ -0,0 +1,265 @@
+use darling::{ast::Data, FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(command), supports(struct_named))]
+struct RedisCommandInfo {
+    ident: syn::Ident,
+    data: Data<(), RedisCommandArgInfo>,
+    name: String,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(arg))]
+struct RedisCommandArgInfo {
+    ident: Option<syn::Ident>,
+    // the argument is a key name, which ends up in the key positions of the command
+    #[darling(default)]
+    key: bool,
+    // the frame is kept as sent instead of being converted
+    #[darling(default)]
+    raw: bool,
+    // a Vec taking every remaining argument, at least `min` of them (1 by default)
+    #[darling(default)]
+    variadic: bool,
+    #[darling(default)]
+    min: Option<usize>,
+    // a bool set by its token alone, like NX
+    #[darling(default)]
+    flag: bool,
+    // an Option set by its token followed by a value, like EX <secs>
+    #[darling(default)]
+    option: bool,
+    // the token of a flag or an option, the field name in upper case when missing
+    #[darling(default)]
+    token: Option<String>,
+}
+
+enum ArgKind {
+    Positional { raw: bool },
+    Variadic { min: usize },
+    Flag(String),
+    Option(String),
+}
+
+struct Arg {
+    ident: syn::Ident,
+    key: bool,
+    kind: ArgKind,
+}
+
+impl RedisCommandArgInfo {
+    fn into_arg(self) -> darling::Result<Arg> {
+        let ident = self.ident.expect("only named fields are supported");
+        let error = |msg: &str| Err(darling::Error::custom(msg).with_span(&ident));
+
+        let kinds = [self.raw, self.variadic, self.flag, self.option];
+        if kinds.iter().filter(|kind| **kind).count() > 1 {
+            return error("only one of raw, variadic, flag and option can be set");
+        }
+        if self.min.is_some() && !self.variadic {
+            return error("min only applies to variadic arguments");
+        }
+        if self.token.is_some() && !(self.flag || self.option) {
+            return error("token only applies to flags and options");
+        }
+        if self.key && (self.flag || self.option) {
+            return error("flags and options can not be keys");
+        }
+
+        let token = self
+            .token
+            .unwrap_or_else(|| ident.to_string())
+            .to_uppercase();
+        let kind = if self.variadic {
+            ArgKind::Variadic {
+                min: self.min.unwrap_or(1),
+            }
+        } else if self.flag {
+            ArgKind::Flag(token)
+        } else if self.option {
+            ArgKind::Option(token)
+        } else {
+            ArgKind::Positional { raw: self.raw }
+        };
+
+        Ok(Arg {
+            ident,
+            key: self.key,
+            kind,
+        })
+    }
+}
+
+pub(crate) fn process_redis_command(input: DeriveInput) -> TokenStream {
+    match expand(input) {
+        Ok(code) => code,
+        Err(e) => e.write_errors(),
+    }
+}
+
+fn expand(input: DeriveInput) -> darling::Result<TokenStream> {
+    let RedisCommandInfo { ident, data, name } = RedisCommandInfo::from_derive_input(&input)?;
+    let args = data
+        .take_struct()
+        .expect("RedisCommand only works on structs")
+        .into_iter()
+        .map(RedisCommandArgInfo::into_arg)
+        .collect::<darling::Result<Vec<_>>>()?;
+    let error = |msg: &str| Err(darling::Error::custom(msg).with_span(&ident));
+
+    let positional: Vec<&Arg> = args
+        .iter()
+        .filter(|arg| matches!(arg.kind, ArgKind::Positional { .. }))
+        .collect();
+    let variadic: Vec<&Arg> = args
+        .iter()
+        .filter(|arg| matches!(arg.kind, ArgKind::Variadic { .. }))
+        .collect();
+    let named: Vec<&Arg> = args
+        .iter()
+        .filter(|arg| matches!(arg.kind, ArgKind::Flag(_) | ArgKind::Option(_)))
+        .collect();
+
+    // a variadic tail would swallow the tokens of flags and options, so it stands alone
+    if variadic.len() > 1 {
+        return error("only one argument can be variadic");
+    }
+    let variadic = variadic.first().copied();
+    if variadic.is_some() && !named.is_empty() {
+        return error("a variadic argument can not be combined with flags or options");
+    }
+
+    // the arity counts the command name, a negative one is a minimum like in redis
+    let min = match variadic {
+        Some(Arg {
+            kind: ArgKind::Variadic { min },
+            ..
+        }) => *min,
+        _ => 0,
+    };
+    let required = (1 + positional.len() + min) as i64;
+    let arity = if variadic.is_some() || !named.is_empty() {
+        -required
+    } else {
+        required
+    };
+
+    // positions count the command name as 0, a variadic key runs to the last argument
+    let mut key_positions: Vec<i64> = positional
+        .iter()
+        .zip(1..)
+        .filter(|(arg, _)| arg.key)
+        .map(|(_, position)| position)
+        .collect();
+    let variadic_key = variadic.is_some_and(|arg| arg.key);
+    if variadic_key {
+        key_positions.push(positional.len() as i64 + 1);
+    }
+    let keys = match (key_positions.first(), key_positions.last()) {
+        (Some(first), Some(last)) => {
+            if last - first + 1 != key_positions.len() as i64 {
+                return error("key arguments must follow each other");
+            }
+            let last = if variadic_key { -1 } else { *last };
+            quote! { crate::command::KeySpec::Range { first: #first, last: #last, step: 1 } }
+        }
+        _ => quote! { crate::command::KeySpec::None },
+    };
+
+    let positional_parsers = positional.iter().map(|arg| {
+        let ident = &arg.ident;
+        match arg.kind {
+            ArgKind::Positional { raw: true } => quote! {
+                let #ident = parse.next()?;
+            },
+            _ => quote! {
+                let #ident = crate::command::parse::FromArg::from_arg(&mut parse)?;
+            },
+        }
+    });
+
+    let named_parser = if named.is_empty() {
+        quote! {}
+    } else {
+        let defaults = named.iter().map(|arg| {
+            let ident = &arg.ident;
+            match arg.kind {
+                ArgKind::Flag(_) => quote! { let mut #ident = false; },
+                _ => quote! { let mut #ident = None; },
+            }
+        });
+        let arms = named.iter().map(|arg| {
+            let ident = &arg.ident;
+            match &arg.kind {
+                ArgKind::Flag(token) => quote! {
+                    #token => #ident = true,
+                },
+                ArgKind::Option(token) => quote! {
+                    #token if parse.len() > 0 => {
+                        #ident = Some(crate::command::parse::FromArg::from_arg(&mut parse)?)
+                    }
+                },
+                _ => quote! {},
+            }
+        });
+
+        quote! {
+            #(#defaults)*
+            while parse.len() > 0 {
+                match parse.next_string()?.to_uppercase().as_str() {
+                    #(#arms)*
+                    _ => anyhow::bail!("syntax error"),
+                }
+            }
+        }
+    };
+
+    let variadic_parser = match variadic {
+        Some(arg) => {
+            let ident = &arg.ident;
+            quote! {
+                let mut #ident = Vec::with_capacity(parse.len());
+                while parse.len() > 0 {
+                    #ident.push(crate::command::parse::FromArg::from_arg(&mut parse)?);
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    let fields = args.iter().map(|arg| &arg.ident);
+    let name = name.to_lowercase();
+
+    Ok(quote! {
+        impl crate::command::RedisCommand for #ident {
+            const NAME: &'static str = #name;
+            const ARITY: i64 = #arity;
+            const KEYS: crate::command::KeySpec = #keys;
+        }
+
+        impl TryFrom<crate::resp::frame::Frame> for #ident {
+            type Error = anyhow::Error;
+
+            fn try_from(frame: crate::resp::frame::Frame) -> anyhow::Result<Self> {
+                let name = <Self as crate::command::RedisCommand>::NAME;
+                let arity = <Self as crate::command::RedisCommand>::ARITY;
+
+                let mut parse = crate::command::parse::Parse::try_new(frame)?;
+                if !parse.next_string()?.eq_ignore_ascii_case(name) {
+                    anyhow::bail!("Invalid command");
+                }
+                crate::command::check_arity(name, arity, parse.length())?;
+
+                #(#positional_parsers)*
+                #named_parser
+                #variadic_parser
+                parse.finish()?;
+
+                Ok(Self { #(#fields),* })
+            }
+        }
+    })
+}
@@ -0,0 +1,591 @@
+use super::{Backend, BackendError, StringValue, STRING_MAX_LEN};
+
+// highest addressable bit of a string capped at STRING_MAX_LEN bytes
+pub const BIT_OFFSET_MAX: u64 = (STRING_MAX_LEN as u64) * 8 - 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitUnit {
+    Byte,
+    Bit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitfieldType {
+    pub signed: bool,
+    pub bits: u32,
+}
+
+impl BitfieldType {
+    // i1 to i64 and u1 to u63, an u64 could not be returned as a RESP integer
+    pub fn parse(s: &str) -> Result<Self, BackendError> {
+        let signed = match s.as_bytes().first() {
+            Some(b'i') | Some(b'I') => true,
+            Some(b'u') | Some(b'U') => false,
+            _ => return Err(BackendError::BitfieldType),
+        };
+
+        match s[1..].parse::<u32>() {
+            Ok(bits) if bits >= 1 && (bits < 64 || signed && bits == 64) => {
+                Ok(Self { signed, bits })
+            }
+            _ => Err(BackendError::BitfieldType),
+        }
+    }
+
+    fn range(&self) -> (i128, i128) {
+        if self.signed {
+            (-(1i128 << (self.bits - 1)), (1i128 << (self.bits - 1)) - 1)
+        } else {
+            (0, (1i128 << self.bits) - 1)
+        }
+    }
+
+    // interpret the low `bits` bits of `raw` as this type
+    fn decode(&self, raw: u64) -> i64 {
+        if self.signed && self.bits < 64 && raw & (1 << (self.bits - 1)) != 0 {
+            (raw | (u64::MAX << self.bits)) as i64
+        } else {
+            raw as i64
+        }
+    }
+
+    // apply the overflow policy, None means FAIL refused the write
+    fn fit(&self, value: i128, overflow: BitfieldOverflow) -> Option<i64> {
+        let (min, max) = self.range();
+
+        if (min..=max).contains(&value) {
+            return Some(value as i64);
+        }
+
+        match overflow {
+            BitfieldOverflow::Wrap => {
+                let mask = (1i128 << self.bits) - 1;
+                Some(self.decode((value & mask) as u64))
+            }
+            BitfieldOverflow::Sat => Some(value.clamp(min, max) as i64),
+            BitfieldOverflow::Fail => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitfieldOverflow {
+    #[default]
+    Wrap,
+    Sat,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitfieldOp {
+    Get {
+        ty: BitfieldType,
+        offset: u64,
+    },
+    Set {
+        ty: BitfieldType,
+        offset: u64,
+        value: i64,
+        overflow: BitfieldOverflow,
+    },
+    IncrBy {
+        ty: BitfieldType,
+        offset: u64,
+        increment: i64,
+        overflow: BitfieldOverflow,
+    },
+}
+
+impl BitfieldOp {
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, BitfieldOp::Get { .. })
+    }
+
+    fn end(&self) -> u64 {
+        match self {
+            BitfieldOp::Get { ty, offset }
+            | BitfieldOp::Set { ty, offset, .. }
+            | BitfieldOp::IncrBy { ty, offset, .. } => offset + ty.bits as u64,
+        }
+    }
+}
+
+impl Backend {
+    pub fn setbit(&self, key: &str, offset: u64, bit: bool) -> Result<bool, BackendError> {
+        if offset > BIT_OFFSET_MAX {
+            return Err(BackendError::BitOffset);
+        }
+
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        let mut value = self
+            .map
+            .entry(key.to_string())
+            .or_insert_with(|| StringValue::Raw(Vec::new()));
+        let bytes = value.make_raw();
+
+        let byte = (offset / 8) as usize;
+        if bytes.len() <= byte {
+            bytes.resize(byte + 1, 0);
+        }
+
+        let mask = 0x80 >> (offset % 8);
+        let old = bytes[byte] & mask != 0;
+        if bit {
+            bytes[byte] |= mask;
+        } else {
+            bytes[byte] &= !mask;
+        }
+
+        Ok(old)
+    }
+
+    pub fn getbit(&self, key: &str, offset: u64) -> bool {
+        self.map
+            .get(key)
+            .is_some_and(|value| get_bit(&value.as_bytes(), offset))
+    }
+
+    pub fn bitcount(&self, key: &str, range: Option<(i64, i64, BitUnit)>) -> u64 {
+        let Some(value) = self.map.get(key) else {
+            return 0;
+        };
+        let bytes = value.as_bytes();
+
+        let Some((start, end, unit)) = range else {
+            return popcount(&bytes);
+        };
+
+        match bit_range(bytes.len(), start, end, unit) {
+            Some((start, end)) => count_bits(&bytes, start, end),
+            None => 0,
+        }
+    }
+
+    // `end` being None lets a search for 0 run past the string, which counts as zero padded
+    pub fn bitpos(&self, key: &str, bit: bool, start: i64, end: Option<i64>, unit: BitUnit) -> i64 {
+        let Some(value) = self.map.get(key) else {
+            return if bit { -1 } else { 0 };
+        };
+        let bytes = value.as_bytes();
+
+        let units = match unit {
+            BitUnit::Byte => bytes.len() as i64,
+            BitUnit::Bit => bytes.len() as i64 * 8,
+        };
+        let Some((first, last)) = bit_range(bytes.len(), start, end.unwrap_or(units - 1), unit)
+        else {
+            return -1;
+        };
+
+        match find_bit(&bytes, bit, first, last) {
+            Some(pos) => pos as i64,
+            None if !bit && end.is_none() => last as i64 + 1,
+            None => -1,
+        }
+    }
+
+    // returns the length of the result, which replaces `dest` whatever it held, an empty result
+    // deletes it
+    pub fn bitop(&self, op: BitOp, dest: &str, keys: &[String]) -> usize {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str).chain([dest]));
+        let sources = keys
+            .iter()
+            .map(|key| {
+                self.get_string(key)
+                    .map(|v| v.into_bytes())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let len = sources.iter().map(Vec::len).max().unwrap_or_default();
+
+        let mut result = vec![0u8; len];
+        for (i, byte) in result.iter_mut().enumerate() {
+            let mut bytes = sources.iter().map(|s| s.get(i).copied().unwrap_or(0));
+
+            *byte = match op {
+                BitOp::And => bytes.fold(0xff, |acc, b| acc & b),
+                BitOp::Or => bytes.fold(0, |acc, b| acc | b),
+                BitOp::Xor => bytes.fold(0, |acc, b| acc ^ b),
+                BitOp::Not => !bytes.next().unwrap_or(0),
+            };
+        }
+
+        self.overwrite(dest, "string");
+        if result.is_empty() {
+            self.map.remove(dest);
+        } else {
+            self.map.insert(dest.to_string(), StringValue::Raw(result));
+        }
+
+        len
+    }
+
+    // one reply per operation, None when OVERFLOW FAIL skipped it
+    pub fn bitfield(
+        &self,
+        key: &str,
+        ops: &[BitfieldOp],
+    ) -> Result<Vec<Option<i64>>, BackendError> {
+        if ops.iter().any(|op| op.end() > BIT_OFFSET_MAX + 1) {
+            return Err(BackendError::BitOffset);
+        }
+
+        if ops.iter().all(BitfieldOp::is_read_only) {
+            let value = self.map.get(key);
+            let bytes = value.as_ref().map(|v| v.as_bytes()).unwrap_or_default();
+            return Ok(ops.iter().map(|op| apply_bitfield(&bytes, op).0).collect());
+        }
+
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        let mut value = self
+            .map
+            .entry(key.to_string())
+            .or_insert_with(|| StringValue::Raw(Vec::new()));
+        let bytes = value.make_raw();
+
+        // like redis the string grows to fit every write up front, even one OVERFLOW FAIL skips
+        let end = ops
+            .iter()
+            .filter(|op| !op.is_read_only())
+            .map(|op| op.end().div_ceil(8) as usize)
+            .max()
+            .unwrap_or_default();
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+
+        let mut replies = Vec::with_capacity(ops.len());
+        for op in ops {
+            let (reply, write) = apply_bitfield(bytes, op);
+
+            if let Some((ty, offset, new)) = write {
+                write_bits(bytes, offset, ty.bits, new as u64);
+            }
+
+            replies.push(reply);
+        }
+
+        Ok(replies)
+    }
+}
+
+// the reply of `op` and the value to store, if any
+fn apply_bitfield(
+    bytes: &[u8],
+    op: &BitfieldOp,
+) -> (Option<i64>, Option<(BitfieldType, u64, i64)>) {
+    match *op {
+        BitfieldOp::Get { ty, offset } => {
+            (Some(ty.decode(read_bits(bytes, offset, ty.bits))), None)
+        }
+        BitfieldOp::Set {
+            ty,
+            offset,
+            value,
+            overflow,
+        } => {
+            let old = ty.decode(read_bits(bytes, offset, ty.bits));
+            match ty.fit(value as i128, overflow) {
+                Some(new) => (Some(old), Some((ty, offset, new))),
+                None => (None, None),
+            }
+        }
+        BitfieldOp::IncrBy {
+            ty,
+            offset,
+            increment,
+            overflow,
+        } => {
+            let old = ty.decode(read_bits(bytes, offset, ty.bits));
+            match ty.fit(old as i128 + increment as i128, overflow) {
+                Some(new) => (Some(new), Some((ty, offset, new))),
+                None => (None, None),
+            }
+        }
+    }
+}
+
+fn get_bit(bytes: &[u8], offset: u64) -> bool {
+    bytes
+        .get((offset / 8) as usize)
+        .is_some_and(|byte| byte & (0x80 >> (offset % 8)) != 0)
+}
+
+// bits past the end of the string read as zero
+fn read_bits(bytes: &[u8], offset: u64, bits: u32) -> u64 {
+    (offset..offset + bits as u64).fold(0, |acc, pos| acc << 1 | get_bit(bytes, pos) as u64)
+}
+
+fn write_bits(bytes: &mut [u8], offset: u64, bits: u32, value: u64) {
+    for i in 0..bits as u64 {
+        let bit = value >> (bits as u64 - 1 - i) & 1;
+        let pos = offset + i;
+        let mask = 0x80 >> (pos % 8);
+
+        if bit == 1 {
+            bytes[(pos / 8) as usize] |= mask;
+        } else {
+            bytes[(pos / 8) as usize] &= !mask;
+        }
+    }
+}
+
+fn popcount(bytes: &[u8]) -> u64 {
+    let chunks = bytes.chunks_exact(8);
+    let tail = chunks
+        .remainder()
+        .iter()
+        .map(|b| b.count_ones() as u64)
+        .sum::<u64>();
+
+    chunks
+        .map(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap()).count_ones() as u64)
+        .sum::<u64>()
+        + tail
+}
+
+// set bits between the inclusive bit positions `start` and `end`
+fn count_bits(bytes: &[u8], start: u64, end: u64) -> u64 {
+    let (first, last) = ((start / 8) as usize, (end / 8) as usize);
+    let head = bytes[first] & !(0xffu8 >> (start % 8));
+    let tail = bytes[last] & 0xffu8.checked_shr((end % 8) as u32 + 1).unwrap_or(0);
+
+    popcount(&bytes[first..=last]) - head.count_ones() as u64 - tail.count_ones() as u64
+}
+
+fn find_bit(bytes: &[u8], bit: bool, start: u64, end: u64) -> Option<u64> {
+    // whole bytes without the wanted bit are skipped at once
+    let skip = if bit { 0x00 } else { 0xff };
+    let mut pos = start;
+
+    while pos <= end {
+        if pos.is_multiple_of(8) && pos + 7 <= end && bytes[(pos / 8) as usize] == skip {
+            pos += 8;
+            continue;
+        }
+
+        if get_bit(bytes, pos) == bit {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+
+    None
+}
+
+// redis style start/end normalization, returns an inclusive range of bit positions
+fn bit_range(len: usize, start: i64, end: i64, unit: BitUnit) -> Option<(u64, u64)> {
+    let units = match unit {
+        BitUnit::Byte => len as i64,
+        BitUnit::Bit => len as i64 * 8,
+    };
+
+    let start = if start < 0 { units + start } else { start }.max(0);
+    let end = if end < 0 { units + end } else { end }
+        .max(0)
+        .min(units - 1);
+
+    if units == 0 || start > end {
+        return None;
+    }
+
+    match unit {
+        BitUnit::Byte => Some((start as u64 * 8, end as u64 * 8 + 7)),
+        BitUnit::Bit => Some((start as u64, end as u64)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u8_type() -> BitfieldType {
+        BitfieldType {
+            signed: false,
+            bits: 8,
+        }
+    }
+
+    #[test]
+    fn test_backend_setbit_getbit() {
+        let backend = Backend::new();
+
+        assert_eq!(backend.setbit("key", 7, true), Ok(false));
+        assert_eq!(backend.setbit("key", 7, false), Ok(true));
+        assert_eq!(backend.setbit("key", 100, true), Ok(false));
+        assert_eq!(backend.strlen("key"), 13);
+        assert!(backend.getbit("key", 100));
+        assert!(!backend.getbit("key", 1000));
+        assert_eq!(
+            backend.setbit("key", BIT_OFFSET_MAX + 1, true),
+            Err(BackendError::BitOffset)
+        );
+    }
+
+    #[test]
+    fn test_backend_bitcount() {
+        let backend = Backend::new();
+        backend.set("key", "foobar");
+
+        assert_eq!(backend.bitcount("key", None), 26);
+        assert_eq!(backend.bitcount("key", Some((0, 0, BitUnit::Byte))), 4);
+        assert_eq!(backend.bitcount("key", Some((1, 1, BitUnit::Byte))), 6);
+        assert_eq!(backend.bitcount("key", Some((1, 1, BitUnit::Bit))), 1);
+        assert_eq!(backend.bitcount("key", Some((5, 30, BitUnit::Bit))), 17);
+        assert_eq!(backend.bitcount("key", Some((-2, -1, BitUnit::Byte))), 7);
+        assert_eq!(backend.bitcount("key", Some((3, 1, BitUnit::Byte))), 0);
+        assert_eq!(backend.bitcount("missing", None), 0);
+    }
+
+    #[test]
+    fn test_backend_bitpos() {
+        let backend = Backend::new();
+        backend.set("key", b"\xff\xf0\x00");
+
+        assert_eq!(backend.bitpos("key", false, 0, None, BitUnit::Byte), 12);
+        assert_eq!(backend.bitpos("key", true, 2, None, BitUnit::Byte), -1);
+        assert_eq!(backend.bitpos("key", true, 7, Some(15), BitUnit::Bit), 7);
+
+        backend.set("ones", b"\xff\xff");
+        assert_eq!(backend.bitpos("ones", false, 0, None, BitUnit::Byte), 16);
+        assert_eq!(
+            backend.bitpos("ones", false, 0, Some(-1), BitUnit::Byte),
+            -1
+        );
+
+        assert_eq!(backend.bitpos("missing", false, 0, None, BitUnit::Byte), 0);
+        assert_eq!(backend.bitpos("missing", true, 0, None, BitUnit::Byte), -1);
+    }
+
+    #[test]
+    fn test_backend_bitop() {
+        let backend = Backend::new();
+        backend.set("a", "foobar");
+        backend.set("b", "abcdef");
+        let keys = ["a".to_string(), "b".to_string()];
+
+        assert_eq!(backend.bitop(BitOp::And, "dest", &keys), 6);
+        assert_eq!(backend.get_string("dest"), Some("`bc`ab".into()));
+        backend.bitop(BitOp::Or, "dest", &keys);
+        assert_eq!(backend.get_string("dest"), Some("goofev".into()));
+        backend.bitop(BitOp::Xor, "dest", &keys);
+        assert_eq!(
+            backend.get_string("dest"),
+            Some(b"\x07\x0d\x0c\x06\x04\x14".into())
+        );
+        backend.bitop(BitOp::Not, "dest", &["a".to_string()]);
+        assert_eq!(
+            backend.get_string("dest"),
+            Some(b"\x99\x90\x90\x9d\x9e\x8d".into())
+        );
+
+        assert_eq!(
+            backend.bitop(BitOp::Or, "dest", &["missing".to_string()]),
+            0
+        );
+        assert_eq!(backend.get_string("dest"), None);
+    }
+
+    #[test]
+    fn test_bitfield_type() {
+        assert_eq!(
+            BitfieldType::parse("i64"),
+            Ok(BitfieldType {
+                signed: true,
+                bits: 64
+            })
+        );
+        assert_eq!(BitfieldType::parse("u63").map(|ty| ty.bits), Ok(63));
+        assert_eq!(BitfieldType::parse("u64"), Err(BackendError::BitfieldType));
+        assert_eq!(BitfieldType::parse("i0"), Err(BackendError::BitfieldType));
+        assert_eq!(BitfieldType::parse("x8"), Err(BackendError::BitfieldType));
+    }
+
+    #[test]
+    fn test_backend_bitfield() {
+        let backend = Backend::new();
+        let i5 = BitfieldType {
+            signed: true,
+            bits: 5,
+        };
+
+        let ops = [
+            BitfieldOp::Set {
+                ty: u8_type(),
+                offset: 0,
+                value: 255,
+                overflow: BitfieldOverflow::Wrap,
+            },
+            BitfieldOp::Get { ty: i5, offset: 0 },
+            BitfieldOp::IncrBy {
+                ty: u8_type(),
+                offset: 0,
+                increment: 10,
+                overflow: BitfieldOverflow::Wrap,
+            },
+            BitfieldOp::IncrBy {
+                ty: u8_type(),
+                offset: 0,
+                increment: 300,
+                overflow: BitfieldOverflow::Sat,
+            },
+            BitfieldOp::IncrBy {
+                ty: u8_type(),
+                offset: 0,
+                increment: 1,
+                overflow: BitfieldOverflow::Fail,
+            },
+        ];
+
+        assert_eq!(
+            backend.bitfield("key", &ops),
+            Ok(vec![Some(0), Some(-1), Some(9), Some(255), None])
+        );
+        assert_eq!(backend.get_string("key"), Some(b"\xff".into()));
+    }
+
+    #[test]
+    fn test_backend_bitfield_read_only() {
+        let backend = Backend::new();
+        let ops = [BitfieldOp::Get {
+            ty: u8_type(),
+            offset: 100,
+        }];
+
+        assert_eq!(backend.bitfield("key", &ops), Ok(vec![Some(0)]));
+        assert_eq!(backend.get_string("key"), None);
+    }
+
+    #[test]
+    fn test_bitfield_signed_overflow() {
+        let i8_type = BitfieldType {
+            signed: true,
+            bits: 8,
+        };
+
+        assert_eq!(i8_type.fit(128, BitfieldOverflow::Wrap), Some(-128));
+        assert_eq!(i8_type.fit(-129, BitfieldOverflow::Wrap), Some(127));
+        assert_eq!(i8_type.fit(1000, BitfieldOverflow::Sat), Some(127));
+        assert_eq!(i8_type.fit(-1000, BitfieldOverflow::Sat), Some(-128));
+        assert_eq!(i8_type.fit(-129, BitfieldOverflow::Fail), None);
+
+        let i64_type = BitfieldType {
+            signed: true,
+            bits: 64,
+        };
+        assert_eq!(
+            i64_type.fit(i64::MAX as i128 + 1, BitfieldOverflow::Wrap),
+            Some(i64::MIN)
+        );
+    }
+}
@@ -0,0 +1,668 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tokio::sync::mpsc;
+
+use super::tracking::ClientTracking;
+use super::{Backend, BackendError, Tracking};
+use crate::config::Config;
+use crate::resp::frame::Frame;
+use crate::resp::RespEncode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionKind {
+    Tcp,
+    Tls,
+    Unix,
+}
+
+impl ConnectionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionKind::Tcp => "tcp",
+            ConnectionKind::Tls => "tls",
+            ConnectionKind::Unix => "unix",
+        }
+    }
+}
+
+// the classes of `client-output-buffer-limit`, there is no replication nor Pub/Sub so every client
+// is a normal one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientClass {
+    Normal,
+    Replica,
+    Pubsub,
+}
+
+impl ClientClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientClass::Normal => "normal",
+            ClientClass::Replica => "replica",
+            ClientClass::Pubsub => "pubsub",
+        }
+    }
+
+    // like redis, `slave` is still accepted for replicas
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "normal" => Some(ClientClass::Normal),
+            "replica" | "slave" => Some(ClientClass::Replica),
+            "pubsub" => Some(ClientClass::Pubsub),
+            _ => None,
+        }
+    }
+}
+
+// a client is closed as soon as its pending output reaches `hard`, or once it stayed at or above
+// `soft` for longer than `soft_seconds`, zero disables either limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputBufferLimit {
+    pub hard: usize,
+    pub soft: usize,
+    pub soft_seconds: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputBufferLimits {
+    pub normal: OutputBufferLimit,
+    pub replica: OutputBufferLimit,
+    pub pubsub: OutputBufferLimit,
+}
+
+// the defaults of redis.conf
+impl Default for OutputBufferLimits {
+    fn default() -> Self {
+        Self {
+            normal: OutputBufferLimit::default(),
+            replica: OutputBufferLimit {
+                hard: 256 << 20,
+                soft: 64 << 20,
+                soft_seconds: 60,
+            },
+            pubsub: OutputBufferLimit {
+                hard: 32 << 20,
+                soft: 8 << 20,
+                soft_seconds: 60,
+            },
+        }
+    }
+}
+
+impl OutputBufferLimits {
+    pub fn get(&self, class: ClientClass) -> OutputBufferLimit {
+        match class {
+            ClientClass::Normal => self.normal,
+            ClientClass::Replica => self.replica,
+            ClientClass::Pubsub => self.pubsub,
+        }
+    }
+
+    // `<class> <hard> <soft> <seconds>`, repeated for as many classes as should change, sizes take
+    // the k, kb, m, mb, g and gb units of redis.conf
+    pub fn set(&mut self, spec: &str) -> Result<(), String> {
+        let words: Vec<&str> = spec.split_whitespace().collect();
+        if words.is_empty() || !words.len().is_multiple_of(4) {
+            return Err("Wrong number of arguments in buffer limit configuration.".to_string());
+        }
+
+        let mut limits = self.clone();
+        for group in words.chunks(4) {
+            let class = ClientClass::parse(group[0]).ok_or_else(|| {
+                format!(
+                    "Invalid client class specified in buffer limit configuration: {}",
+                    group[0]
+                )
+            })?;
+            let invalid = || {
+                "Error in hard, soft or soft_seconds setting in buffer limit configuration."
+                    .to_string()
+            };
+            let limit = OutputBufferLimit {
+                hard: parse_memory(group[1]).ok_or_else(invalid)?,
+                soft: parse_memory(group[2]).ok_or_else(invalid)?,
+                soft_seconds: group[3].parse().map_err(|_| invalid())?,
+            };
+            match class {
+                ClientClass::Normal => limits.normal = limit,
+                ClientClass::Replica => limits.replica = limit,
+                ClientClass::Pubsub => limits.pubsub = limit,
+            }
+        }
+        *self = limits;
+        Ok(())
+    }
+}
+
+// bytes with an optional unit, `1k` is 1000 and `1kb` is 1024 like in redis.conf
+fn parse_memory(size: &str) -> Option<usize> {
+    let size = size.to_lowercase();
+    let split = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let multiplier: usize = match unit {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+// a connected client, the connection holds it for as long as it is open
+#[derive(Debug)]
+pub struct ClientInfo {
+    pub id: u64,
+    pub kind: ConnectionKind,
+    // like redis, `ip:port` for sockets and `path:0` for unix sockets
+    pub addr: String,
+    pub laddr: String,
+    created: Instant,
+    // the database selected by the connection, shared with its backend
+    db: Arc<AtomicUsize>,
+    name: Mutex<String>,
+    last: Mutex<(Instant, String)>,
+    // 2 until HELLO switches the connection to RESP3
+    protocol: AtomicU8,
+    // messages sent to the connection between replies, like invalidations
+    pushes: Mutex<Option<mpsc::UnboundedSender<Frame>>>,
+    // bytes received but not parsed into a command yet
+    query_buffer: AtomicUsize,
+    // bytes of replies encoded but not written to the socket yet, and of the pushes waiting behind
+    // them, see `Backend::over_output_limit`
+    output_buffer: AtomicUsize,
+    queued_pushes: AtomicUsize,
+    queued_push_bytes: AtomicUsize,
+    // when the output first reached the soft limit, none while it is below
+    soft_limit_since: Mutex<Option<Instant>>,
+    pub(super) tracking: Mutex<ClientTracking>,
+    tracking_table: Arc<Tracking>,
+    _slot: ClientSlot,
+}
+
+// one of the `maxclients` connections, given back when the client goes away
+#[derive(Debug)]
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ClientInfo {
+    pub fn name(&self) -> String {
+        self.name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn set_name(&self, name: impl Into<String>) {
+        *self.name.lock().unwrap_or_else(PoisonError::into_inner) = name.into();
+    }
+
+    // remembers the command the client is about to run, for idle and cmd in CLIENT LIST
+    pub fn touch(&self, command: &str) {
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) =
+            (Instant::now(), command.to_lowercase());
+    }
+
+    // when the client sent its last command, or connected when it sent none yet
+    pub fn last_interaction(&self) -> Instant {
+        self.last.lock().unwrap_or_else(PoisonError::into_inner).0
+    }
+
+    pub fn db(&self) -> usize {
+        self.db.load(Ordering::Relaxed)
+    }
+
+    pub fn protocol(&self) -> u8 {
+        self.protocol.load(Ordering::Relaxed)
+    }
+
+    pub fn set_protocol(&self, protocol: u8) {
+        self.protocol.store(protocol, Ordering::Relaxed);
+    }
+
+    // the connection serving this client reads what `push` sends from here, pushes made before
+    // are dropped
+    pub fn push_receiver(&self) -> mpsc::UnboundedReceiver<Frame> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        *self.pushes.lock().unwrap_or_else(PoisonError::into_inner) = Some(sender);
+        receiver
+    }
+
+    pub fn push(&self, frame: Frame) {
+        if let Some(sender) = &*self.pushes.lock().unwrap_or_else(PoisonError::into_inner) {
+            let bytes = frame.encode().len();
+            if sender.send(frame).is_ok() {
+                self.queued_pushes.fetch_add(1, Ordering::Relaxed);
+                self.queued_push_bytes.fetch_add(bytes, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn set_query_buffer(&self, bytes: usize) {
+        self.query_buffer.store(bytes, Ordering::Relaxed);
+    }
+
+    // a reply of `bytes` was encoded into the output buffer, with `push` it is a push taken from
+    // the queue
+    pub fn buffer_output(&self, bytes: usize, push: bool) {
+        if push {
+            self.queued_pushes.fetch_sub(1, Ordering::Relaxed);
+            sub(&self.queued_push_bytes, bytes);
+        }
+        self.output_buffer.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn output_written(&self, bytes: usize) {
+        sub(&self.output_buffer, bytes);
+    }
+
+    // pending output, what `client-output-buffer-limit` restricts
+    pub fn output_memory(&self) -> usize {
+        self.output_buffer.load(Ordering::Relaxed) + self.queued_push_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn class(&self) -> ClientClass {
+        ClientClass::Normal
+    }
+
+    // whether the pending output broke `limit`, the soft limit is only broken once the output
+    // stayed above it for longer than its seconds
+    pub fn over_output_limit(&self, limit: OutputBufferLimit) -> bool {
+        let used = self.output_memory();
+        if limit.hard > 0 && used >= limit.hard {
+            return true;
+        }
+
+        let mut since = self
+            .soft_limit_since
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if limit.soft == 0 || used < limit.soft {
+            *since = None;
+            return false;
+        }
+        since.get_or_insert_with(Instant::now).elapsed() > Duration::from_secs(limit.soft_seconds)
+    }
+
+    // a line of CLIENT LIST
+    pub fn describe(&self) -> String {
+        let (last, cmd) = self
+            .last
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut flags = match self.kind {
+            ConnectionKind::Unix => "U",
+            _ => "",
+        }
+        .to_string();
+        if self
+            .tracking
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_on()
+        {
+            flags.push('t');
+        }
+        if flags.is_empty() {
+            flags.push('N');
+        }
+
+        format!(
+            "id={} addr={} laddr={} conn={} name={} age={} idle={} flags={} db={} qbuf={} obl={} oll={} omem={} cmd={}",
+            self.id,
+            self.addr,
+            self.laddr,
+            self.kind.as_str(),
+            self.name(),
+            self.created.elapsed().as_secs(),
+            last.elapsed().as_secs(),
+            flags,
+            self.db(),
+            self.query_buffer.load(Ordering::Relaxed),
+            self.output_buffer.load(Ordering::Relaxed),
+            self.queued_pushes.load(Ordering::Relaxed),
+            self.output_memory(),
+            if cmd.is_empty() { "NULL" } else { &cmd },
+        )
+    }
+}
+
+impl Drop for ClientInfo {
+    fn drop(&mut self) {
+        let tracking = self
+            .tracking
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if tracking.is_on() {
+            self.tracking_table.forget(self.id);
+        }
+    }
+}
+
+// the counters are lowered by what was added to them before, a race with the adding side only
+// must not wrap them around
+fn sub(counter: &AtomicUsize, bytes: usize) {
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| {
+        Some(value.saturating_sub(bytes))
+    });
+}
+
+// the connection limits of `Config`, they can be changed while the server runs
+#[derive(Debug)]
+struct ClientLimits {
+    output: Mutex<OutputBufferLimits>,
+    timeout: AtomicU64,
+    tcp_keepalive: AtomicU64,
+    maxclients: AtomicUsize,
+}
+
+impl ClientLimits {
+    fn new(config: &Config) -> Self {
+        Self {
+            output: Mutex::new(config.client_output_buffer_limits.clone()),
+            timeout: AtomicU64::new(config.timeout),
+            tcp_keepalive: AtomicU64::new(config.tcp_keepalive),
+            maxclients: AtomicUsize::new(config.maxclients),
+        }
+    }
+}
+
+// every connected client, entries go away with the connection holding them
+#[derive(Debug)]
+pub struct Clients {
+    next_id: AtomicU64,
+    clients: DashMap<u64, Weak<ClientInfo>>,
+    // the slots taken, a client holds one until it is dropped
+    connected: Arc<AtomicUsize>,
+    limits: ClientLimits,
+}
+
+impl Default for Clients {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+impl Clients {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            clients: DashMap::new(),
+            connected: Arc::default(),
+            limits: ClientLimits::new(config),
+        }
+    }
+
+    pub fn configure(&self, config: &Config) {
+        let limits = &self.limits;
+        *limits.output.lock().unwrap_or_else(PoisonError::into_inner) =
+            config.client_output_buffer_limits.clone();
+        limits.timeout.store(config.timeout, Ordering::Relaxed);
+        limits
+            .tcp_keepalive
+            .store(config.tcp_keepalive, Ordering::Relaxed);
+        limits
+            .maxclients
+            .store(config.maxclients, Ordering::Relaxed);
+    }
+
+    pub fn output_buffer_limit(&self, class: ClientClass) -> OutputBufferLimit {
+        self.limits
+            .output
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(class)
+    }
+
+    // how long a client may stay idle before it is closed, none when it never is
+    pub fn timeout(&self) -> Option<Duration> {
+        match self.limits.timeout.load(Ordering::Relaxed) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        match self.limits.tcp_keepalive.load(Ordering::Relaxed) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn maxclients(&self) -> usize {
+        self.limits.maxclients.load(Ordering::Relaxed)
+    }
+
+    // takes a slot unless `maxclients` are connected already, checking and taking it is one step
+    // so connections accepted at the same time can not go over the limit together
+    fn reserve(&self) -> Option<ClientSlot> {
+        let maxclients = self.maxclients();
+        self.connected
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |connected| {
+                (connected < maxclients).then_some(connected + 1)
+            })
+            .ok()
+            .map(|_| ClientSlot(self.connected.clone()))
+    }
+
+    fn register(
+        &self,
+        kind: ConnectionKind,
+        addr: String,
+        laddr: String,
+        db: Arc<AtomicUsize>,
+        tracking_table: Arc<Tracking>,
+    ) -> Result<Arc<ClientInfo>, BackendError> {
+        let slot = self.reserve().ok_or(BackendError::MaxClients)?;
+        let now = Instant::now();
+        let client = Arc::new(ClientInfo {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            kind,
+            addr,
+            laddr,
+            created: now,
+            db,
+            name: Mutex::default(),
+            last: Mutex::new((now, String::new())),
+            protocol: AtomicU8::new(2),
+            pushes: Mutex::default(),
+            query_buffer: AtomicUsize::new(0),
+            output_buffer: AtomicUsize::new(0),
+            queued_pushes: AtomicUsize::new(0),
+            queued_push_bytes: AtomicUsize::new(0),
+            soft_limit_since: Mutex::default(),
+            tracking: Mutex::default(),
+            tracking_table,
+            _slot: slot,
+        });
+
+        self.clients.insert(client.id, Arc::downgrade(&client));
+        Ok(client)
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<ClientInfo>> {
+        self.clients.get(&id).and_then(|client| client.upgrade())
+    }
+
+    // connected clients ordered by id
+    pub fn list(&self) -> Vec<Arc<ClientInfo>> {
+        self.clients.retain(|_, client| client.strong_count() > 0);
+        let mut clients: Vec<Arc<ClientInfo>> = self
+            .clients
+            .iter()
+            .filter_map(|client| client.upgrade())
+            .collect();
+        clients.sort_by_key(|client| client.id);
+        clients
+    }
+
+    pub fn len(&self) -> usize {
+        self.list().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Backend {
+    pub fn clients(&self) -> &Clients {
+        &self.inner.clients
+    }
+
+    // the client this handle serves, none for handles that are not a connection
+    pub fn client(&self) -> Option<&ClientInfo> {
+        self.client.as_deref()
+    }
+
+    // whether the client of this handle has to be closed for the output it did not read yet
+    pub fn over_output_limit(&self) -> bool {
+        let Some(client) = self.client() else {
+            return false;
+        };
+        let limit = self.clients().output_buffer_limit(client.class());
+        let over = client.over_output_limit(limit);
+        if over {
+            self.stats().record_output_limit_disconnection();
+        }
+        over
+    }
+
+    // a session registered as a client until the returned handle and its clones are dropped,
+    // refused when `maxclients` are connected
+    pub fn connect(
+        &self,
+        kind: ConnectionKind,
+        addr: String,
+        laddr: String,
+    ) -> Result<Self, BackendError> {
+        let mut session = self.session();
+        let client = self.inner.clients.register(
+            kind,
+            addr,
+            laddr,
+            session.db.clone(),
+            self.inner.tracking.clone(),
+        )?;
+        session.client = Some(client);
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_register() {
+        let backend = Backend::new();
+        let first = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into())
+            .unwrap();
+        let second = backend
+            .connect(ConnectionKind::Unix, "/tmp/s:0".into(), "".into())
+            .unwrap();
+        assert_eq!(backend.clients().len(), 2);
+        assert!(backend.client().is_none());
+
+        second.select(3).unwrap();
+        second.client().unwrap().set_name("sidecar");
+        second.client().unwrap().touch("GET");
+        let line = second.client().unwrap().describe();
+        assert!(line.starts_with("id=2 addr=/tmp/s:0 laddr= conn=unix name=sidecar "));
+        assert!(line.ends_with("flags=U db=3 qbuf=0 obl=0 oll=0 omem=0 cmd=get"));
+
+        drop(first);
+        let clients = backend.clients().list();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].id, 2);
+    }
+
+    #[test]
+    fn test_clients_maxclients() {
+        let config = Config {
+            maxclients: 1,
+            ..Config::default()
+        };
+        let backend = Backend::with_config(&config);
+        let connect = || backend.connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into());
+
+        let first = connect().unwrap();
+        assert_eq!(connect().unwrap_err(), BackendError::MaxClients);
+        // the slot is given back with the last handle of the client
+        let clone = first.clone();
+        drop(first);
+        assert!(connect().is_err());
+        drop(clone);
+        assert!(connect().is_ok());
+    }
+
+    #[test]
+    fn test_output_buffer_limits_set() {
+        let mut limits = OutputBufferLimits::default();
+        limits.set("normal 1mb 512kb 10 slave 1g 0 0").unwrap();
+        assert_eq!(
+            limits.get(ClientClass::Normal),
+            OutputBufferLimit {
+                hard: 1 << 20,
+                soft: 512 << 10,
+                soft_seconds: 10,
+            }
+        );
+        assert_eq!(limits.get(ClientClass::Replica).hard, 1_000_000_000);
+        assert_eq!(limits.get(ClientClass::Pubsub).hard, 32 << 20);
+
+        // a bad group leaves the limits as they were
+        let before = limits.clone();
+        assert!(limits.set("normal 0 0 0 pubsub 1x 0 0").is_err());
+        assert!(limits.set("master 0 0 0").is_err());
+        assert!(limits.set("normal 0 0").is_err());
+        assert_eq!(limits, before);
+    }
+
+    #[test]
+    fn test_client_output_limit() {
+        let backend = Backend::new();
+        let session = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into())
+            .unwrap();
+        let client = session.client().unwrap();
+        let _pushes = client.push_receiver();
+        let limit = OutputBufferLimit {
+            hard: 100,
+            soft: 10,
+            soft_seconds: 60,
+        };
+
+        client.buffer_output(50, false);
+        // above the soft limit, but not for long enough
+        assert!(!client.over_output_limit(limit));
+        client.push(b"message".into());
+        assert_eq!(client.output_memory(), 63);
+        assert!(client.describe().contains(" obl=50 oll=1 omem=63 "));
+        client.output_written(40);
+        client.buffer_output(13, true);
+        assert_eq!(client.output_memory(), 23);
+        assert!(!client.over_output_limit(limit));
+
+        client.buffer_output(77, false);
+        assert!(client.over_output_limit(limit));
+        // normal clients have no limits by default
+        assert!(!session.over_output_limit());
+        assert_eq!(backend.stats().output_limit_disconnections(), 0);
+    }
+}
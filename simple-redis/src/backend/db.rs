@@ -0,0 +1,361 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::Ordering;
+
+use dashmap::DashMap;
+
+use super::{Backend, BackendError, HashValue, KeyMeta, LazyFreeCause, SortedSet, StringValue};
+
+// one logical database, every value type has its own map
+#[derive(Debug, Default)]
+pub struct Db {
+    pub(super) set: DashMap<String, HashSet<String>>,
+    pub(super) map: DashMap<String, StringValue>,
+    pub(super) hmap: DashMap<String, HashValue>,
+    pub(super) zset: DashMap<String, SortedSet>,
+    pub(super) list: DashMap<String, VecDeque<Vec<u8>>>,
+    // access time, access frequency and size of the keys, kept up to date by `touch`
+    pub(super) meta: DashMap<String, KeyMeta>,
+    // the unix time in milliseconds the keys with a time to live expire at
+    pub(super) expires: DashMap<String, u64>,
+}
+
+// a value taken out of a database, whatever its type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(StringValue),
+    Hash(HashValue),
+    Set(HashSet<String>),
+    ZSet(SortedSet),
+    List(VecDeque<Vec<u8>>),
+}
+
+impl Value {
+    pub fn kind(&self) -> &'static str {
+        self.borrow().kind()
+    }
+
+    // bytes of a string, elements of anything else
+    pub fn len(&self) -> usize {
+        match self {
+            Value::String(value) => value.len(),
+            Value::Hash(hash) => hash.len(),
+            Value::Set(set) => set.len(),
+            Value::ZSet(zset) => zset.len(),
+            Value::List(list) => list.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Db {
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.map.contains_key(key)
+            || self.hmap.contains_key(key)
+            || self.set.contains_key(key)
+            || self.zset.contains_key(key)
+            || self.list.contains_key(key)
+    }
+
+    // a key only ever holds one type, so the maps never share a name
+    pub fn len(&self) -> usize {
+        self.map.len() + self.hmap.len() + self.set.len() + self.zset.len() + self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+            && self.hmap.is_empty()
+            && self.set.is_empty()
+            && self.zset.is_empty()
+            && self.list.is_empty()
+    }
+
+    // every key across all value types, sorted so a cursor can walk them
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .map
+            .iter()
+            .map(|v| v.key().clone())
+            .chain(self.hmap.iter().map(|v| v.key().clone()))
+            .chain(self.set.iter().map(|v| v.key().clone()))
+            .chain(self.zset.iter().map(|v| v.key().clone()))
+            .chain(self.list.iter().map(|v| v.key().clone()))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    pub fn value(&self, key: &str) -> Option<Value> {
+        if let Some(value) = self.map.get(key) {
+            return Some(Value::String(value.clone()));
+        }
+        if let Some(value) = self.hmap.get(key) {
+            return Some(Value::Hash(value.clone()));
+        }
+        if let Some(value) = self.set.get(key) {
+            return Some(Value::Set(value.clone()));
+        }
+        if let Some(value) = self.zset.get(key) {
+            return Some(Value::ZSet(value.clone()));
+        }
+        self.list.get(key).map(|value| Value::List(value.clone()))
+    }
+
+    // removes `key` from every map, so no value of another type nor its deadline is left behind
+    // under the name
+    pub fn take(&self, key: &str) -> Option<Value> {
+        self.expires.remove(key);
+        let string = self.map.remove(key).map(|(_, value)| Value::String(value));
+        let hash = self.hmap.remove(key).map(|(_, value)| Value::Hash(value));
+        let set = self.set.remove(key).map(|(_, value)| Value::Set(value));
+        let zset = self.zset.remove(key).map(|(_, value)| Value::ZSet(value));
+        let list = self.list.remove(key).map(|(_, value)| Value::List(value));
+        string.or(hash).or(set).or(zset).or(list)
+    }
+
+    // unlinks the hash at `key` once no field is left and hands it to `free`, called under the
+    // key lock
+    pub(super) fn remove_empty_hash(&self, key: &str, free: impl FnOnce(HashValue)) {
+        if let Some((_, hash)) = self.hmap.remove_if(key, |_, hash| hash.is_empty()) {
+            self.meta.remove(key);
+            self.expires.remove(key);
+            free(hash);
+        }
+    }
+
+    // unlinks the set at `key` once no member is left, called under the key lock
+    pub(super) fn remove_empty_set(&self, key: &str) {
+        if self.set.remove_if(key, |_, set| set.is_empty()).is_some() {
+            self.expires.remove(key);
+        }
+    }
+
+    pub fn insert(&self, key: String, value: Value) {
+        self.create_meta(&key, &value);
+        match value {
+            Value::String(value) => drop(self.map.insert(key, value)),
+            Value::Hash(value) => drop(self.hmap.insert(key, value)),
+            Value::Set(value) => drop(self.set.insert(key, value)),
+            Value::ZSet(value) => drop(self.zset.insert(key, value)),
+            Value::List(value) => drop(self.list.insert(key, value)),
+        }
+    }
+
+    // unlinks every key and hands the values back, so the caller decides where they are freed
+    pub fn drain(&self) -> Vec<Value> {
+        let values = self
+            .keys()
+            .iter()
+            .filter_map(|key| self.take(key))
+            .collect();
+        self.meta.clear();
+        self.expires.clear();
+        values
+    }
+}
+
+impl Backend {
+    pub fn databases(&self) -> usize {
+        self.inner.dbs.len()
+    }
+
+    pub fn selected_db(&self) -> usize {
+        self.db.load(Ordering::Relaxed)
+    }
+
+    // changes the database of this handle and of every clone sharing it
+    pub fn select(&self, index: i64) -> Result<(), BackendError> {
+        let index = self.db_index(index)?;
+        self.db.store(index, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn dbsize(&self) -> usize {
+        self.len()
+    }
+
+    // removes `key` whatever its type on behalf of the server once MIGRATE restored it elsewhere,
+    // unless it no longer is what was dumped as `payload`; clients caching it are told
+    pub fn delete_dumped(&self, key: &str, payload: &[u8]) -> bool {
+        let value = {
+            let _guard = self.lock_key(key);
+            if self.dump(key).as_deref() != Some(payload) {
+                return false;
+            }
+            self.meta.remove(key);
+            self.take(key)
+        };
+        let removed = value
+            .map(|value| self.free_value(value, LazyFreeCause::ServerDel))
+            .is_some();
+        if removed {
+            self.invalidate(&[&key.to_string()]);
+        }
+        removed
+    }
+
+    // moves `key` to database `index` unless it is missing here or already exists there
+    pub fn move_key(&self, key: &str, index: i64) -> Result<bool, BackendError> {
+        let index = self.db_index(index)?;
+        if index == self.selected_db() {
+            return Err(BackendError::SameObject);
+        }
+
+        let _guard = self.lock_keyspace();
+        let target = self.db(index);
+        if target.contains_key(key) {
+            return Ok(false);
+        }
+
+        match self.take_expiring(key) {
+            Some((value, expire_at)) => {
+                if let Some((key, meta)) = self.meta.remove(key) {
+                    target.meta.insert(key, meta);
+                }
+                target.insert_expiring(key.to_string(), value, expire_at);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // connections that selected one database see the other one from now on, no key is copied
+    pub fn swapdb(&self, a: i64, b: i64) -> Result<(), BackendError> {
+        let a = self.db_index(a)?;
+        let b = self.db_index(b)?;
+
+        let _guard = self.lock_keyspace();
+        let slot_a = self.inner.slots[a].load(Ordering::Relaxed);
+        let slot_b = self.inner.slots[b].swap(slot_a, Ordering::Relaxed);
+        self.inner.slots[a].store(slot_b, Ordering::Relaxed);
+        Ok(())
+    }
+
+    // with `lazy` the values are freed by the lazyfree thread once the keys are unlinked
+    pub fn flushdb(&self, lazy: bool) {
+        let _guard = self.lock_keyspace();
+        self.free(self.drain(), lazy);
+    }
+
+    pub fn flushall(&self, lazy: bool) {
+        let _guard = self.lock_keyspace();
+        let values = (0..self.databases())
+            .flat_map(|index| self.db(index).drain())
+            .collect();
+        self.free(values, lazy);
+    }
+
+    // the number of keys and of keys with a deadline of every database holding any, for the
+    // keyspace section of INFO
+    pub fn keyspace(&self) -> Vec<(usize, usize, usize)> {
+        (0..self.databases())
+            .map(|index| (index, self.db(index).len(), self.db(index).expires()))
+            .filter(|(_, keys, _)| *keys > 0)
+            .collect()
+    }
+
+    // database `index`, whichever database is selected
+    pub fn db(&self, index: usize) -> &Db {
+        let slot = self.inner.slots[index].load(Ordering::Relaxed);
+        &self.inner.dbs[slot]
+    }
+
+    // without `lazy` the values are dropped right here
+    fn free(&self, values: Vec<Value>, lazy: bool) {
+        if lazy {
+            self.lazyfree().free(values);
+        }
+    }
+
+    fn db_index(&self, index: i64) -> Result<usize, BackendError> {
+        usize::try_from(index)
+            .ok()
+            .filter(|index| *index < self.databases())
+            .ok_or(BackendError::DbIndexOutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_and_move() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+
+        let other = backend.session();
+        other.select(1).unwrap();
+        assert_eq!(other.get("key"), None);
+        assert_eq!(other.selected_db(), 1);
+        assert_eq!(backend.selected_db(), 0);
+        assert_eq!(other.select(16), Err(BackendError::DbIndexOutOfRange));
+        assert_eq!(other.select(-1), Err(BackendError::DbIndexOutOfRange));
+
+        assert_eq!(backend.move_key("key", 1), Ok(true));
+        assert_eq!(backend.move_key("key", 1), Ok(false));
+        assert_eq!(backend.move_key("key", 0), Err(BackendError::SameObject));
+        assert_eq!(backend.dbsize(), 0);
+        assert_eq!(other.get("key"), Some(b"value".into()));
+
+        // an existing key in the target database is left alone
+        backend.set("key", "other");
+        assert_eq!(backend.move_key("key", 1), Ok(false));
+        assert_eq!(backend.get("key"), Some(b"other".into()));
+    }
+
+    #[test]
+    fn test_take_clears_every_type() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        backend.insert(
+            "key".to_string(),
+            Value::Set(HashSet::from(["a".to_string()])),
+        );
+
+        assert_eq!(backend.del(&["key".to_string()], false), 1);
+        assert!(!backend.contains_key("key"));
+        assert_eq!(backend.smembers("key"), None);
+    }
+
+    #[test]
+    fn test_expired_hash_leaves_no_meta() {
+        let backend = Backend::new();
+        for key in ["read", "written"] {
+            backend.hset(key, "field", "value").unwrap();
+            backend.key_meta(key).unwrap();
+            backend.hmap.get_mut(key).unwrap().set_expire("field", 1);
+        }
+
+        // a reader drops the hash its expired fields left empty, like a writer does
+        assert_eq!(backend.hget("read", "field"), None);
+        assert_eq!(backend.hdel("written", &["other".to_string()]), 0);
+        for key in ["read", "written"] {
+            assert!(!backend.contains_key(key));
+            assert!(!backend.meta.contains_key(key));
+        }
+    }
+
+    #[test]
+    fn test_swapdb_and_flush() {
+        let backend = Backend::new();
+        let other = backend.session();
+        other.select(2).unwrap();
+
+        backend.set("a", "0");
+        other.sadd("b", &["2".to_string()]).unwrap();
+        backend.swapdb(0, 2).unwrap();
+
+        assert_eq!(backend.keys(), vec!["b"]);
+        assert_eq!(other.keys(), vec!["a"]);
+        assert_eq!(backend.keyspace(), vec![(0, 1, 0), (2, 1, 0)]);
+
+        backend.flushdb(false);
+        assert_eq!(backend.keyspace(), vec![(2, 1, 0)]);
+        backend.flushall(true);
+        assert!(backend.keyspace().is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use std::time::Duration;
+
+use super::{unix_time, Backend, BackendError, LazyFreeCause, Value, ValueRef};
+use crate::rdb::{decode_value, encode_value, RdbError, RdbLoad, RdbReader, RdbRecord, RdbWriter};
+
+// the rdb version written after a payload, restoring accepts it and anything older
+pub const DUMP_VERSION: u16 = 12;
+
+// the crc-64-jones redis checksums payloads with, reflected polynomial
+const CRC64_POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+const CRC64_TABLE: [u64; 256] = crc64_table();
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC64_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+pub fn crc64(crc: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(crc, |crc, byte| {
+        CRC64_TABLE[((crc ^ *byte as u64) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+// the serialized value followed by the version and the checksum of everything before it
+pub(crate) fn dump_value(value: ValueRef<'_>) -> Vec<u8> {
+    let mut payload = Vec::new();
+    encode_value(value, &mut payload);
+    payload.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+    let crc = crc64(0, &payload);
+    payload.extend_from_slice(&crc.to_le_bytes());
+    payload
+}
+
+pub(crate) fn parse_dump(payload: &[u8]) -> Result<Value, BackendError> {
+    let Some(body_len) = payload.len().checked_sub(10) else {
+        return Err(BackendError::BadDump);
+    };
+    let (body, footer) = payload.split_at(body_len);
+    let version = u16::from_le_bytes([footer[0], footer[1]]);
+    let crc = u64::from_le_bytes(footer[2..].try_into().unwrap_or_default());
+    if version > DUMP_VERSION || crc64(0, &payload[..body_len + 2]) != crc {
+        return Err(BackendError::BadDump);
+    }
+
+    let mut input = body;
+    let value = decode_value(&mut input)?;
+    if !input.is_empty() {
+        return Err(BackendError::BadData);
+    }
+    Ok(value)
+}
+
+impl Backend {
+    // the value of `key` in the format of DUMP
+    pub fn dump(&self, key: &str) -> Option<Vec<u8>> {
+        self.inspect(key, dump_value)
+    }
+
+    // creates `key` from a DUMP payload, an existing one is only overwritten with `replace`;
+    // the key starts out idle for `idle` with an access counter of `freq` when they are given,
+    // and expires at `expire_at` in unix milliseconds, one that already passed restores nothing
+    pub fn restore(
+        &self,
+        key: &str,
+        payload: &[u8],
+        replace: bool,
+        expire_at: Option<u64>,
+        idle: Option<Duration>,
+        freq: Option<u8>,
+    ) -> Result<(), BackendError> {
+        let expired = expire_at.is_some_and(|at| at <= unix_time().as_millis() as u64);
+        let value = parse_dump(payload)?;
+
+        let guard = self.lock_key(key);
+        if self.contains_key(key) {
+            if !replace {
+                return Err(BackendError::BusyKey);
+            }
+            if let Some(old) = self.take(key) {
+                self.free_value(old, LazyFreeCause::ServerDel);
+            }
+        }
+        self.meta.remove(key);
+        if !expired {
+            self.insert(key.to_string(), value);
+            self.set_meta(key, idle, freq);
+            self.set_expire_at(key, expire_at);
+        }
+        drop(guard);
+
+        self.invalidate(&[&key.to_string()]);
+        Ok(())
+    }
+
+    // every key of every database as an rdb file, with the idle time and access counter redis
+    // evicts by
+    pub fn save_rdb(&self) -> Vec<u8> {
+        let mut writer = RdbWriter::new();
+        writer.aux("simple-redis-ver", env!("CARGO_PKG_VERSION"));
+        writer.aux("redis-bits", &usize::BITS.to_string());
+        writer.aux("ctime", &unix_time().as_secs().to_string());
+        for index in 0..self.databases() {
+            let db = self.db(index);
+            let keys = db.keys();
+            if keys.is_empty() {
+                continue;
+            }
+            let expires = keys
+                .iter()
+                .filter(|key| db.expire_at(key).is_some())
+                .count();
+            writer.select_db(index, keys.len(), expires);
+            for key in &keys {
+                let expire_at = db.expire_at(key);
+                let (idle, freq) = db
+                    .meta
+                    .get(key)
+                    .map(|meta| (meta.idle(), meta.freq()))
+                    .unzip();
+                db.inspect(key, |value| writer.entry(key, value, expire_at, idle, freq));
+            }
+        }
+        writer.finish()
+    }
+
+    // adds the keys of an rdb file with their deadlines, replacing keys of the same name. a key
+    // whose deadline passed is left out. nothing is added unless the whole file reads
+    pub fn load_rdb(&self, bytes: &[u8]) -> Result<RdbLoad, RdbError> {
+        let now = unix_time().as_millis() as u64;
+        let mut load = RdbLoad::default();
+        let mut entries = Vec::new();
+        for record in RdbReader::new(bytes)? {
+            let entry = match record? {
+                RdbRecord::Entry(entry) => entry,
+                RdbRecord::Function(_) => {
+                    load.functions += 1;
+                    continue;
+                }
+                RdbRecord::Aux(..) => continue,
+            };
+            if entry.db >= self.databases() {
+                return Err(RdbError::Database(entry.db));
+            }
+            if entry.expire_at.is_some_and(|at| at <= now) {
+                load.expired += 1;
+                continue;
+            }
+            entries.push(entry);
+        }
+
+        for entry in entries {
+            let db = self.db(entry.db);
+            db.take(&entry.key);
+            db.meta.remove(&entry.key);
+            db.insert_expiring(entry.key.clone(), entry.value, entry.expire_at);
+            db.set_meta(&entry.key, entry.idle, entry.freq);
+            load.keys += 1;
+        }
+        Ok(load)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{HashValue, SortedSet, StringValue};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_crc64_jones() {
+        assert_eq!(crc64(0, b"123456789"), 0xe9c6_d914_c4b8_d9ca);
+    }
+
+    #[test]
+    fn test_dump_string_matches_redis() {
+        // what redis 7.4 replies to DUMP of "SET key 10" and "SET key hello", before the footer
+        let mut buf = Vec::new();
+        encode_value(ValueRef::String(&StringValue::Int(10)), &mut buf);
+        assert_eq!(buf, b"\x00\xc0\x0a");
+
+        let mut buf = Vec::new();
+        encode_value(ValueRef::String(&"hello".into()), &mut buf);
+        assert_eq!(buf, b"\x00\x05hello");
+    }
+
+    #[test]
+    fn test_dump_round_trip() {
+        let mut hash = HashValue::default();
+        hash.insert("a", "1");
+        hash.insert("b", "x".repeat(300));
+        hash.insert("c", 70000);
+        hash.set_expire("a", 1_900_000_000_000);
+        hash.set_expire("b", 1_900_000_000_500);
+        let mut zset = SortedSet::new();
+        zset.insert("one", 1.0);
+        zset.insert("inf", f64::INFINITY);
+        let set: HashSet<String> = (0..100).map(|i| i.to_string()).collect();
+
+        for value in [
+            Value::String(StringValue::Int(-129)),
+            Value::String("x".repeat(20000).into()),
+            Value::Hash(hash),
+            Value::ZSet(zset),
+            Value::Set(set),
+            Value::List([b"a".to_vec(), vec![0xff; 30]].into()),
+        ] {
+            let payload = dump_value(value.borrow());
+            assert_eq!(parse_dump(&payload), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_parse_dump_checks_footer() {
+        let mut payload = dump_value(ValueRef::String(&"hello".into()));
+        assert!(parse_dump(&payload[..5]).is_err());
+
+        let last = payload.len() - 1;
+        payload[last] ^= 1;
+        assert_eq!(parse_dump(&payload), Err(BackendError::BadDump));
+
+        // a newer version is refused even with a valid checksum
+        let mut payload = Vec::new();
+        encode_value(ValueRef::String(&"hello".into()), &mut payload);
+        payload.extend_from_slice(&(DUMP_VERSION + 1).to_le_bytes());
+        let crc = crc64(0, &payload);
+        payload.extend_from_slice(&crc.to_le_bytes());
+        assert_eq!(parse_dump(&payload), Err(BackendError::BadDump));
+    }
+
+    #[test]
+    fn test_backend_restore() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        let payload = backend.dump("key").unwrap();
+
+        assert_eq!(
+            backend.restore("key", &payload, false, None, None, None),
+            Err(BackendError::BusyKey)
+        );
+        backend
+            .restore(
+                "copy",
+                &payload,
+                false,
+                None,
+                Some(Duration::from_secs(120)),
+                Some(1),
+            )
+            .unwrap();
+        assert_eq!(backend.get_string("copy"), Some("value".into()));
+        let meta = backend.key_meta("copy").unwrap();
+        assert_eq!(meta.idle().as_secs(), 120);
+        assert_eq!(meta.freq(), 1);
+
+        backend.sadd("set", &["member".to_string()]).unwrap();
+        let payload = backend.dump("set").unwrap();
+        backend
+            .restore("key", &payload, true, None, None, None)
+            .unwrap();
+        assert_eq!(backend.value("key"), backend.value("set"));
+
+        // an expired deadline removes the replaced key and restores nothing
+        backend
+            .restore("key", &payload, true, Some(1), None, None)
+            .unwrap();
+        assert!(!backend.contains_key("key"));
+        backend
+            .restore("key", &payload, false, Some(u64::MAX), None, None)
+            .unwrap();
+        assert_eq!(backend.expire_at("key"), Some(u64::MAX));
+        assert_eq!(backend.dump("missing"), None);
+    }
+
+    #[test]
+    fn test_backend_rdb_round_trip() {
+        let backend = Backend::new();
+        backend.set("string", "value");
+        backend.set("int", "-5");
+        backend
+            .sadd("set", &["a".to_string(), "b".to_string()])
+            .unwrap();
+        backend.hset("hash", "f", "v").unwrap();
+        let mut zset = SortedSet::new();
+        zset.insert("m", 1.5);
+        backend.db(3).insert("zset".to_string(), Value::ZSet(zset));
+        // commands create the metadata of the keys they write
+        backend.touch(&[&"string".to_string()], true);
+        backend.set_meta("string", Some(Duration::from_secs(300)), Some(7));
+        backend.set_expire_at("int", Some(u64::MAX));
+
+        let bytes = backend.save_rdb();
+        assert!(bytes.starts_with(b"REDIS0009"));
+
+        let copy = Backend::new();
+        let load = copy.load_rdb(&bytes).unwrap();
+        assert_eq!(load.keys, 5);
+        for index in 0..backend.databases() {
+            let (db, copied) = (backend.db(index), copy.db(index));
+            assert_eq!(db.keys(), copied.keys());
+            for key in db.keys() {
+                assert_eq!(db.value(&key), copied.value(&key));
+            }
+        }
+        let meta = copy.key_meta("string").unwrap();
+        assert_eq!(meta.idle().as_secs(), 300);
+        assert_eq!(meta.freq(), 7);
+        assert_eq!(copy.expire_at("int"), Some(u64::MAX));
+        assert_eq!(copy.expire_at("string"), None);
+
+        // a server with fewer databases can not hold database 3
+        let config = crate::config::Config {
+            databases: 2,
+            ..Default::default()
+        };
+        let small = Backend::with_config(&config);
+        assert!(matches!(small.load_rdb(&bytes), Err(RdbError::Database(3))));
+    }
+
+    #[test]
+    fn test_backend_load_rdb_leaves_out() {
+        // an expired key, a key without a time to live and a list, as redis writes them
+        let rdb = |entries: &[u8]| {
+            let mut bytes = b"REDIS0011".to_vec();
+            bytes.extend_from_slice(entries);
+            bytes.push(0xff);
+            let crc = crc64(0, &bytes);
+            bytes.extend_from_slice(&crc.to_le_bytes());
+            bytes
+        };
+        let mut entries = vec![0xfc];
+        entries.extend_from_slice(&1u64.to_le_bytes());
+        entries.extend_from_slice(b"\x00\x03old\x01v");
+        entries.extend_from_slice(b"\x00\x03new\x01v");
+        entries.extend_from_slice(b"\x01\x04list\x01\x01x");
+
+        let backend = Backend::new();
+        backend.set("old", "kept");
+        let load = backend.load_rdb(&rdb(&entries)).unwrap();
+        assert_eq!(
+            load,
+            RdbLoad {
+                keys: 2,
+                expired: 1,
+                functions: 0
+            }
+        );
+        assert_eq!(backend.get_string("old"), Some("kept".into()));
+        assert_eq!(backend.get_string("new"), Some("v".into()));
+        assert_eq!(backend.lrange("list", 0, -1), vec![b"x".to_vec()]);
+
+        // a key with a deadline still ahead keeps it
+        let mut entries = vec![0xfc];
+        entries.extend_from_slice(&u64::MAX.to_le_bytes());
+        entries.extend_from_slice(b"\x00\x06future\x01v");
+        assert_eq!(backend.load_rdb(&rdb(&entries)).unwrap().keys, 1);
+        assert_eq!(backend.expire_at("future"), Some(u64::MAX));
+
+        // a file that does not read adds none of its keys
+        let mut entries = b"\x00\x07partial\x01v".to_vec();
+        entries.extend_from_slice(b"\xfe\x20\x00\x04last\x01v");
+        assert!(matches!(
+            backend.load_rdb(&rdb(&entries)),
+            Err(RdbError::Database(32))
+        ));
+        assert!(!backend.contains_key("partial"));
+
+        // RESTORE of a list DUMP from redis
+        let mut payload = b"\x01\x01\x01x".to_vec();
+        payload.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+        let crc = crc64(0, &payload);
+        payload.extend_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            parse_dump(&payload),
+            Ok(Value::List([b"x".to_vec()].into()))
+        );
+    }
+}
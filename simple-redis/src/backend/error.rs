@@ -0,0 +1,86 @@
+use thiserror::Error;
+
+use crate::resp::frame::Frame;
+use crate::resp::SimpleError;
+
+// errors a command reports back to the client, the messages match redis word for word
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    #[error("ERR value is not an integer or out of range")]
+    NotInteger,
+
+    #[error("ERR value is not a valid float")]
+    NotFloat,
+
+    #[error("ERR increment or decrement would overflow")]
+    Overflow,
+
+    #[error("ERR increment would produce NaN or Infinity")]
+    NanOrInfinity,
+
+    #[error("ERR offset is out of range")]
+    OffsetOutOfRange,
+
+    #[error("ERR string exceeds maximum allowed size (proto-max-bulk-len)")]
+    StringTooLong,
+
+    #[error("ERR bit offset is not an integer or out of range")]
+    BitOffset,
+
+    #[error("ERR bit is not an integer or out of range")]
+    BitValue,
+
+    #[error("ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.")]
+    BitfieldType,
+
+    #[error("ERR hash value is not an integer")]
+    HashNotInteger,
+
+    #[error("ERR hash value is not a float")]
+    HashNotFloat,
+
+    #[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+    WrongType,
+
+    #[error("WRONGTYPE Key is not a valid HyperLogLog string value.")]
+    InvalidHll,
+
+    #[error("INVALIDOBJ Corrupted HLL object detected")]
+    CorruptedHll,
+
+    #[error("ERR DB index is out of range")]
+    DbIndexOutOfRange,
+
+    #[error("ERR source and destination objects are the same")]
+    SameObject,
+
+    #[error("ERR syntax error")]
+    Syntax,
+
+    #[error("NOPROTO unsupported protocol version")]
+    NoProto,
+
+    #[error("ERR max number of clients reached")]
+    MaxClients,
+
+    #[error("BUSYKEY Target key name already exists.")]
+    BusyKey,
+
+    #[error("ERR DUMP payload version or checksum are wrong")]
+    BadDump,
+
+    #[error("ERR Bad data format")]
+    BadData,
+
+    #[error("IOERR error or timeout talking to the target instance: {0}")]
+    Io(String),
+
+    #[error("ERR {0}")]
+    Other(String),
+}
+
+impl From<BackendError> for Frame {
+    fn from(error: BackendError) -> Self {
+        Frame::SimpleError(SimpleError::new(error))
+    }
+}
@@ -0,0 +1,154 @@
+use super::{unix_time, Backend, Db, LazyFreeCause, Value};
+
+fn now_millis() -> u64 {
+    unix_time().as_millis() as u64
+}
+
+impl Db {
+    // the unix time in milliseconds `key` expires at
+    pub fn expire_at(&self, key: &str) -> Option<u64> {
+        self.expires.get(key).map(|at| *at)
+    }
+
+    // without a deadline the key lives until it is deleted
+    pub fn set_expire_at(&self, key: &str, at: Option<u64>) {
+        match at {
+            Some(at) => drop(self.expires.insert(key.to_string(), at)),
+            None => drop(self.expires.remove(key)),
+        }
+    }
+
+    // takes `key` out with its deadline, for moving it to another database
+    pub fn take_expiring(&self, key: &str) -> Option<(Value, Option<u64>)> {
+        let expire_at = self.expire_at(key);
+        self.take(key).map(|value| (value, expire_at))
+    }
+
+    pub fn insert_expiring(&self, key: String, value: Value, expire_at: Option<u64>) {
+        self.set_expire_at(&key, expire_at);
+        self.insert(key, value);
+    }
+
+    // the number of keys with a deadline, for the keyspace section of INFO
+    pub fn expires(&self) -> usize {
+        self.expires.len()
+    }
+
+    // unlinks `key` once its deadline passed, a deadline left behind by a key that is gone
+    // goes with it
+    fn take_expired(&self, key: &str, now: u64) -> Option<Value> {
+        self.expires.remove_if(key, |_, at| *at <= now)?;
+        self.meta.remove(key);
+        self.take(key)
+    }
+}
+
+impl Backend {
+    // the milliseconds `key` has left to live, -1 when it has no deadline and -2 when it is
+    // missing, like PTTL
+    pub fn pttl(&self, key: &str) -> i64 {
+        if !self.contains_key(key) {
+            return -2;
+        }
+        match self.expire_at(key) {
+            Some(at) => i64::try_from(at.saturating_sub(now_millis())).unwrap_or(i64::MAX),
+            None => -1,
+        }
+    }
+
+    // deletes the keys of a command whose deadline passed before it runs, so it finds them
+    // missing like it would with redis
+    pub fn expire_keys(&self, keys: &[&String]) {
+        let now = now_millis();
+        for key in keys {
+            if self.expire_at(key).is_some_and(|at| at <= now) {
+                let _guard = self.lock_key(key);
+                self.expire_key(self, key);
+            }
+        }
+    }
+
+    // deletes `key` of `db` when its deadline passed and tells the clients caching it; called
+    // under the key lock
+    pub(super) fn expire_key(&self, db: &Db, key: &str) -> bool {
+        let Some(value) = db.take_expired(key, now_millis()) else {
+            return false;
+        };
+        self.free_value(value, LazyFreeCause::Expire);
+        self.stats().record_expired(0, true);
+        self.invalidate(&[&key.to_string()]);
+        true
+    }
+
+    // deletes the keys of database `index` whose deadline passed, for the active expire cycle;
+    // the number of keys deleted
+    pub(super) fn expire_due_keys(&self, index: usize) -> usize {
+        let db = self.db(index);
+        let now = now_millis();
+        let due: Vec<String> = db
+            .expires
+            .iter()
+            .filter(|at| *at.value() <= now)
+            .map(|at| at.key().clone())
+            .collect();
+        due.into_iter()
+            .filter(|key| {
+                let _guard = self.lock_key(key);
+                self.expire_key(db, key)
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::backend::StringValue;
+
+    #[test]
+    fn test_expire_keys() {
+        let backend = Backend::new();
+        backend.set("expired", "value");
+        backend.set("alive", "value");
+        backend.set_expire_at("expired", Some(now_millis() - 1));
+        backend.set_expire_at("alive", Some(now_millis() + 100_000));
+
+        assert_eq!(backend.pttl("missing"), -2);
+        assert!(backend.pttl("alive") > 99_000);
+        backend.expire_keys(&[&"expired".to_string(), &"alive".to_string()]);
+        assert!(!backend.contains_key("expired"));
+        assert_eq!(backend.expire_at("expired"), None);
+        assert!(backend.contains_key("alive"));
+        assert_eq!(backend.stats().expired_keys(), 1);
+
+        // replacing the value drops the deadline
+        backend.set("alive", "other");
+        assert_eq!(backend.pttl("alive"), -1);
+    }
+
+    #[test]
+    fn test_expire_before_writing() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        backend.set_expire_at("key", Some(now_millis() - 1));
+
+        // a writer starts over from a missing key instead of appending to the expired one
+        assert_eq!(backend.append("key", b"new"), Ok(3));
+        assert_eq!(backend.get_string("key"), Some(StringValue::from("new")));
+        assert_eq!(backend.pttl("key"), -1);
+    }
+
+    #[test]
+    fn test_active_expire_keys() {
+        let backend = Backend::new();
+        backend.sadd("set", &["a".to_string()]).unwrap();
+        backend.set_expire_at("set", Some(now_millis() + 1));
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert_eq!(backend.active_expire_cycle(), 1);
+        assert!(!backend.contains_key("set"));
+        assert_eq!(backend.active_expire_cycle(), 0);
+    }
+}
@@ -0,0 +1,577 @@
+use super::{Backend, BackendError, LazyFreeCause, SortedSet, Value};
+
+// web mercator limits, the same ones redis accepts
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+
+// 26 bits per coordinate, interleaved into a 52 bit score that is exact as an f64
+const GEO_STEP_MAX: u32 = 26;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const GEO_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+// at most this many geohash cells are scanned for a search
+const GEO_SEARCH_MAX_CELLS: u64 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoUnit {
+    Meters,
+    Kilometers,
+    Feet,
+    Miles,
+}
+
+impl GeoUnit {
+    pub fn parse(s: &str) -> Result<Self, BackendError> {
+        match s.to_lowercase().as_str() {
+            "m" => Ok(GeoUnit::Meters),
+            "km" => Ok(GeoUnit::Kilometers),
+            "ft" => Ok(GeoUnit::Feet),
+            "mi" => Ok(GeoUnit::Miles),
+            _ => Err(BackendError::Other(
+                "unsupported unit provided. please use M, KM, FT, MI".to_string(),
+            )),
+        }
+    }
+
+    pub fn to_meters(&self) -> f64 {
+        match self {
+            GeoUnit::Meters => 1.0,
+            GeoUnit::Kilometers => 1000.0,
+            GeoUnit::Feet => 0.3048,
+            GeoUnit::Miles => 1609.34,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoFrom {
+    Member(String),
+    LonLat(f64, f64),
+}
+
+// sizes are in meters, a box is width by height centered on the origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoShape {
+    Radius(f64),
+    Box(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoSort {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoQuery {
+    pub from: GeoFrom,
+    pub shape: GeoShape,
+    pub sort: Option<GeoSort>,
+    pub count: Option<usize>,
+    pub any: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoMatch {
+    pub member: String,
+    pub distance: f64,
+    pub hash: u64,
+    pub lon: f64,
+    pub lat: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeoAddOptions {
+    pub nx: bool,
+    pub xx: bool,
+    pub ch: bool,
+}
+
+pub fn validate_lonlat(lon: f64, lat: f64) -> Result<(), BackendError> {
+    if (GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat) {
+        Ok(())
+    } else {
+        Err(BackendError::Other(format!(
+            "invalid longitude,latitude pair {:.6},{:.6}",
+            lon, lat
+        )))
+    }
+}
+
+pub fn geohash_encode(lon: f64, lat: f64) -> u64 {
+    encode(
+        lon,
+        lat,
+        (GEO_LONG_MIN, GEO_LONG_MAX),
+        (GEO_LAT_MIN, GEO_LAT_MAX),
+        GEO_STEP_MAX,
+    )
+}
+
+// the center of the cell a score stands for
+pub fn geohash_decode(hash: u64) -> (f64, f64) {
+    let (lat_index, lon_index) = deinterleave(hash);
+    let cells = (1u64 << GEO_STEP_MAX) as f64;
+
+    let center = |index: u32, (min, max): (f64, f64)| {
+        let low = min + (index as f64 / cells) * (max - min);
+        let high = min + ((index as f64 + 1.0) / cells) * (max - min);
+        ((low + high) / 2.0).clamp(min, max)
+    };
+
+    (
+        center(lon_index, (GEO_LONG_MIN, GEO_LONG_MAX)),
+        center(lat_index, (GEO_LAT_MIN, GEO_LAT_MAX)),
+    )
+}
+
+// the standard 11 character geohash, computed over the full -90..90 latitude range
+pub fn geohash_string(hash: u64) -> String {
+    let (lon, lat) = geohash_decode(hash);
+    let standard = encode(lon, lat, (-180.0, 180.0), (-90.0, 90.0), GEO_STEP_MAX);
+
+    (0..11)
+        .map(|i| {
+            let index = if i == 10 {
+                0
+            } else {
+                (standard >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEO_ALPHABET[index as usize] as char
+        })
+        .collect()
+}
+
+pub fn geo_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lon1, lat2, lon2) = (
+        lat1.to_radians(),
+        lon1.to_radians(),
+        lat2.to_radians(),
+        lon2.to_radians(),
+    );
+    let u = ((lat2 - lat1) / 2.0).sin();
+    let v = ((lon2 - lon1) / 2.0).sin();
+
+    2.0 * EARTH_RADIUS_IN_METERS * (u * u + lat1.cos() * lat2.cos() * v * v).sqrt().asin()
+}
+
+fn encode(lon: f64, lat: f64, lon_range: (f64, f64), lat_range: (f64, f64), step: u32) -> u64 {
+    let cells = 1u64 << step;
+    let offset = |value: f64, (min, max): (f64, f64)| {
+        (((value - min) / (max - min)) * cells as f64).min((cells - 1) as f64) as u32
+    };
+
+    interleave(offset(lat, lat_range), offset(lon, lon_range))
+}
+
+// x takes the even bits and y the odd ones
+fn interleave(x: u32, y: u32) -> u64 {
+    (0..32).fold(0, |acc, i| {
+        acc | ((x as u64 >> i) & 1) << (2 * i) | ((y as u64 >> i) & 1) << (2 * i + 1)
+    })
+}
+
+fn deinterleave(hash: u64) -> (u32, u32) {
+    (0..32).fold((0, 0), |(x, y), i| {
+        (
+            x | (((hash >> (2 * i)) & 1) as u32) << i,
+            y | (((hash >> (2 * i + 1)) & 1) as u32) << i,
+        )
+    })
+}
+
+// (min_lon, min_lat, max_lon, max_lat) around the center, longitudes may leave -180..180
+fn bounding_box(lon: f64, lat: f64, shape: GeoShape) -> (f64, f64, f64, f64) {
+    let (width, height) = match shape {
+        GeoShape::Radius(radius) => (radius, radius),
+        GeoShape::Box(width, height) => (width / 2.0, height / 2.0),
+    };
+
+    let lat_delta = (height / EARTH_RADIUS_IN_METERS).to_degrees();
+    let (bottom, top) = (lat - lat_delta, lat + lat_delta);
+
+    // the widest longitude span is at the edge closest to a pole
+    let lon_delta = if top >= 90.0 || bottom <= -90.0 {
+        180.0
+    } else {
+        let cos = top.to_radians().cos().min(bottom.to_radians().cos());
+        (width / EARTH_RADIUS_IN_METERS / cos)
+            .to_degrees()
+            .min(180.0)
+    };
+
+    (lon - lon_delta, bottom, lon + lon_delta, top)
+}
+
+// score ranges of the geohash cells covering the bounding box, at the finest resolution that
+// keeps the number of cells small
+fn search_ranges(lon: f64, lat: f64, shape: GeoShape) -> Vec<(f64, f64)> {
+    let (min_lon, min_lat, max_lon, max_lat) = bounding_box(lon, lat, shape);
+
+    for step in (1..=GEO_STEP_MAX).rev() {
+        let cells = 1i64 << step;
+        let lat_index = |lat: f64| {
+            let offset =
+                (lat.clamp(GEO_LAT_MIN, GEO_LAT_MAX) - GEO_LAT_MIN) / (GEO_LAT_MAX - GEO_LAT_MIN);
+            ((offset * cells as f64) as i64).clamp(0, cells - 1)
+        };
+        let lon_index = |lon: f64| ((lon + 180.0) / 360.0 * cells as f64).floor() as i64;
+
+        let (lat_low, lat_high) = (lat_index(min_lat), lat_index(max_lat));
+        let (lon_low, lon_high) = if max_lon - min_lon >= 360.0 {
+            (0, cells - 1)
+        } else {
+            (
+                lon_index(min_lon),
+                lon_index(max_lon).min(lon_index(min_lon) + cells - 1),
+            )
+        };
+
+        let count = (lat_high - lat_low + 1) as u64 * (lon_high - lon_low + 1) as u64;
+        if count > GEO_SEARCH_MAX_CELLS && step > 1 {
+            continue;
+        }
+
+        let shift = 52 - 2 * step;
+        let mut ranges = Vec::with_capacity(count as usize);
+        for lat_cell in lat_low..=lat_high {
+            for lon_cell in lon_low..=lon_high {
+                let cell = interleave(lat_cell as u32, lon_cell.rem_euclid(cells) as u32);
+                let min = cell << shift;
+                ranges.push((min as f64, (min + (1 << shift)) as f64));
+            }
+        }
+        return ranges;
+    }
+
+    unreachable!("step 1 always covers the box with at most 4 cells")
+}
+
+// the distance from the center when the point lies within the shape
+fn distance_in_shape(lon: f64, lat: f64, shape: GeoShape, x: f64, y: f64) -> Option<f64> {
+    match shape {
+        GeoShape::Radius(radius) => {
+            let distance = geo_distance(lon, lat, x, y);
+            (distance <= radius).then_some(distance)
+        }
+        GeoShape::Box(width, height) => {
+            let lat_distance = EARTH_RADIUS_IN_METERS * (y.to_radians() - lat.to_radians()).abs();
+            if lat_distance > height / 2.0 {
+                return None;
+            }
+
+            let lon_distance = geo_distance(x, y, lon, y);
+            if lon_distance > width / 2.0 {
+                return None;
+            }
+
+            Some(geo_distance(lon, lat, x, y))
+        }
+    }
+}
+
+impl Backend {
+    pub fn geoadd(
+        &self,
+        key: &str,
+        items: &[(f64, f64, String)],
+        options: GeoAddOptions,
+    ) -> Result<usize, BackendError> {
+        if options.nx && options.xx {
+            return Err(BackendError::Other(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+
+        for (lon, lat, _) in items {
+            validate_lonlat(*lon, *lat)?;
+        }
+
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "zset")?;
+        let mut zset = self.zset.entry(key.to_string()).or_default();
+        let mut changed = 0;
+
+        for (lon, lat, member) in items {
+            let score = geohash_encode(*lon, *lat) as f64;
+
+            match zset.score(member) {
+                Some(_) if options.nx => continue,
+                None if options.xx => continue,
+                Some(old) => {
+                    if old != score {
+                        zset.insert(member, score);
+                        changed += options.ch as usize;
+                    }
+                }
+                None => {
+                    zset.insert(member, score);
+                    changed += 1;
+                }
+            }
+        }
+
+        if zset.is_empty() {
+            drop(zset);
+            self.zset.remove_if(key, |_, zset| zset.is_empty());
+        }
+
+        Ok(changed)
+    }
+
+    pub fn geopos(&self, key: &str, members: &[String]) -> Vec<Option<(f64, f64)>> {
+        let zset = self.zset.get(key);
+
+        members
+            .iter()
+            .map(|member| {
+                let score = zset.as_ref()?.score(member)?;
+                Some(geohash_decode(score as u64))
+            })
+            .collect()
+    }
+
+    // in meters
+    pub fn geodist(&self, key: &str, member1: &str, member2: &str) -> Option<f64> {
+        let zset = self.zset.get(key)?;
+        let (lon1, lat1) = geohash_decode(zset.score(member1)? as u64);
+        let (lon2, lat2) = geohash_decode(zset.score(member2)? as u64);
+
+        Some(geo_distance(lon1, lat1, lon2, lat2))
+    }
+
+    pub fn geohash(&self, key: &str, members: &[String]) -> Vec<Option<String>> {
+        let zset = self.zset.get(key);
+
+        members
+            .iter()
+            .map(|member| {
+                let score = zset.as_ref()?.score(member)?;
+                Some(geohash_string(score as u64))
+            })
+            .collect()
+    }
+
+    pub fn geosearch(&self, key: &str, query: &GeoQuery) -> Result<Vec<GeoMatch>, BackendError> {
+        let Some(zset) = self.zset.get(key) else {
+            return Ok(Vec::new());
+        };
+
+        let (lon, lat) = match &query.from {
+            GeoFrom::Member(member) => match zset.score(member) {
+                Some(score) => geohash_decode(score as u64),
+                None => {
+                    return Err(BackendError::Other(
+                        "could not decode requested zset member".to_string(),
+                    ))
+                }
+            },
+            GeoFrom::LonLat(lon, lat) => (*lon, *lat),
+        };
+
+        let mut matches = Vec::new();
+        'scan: for (min, max) in search_ranges(lon, lat, query.shape) {
+            for (member, score) in zset.range_by_score(min, max) {
+                let hash = score as u64;
+                let (x, y) = geohash_decode(hash);
+
+                if let Some(distance) = distance_in_shape(lon, lat, query.shape, x, y) {
+                    matches.push(GeoMatch {
+                        member: member.to_string(),
+                        distance,
+                        hash,
+                        lon: x,
+                        lat: y,
+                    });
+
+                    if query.any && Some(matches.len()) == query.count {
+                        break 'scan;
+                    }
+                }
+            }
+        }
+
+        // like redis a COUNT without ANY returns the closest matches
+        let sort = match query.sort {
+            None if query.count.is_some() && !query.any => Some(GeoSort::Asc),
+            sort => sort,
+        };
+        match sort {
+            Some(GeoSort::Asc) => matches.sort_by(|a, b| a.distance.total_cmp(&b.distance)),
+            Some(GeoSort::Desc) => matches.sort_by(|a, b| b.distance.total_cmp(&a.distance)),
+            None => {}
+        }
+
+        if let Some(count) = query.count {
+            matches.truncate(count);
+        }
+
+        Ok(matches)
+    }
+
+    // replaces whatever `key` held, an empty sorted set deletes it
+    pub fn zset_replace(&self, key: &str, zset: SortedSet) {
+        let _guard = self.lock_key(key);
+        self.overwrite(key, "zset");
+        let old = if zset.is_empty() {
+            self.zset.remove(key).map(|(_, old)| old)
+        } else {
+            self.zset.insert(key.to_string(), zset)
+        };
+        if let Some(old) = old {
+            self.free_value(Value::ZSet(old), LazyFreeCause::ServerDel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sicily() -> Backend {
+        let backend = Backend::new();
+        let items = [
+            (13.361389, 38.115556, "Palermo".to_string()),
+            (15.087269, 37.502669, "Catania".to_string()),
+        ];
+        backend
+            .geoadd("Sicily", &items, GeoAddOptions::default())
+            .unwrap();
+        backend
+    }
+
+    #[test]
+    fn test_geohash_encode_decode() {
+        assert_eq!(geohash_encode(13.361389, 38.115556), 3479099956230698);
+        assert_eq!(geohash_encode(15.087269, 37.502669), 3479447370796909);
+
+        let (lon, lat) = geohash_decode(3479099956230698);
+        assert_eq!(format!("{:.17}", lon), "13.36138933897018433");
+        assert_eq!(format!("{:.17}", lat), "38.11555639549629859");
+    }
+
+    #[test]
+    fn test_geohash_string() {
+        assert_eq!(geohash_string(3479099956230698), "sqc8b49rny0");
+        assert_eq!(geohash_string(3479447370796909), "sqdtr74hyu0");
+    }
+
+    #[test]
+    fn test_backend_geodist() {
+        let backend = sicily();
+
+        let distance = backend.geodist("Sicily", "Palermo", "Catania").unwrap();
+        assert_eq!(format!("{:.4}", distance), "166274.1516");
+        assert_eq!(backend.geodist("Sicily", "Palermo", "Rome"), None);
+    }
+
+    #[test]
+    fn test_backend_geoadd_options() {
+        let backend = sicily();
+        let moved = [(13.5, 38.0, "Palermo".to_string())];
+        let added = [(12.0, 38.0, "Marsala".to_string())];
+
+        let nx = GeoAddOptions {
+            nx: true,
+            ..Default::default()
+        };
+        assert_eq!(backend.geoadd("Sicily", &moved, nx), Ok(0));
+
+        let xx = GeoAddOptions {
+            xx: true,
+            ..Default::default()
+        };
+        assert_eq!(backend.geoadd("Sicily", &added, xx), Ok(0));
+        assert_eq!(
+            backend.geopos("Sicily", &["Marsala".to_string()]),
+            vec![None]
+        );
+
+        let ch = GeoAddOptions {
+            ch: true,
+            ..Default::default()
+        };
+        assert_eq!(backend.geoadd("Sicily", &moved, ch), Ok(1));
+
+        assert_eq!(backend.geoadd("Empty", &added, xx), Ok(0));
+        assert!(backend.zset.get("Empty").is_none());
+
+        assert_eq!(
+            backend.geoadd(
+                "Sicily",
+                &[(181.0, 10.0, "x".to_string())],
+                Default::default()
+            ),
+            Err(BackendError::Other(
+                "invalid longitude,latitude pair 181.000000,10.000000".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_backend_geosearch() {
+        let backend = sicily();
+        let edges = [
+            (12.758489, 38.788135, "edge1".to_string()),
+            (17.241510, 38.788135, "edge2".to_string()),
+        ];
+        backend
+            .geoadd("Sicily", &edges, GeoAddOptions::default())
+            .unwrap();
+
+        let mut query = GeoQuery {
+            from: GeoFrom::LonLat(15.0, 37.0),
+            shape: GeoShape::Radius(200_000.0),
+            sort: Some(GeoSort::Asc),
+            count: None,
+            any: false,
+        };
+        let found = |query: &GeoQuery| {
+            backend
+                .geosearch("Sicily", query)
+                .unwrap()
+                .into_iter()
+                .map(|m| (m.member, format!("{:.4}", m.distance / 1000.0)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            found(&query),
+            vec![
+                ("Catania".to_string(), "56.4413".to_string()),
+                ("Palermo".to_string(), "190.4424".to_string()),
+            ]
+        );
+
+        query.shape = GeoShape::Box(400_000.0, 400_000.0);
+        assert_eq!(
+            found(&query),
+            vec![
+                ("Catania".to_string(), "56.4413".to_string()),
+                ("Palermo".to_string(), "190.4424".to_string()),
+                ("edge2".to_string(), "279.7403".to_string()),
+                ("edge1".to_string(), "279.7405".to_string()),
+            ]
+        );
+
+        query.sort = Some(GeoSort::Desc);
+        query.count = Some(1);
+        assert_eq!(found(&query)[0].0, "edge1");
+
+        query.from = GeoFrom::Member("Rome".to_string());
+        assert!(backend.geosearch("Sicily", &query).is_err());
+        assert_eq!(backend.geosearch("missing", &query), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_search_ranges_cross_antimeridian() {
+        let ranges = search_ranges(179.9, 0.0, GeoShape::Radius(50_000.0));
+        let west = geohash_encode(-179.9, 0.0) as f64;
+        let east = geohash_encode(179.9, 0.0) as f64;
+
+        assert!(ranges.iter().any(|(min, max)| (*min..*max).contains(&west)));
+        assert!(ranges.iter().any(|(min, max)| (*min..*max).contains(&east)));
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+use super::{unix_time, Backend, BackendError, LazyFreeCause, StringValue, Value};
+
+// replies of HEXPIRE and HPERSIST for each field, same numbers as redis
+pub const HASH_FIELD_MISSING: i64 = -2;
+pub const HASH_FIELD_NO_EXPIRE: i64 = -1;
+pub const HASH_EXPIRE_NOT_SET: i64 = 0;
+pub const HASH_EXPIRE_SET: i64 = 1;
+pub const HASH_FIELD_DELETED: i64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpireCondition {
+    Nx,
+    Xx,
+    Gt,
+    Lt,
+}
+
+// a hash whose fields may expire on their own, deadlines are unix time in milliseconds
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HashValue {
+    fields: HashMap<String, StringValue>,
+    expires: HashMap<String, u64>,
+}
+
+impl HashValue {
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, field: &str) -> Option<&StringValue> {
+        self.fields.get(field)
+    }
+
+    // like redis, overwriting a field clears its expiry
+    pub fn insert(&mut self, field: impl ToString, value: impl Into<StringValue>) -> bool {
+        let field = field.to_string();
+        self.expires.remove(&field);
+        self.fields.insert(field, value.into()).is_none()
+    }
+
+    pub fn remove(&mut self, field: &str) -> Option<StringValue> {
+        self.expires.remove(field);
+        self.fields.remove(field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &StringValue)> {
+        self.fields.iter()
+    }
+
+    pub fn expire_at(&self, field: &str) -> Option<u64> {
+        self.expires.get(field).copied()
+    }
+
+    // `at` is unix time in milliseconds, a missing field is left alone
+    pub fn set_expire(&mut self, field: &str, at: u64) {
+        if self.fields.contains_key(field) {
+            self.expires.insert(field.to_string(), at);
+        }
+    }
+
+    // the number of fields that were removed
+    fn remove_expired(&mut self, now: u64) -> usize {
+        if self.expires.is_empty() {
+            return 0;
+        }
+
+        let expired: Vec<String> = self
+            .expires
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(field, _)| field.clone())
+            .collect();
+        for field in &expired {
+            self.remove(field);
+        }
+        expired.len()
+    }
+}
+
+fn now_millis() -> u64 {
+    unix_time().as_millis() as u64
+}
+
+impl Backend {
+    // expired fields change the hash, so clients caching it are told
+    fn remove_expired(&self, key: &str, hash: &mut HashValue) -> usize {
+        let expired = hash.remove_expired(now_millis());
+        if expired > 0 {
+            self.stats().record_expired(expired, hash.is_empty());
+            self.invalidate_by(&[&key.to_string()], None);
+        }
+        expired
+    }
+
+    // removes the keys whose deadline passed and the expired fields of every hash of this backend
+    // and its partitions, like reading them would, so keys nobody reads do not hold on to them;
+    // the number of keys and fields removed
+    pub fn active_expire_cycle(&self) -> usize {
+        let mut removed = 0;
+        for backend in self.with_partitions() {
+            for index in 0..backend.databases() {
+                removed += backend.expire_due_keys(index);
+                let mut emptied = Vec::new();
+                {
+                    let _guard = backend.share_keyspace();
+                    for mut entry in backend.db(index).hmap.iter_mut() {
+                        let (key, hash) = entry.pair_mut();
+                        removed += backend.remove_expired(key, hash);
+                        if hash.is_empty() {
+                            emptied.push(key.clone());
+                        }
+                    }
+                }
+                // clients caching them were told when their fields expired
+                for key in emptied {
+                    let _guard = backend.lock_key(&key);
+                    backend.db(index).remove_empty_hash(&key, |hash| {
+                        backend.free_value(Value::Hash(hash), LazyFreeCause::Expire)
+                    });
+                }
+            }
+        }
+        removed
+    }
+
+    // drops the expired fields of the hash at `key`, and the key once none is left; called under
+    // the key lock
+    pub(super) fn purge_hash(&self, key: &str) {
+        self.with_hash_locked(key, |_| ());
+    }
+
+    // runs `f` on the live fields of an existing hash and drops the key once it is empty, for
+    // the writers holding the key lock
+    fn with_hash_locked<R>(&self, key: &str, f: impl FnOnce(&mut HashValue) -> R) -> Option<R> {
+        let result = {
+            let mut hash = self.hmap.get_mut(key)?;
+            self.remove_expired(key, &mut hash);
+            f(&mut hash)
+        };
+        self.remove_empty_hash(key, drop);
+        Some(result)
+    }
+
+    // same for the readers, which only take the key lock to drop a hash whose fields all expired;
+    // when a writer holds it, that writer or the expire cycle drops the hash instead
+    fn with_hash<R>(&self, key: &str, f: impl FnOnce(&HashValue) -> R) -> Option<R> {
+        let (result, emptied) = {
+            let mut hash = self.hmap.get_mut(key)?;
+            self.remove_expired(key, &mut hash);
+            (f(&hash), hash.is_empty())
+        };
+        if emptied {
+            if let Some(_guard) = self.try_lock_key(key) {
+                self.remove_empty_hash(key, |hash| {
+                    self.free_value(Value::Hash(hash), LazyFreeCause::Expire)
+                });
+            }
+        }
+        Some(result)
+    }
+
+    // same as `with_hash` but creates the hash when it is missing, under the key lock since it
+    // may create the key, an error leaves it untouched
+    fn update_hash<R>(
+        &self,
+        key: &str,
+        f: impl FnOnce(&mut HashValue) -> Result<R, BackendError>,
+    ) -> Result<R, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "hash")?;
+        let result = {
+            let mut hash = self.hmap.entry(key.to_string()).or_default();
+            self.remove_expired(key, &mut hash);
+            f(&mut hash)
+        };
+        self.remove_empty_hash(key, drop);
+        result
+    }
+
+    pub fn hset(
+        &self,
+        key: impl ToString,
+        field: impl ToString,
+        value: impl Into<StringValue>,
+    ) -> Result<bool, BackendError> {
+        let key = key.to_string();
+        self.update_hash(&key, |hash| Ok(hash.insert(field, value)))
+    }
+
+    // sets every pair under one lock and returns how many fields are new
+    pub fn hset_many(
+        &self,
+        key: &str,
+        pairs: &[(String, StringValue)],
+    ) -> Result<usize, BackendError> {
+        self.update_hash(key, |hash| {
+            Ok(pairs
+                .iter()
+                .filter(|(field, value)| hash.insert(field, value.clone()))
+                .count())
+        })
+    }
+
+    pub fn hsetnx(
+        &self,
+        key: &str,
+        field: &str,
+        value: impl Into<StringValue>,
+    ) -> Result<bool, BackendError> {
+        self.update_hash(key, |hash| {
+            Ok(hash.get(field).is_none() && hash.insert(field, value))
+        })
+    }
+
+    pub fn hget(&self, key: &str, field: &str) -> Option<StringValue> {
+        self.with_hash(key, |hash| hash.get(field).cloned())
+            .flatten()
+    }
+
+    pub fn hdel(&self, key: &str, fields: &[String]) -> usize {
+        let _guard = self.lock_key(key);
+        self.with_hash_locked(key, |hash| {
+            fields
+                .iter()
+                .filter(|field| hash.remove(field).is_some())
+                .count()
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn hexists(&self, key: &str, field: &str) -> bool {
+        self.with_hash(key, |hash| hash.get(field).is_some())
+            .unwrap_or_default()
+    }
+
+    pub fn hlen(&self, key: &str) -> usize {
+        self.with_hash(key, |hash| hash.len()).unwrap_or_default()
+    }
+
+    pub fn hstrlen(&self, key: &str, field: &str) -> usize {
+        self.with_hash(key, |hash| hash.get(field).map_or(0, StringValue::len))
+            .unwrap_or_default()
+    }
+
+    // fields sorted by name, so HSCAN can use an index into them as its cursor
+    pub fn hgetall(&self, key: &str) -> Option<Vec<(String, StringValue)>> {
+        self.with_hash(key, |hash| {
+            let mut pairs: Vec<(String, StringValue)> = hash
+                .iter()
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect();
+            pairs.sort_by(|a, b| a.0.cmp(&b.0));
+            pairs
+        })
+    }
+
+    pub fn hincrby(&self, key: &str, field: &str, delta: i64) -> Result<i64, BackendError> {
+        self.update_hash(key, |hash| {
+            let current = match hash.get(field) {
+                Some(value) => value.to_int().ok_or(BackendError::HashNotInteger)?,
+                None => 0,
+            };
+            let result = current.checked_add(delta).ok_or(BackendError::Overflow)?;
+
+            hash.fields.insert(field.to_string(), result.into());
+            Ok(result)
+        })
+    }
+
+    pub fn hincrbyfloat(
+        &self,
+        key: &str,
+        field: &str,
+        delta: f64,
+    ) -> Result<StringValue, BackendError> {
+        self.update_hash(key, |hash| {
+            let current = match hash.get(field) {
+                Some(value) => value.to_float().ok_or(BackendError::HashNotFloat)?,
+                None => 0.0,
+            };
+            let result = current + delta;
+            if !result.is_finite() {
+                return Err(BackendError::NanOrInfinity);
+            }
+
+            let value = StringValue::from(result.to_string());
+            hash.fields.insert(field.to_string(), value.clone());
+            Ok(value)
+        })
+    }
+
+    // a positive count returns distinct fields, a negative one may repeat them, like redis
+    pub fn hrandfield(&self, key: &str, count: i64) -> Vec<(String, StringValue)> {
+        self.with_hash(key, |hash| {
+            let mut rng = rand::thread_rng();
+            let pair = |(field, value): (&String, &StringValue)| (field.clone(), value.clone());
+
+            if count >= 0 {
+                hash.iter()
+                    .choose_multiple(&mut rng, count as usize)
+                    .into_iter()
+                    .map(pair)
+                    .collect()
+            } else if hash.is_empty() {
+                Vec::new()
+            } else {
+                let pairs: Vec<_> = hash.iter().collect();
+                (0..count.unsigned_abs())
+                    .map(|_| pair(pairs[rng.gen_range(0..pairs.len())]))
+                    .collect()
+            }
+        })
+        .unwrap_or_default()
+    }
+
+    // sets the deadline of each field, `at` is unix time in milliseconds
+    pub fn hexpire(
+        &self,
+        key: &str,
+        at: u64,
+        condition: Option<ExpireCondition>,
+        fields: &[String],
+    ) -> Vec<i64> {
+        let now = now_millis();
+        let _guard = self.lock_key(key);
+        self.with_hash_locked(key, |hash| {
+            fields
+                .iter()
+                .map(|field| {
+                    if hash.get(field).is_none() {
+                        return HASH_FIELD_MISSING;
+                    }
+
+                    // a field without expiry counts as expiring never
+                    let current = hash.expire_at(field);
+                    let allowed = match condition {
+                        None => true,
+                        Some(ExpireCondition::Nx) => current.is_none(),
+                        Some(ExpireCondition::Xx) => current.is_some(),
+                        Some(ExpireCondition::Gt) => current.is_some_and(|current| at > current),
+                        Some(ExpireCondition::Lt) => current.is_none_or(|current| at < current),
+                    };
+
+                    if !allowed {
+                        HASH_EXPIRE_NOT_SET
+                    } else if at <= now {
+                        hash.remove(field);
+                        HASH_FIELD_DELETED
+                    } else {
+                        hash.expires.insert(field.clone(), at);
+                        HASH_EXPIRE_SET
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| vec![HASH_FIELD_MISSING; fields.len()])
+    }
+
+    // remaining time to live of each field in milliseconds
+    pub fn hpttl(&self, key: &str, fields: &[String]) -> Vec<i64> {
+        let now = now_millis();
+
+        self.with_hash(key, |hash| {
+            fields
+                .iter()
+                .map(|field| match (hash.get(field), hash.expire_at(field)) {
+                    (None, _) => HASH_FIELD_MISSING,
+                    (Some(_), None) => HASH_FIELD_NO_EXPIRE,
+                    (Some(_), Some(at)) => at.saturating_sub(now) as i64,
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| vec![HASH_FIELD_MISSING; fields.len()])
+    }
+
+    pub fn hpersist(&self, key: &str, fields: &[String]) -> Vec<i64> {
+        let _guard = self.lock_key(key);
+        self.with_hash_locked(key, |hash| {
+            fields
+                .iter()
+                .map(|field| {
+                    if hash.get(field).is_none() {
+                        HASH_FIELD_MISSING
+                    } else if hash.expires.remove(field).is_some() {
+                        HASH_EXPIRE_SET
+                    } else {
+                        HASH_FIELD_NO_EXPIRE
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| vec![HASH_FIELD_MISSING; fields.len()])
+    }
+}
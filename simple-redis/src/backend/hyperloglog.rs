@@ -0,0 +1,538 @@
+use dashmap::mapref::entry::Entry;
+
+use super::{Backend, BackendError, StringValue};
+
+// the layout below is byte for byte the one redis uses, so the strings can be moved between both:
+// "HYLL" | encoding | 3 unused bytes | cached cardinality, 8 bytes little endian | registers
+const HLL_MAGIC: &[u8; 4] = b"HYLL";
+const HLL_HEADER_LEN: usize = 16;
+const HLL_DENSE: u8 = 0;
+const HLL_SPARSE: u8 = 1;
+
+const HLL_P: u32 = 14;
+const HLL_Q: u32 = 64 - HLL_P;
+const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_BITS: usize = 6;
+const HLL_DENSE_LEN: usize = HLL_HEADER_LEN + (HLL_REGISTERS * HLL_BITS).div_ceil(8);
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+
+// sparse opcodes store runs of registers, larger values or strings force the dense encoding
+const HLL_SPARSE_VAL_MAX: u8 = 32;
+const HLL_SPARSE_MAX_LEN: usize = 3000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    dense: bool,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+            dense: false,
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BackendError> {
+        if bytes.len() < HLL_HEADER_LEN || &bytes[..4] != HLL_MAGIC {
+            return Err(BackendError::InvalidHll);
+        }
+
+        let mut registers = vec![0; HLL_REGISTERS];
+        let body = &bytes[HLL_HEADER_LEN..];
+
+        match bytes[4] {
+            HLL_DENSE if bytes.len() == HLL_DENSE_LEN => {
+                for (i, register) in registers.iter_mut().enumerate() {
+                    *register = dense_get(body, i);
+                }
+                Ok(Self {
+                    registers,
+                    dense: true,
+                })
+            }
+            HLL_SPARSE => {
+                sparse_decode(body, &mut registers)?;
+                Ok(Self {
+                    registers,
+                    dense: false,
+                })
+            }
+            _ => Err(BackendError::InvalidHll),
+        }
+    }
+
+    pub fn is_dense(&self) -> bool {
+        self.dense
+    }
+
+    // returns whether a register changed, i.e. the estimate may have changed
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let (index, count) = hash_element(element);
+
+        if self.registers[index] >= count {
+            return false;
+        }
+
+        self.registers[index] = count;
+        true
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+        self.dense |= other.dense;
+    }
+
+    // the improved estimator from Otmar Ertl's "New cardinality estimation algorithms for
+    // HyperLogLog sketches", the same one redis uses
+    pub fn count(&self) -> u64 {
+        let mut histogram = [0u32; 64];
+        for register in &self.registers {
+            histogram[*register as usize] += 1;
+        }
+
+        let m = HLL_REGISTERS as f64;
+        let mut z = m * tau((m - histogram[HLL_Q as usize + 1] as f64) / m);
+        for count in histogram[1..=HLL_Q as usize].iter().rev() {
+            z += *count as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+
+        (HLL_ALPHA_INF * m * m / z).round() as u64
+    }
+
+    // `cardinality` is stored as a cache, None marks the cache as stale
+    pub fn to_bytes(&self, cardinality: Option<u64>) -> Vec<u8> {
+        if !self.dense {
+            if let Some(body) = sparse_encode(&self.registers) {
+                return with_header(HLL_SPARSE, cardinality, body);
+            }
+        }
+
+        let mut body = vec![0; HLL_DENSE_LEN - HLL_HEADER_LEN];
+        for (i, register) in self.registers.iter().enumerate() {
+            dense_set(&mut body, i, *register);
+        }
+        with_header(HLL_DENSE, cardinality, body)
+    }
+}
+
+fn with_header(encoding: u8, cardinality: Option<u64>, body: Vec<u8>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HLL_HEADER_LEN + body.len());
+    bytes.extend_from_slice(HLL_MAGIC);
+    bytes.extend_from_slice(&[encoding, 0, 0, 0]);
+
+    let mut card = cardinality.unwrap_or_default().to_le_bytes();
+    if cardinality.is_none() {
+        card[7] |= 0x80;
+    }
+    bytes.extend_from_slice(&card);
+    bytes.extend(body);
+    bytes
+}
+
+fn cached_cardinality(bytes: &[u8]) -> Option<u64> {
+    let card: [u8; 8] = bytes.get(8..HLL_HEADER_LEN)?.try_into().ok()?;
+    (card[7] & 0x80 == 0).then(|| u64::from_le_bytes(card))
+}
+
+// dense registers are 6 bit wide and packed starting from the least significant bit
+fn dense_get(body: &[u8], index: usize) -> u8 {
+    let byte = index * HLL_BITS / 8;
+    let fb = (index * HLL_BITS % 8) as u32;
+    let b0 = body[byte] as u16;
+    let b1 = body.get(byte + 1).copied().unwrap_or(0) as u16;
+
+    (((b0 >> fb) | (b1 << (8 - fb))) & 63) as u8
+}
+
+fn dense_set(body: &mut [u8], index: usize, value: u8) {
+    let byte = index * HLL_BITS / 8;
+    let fb = (index * HLL_BITS % 8) as u32;
+    let value = value as u16;
+
+    body[byte] &= !((63u16 << fb) as u8);
+    body[byte] |= (value << fb) as u8;
+    if let Some(next) = body.get_mut(byte + 1) {
+        *next &= !((63u16 >> (8 - fb)) as u8);
+        *next |= (value >> (8 - fb)) as u8;
+    }
+}
+
+// opcodes: ZERO 00xxxxxx, XZERO 01xxxxxx yyyyyyyy and VAL 1vvvvvxx, each holding a run length
+fn sparse_decode(body: &[u8], registers: &mut [u8]) -> Result<(), BackendError> {
+    let mut index = 0;
+    let mut bytes = body.iter();
+
+    while let Some(&op) = bytes.next() {
+        let (value, len) = match op & 0xc0 {
+            0x00 => (0, (op & 0x3f) as usize + 1),
+            0x40 => {
+                let low = *bytes.next().ok_or(BackendError::CorruptedHll)? as usize;
+                (0, (((op & 0x3f) as usize) << 8 | low) + 1)
+            }
+            _ => (((op >> 2) & 0x1f) + 1, (op & 0x03) as usize + 1),
+        };
+
+        let run = registers
+            .get_mut(index..index + len)
+            .ok_or(BackendError::CorruptedHll)?;
+        run.fill(value);
+        index += len;
+    }
+
+    if index != HLL_REGISTERS {
+        return Err(BackendError::CorruptedHll);
+    }
+
+    Ok(())
+}
+
+fn sparse_encode(registers: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut index = 0;
+
+    while index < registers.len() {
+        let value = registers[index];
+        let run = registers[index..]
+            .iter()
+            .take_while(|register| **register == value)
+            .count();
+        index += run;
+
+        if value > HLL_SPARSE_VAL_MAX {
+            return None;
+        }
+
+        let mut left = run;
+        while left > 0 {
+            let len = match value {
+                0 if left > 64 => {
+                    let len = left.min(HLL_REGISTERS);
+                    body.extend_from_slice(&[0x40 | ((len - 1) >> 8) as u8, (len - 1) as u8]);
+                    len
+                }
+                0 => {
+                    body.push((left - 1) as u8);
+                    left
+                }
+                _ => {
+                    let len = left.min(4);
+                    body.push(0x80 | (value - 1) << 2 | (len - 1) as u8);
+                    len
+                }
+            };
+            left -= len;
+        }
+
+        if HLL_HEADER_LEN + body.len() > HLL_SPARSE_MAX_LEN {
+            return None;
+        }
+    }
+
+    Some(body)
+}
+
+// register index from the low bits of the hash, the value is the position of the first set bit
+// in the remaining ones
+fn hash_element(element: &[u8]) -> (usize, u8) {
+    let hash = murmurhash64a(element, 0xadc8_3b19);
+    let index = (hash & (HLL_REGISTERS as u64 - 1)) as usize;
+    let rest = (hash >> HLL_P) | (1 << HLL_Q);
+
+    (index, rest.trailing_zeros() as u8 + 1)
+}
+
+fn murmurhash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let chunks = key.chunks_exact(8);
+    let tail = chunks.remainder();
+
+    for chunk in chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= (*byte as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if previous == z {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if previous == z {
+            return z / 3.0;
+        }
+    }
+}
+
+impl Backend {
+    // creating the key counts as a change even without elements, like redis
+    pub fn pfadd(&self, key: &str, elements: &[Vec<u8>]) -> Result<bool, BackendError> {
+        let add = |hll: &mut HyperLogLog| {
+            elements
+                .iter()
+                .fold(false, |changed, element| hll.add(element) | changed)
+        };
+
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        match self.map.entry(key.to_string()) {
+            Entry::Occupied(mut entry) => {
+                let mut hll = HyperLogLog::from_bytes(&entry.get().as_bytes())?;
+                let changed = add(&mut hll);
+
+                if changed {
+                    entry.insert(StringValue::Raw(hll.to_bytes(None)));
+                }
+                Ok(changed)
+            }
+            Entry::Vacant(entry) => {
+                let mut hll = HyperLogLog::new();
+                add(&mut hll);
+
+                entry.insert(StringValue::Raw(hll.to_bytes(None)));
+                Ok(true)
+            }
+        }
+    }
+
+    // a single key caches the estimate in its header, several keys are counted as their union
+    pub fn pfcount(&self, keys: &[String]) -> Result<u64, BackendError> {
+        if let [key] = keys {
+            let _guard = self.lock_key(key);
+            let Some(mut value) = self.map.get_mut(key) else {
+                return Ok(0);
+            };
+
+            let hll = HyperLogLog::from_bytes(&value.as_bytes())?;
+            let bytes = value.make_raw();
+            if let Some(cardinality) = cached_cardinality(bytes) {
+                return Ok(cardinality);
+            }
+
+            let cardinality = hll.count();
+            bytes[8..HLL_HEADER_LEN].copy_from_slice(&cardinality.to_le_bytes());
+            return Ok(cardinality);
+        }
+
+        let _guard = self.lock_keys(keys.iter().map(String::as_str));
+        let mut union = HyperLogLog::new();
+        for hll in self.load_hlls(keys)?.iter().flatten() {
+            union.merge(hll);
+        }
+
+        Ok(union.count())
+    }
+
+    pub fn pfmerge(&self, dest: &str, keys: &[String]) -> Result<(), BackendError> {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str).chain([dest]));
+        self.check_kind(dest, "string")?;
+        let sources = self.load_hlls(keys)?;
+
+        let mut entry = self
+            .map
+            .entry(dest.to_string())
+            .or_insert_with(|| StringValue::Raw(HyperLogLog::new().to_bytes(Some(0))));
+
+        let mut merged = HyperLogLog::from_bytes(&entry.as_bytes())?;
+        for hll in sources.iter().flatten() {
+            merged.merge(hll);
+        }
+
+        *entry = StringValue::Raw(merged.to_bytes(None));
+        Ok(())
+    }
+
+    fn load_hlls(&self, keys: &[String]) -> Result<Vec<Option<HyperLogLog>>, BackendError> {
+        keys.iter()
+            .map(|key| match self.map.get(key) {
+                Some(value) => HyperLogLog::from_bytes(&value.as_bytes()).map(Some),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_murmurhash64a() {
+        assert_eq!(murmurhash64a(b"", 0), 0);
+        assert_ne!(murmurhash64a(b"a", 0), murmurhash64a(b"b", 0));
+        assert_ne!(
+            murmurhash64a(b"12345678", 0xadc8_3b19),
+            murmurhash64a(b"123456789", 0xadc8_3b19)
+        );
+    }
+
+    #[test]
+    fn test_hll_empty_encoding() {
+        let bytes = HyperLogLog::new().to_bytes(Some(0));
+
+        // a sparse HLL with all registers zero is a single XZERO opcode covering 16384 registers
+        assert_eq!(
+            bytes,
+            [b'H', b'Y', b'L', b'L', 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x7f, 0xff]
+        );
+        assert_eq!(HyperLogLog::from_bytes(&bytes), Ok(HyperLogLog::new()));
+    }
+
+    #[test]
+    fn test_hll_round_trip() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..100 {
+            hll.add(format!("element:{}", i).as_bytes());
+        }
+
+        let sparse = hll.to_bytes(None);
+        assert_eq!(sparse[4], HLL_SPARSE);
+        assert_eq!(cached_cardinality(&sparse), None);
+        assert_eq!(HyperLogLog::from_bytes(&sparse).unwrap(), hll);
+
+        let mut dense = hll.clone();
+        dense.dense = true;
+        let bytes = dense.to_bytes(Some(100));
+        assert_eq!(bytes.len(), HLL_DENSE_LEN);
+        assert_eq!(cached_cardinality(&bytes), Some(100));
+        assert_eq!(HyperLogLog::from_bytes(&bytes).unwrap(), dense);
+    }
+
+    #[test]
+    fn test_hll_dense_registers() {
+        let mut body = vec![0; HLL_DENSE_LEN - HLL_HEADER_LEN];
+        for i in 0..HLL_REGISTERS {
+            dense_set(&mut body, i, (i % 64) as u8);
+        }
+        for i in 0..HLL_REGISTERS {
+            assert_eq!(dense_get(&body, i), (i % 64) as u8);
+        }
+    }
+
+    #[test]
+    fn test_hll_accuracy() {
+        let mut hll = HyperLogLog::new();
+        assert_eq!(hll.count(), 0);
+
+        for i in 0..10 {
+            hll.add(format!("{}", i).as_bytes());
+        }
+        assert_eq!(hll.count(), 10);
+
+        for i in 0..100_000 {
+            hll.add(format!("element:{}", i).as_bytes());
+        }
+        let error = (hll.count() as f64 - 100_010.0).abs() / 100_010.0;
+        assert!(error < 0.02, "error {} too large", error);
+        assert!(hll.to_bytes(None).len() == HLL_DENSE_LEN);
+    }
+
+    #[test]
+    fn test_hll_invalid() {
+        assert_eq!(
+            HyperLogLog::from_bytes(b"not an hll"),
+            Err(BackendError::InvalidHll)
+        );
+
+        let mut bytes = HyperLogLog::new().to_bytes(None);
+        bytes.pop();
+        assert_eq!(
+            HyperLogLog::from_bytes(&bytes),
+            Err(BackendError::CorruptedHll)
+        );
+    }
+
+    #[test]
+    fn test_backend_pfadd_pfcount() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            backend.pfadd("hll", &[b"a".to_vec(), b"b".to_vec()]),
+            Ok(true)
+        );
+        assert_eq!(backend.pfadd("hll", &[b"a".to_vec()]), Ok(false));
+        assert_eq!(backend.pfadd("empty", &[]), Ok(true));
+        assert_eq!(backend.pfadd("empty", &[]), Ok(false));
+
+        let keys = ["hll".to_string()];
+        assert_eq!(backend.pfcount(&keys), Ok(2));
+        let bytes = backend.get_string("hll").unwrap().into_bytes();
+        assert_eq!(cached_cardinality(&bytes), Some(2));
+
+        backend.set("text", "abc");
+        assert_eq!(
+            backend.pfadd("text", &[b"a".to_vec()]),
+            Err(BackendError::InvalidHll)
+        );
+        assert_eq!(backend.pfcount(&["missing".to_string()]), Ok(0));
+    }
+
+    #[test]
+    fn test_backend_pfmerge() {
+        let backend = Backend::new();
+        backend.pfadd("a", &[b"1".to_vec(), b"2".to_vec()]).unwrap();
+        backend.pfadd("b", &[b"2".to_vec(), b"3".to_vec()]).unwrap();
+
+        let keys = ["a".to_string(), "b".to_string()];
+        assert_eq!(backend.pfcount(&keys), Ok(3));
+
+        backend.pfmerge("dest", &keys).unwrap();
+        assert_eq!(backend.pfcount(&["dest".to_string()]), Ok(3));
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use super::unix_time;
+
+const LATENCY_TS_LEN: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySample {
+    pub timestamp: u64,
+    pub latency: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LatencyEvent {
+    pub samples: VecDeque<LatencySample>,
+    pub max: u64,
+}
+
+impl LatencyEvent {
+    pub fn latest(&self) -> Option<&LatencySample> {
+        self.samples.back()
+    }
+}
+
+#[derive(Debug)]
+pub struct LatencyMonitor {
+    events: Mutex<BTreeMap<String, LatencyEvent>>,
+    threshold: AtomicU64,
+}
+
+impl LatencyMonitor {
+    pub fn new(threshold: u64) -> Self {
+        Self {
+            events: Mutex::new(BTreeMap::new()),
+            threshold: AtomicU64::new(threshold),
+        }
+    }
+
+    pub fn set_threshold(&self, millis: u64) {
+        self.threshold.store(millis, Ordering::Relaxed);
+    }
+
+    // samples that land in the same second are merged, keeping the highest latency
+    pub fn add_sample(&self, event: &str, latency: u64) {
+        let threshold = self.threshold.load(Ordering::Relaxed);
+
+        if threshold == 0 || latency < threshold {
+            return;
+        }
+
+        let timestamp = unix_time().as_secs();
+        let mut events = self.events.lock().unwrap();
+        let event = events.entry(event.to_string()).or_default();
+
+        event.max = event.max.max(latency);
+
+        match event.samples.back_mut() {
+            Some(last) if last.timestamp == timestamp => {
+                last.latency = last.latency.max(latency);
+            }
+            _ => {
+                event
+                    .samples
+                    .push_back(LatencySample { timestamp, latency });
+                if event.samples.len() > LATENCY_TS_LEN {
+                    event.samples.pop_front();
+                }
+            }
+        }
+    }
+
+    pub fn latest(&self) -> Vec<(String, LatencyEvent)> {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .map(|(name, event)| (name.clone(), event.clone()))
+            .collect()
+    }
+
+    pub fn history(&self, event: &str) -> Vec<LatencySample> {
+        let events = self.events.lock().unwrap();
+        events
+            .get(event)
+            .map(|event| event.samples.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    // reset the given events, or all of them when none are given
+    pub fn reset(&self, names: &[String]) -> usize {
+        let mut events = self.events.lock().unwrap();
+
+        if names.is_empty() {
+            let count = events.len();
+            events.clear();
+            count
+        } else {
+            names
+                .iter()
+                .filter(|name| events.remove(name.as_str()).is_some())
+                .count()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_disabled_by_default_threshold() {
+        let monitor = LatencyMonitor::new(0);
+        monitor.add_sample("command", 100);
+        assert!(monitor.latest().is_empty());
+    }
+
+    #[test]
+    fn test_latency_add_sample() {
+        let monitor = LatencyMonitor::new(10);
+
+        monitor.add_sample("command", 5);
+        assert!(monitor.latest().is_empty());
+
+        monitor.add_sample("command", 20);
+        monitor.add_sample("command", 15);
+
+        let latest = monitor.latest();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].0, "command");
+        assert_eq!(latest[0].1.max, 20);
+
+        let history = monitor.history("command");
+        assert!(!history.is_empty());
+        assert_eq!(history.last().unwrap().latency, 20);
+        assert!(monitor.history("fast-command").is_empty());
+    }
+
+    #[test]
+    fn test_latency_reset() {
+        let monitor = LatencyMonitor::new(1);
+        monitor.add_sample("command", 20);
+        monitor.add_sample("fast-command", 20);
+
+        assert_eq!(
+            monitor.reset(&["command".to_string(), "nope".to_string()]),
+            1
+        );
+        assert_eq!(monitor.reset(&[]), 1);
+        assert!(monitor.latest().is_empty());
+    }
+}
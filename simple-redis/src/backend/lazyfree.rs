@@ -0,0 +1,259 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, OnceLock};
+
+use super::{Backend, Value};
+use crate::config::Config;
+
+// values with more elements than this are freed in the background, dropping smaller ones right
+// away is cheaper than handing them over, like LAZYFREE_THRESHOLD in redis
+pub const LAZYFREE_THRESHOLD: usize = 64;
+
+// why a key is being deleted, each has its lazyfree-lazy-* option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LazyFreeCause {
+    Eviction,
+    Expire,
+    // the server deletes or overwrites a key as a side effect, like RESTORE REPLACE or the
+    // destination of SINTERSTORE
+    ServerDel,
+    // DEL, UNLINK is always lazy
+    UserDel,
+}
+
+// drops unlinked values on a background thread, so freeing a big one doesn't stall the shard it
+// was in for every other client
+#[derive(Debug)]
+pub struct LazyFree {
+    // started by the first value handed over
+    sender: OnceLock<Sender<Vec<Value>>>,
+    pending: Arc<AtomicU64>,
+    freed: Arc<AtomicU64>,
+    eviction: AtomicBool,
+    expire: AtomicBool,
+    server_del: AtomicBool,
+    user_del: AtomicBool,
+}
+
+impl LazyFree {
+    pub fn new(config: &Config) -> Self {
+        let lazyfree = Self {
+            sender: OnceLock::new(),
+            pending: Arc::default(),
+            freed: Arc::default(),
+            eviction: AtomicBool::new(false),
+            expire: AtomicBool::new(false),
+            server_del: AtomicBool::new(false),
+            user_del: AtomicBool::new(false),
+        };
+        lazyfree.configure(config);
+        lazyfree
+    }
+
+    pub fn configure(&self, config: &Config) {
+        self.eviction
+            .store(config.lazyfree_lazy_eviction, Ordering::Relaxed);
+        self.expire
+            .store(config.lazyfree_lazy_expire, Ordering::Relaxed);
+        self.server_del
+            .store(config.lazyfree_lazy_server_del, Ordering::Relaxed);
+        self.user_del
+            .store(config.lazyfree_lazy_user_del, Ordering::Relaxed);
+    }
+
+    // whether keys deleted for `cause` are freed in the background
+    pub fn enabled(&self, cause: LazyFreeCause) -> bool {
+        match cause {
+            LazyFreeCause::Eviction => &self.eviction,
+            LazyFreeCause::Expire => &self.expire,
+            LazyFreeCause::ServerDel => &self.server_del,
+            LazyFreeCause::UserDel => &self.user_del,
+        }
+        .load(Ordering::Relaxed)
+    }
+
+    // hands `values` to the background thread whatever their size
+    pub fn free(&self, values: Vec<Value>) {
+        if values.is_empty() {
+            return;
+        }
+        self.pending
+            .fetch_add(values.len() as u64, Ordering::Relaxed);
+        if let Err(mpsc::SendError(values)) = self.sender().send(values) {
+            // the thread is gone, which only happens when it panicked
+            self.freed(values);
+        }
+    }
+
+    // values handed over and not dropped yet
+    pub fn pending(&self) -> u64 {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    // values the background thread dropped since the server started
+    pub fn freed_objects(&self) -> u64 {
+        self.freed.load(Ordering::Relaxed)
+    }
+
+    fn sender(&self) -> &Sender<Vec<Value>> {
+        self.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel::<Vec<Value>>();
+            let pending = self.pending.clone();
+            let freed = self.freed.clone();
+            std::thread::Builder::new()
+                .name("lazyfree".to_string())
+                .spawn(move || {
+                    for values in receiver {
+                        let count = values.len() as u64;
+                        drop(values);
+                        pending.fetch_sub(count, Ordering::Relaxed);
+                        freed.fetch_add(count, Ordering::Relaxed);
+                    }
+                })
+                .expect("failed to spawn the lazyfree thread");
+            sender
+        })
+    }
+
+    fn freed(&self, values: Vec<Value>) {
+        let count = values.len() as u64;
+        drop(values);
+        self.pending.fetch_sub(count, Ordering::Relaxed);
+        self.freed.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+impl Default for LazyFree {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+// how much work dropping `value` is, one per element
+fn free_effort(value: &Value) -> usize {
+    match value {
+        Value::String(_) => 1,
+        Value::Hash(hash) => hash.len(),
+        Value::Set(set) => set.len(),
+        Value::ZSet(zset) => zset.len(),
+        Value::List(list) => list.len(),
+    }
+}
+
+impl Backend {
+    pub fn lazyfree(&self) -> &LazyFree {
+        &self.inner.lazyfree
+    }
+
+    // drops a value that was unlinked for `cause`, in the background when that is lazy and the
+    // value is big enough to be worth it
+    pub(crate) fn free_value(&self, value: Value, cause: LazyFreeCause) {
+        self.free_lazily(value, self.lazyfree().enabled(cause));
+    }
+
+    pub(crate) fn free_lazily(&self, value: Value, lazy: bool) {
+        if lazy && free_effort(&value) > LAZYFREE_THRESHOLD {
+            self.lazyfree().free(vec![value]);
+        }
+    }
+
+    // removes `keys` whatever their type and returns how many existed, UNLINK frees them lazily
+    // and DEL only with lazyfree-lazy-user-del. each key is unlinked under its own lock and freed
+    // once it is released, so a big value only holds up the writers of its stripe while it is
+    // taken out
+    pub fn del(&self, keys: &[String], unlink: bool) -> usize {
+        let lazy = unlink || self.lazyfree().enabled(LazyFreeCause::UserDel);
+        let removed: Vec<&String> = keys
+            .iter()
+            .filter(|key| {
+                let value = {
+                    let _guard = self.lock_key(key);
+                    self.meta.remove(key.as_str());
+                    self.take(key)
+                };
+                value.map(|value| self.free_lazily(value, lazy)).is_some()
+            })
+            .collect();
+        if !removed.is_empty() {
+            self.invalidate(&removed);
+        }
+        removed.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn members(count: usize) -> Vec<String> {
+        (0..count).map(|i| i.to_string()).collect()
+    }
+
+    fn wait_for_pending(lazyfree: &LazyFree) {
+        let start = Instant::now();
+        while lazyfree.pending() > 0 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_unlink() {
+        let backend = Backend::new();
+        backend
+            .sadd("big", &members(LAZYFREE_THRESHOLD + 1))
+            .unwrap();
+        backend.sadd("small", &members(2)).unwrap();
+        backend.set("string", "value");
+
+        let keys = ["big", "small", "string", "missing"].map(String::from);
+        assert_eq!(backend.del(&keys, true), 3);
+        assert_eq!(backend.dbsize(), 0);
+
+        // only the big set is worth a trip to the background thread
+        wait_for_pending(backend.lazyfree());
+        assert_eq!(backend.lazyfree().freed_objects(), 1);
+    }
+
+    #[test]
+    fn test_lazyfree_options() {
+        let backend = Backend::new();
+        backend
+            .sadd("big", &members(LAZYFREE_THRESHOLD + 1))
+            .unwrap();
+        assert_eq!(backend.del(&["big".to_string()], false), 1);
+        assert_eq!(backend.lazyfree().freed_objects(), 0);
+        assert!(!backend.lazyfree().enabled(LazyFreeCause::ServerDel));
+
+        let config = Config {
+            lazyfree_lazy_user_del: true,
+            lazyfree_lazy_server_del: true,
+            ..Config::default()
+        };
+        backend.configure(&config);
+        assert!(backend.lazyfree().enabled(LazyFreeCause::ServerDel));
+        assert!(!backend.lazyfree().enabled(LazyFreeCause::Expire));
+
+        backend
+            .sadd("big", &members(LAZYFREE_THRESHOLD + 1))
+            .unwrap();
+        assert_eq!(backend.del(&["big".to_string()], false), 1);
+        wait_for_pending(backend.lazyfree());
+        assert_eq!(backend.lazyfree().freed_objects(), 1);
+    }
+
+    #[test]
+    fn test_flush_async() {
+        let backend = Backend::new();
+        backend.sadd("a", &members(3)).unwrap();
+        backend.set("b", "value");
+        backend.flushall(true);
+        assert_eq!(backend.dbsize(), 0);
+
+        // a flush hands over every value however small
+        wait_for_pending(backend.lazyfree());
+        assert_eq!(backend.lazyfree().freed_objects(), 2);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+use super::{Backend, BackendError, LazyFreeCause, Value};
+
+impl Backend {
+    // pushes `elements` one after the other at the head, or at the tail with `tail`, and returns
+    // the length of the list
+    pub fn push(&self, key: &str, elements: &[Vec<u8>], tail: bool) -> Result<usize, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "list")?;
+        let mut list = self.list.entry(key.to_string()).or_default();
+        for element in elements {
+            match tail {
+                true => list.push_back(element.clone()),
+                false => list.push_front(element.clone()),
+            }
+        }
+        Ok(list.len())
+    }
+
+    pub fn llen(&self, key: &str) -> usize {
+        self.list.get(key).map_or(0, |list| list.len())
+    }
+
+    // the elements from `start` to `stop` included, negative indexes count from the tail
+    pub fn lrange(&self, key: &str, start: i64, stop: i64) -> Vec<Vec<u8>> {
+        let Some(list) = self.list.get(key) else {
+            return Vec::new();
+        };
+
+        let len = list.len() as i64;
+        let start = if start < 0 {
+            (len + start).max(0)
+        } else {
+            start
+        };
+        let stop = if stop < 0 {
+            len + stop
+        } else {
+            stop.min(len - 1)
+        };
+
+        if start > stop || start >= len {
+            return Vec::new();
+        }
+
+        list.range(start as usize..=stop as usize)
+            .cloned()
+            .collect()
+    }
+
+    // replaces whatever `key` held, an empty list deletes it; called under the key lock
+    pub(super) fn list_replace(&self, key: &str, list: VecDeque<Vec<u8>>) {
+        self.overwrite(key, "list");
+        let old = if list.is_empty() {
+            self.list.remove(key).map(|(_, old)| old)
+        } else {
+            self.list.insert(key.to_string(), list)
+        };
+        if let Some(old) = old {
+            self.free_value(Value::List(old), LazyFreeCause::ServerDel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(elements: &[&str]) -> Vec<Vec<u8>> {
+        elements.iter().map(|e| e.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_push_and_range() {
+        let backend = Backend::new();
+        assert_eq!(backend.push("list", &elements(&["b", "a"]), false), Ok(2));
+        assert_eq!(backend.push("list", &elements(&["c", "d"]), true), Ok(4));
+        assert_eq!(backend.llen("list"), 4);
+
+        assert_eq!(
+            backend.lrange("list", 0, -1),
+            elements(&["a", "b", "c", "d"])
+        );
+        assert_eq!(backend.lrange("list", -2, 10), elements(&["c", "d"]));
+        assert!(backend.lrange("list", 3, 1).is_empty());
+        assert!(backend.lrange("missing", 0, -1).is_empty());
+
+        backend.set("str", "x");
+        assert_eq!(
+            backend.push("str", &elements(&["a"]), true),
+            Err(BackendError::WrongType)
+        );
+    }
+}
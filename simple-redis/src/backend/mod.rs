@@ -1,42 +1,180 @@
-use dashmap::{DashMap, DashSet};
+mod bitmap;
+mod client;
+mod db;
+mod dump;
+mod error;
+mod expire;
+mod geo;
+mod hash;
+mod hyperloglog;
+mod latency;
+mod lazyfree;
+mod list;
+mod monitor;
+mod object;
+mod set;
+mod shutdown;
+mod slowlog;
+mod sort;
+mod stats;
+mod string;
+mod tracking;
+mod zset;
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{
+    Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError, Weak,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{ops::Deref, sync::Arc};
 
+use crate::config::Config;
 use crate::resp::frame::Frame;
+pub use bitmap::{BitOp, BitUnit, BitfieldOp, BitfieldOverflow, BitfieldType, BIT_OFFSET_MAX};
+pub use client::{
+    ClientClass, ClientInfo, Clients, ConnectionKind, OutputBufferLimit, OutputBufferLimits,
+};
+pub use db::{Db, Value};
+pub use dump::{crc64, DUMP_VERSION};
+pub use error::BackendError;
+pub use geo::{
+    geohash_decode, geohash_encode, validate_lonlat, GeoAddOptions, GeoFrom, GeoMatch, GeoQuery,
+    GeoShape, GeoSort, GeoUnit,
+};
+pub use hash::{
+    ExpireCondition, HashValue, HASH_EXPIRE_NOT_SET, HASH_EXPIRE_SET, HASH_FIELD_DELETED,
+    HASH_FIELD_MISSING, HASH_FIELD_NO_EXPIRE,
+};
+pub use hyperloglog::HyperLogLog;
+pub use latency::{LatencyEvent, LatencyMonitor, LatencySample};
+pub use lazyfree::{LazyFree, LazyFreeCause, LAZYFREE_THRESHOLD};
+pub use monitor::Monitor;
+pub(crate) use object::ValueRef;
+pub use object::{KeyMeta, MemoryStats, LFU_INIT_VAL, MEMORY_USAGE_SAMPLES};
+pub use set::SetOp;
+pub use shutdown::{Shutdown, ShutdownMode};
+pub use slowlog::{SlowLog, SlowLogEntry};
+pub use sort::SortOptions;
+pub use stats::{CommandStats, Stats, LATENCY_BUCKETS};
+pub use string::{StringValue, STRING_MAX_LEN};
+pub use tracking::{Tracking, TrackingOptions};
+pub use zset::{Score, SortedSet};
+
+// how many locks the keys are spread over, two writers of different keys rarely share one
+const KEY_STRIPES: usize = 256;
 
 #[derive(Debug, Clone)]
 pub struct Backend {
     inner: Arc<BackendInner>,
+    // the database selected by a connection, the clones it hands out share it
+    db: Arc<AtomicUsize>,
+    client: Option<Arc<ClientInfo>>,
 }
 
 #[derive(Debug)]
 pub struct BackendInner {
-    set: DashMap<String, DashSet<String>>,
-    map: DashMap<String, Frame>,
-    hmap: DashMap<String, DashMap<String, Frame>>,
+    dbs: Vec<Db>,
+    // which of `dbs` each database number points at, SWAPDB swaps two of them
+    slots: Vec<AtomicUsize>,
+    // server wide state, partitions of the thread-per-core engine share it
+    slowlog: Arc<SlowLog>,
+    latency: Arc<LatencyMonitor>,
+    monitor: Arc<Monitor>,
+    shutdown: Arc<Shutdown>,
+    clients: Arc<Clients>,
+    stats: Arc<Stats>,
+    tracking: Arc<Tracking>,
+    lazyfree: Arc<LazyFree>,
+    // whether hash fields are expired in the background or only when they are read
+    active_expire: Arc<AtomicBool>,
+    // every partition made from this backend or its partitions, for stats that count keys
+    partitions: Arc<Mutex<Vec<Weak<BackendInner>>>>,
+    // commands share this lock, the ones replacing whole databases like FLUSHALL or SWAPDB hold
+    // it exclusively
+    keyspace: RwLock<()>,
+    // a writer also holds the stripe of its key, so checking the type of the key and writing it
+    // is one step for the other writers of that key; multi-key commands hold the stripes of all
+    // their keys, so they see and write one consistent snapshot of them
+    stripes: Vec<Mutex<()>>,
+}
+
+// the shared keyspace lock and the stripe of one key, see `Backend::lock_key`
+pub(crate) struct KeyGuard<'a> {
+    _keyspace: RwLockReadGuard<'a, ()>,
+    _stripe: MutexGuard<'a, ()>,
+}
+
+// the shared keyspace lock and the stripes of several keys, see `Backend::lock_keys`
+pub(crate) struct KeysGuard<'a> {
+    _keyspace: RwLockReadGuard<'a, ()>,
+    _stripes: Vec<MutexGuard<'a, ()>>,
 }
 
 impl Default for Backend {
     fn default() -> Self {
         let inner = Arc::new(BackendInner::default());
-        Self { inner }
+        Self::from_inner(inner)
     }
 }
 
 impl Default for BackendInner {
     fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+impl BackendInner {
+    fn new(config: &Config) -> Self {
+        let databases = config.databases.max(1);
         Self {
-            set: DashMap::new(),
-            map: DashMap::new(),
-            hmap: DashMap::new(),
+            dbs: (0..databases).map(|_| Db::default()).collect(),
+            slots: (0..databases).map(AtomicUsize::new).collect(),
+            slowlog: Arc::new(SlowLog::new(
+                config.slowlog_log_slower_than,
+                config.slowlog_max_len,
+            )),
+            latency: Arc::new(LatencyMonitor::new(config.latency_monitor_threshold)),
+            monitor: Arc::default(),
+            shutdown: Arc::default(),
+            clients: Arc::new(Clients::new(config)),
+            stats: Arc::default(),
+            tracking: Arc::new(Tracking::new(config.tracking_table_max_keys)),
+            lazyfree: Arc::new(LazyFree::new(config)),
+            active_expire: Arc::new(AtomicBool::new(true)),
+            partitions: Arc::default(),
+            keyspace: RwLock::new(()),
+            stripes: (0..KEY_STRIPES).map(|_| Mutex::new(())).collect(),
+        }
+    }
+
+    // empty databases, the server wide state is shared with `self`
+    fn partition(&self) -> Self {
+        Self {
+            dbs: self.dbs.iter().map(|_| Db::default()).collect(),
+            slots: (0..self.dbs.len()).map(AtomicUsize::new).collect(),
+            slowlog: self.slowlog.clone(),
+            latency: self.latency.clone(),
+            monitor: self.monitor.clone(),
+            shutdown: self.shutdown.clone(),
+            clients: self.clients.clone(),
+            stats: self.stats.clone(),
+            tracking: self.tracking.clone(),
+            lazyfree: self.lazyfree.clone(),
+            active_expire: self.active_expire.clone(),
+            partitions: self.partitions.clone(),
+            keyspace: RwLock::new(()),
+            stripes: (0..KEY_STRIPES).map(|_| Mutex::new(())).collect(),
         }
     }
 }
 
+// the value types reach the keys of the selected database through here
 impl Deref for Backend {
-    type Target = BackendInner;
+    type Target = Db;
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        self.db(self.selected_db())
     }
 }
 
@@ -45,51 +183,217 @@ impl Backend {
         Self::default()
     }
 
+    pub fn with_config(config: &Config) -> Self {
+        let inner = Arc::new(BackendInner::new(config));
+        Self::from_inner(inner)
+    }
+
+    fn from_inner(inner: Arc<BackendInner>) -> Self {
+        Self {
+            inner,
+            db: Arc::new(AtomicUsize::new(0)),
+            client: None,
+        }
+    }
+
+    // a handle on the same data with its own selected database, one per connection
+    pub fn session(&self) -> Self {
+        Self::from_inner(self.inner.clone())
+    }
+
+    // a backend with the same databases, all empty, that shares the slow log, latency monitor,
+    // monitor feed, shutdown signal, clients, stats, tracking table and lazyfree thread with this
+    // one
+    pub fn partition(&self) -> Self {
+        let partition = self.scratch();
+        let mut partitions = self
+            .inner
+            .partitions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        partitions.retain(|partition| partition.strong_count() > 0);
+        partitions.push(Arc::downgrade(&partition.inner));
+        drop(partitions);
+        partition
+    }
+
+    // a partition the stats that count keys leave out, it holds copies of keys other partitions
+    // own while a command of the thread-per-core engine runs on them
+    pub(crate) fn scratch(&self) -> Self {
+        Self::from_inner(Arc::new(self.inner.partition()))
+    }
+
+    // this backend and its live partitions, for commands that look at every key of the server
+    pub(crate) fn with_partitions(&self) -> Vec<Backend> {
+        let partitions = self
+            .inner
+            .partitions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter_map(|partition| partition.upgrade())
+            .map(Backend::from_inner)
+            .collect::<Vec<_>>();
+        std::iter::once(self.clone()).chain(partitions).collect()
+    }
+
+    // apply the runtime tunable settings of `config` to an existing backend
+    pub fn configure(&self, config: &Config) {
+        let inner = &self.inner;
+        inner
+            .slowlog
+            .set_log_slower_than(config.slowlog_log_slower_than);
+        inner.slowlog.set_max_len(config.slowlog_max_len);
+        inner
+            .latency
+            .set_threshold(config.latency_monitor_threshold);
+        inner.tracking.set_max_keys(config.tracking_table_max_keys);
+        inner.clients.configure(config);
+        inner.lazyfree.configure(config);
+    }
+
+    pub fn slowlog(&self) -> &SlowLog {
+        &self.inner.slowlog
+    }
+
+    pub fn latency(&self) -> &LatencyMonitor {
+        &self.inner.latency
+    }
+
+    pub fn monitor(&self) -> &Monitor {
+        &self.inner.monitor
+    }
+
+    pub fn shutdown(&self) -> &Shutdown {
+        &self.inner.shutdown
+    }
+
+    pub fn active_expire(&self) -> bool {
+        self.inner.active_expire.load(Ordering::Relaxed)
+    }
+
+    pub fn set_active_expire(&self, enabled: bool) {
+        self.inner.active_expire.store(enabled, Ordering::Relaxed);
+    }
+
     pub fn get(&self, key: &str) -> Option<Frame> {
-        self.map.get(key).map(|v| v.value().clone())
+        self.get_string(key).map(Into::into)
     }
 
-    pub fn set(&self, key: impl ToString, value: Frame) {
-        self.map.insert(key.to_string(), value);
+    // replaces whatever `key` holds, like SET does with a value of any type
+    pub fn set(&self, key: impl ToString, value: impl Into<StringValue>) {
+        let key = key.to_string();
+        let _guard = self.lock_key(&key);
+        self.overwrite(&key, "string");
+        self.map.insert(key, value.into());
     }
 
-    pub fn hset(&self, key: impl ToString, field: impl ToString, value: Frame) {
-        let hmap = self.hmap.entry(key.to_string()).or_default();
-        hmap.insert(field.to_string(), value);
+    // taken by writers that touch a single key, it is not reentrant so a writer never calls
+    // another one while holding it
+    pub(crate) fn lock_key(&self, key: &str) -> KeyGuard<'_> {
+        let keyspace = self.share_keyspace();
+        let stripe = &self.inner.stripes[stripe_of(key)];
+        KeyGuard {
+            _keyspace: keyspace,
+            _stripe: stripe.lock().unwrap_or_else(PoisonError::into_inner),
+        }
     }
 
-    pub fn hget(&self, key: &str, field: &str) -> Option<Frame> {
-        self.hmap
-            .get(key)
-            .and_then(|hmap| hmap.get(field).map(|v| v.value().clone()))
+    // taken by commands that read or write several keys at once, the stripes are locked in
+    // ascending order so two of them never wait on each other
+    pub(crate) fn lock_keys<'k>(&self, keys: impl IntoIterator<Item = &'k str>) -> KeysGuard<'_> {
+        let keyspace = self.share_keyspace();
+        let mut stripes: Vec<usize> = keys.into_iter().map(stripe_of).collect();
+        stripes.sort_unstable();
+        stripes.dedup();
+        KeysGuard {
+            _keyspace: keyspace,
+            _stripes: stripes
+                .into_iter()
+                .map(|stripe| {
+                    self.inner.stripes[stripe]
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                })
+                .collect(),
+        }
     }
 
-    pub fn hgetall(&self, key: &str) -> Option<DashMap<String, Frame>> {
-        self.hmap.get(key).map(|v| v.clone())
+    // like `lock_key` but never waits, for readers that clean up a key they found dead; one that
+    // can't have the lock leaves it to the next writer, which is also what keeps a reader running
+    // under another guard from waiting for itself
+    pub(crate) fn try_lock_key(&self, key: &str) -> Option<KeyGuard<'_>> {
+        let keyspace = match self.inner.keyspace.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        let stripe = match self.inner.stripes[stripe_of(key)].try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        Some(KeyGuard {
+            _keyspace: keyspace,
+            _stripe: stripe,
+        })
     }
 
-    pub fn sadd(&self, key: &str, field: &str) -> bool {
-        let set = self.set.entry(key.to_string()).or_default();
+    // taken by background work that changes values in place without creating keys, it only
+    // keeps out the commands replacing whole databases
+    pub(crate) fn share_keyspace(&self) -> RwLockReadGuard<'_, ()> {
+        self.inner
+            .keyspace
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
 
-        if set.contains(field) {
-            false
-        } else {
-            set.insert(field.to_string());
-            true
+    // a writer creating or updating `key` as a `kind` fails when it holds another type, a key
+    // whose deadline passed or a hash whose fields all expired counts as missing; called under
+    // `lock_key` or `lock_keys`
+    pub(crate) fn check_kind(&self, key: &str, kind: &str) -> Result<(), BackendError> {
+        self.expire_key(self, key);
+        if kind != "hash" {
+            self.purge_hash(key);
+        }
+        match self.inspect(key, |value| value.kind()) {
+            Some(existing) if existing != kind => Err(BackendError::WrongType),
+            _ => Ok(()),
         }
     }
 
-    pub fn smembers(&self, key: &str) -> Option<Vec<String>> {
-        self.set
-            .get(key)
-            .map(|v| v.iter().map(|v| v.clone()).collect())
+    // drops a value of another type than `kind` and the deadline of `key` before a writer
+    // replaces it, same locking as `check_kind`
+    pub(crate) fn overwrite(&self, key: &str, kind: &str) {
+        if self.check_kind(key, kind).is_err() {
+            if let Some(old) = self.take(key) {
+                self.free_value(old, LazyFreeCause::ServerDel);
+            }
+        }
+        self.set_expire_at(key, None);
     }
 
-    pub fn sismember(&self, key: &str, field: &str) -> bool {
-        self.set.get(key).map_or(false, |v| v.contains(field))
+    // taken by commands that replace whole databases
+    pub(crate) fn lock_keyspace(&self) -> RwLockWriteGuard<'_, ()> {
+        self.inner
+            .keyspace
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
     }
 }
 
+fn stripe_of(key: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish() as usize % KEY_STRIPES
+}
+
+pub(crate) fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,24 +401,76 @@ mod tests {
     #[test]
     fn test_backend_get_set() {
         let backend = Backend::new();
-        backend.set("key", "value".into());
+        backend.set("key", "value");
         let result = backend.get("key").unwrap();
-        assert_eq!(result, "value".into());
+        assert_eq!(result, b"value".into());
     }
 
     #[test]
     fn test_backend_hset_hget() {
         let backend = Backend::new();
-        backend.hset("key", "field", "value".into());
+        backend.hset("key", "field", "value").unwrap();
         let result = backend.hget("key", "field").unwrap();
         assert_eq!(result, "value".into());
     }
 
+    #[test]
+    fn test_backend_one_type_per_key() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+
+        assert_eq!(
+            backend.sadd("key", &["a".to_string()]),
+            Err(BackendError::WrongType)
+        );
+        assert_eq!(backend.hset("key", "f", "v"), Err(BackendError::WrongType));
+        assert_eq!(backend.append("key", b"!"), Ok(6));
+
+        backend.hset("hash", "f", "v").unwrap();
+        assert_eq!(backend.incr_by("hash", 1), Err(BackendError::WrongType));
+        backend.set("hash", "value");
+        assert_eq!(backend.hget("hash", "f"), None);
+        assert_eq!(backend.get_string("hash"), Some("value".into()));
+    }
+
+    #[test]
+    fn test_backend_lock_keys() {
+        let backend = Backend::new();
+        // a key named twice or two keys sharing a stripe are locked once
+        let other = (0..)
+            .map(|i| format!("key{}", i))
+            .find(|key| stripe_of(key) == stripe_of("a"))
+            .unwrap();
+        drop(backend.lock_keys(["a", "a", &other]));
+
+        // only the writers of the locked keys wait
+        let free = (0..)
+            .map(|i| format!("key{}", i))
+            .find(|key| stripe_of(key) != stripe_of("a"))
+            .unwrap();
+        let guard = backend.lock_keys(["a"]);
+        let writer = backend.clone();
+        std::thread::spawn(move || writer.set(free, "value"))
+            .join()
+            .unwrap();
+        drop(guard);
+        assert_eq!(backend.dbsize(), 1);
+    }
+
+    #[test]
+    fn test_backend_keys() {
+        let backend = Backend::new();
+        backend.set("b", "value");
+        backend.hset("a", "field", "value").unwrap();
+        backend.sadd("c", &["member".to_string()]).unwrap();
+        assert_eq!(backend.keys(), vec!["a", "b", "c"]);
+    }
+
     #[test]
     fn test_backend_hgetall() {
         let backend = Backend::new();
-        backend.hset("key", "field1", "value1".into());
-        backend.hset("key", "field2", "value2".into());
+        backend.hset("key", "field1", "value1").unwrap();
+        backend.hset("key", "field2", "value2").unwrap();
         let result = backend.hgetall("key").unwrap();
         assert_eq!(result.len(), 2);
     }
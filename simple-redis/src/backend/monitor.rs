@@ -0,0 +1,101 @@
+use std::fmt::Write;
+use std::net::SocketAddr;
+
+use tokio::sync::broadcast;
+
+use super::unix_time;
+
+const MONITOR_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug)]
+pub struct Monitor {
+    sender: broadcast::Sender<String>,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(MONITOR_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl Monitor {
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.sender.subscribe()
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    // format: 1339518083.107412 [0 127.0.0.1:60866] "keys" "*"
+    pub fn feed(&self, args: &[String], client: Option<SocketAddr>, db: usize) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let now = unix_time();
+        let mut line = format!("{}.{:06} [{}", now.as_secs(), now.subsec_micros(), db);
+
+        match client {
+            Some(addr) => write!(line, " {}]", addr).unwrap(),
+            None => line.push(']'),
+        }
+
+        for arg in args {
+            line.push(' ');
+            line.push_str(&quote(arg));
+        }
+
+        // a send error only means the last monitor went away in the meantime
+        let _ = self.sender.send(line);
+    }
+}
+
+fn quote(arg: &str) -> String {
+    let mut result = String::with_capacity(arg.len() + 2);
+    result.push('"');
+
+    for c in arg.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\x07' => result.push_str("\\a"),
+            '\x08' => result.push_str("\\b"),
+            c if c.is_control() => write!(result, "\\x{:02x}", c as u32).unwrap(),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_quote() {
+        assert_eq!(quote("key"), "\"key\"");
+        assert_eq!(quote("a\"b\\c\r\n"), "\"a\\\"b\\\\c\\r\\n\"");
+        assert_eq!(quote("\x01"), "\"\\x01\"");
+    }
+
+    #[test]
+    fn test_monitor_feed() {
+        let monitor = Monitor::default();
+        monitor.feed(&["get".to_string()], None, 0);
+        assert!(!monitor.has_subscribers());
+
+        let mut receiver = monitor.subscribe();
+        let addr: SocketAddr = "127.0.0.1:6000".parse().unwrap();
+        monitor.feed(&["set".to_string(), "key".to_string()], Some(addr), 0);
+
+        let line = receiver.try_recv().unwrap();
+        assert!(line.ends_with(" [0 127.0.0.1:6000] \"set\" \"key\""));
+    }
+}
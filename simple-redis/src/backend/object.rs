@@ -0,0 +1,555 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::mem::size_of;
+use std::time::Duration;
+
+use rand::Rng;
+
+use super::{unix_time, Backend, Db, HashValue, Score, SortedSet, StringValue, Value};
+
+// like redis, a new key starts with some frequency so it is not the first one to look cold
+pub const LFU_INIT_VAL: u8 = 5;
+// the higher the factor, the more accesses it takes to raise a high counter
+const LFU_LOG_FACTOR: f64 = 10.0;
+// minutes of idleness that take one off the counter
+const LFU_DECAY_TIME: u64 = 1;
+// the elements of an aggregate MEMORY USAGE looks at unless told otherwise
+pub const MEMORY_USAGE_SAMPLES: usize = 5;
+
+// the size limits below which redis keeps a value in its compact encoding
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE: usize = 64;
+const INTSET_MAX_ENTRIES: usize = 512;
+const EMBSTR_MAX_LEN: usize = 44;
+
+// what the backend knows about a key besides its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyMeta {
+    // unix time in milliseconds of the last access
+    access: u64,
+    // logarithmic access counter as of `access`, it decays while the key sits idle
+    lfu: u8,
+    // estimated bytes of the key and its value as of the last write
+    size: usize,
+}
+
+impl KeyMeta {
+    fn new(size: usize) -> Self {
+        Self {
+            access: now_millis(),
+            lfu: LFU_INIT_VAL,
+            size,
+        }
+    }
+
+    // unix time in milliseconds
+    pub fn access(&self) -> u64 {
+        self.access
+    }
+
+    pub fn idle(&self) -> Duration {
+        Duration::from_millis(now_millis().saturating_sub(self.access))
+    }
+
+    // the access counter with the decay of the idle time applied
+    pub fn freq(&self) -> u8 {
+        self.lfu.saturating_sub(lfu_decay(self.idle()))
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn touch(&mut self) {
+        self.lfu = lfu_increment(self.freq());
+        self.access = now_millis();
+    }
+}
+
+// how much the access counter dropped over `idle`
+fn lfu_decay(idle: Duration) -> u8 {
+    let periods = idle.as_secs() / 60 / LFU_DECAY_TIME;
+    periods.min(u8::MAX as u64) as u8
+}
+
+// the counter grows with a probability that shrinks as it gets higher, so 255 takes about a
+// million accesses
+fn lfu_increment(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    let p = 1.0 / (base * LFU_LOG_FACTOR + 1.0);
+    if rand::thread_rng().gen::<f64>() < p {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+fn now_millis() -> u64 {
+    unix_time().as_millis() as u64
+}
+
+// a value borrowed from its map, so inspecting a big one does not copy it
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ValueRef<'a> {
+    String(&'a StringValue),
+    Hash(&'a HashValue),
+    Set(&'a HashSet<String>),
+    ZSet(&'a SortedSet),
+    List(&'a VecDeque<Vec<u8>>),
+}
+
+impl Value {
+    pub(crate) fn borrow(&self) -> ValueRef<'_> {
+        match self {
+            Value::String(value) => ValueRef::String(value),
+            Value::Hash(value) => ValueRef::Hash(value),
+            Value::Set(value) => ValueRef::Set(value),
+            Value::ZSet(value) => ValueRef::ZSet(value),
+            Value::List(value) => ValueRef::List(value),
+        }
+    }
+}
+
+impl ValueRef<'_> {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ValueRef::String(_) => "string",
+            ValueRef::Hash(_) => "hash",
+            ValueRef::Set(_) => "set",
+            ValueRef::ZSet(_) => "zset",
+            ValueRef::List(_) => "list",
+        }
+    }
+
+    // the name redis gives the representation it would pick for a value like this one
+    pub fn encoding(&self) -> &'static str {
+        match *self {
+            ValueRef::String(StringValue::Int(_)) => "int",
+            ValueRef::String(value) if value.len() <= EMBSTR_MAX_LEN => "embstr",
+            ValueRef::String(_) => "raw",
+            ValueRef::Hash(hash) => {
+                let lens = hash
+                    .iter()
+                    .flat_map(|(field, value)| [field.len(), value.len()]);
+                if compact(hash.len(), lens) {
+                    "listpack"
+                } else {
+                    "hashtable"
+                }
+            }
+            ValueRef::Set(set) => {
+                if set.len() <= INTSET_MAX_ENTRIES
+                    && set.iter().all(|member| member.parse::<i64>().is_ok())
+                {
+                    "intset"
+                } else if compact(set.len(), set.iter().map(String::len)) {
+                    "listpack"
+                } else {
+                    "hashtable"
+                }
+            }
+            ValueRef::ZSet(zset) => {
+                let lens = zset.iter().map(|(member, _)| member.len());
+                if compact(zset.len(), lens) {
+                    "listpack"
+                } else {
+                    "skiplist"
+                }
+            }
+            ValueRef::List(list) => {
+                if compact(list.len(), list.iter().map(Vec::len)) {
+                    "listpack"
+                } else {
+                    "quicklist"
+                }
+            }
+        }
+    }
+
+    // the bytes of the payload alone, what a dump of the value would at least take
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            ValueRef::String(value) => value.len(),
+            ValueRef::Hash(hash) => hash
+                .iter()
+                .map(|(field, value)| field.len() + value.len())
+                .sum(),
+            ValueRef::Set(set) => set.iter().map(String::len).sum(),
+            ValueRef::ZSet(zset) => zset
+                .iter()
+                .map(|(member, _)| member.len() + size_of::<f64>())
+                .sum(),
+            ValueRef::List(list) => list.iter().map(Vec::len).sum(),
+        }
+    }
+
+    // bytes of the value in memory, an aggregate is estimated from `samples` of its elements
+    // like MEMORY USAGE does, 0 looks at all of them
+    pub fn memory_usage(&self, samples: usize) -> usize {
+        match self {
+            ValueRef::String(StringValue::Int(_)) => size_of::<StringValue>(),
+            ValueRef::String(value) => size_of::<StringValue>() + value.len(),
+            ValueRef::Hash(hash) => {
+                let element = |(field, value): (&String, &StringValue)| {
+                    size_of::<String>() + field.len() + size_of::<StringValue>() + value.len()
+                };
+                size_of::<HashValue>() + estimate(hash.len(), hash.iter().map(element), samples)
+            }
+            ValueRef::Set(set) => {
+                let element = |member: &String| size_of::<String>() + member.len();
+                size_of::<HashSet<String>>() + estimate(set.len(), set.iter().map(element), samples)
+            }
+            ValueRef::ZSet(zset) => {
+                // every member is kept twice, once by name and once in score order
+                let element = |(member, _): (&str, f64)| {
+                    2 * (size_of::<String>() + member.len()) + size_of::<f64>() + size_of::<Score>()
+                };
+                size_of::<SortedSet>() + estimate(zset.len(), zset.iter().map(element), samples)
+            }
+            ValueRef::List(list) => {
+                let element = |element: &Vec<u8>| size_of::<Vec<u8>>() + element.len();
+                size_of::<VecDeque<Vec<u8>>>()
+                    + estimate(list.len(), list.iter().map(element), samples)
+            }
+        }
+    }
+}
+
+fn compact(len: usize, mut lens: impl Iterator<Item = usize>) -> bool {
+    len <= LISTPACK_MAX_ENTRIES && lens.all(|len| len <= LISTPACK_MAX_VALUE)
+}
+
+fn estimate(len: usize, sizes: impl Iterator<Item = usize>, samples: usize) -> usize {
+    let take = if samples == 0 { len } else { samples.min(len) };
+    if take == 0 {
+        return 0;
+    }
+    let sampled: usize = sizes.take(take).sum();
+    sampled * len / take
+}
+
+fn key_usage(key: &str, value: ValueRef<'_>, samples: usize) -> usize {
+    size_of::<String>() + key.len() + size_of::<KeyMeta>() + value.memory_usage(samples)
+}
+
+impl Db {
+    // runs `f` on the value of `key` where it is stored
+    pub(crate) fn inspect<R>(&self, key: &str, f: impl FnOnce(ValueRef<'_>) -> R) -> Option<R> {
+        if let Some(value) = self.map.get(key) {
+            return Some(f(ValueRef::String(&value)));
+        }
+        if let Some(value) = self.hmap.get(key) {
+            return Some(f(ValueRef::Hash(&value)));
+        }
+        if let Some(value) = self.set.get(key) {
+            return Some(f(ValueRef::Set(&value)));
+        }
+        if let Some(value) = self.zset.get(key) {
+            return Some(f(ValueRef::ZSet(&value)));
+        }
+        self.list.get(key).map(|value| f(ValueRef::List(&value)))
+    }
+
+    pub fn memory_usage(&self, key: &str, samples: usize) -> Option<usize> {
+        self.inspect(key, |value| key_usage(key, value, samples))
+    }
+
+    // the metadata of an existing key, one that was never accessed gets a fresh one
+    pub fn key_meta(&self, key: &str) -> Option<KeyMeta> {
+        let size = self.memory_usage(key, MEMORY_USAGE_SAMPLES)?;
+        let meta = self
+            .meta
+            .entry(key.to_string())
+            .or_insert_with(|| KeyMeta::new(size));
+        Some(*meta)
+    }
+
+    // starts the metadata of `key` over as if it was last accessed `idle` ago, with an access
+    // counter of `freq` now
+    pub(super) fn set_meta(&self, key: &str, idle: Option<Duration>, freq: Option<u8>) {
+        if let Some(mut meta) = self.meta.get_mut(key) {
+            if let Some(idle) = idle {
+                meta.access = now_millis().saturating_sub(idle.as_millis() as u64);
+            }
+            // the counter is kept as of the last access, before the decay of the idle time
+            if let Some(freq) = freq {
+                meta.lfu = freq.saturating_add(lfu_decay(meta.idle()));
+            }
+        }
+    }
+
+    pub(super) fn create_meta(&self, key: &str, value: &Value) {
+        if !self.meta.contains_key(key) {
+            let size = key_usage(key, value.borrow(), MEMORY_USAGE_SAMPLES);
+            self.meta.insert(key.to_string(), KeyMeta::new(size));
+        }
+    }
+
+    // bumps the access time and counter of `keys` after a command used them, a write also
+    // refreshes the size estimate and the keys it removed lose their metadata
+    pub fn touch(&self, keys: &[&String], write: bool) {
+        for key in keys {
+            let size = self.memory_usage(key, MEMORY_USAGE_SAMPLES);
+            let Some(size) = size else {
+                self.meta.remove(key.as_str());
+                continue;
+            };
+            match self.meta.get_mut(key.as_str()) {
+                Some(mut meta) => {
+                    meta.touch();
+                    if write {
+                        meta.size = size;
+                    }
+                }
+                None => drop(self.meta.insert(key.to_string(), KeyMeta::new(size))),
+            }
+        }
+    }
+}
+
+// totals for MEMORY STATS and MEMORY DOCTOR
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub rss: Option<u64>,
+    pub keys: usize,
+    // estimated bytes of all keys and values
+    pub dataset: usize,
+    // keys and estimated bytes of every database holding any
+    pub dbs: Vec<(usize, usize, usize)>,
+    // the estimated bytes of the biggest key
+    pub biggest: usize,
+}
+
+impl Backend {
+    // estimated sizes of the keys of every database, the partitions of the thread-per-core engine
+    // included
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats {
+            rss: crate::utils::resident_memory(),
+            ..Default::default()
+        };
+        let backends = self.with_partitions();
+        for index in 0..self.databases() {
+            let (mut keys, mut bytes) = (0, 0);
+            for backend in &backends {
+                for key in backend.db(index).keys() {
+                    if let Some(meta) = backend.db(index).key_meta(&key) {
+                        keys += 1;
+                        bytes += meta.size;
+                        stats.biggest = stats.biggest.max(meta.size);
+                    }
+                }
+            }
+            if keys > 0 {
+                stats.keys += keys;
+                stats.dataset += bytes;
+                stats.dbs.push((index, keys, bytes));
+            }
+        }
+        stats
+    }
+
+    // how many keys of each type fall into every power of two of estimated size, per database
+    pub fn key_size_histogram(&self) -> Vec<(usize, &'static str, BTreeMap<usize, usize>)> {
+        let backends = self.with_partitions();
+        let mut histogram = Vec::new();
+        for index in 0..self.databases() {
+            let mut types: BTreeMap<&'static str, BTreeMap<usize, usize>> = BTreeMap::new();
+            for backend in &backends {
+                let db = backend.db(index);
+                for key in db.keys() {
+                    let Some(kind) = db.inspect(&key, |value| value.kind()) else {
+                        continue;
+                    };
+                    let Some(meta) = db.key_meta(&key) else {
+                        continue;
+                    };
+                    let bin = meta.size.next_power_of_two();
+                    *types.entry(kind).or_default().entry(bin).or_default() += 1;
+                }
+            }
+            histogram.extend(types.into_iter().map(|(kind, bins)| (index, kind, bins)));
+        }
+        histogram
+    }
+
+    // what MEMORY DOCTOR tells about the memory of the server
+    pub fn memory_doctor(&self) -> String {
+        let stats = self.memory_stats();
+        // like redis, below 5mb there is too little to say anything about
+        if stats.dataset < 5 * 1024 * 1024 {
+            return "Hi Sam, this instance is empty or is using very little memory, my issues \
+                    detector can't be used in these conditions. Please, leave for your mission on \
+                    Earth and fill it with some data. The new Sam and I will be back to our \
+                    programming as soon as I finished rebooting."
+                .to_string();
+        }
+
+        let mut issues = Vec::new();
+        if let Some(rss) = stats.rss {
+            let overhead = rss.saturating_sub(stats.dataset as u64);
+            if overhead > 10 * 1024 * 1024 && rss as f64 > stats.dataset as f64 * 1.4 {
+                issues.push(format!(
+                    " * High process RSS overhead: the process uses {} bytes for an estimated \
+                     dataset of {} bytes, memory is held by the allocator or by buffers rather \
+                     than by keys.",
+                    rss, stats.dataset
+                ));
+            }
+        }
+        if stats.keys > 1 && stats.biggest * 2 > stats.dataset {
+            issues.push(format!(
+                " * Big key: a single key holds {} of the {} bytes of the dataset, look for it \
+                 with MEMORY USAGE and consider splitting it.",
+                stats.biggest, stats.dataset
+            ));
+        }
+
+        if issues.is_empty() {
+            return "Hi Sam, I can't find any memory issue in your instance. I can only account \
+                    for what occurs on this base."
+                .to_string();
+        }
+        format!(
+            "Sam, I detected a few issues in this Redis instance memory implants:\n\n{}\n",
+            issues.join("\n\n")
+        )
+    }
+
+    // takes every value of this backend out and puts it back, like a save and reload would,
+    // which leaves the keys with fresh metadata
+    pub fn debug_reload(&self) {
+        let _guard = self.lock_keyspace();
+        for index in 0..self.databases() {
+            let db = self.db(index);
+            let values: Vec<(String, Value, Option<u64>)> = db
+                .keys()
+                .into_iter()
+                .filter_map(|key| {
+                    let (value, expire_at) = db.take_expiring(&key)?;
+                    Some((key, value, expire_at))
+                })
+                .collect();
+            db.meta.clear();
+            for (key, value, expire_at) in values {
+                db.insert_expiring(key, value, expire_at);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfu_counter() {
+        assert_eq!(lfu_increment(u8::MAX), u8::MAX);
+        // below the initial value every access counts
+        assert_eq!(lfu_increment(LFU_INIT_VAL), LFU_INIT_VAL + 1);
+
+        let mut meta = KeyMeta::new(0);
+        meta.access -= 3 * 60 * 1000;
+        assert_eq!(meta.freq(), LFU_INIT_VAL - 3);
+        meta.touch();
+        assert!(meta.idle() < Duration::from_secs(1));
+        assert_eq!(meta.freq(), LFU_INIT_VAL - 2);
+    }
+
+    #[test]
+    fn test_value_encoding() {
+        let backend = Backend::new();
+        backend.set("int", 12);
+        backend.set("short", "hello");
+        backend.set("long", "x".repeat(45));
+        backend.hset("hash", "field", "value").unwrap();
+        backend
+            .sadd("ints", &["1".to_string(), "2".to_string()])
+            .unwrap();
+        backend.sadd("words", &["a".to_string()]).unwrap();
+        backend
+            .sadd(
+                "big",
+                &(0..200).map(|i| format!("m{}", i)).collect::<Vec<_>>(),
+            )
+            .unwrap();
+
+        let encoding = |key| backend.inspect(key, |value| value.encoding()).unwrap();
+        assert_eq!(encoding("int"), "int");
+        assert_eq!(encoding("short"), "embstr");
+        assert_eq!(encoding("long"), "raw");
+        assert_eq!(encoding("hash"), "listpack");
+        assert_eq!(encoding("ints"), "intset");
+        assert_eq!(encoding("words"), "listpack");
+        assert_eq!(encoding("big"), "hashtable");
+    }
+
+    #[test]
+    fn test_memory_usage_samples() {
+        let backend = Backend::new();
+        let members: Vec<String> = (0..100).map(|i| format!("member:{:03}", i)).collect();
+        backend.sadd("set", &members).unwrap();
+
+        // equally sized members, a few samples estimate the same as all of them
+        let sampled = backend.memory_usage("set", MEMORY_USAGE_SAMPLES).unwrap();
+        assert_eq!(sampled, backend.memory_usage("set", 0).unwrap());
+        assert!(sampled > 100 * "member:000".len());
+        assert_eq!(backend.memory_usage("missing", 0), None);
+    }
+
+    #[test]
+    fn test_touch_and_reload() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        let key = "key".to_string();
+
+        backend.touch(&[&key], false);
+        let meta = backend.key_meta("key").unwrap();
+        assert_eq!(meta.freq(), LFU_INIT_VAL);
+
+        backend.set("key", "x".repeat(100));
+        backend.touch(&[&key], true);
+        let meta = backend.key_meta("key").unwrap();
+        assert!(meta.size() > 100);
+        assert!(meta.freq() > LFU_INIT_VAL);
+
+        backend.debug_reload();
+        assert_eq!(backend.key_meta("key").unwrap().freq(), LFU_INIT_VAL);
+        assert_eq!(backend.get_string("key"), Some("x".repeat(100).into()));
+
+        backend.take("key");
+        backend.touch(&[&key], true);
+        assert!(!backend.meta.contains_key("key"));
+    }
+
+    #[test]
+    fn test_memory_stats_and_histogram() {
+        let backend = Backend::new();
+        assert!(backend
+            .memory_doctor()
+            .starts_with("Hi Sam, this instance is empty"));
+
+        backend.set("a", "1");
+        backend.set("b", "2");
+        let partition = backend.partition();
+        partition.hset("c", "field", "value").unwrap();
+
+        let stats = backend.memory_stats();
+        assert_eq!(stats.keys, 3);
+        assert_eq!(stats.dbs.len(), 1);
+        assert_eq!(stats.dbs[0].1, 3);
+        assert!(stats.dataset > 0);
+
+        let histogram = backend.key_size_histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[0].1, "hash");
+        assert_eq!(histogram[1].1, "string");
+        assert_eq!(histogram[1].2.values().sum::<usize>(), 2);
+
+        backend.set("big", "x".repeat(6 * 1024 * 1024));
+        let doctor = backend.memory_doctor();
+        assert!(doctor.contains("Big key"), "{}", doctor);
+    }
+}
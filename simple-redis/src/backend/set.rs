@@ -0,0 +1,305 @@
+use std::collections::HashSet;
+
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+use super::{Backend, BackendError, LazyFreeCause, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOp {
+    Inter,
+    Union,
+    Diff,
+}
+
+impl Backend {
+    pub fn sadd(&self, key: &str, members: &[String]) -> Result<usize, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "set")?;
+        let mut set = self.set.entry(key.to_string()).or_default();
+
+        Ok(members
+            .iter()
+            .filter(|member| set.insert(member.to_string()))
+            .count())
+    }
+
+    pub fn srem(&self, key: &str, members: &[String]) -> usize {
+        let _guard = self.lock_key(key);
+        let removed = match self.set.get_mut(key) {
+            Some(mut set) => members.iter().filter(|member| set.remove(*member)).count(),
+            None => 0,
+        };
+        self.remove_empty_set(key);
+
+        removed
+    }
+
+    pub fn scard(&self, key: &str) -> usize {
+        self.set.get(key).map_or(0, |set| set.len())
+    }
+
+    pub fn smembers(&self, key: &str) -> Option<Vec<String>> {
+        self.set.get(key).map(|set| set.iter().cloned().collect())
+    }
+
+    pub fn sismember(&self, key: &str, member: &str) -> bool {
+        self.set.get(key).is_some_and(|set| set.contains(member))
+    }
+
+    pub fn smismember(&self, key: &str, members: &[String]) -> Vec<bool> {
+        let set = self.set.get(key);
+        members
+            .iter()
+            .map(|member| set.as_ref().is_some_and(|set| set.contains(member)))
+            .collect()
+    }
+
+    // removes and returns up to `count` random members
+    pub fn spop(&self, key: &str, count: usize) -> Vec<String> {
+        let _guard = self.lock_key(key);
+        let popped = match self.set.get_mut(key) {
+            Some(mut set) => {
+                let popped: Vec<String> = set
+                    .iter()
+                    .cloned()
+                    .choose_multiple(&mut rand::thread_rng(), count);
+                for member in &popped {
+                    set.remove(member);
+                }
+                popped
+            }
+            None => Vec::new(),
+        };
+        self.remove_empty_set(key);
+
+        popped
+    }
+
+    // a positive count returns distinct members, a negative one may repeat them, like redis
+    pub fn srandmember(&self, key: &str, count: i64) -> Vec<String> {
+        let Some(set) = self.set.get(key) else {
+            return Vec::new();
+        };
+        let mut rng = rand::thread_rng();
+
+        if count >= 0 {
+            set.iter()
+                .cloned()
+                .choose_multiple(&mut rng, count as usize)
+        } else {
+            let members: Vec<&String> = set.iter().collect();
+            (0..count.unsigned_abs())
+                .map(|_| members[rng.gen_range(0..members.len())].clone())
+                .collect()
+        }
+    }
+
+    pub fn smove(
+        &self,
+        source: &str,
+        destination: &str,
+        member: &str,
+    ) -> Result<bool, BackendError> {
+        let _guard = self.lock_keys([source, destination]);
+        self.check_kind(source, "set")?;
+        self.check_kind(destination, "set")?;
+        // moving a member onto its own set changes nothing
+        if source == destination {
+            return Ok(self.set.get(source).is_some_and(|set| set.contains(member)));
+        }
+        let removed = self
+            .set
+            .get_mut(source)
+            .is_some_and(|mut set| set.remove(member));
+        if !removed {
+            return Ok(false);
+        }
+
+        self.remove_empty_set(source);
+        self.set
+            .entry(destination.to_string())
+            .or_default()
+            .insert(member.to_string());
+        Ok(true)
+    }
+
+    pub fn set_op(&self, op: SetOp, keys: &[String]) -> HashSet<String> {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str));
+        self.compute_set_op(op, keys)
+    }
+
+    // stores the result in `destination` whatever it held and returns its size, an empty result
+    // deletes it
+    pub fn set_op_store(&self, op: SetOp, destination: &str, keys: &[String]) -> usize {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str).chain([destination]));
+        let result = self.compute_set_op(op, keys);
+        let len = result.len();
+        self.overwrite(destination, "set");
+
+        let old = if result.is_empty() {
+            self.set.remove(destination).map(|(_, old)| old)
+        } else {
+            self.set.insert(destination.to_string(), result)
+        };
+        if let Some(old) = old {
+            self.free_value(Value::Set(old), LazyFreeCause::ServerDel);
+        }
+
+        len
+    }
+
+    // size of the intersection, counting stops at `limit` unless it is 0
+    pub fn sintercard(&self, keys: &[String], limit: usize) -> usize {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str));
+        let Some(smallest) = self.smallest_set(keys) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for member in smallest {
+            if keys.iter().all(|key| self.sismember(key, &member)) {
+                count += 1;
+                if count == limit {
+                    break;
+                }
+            }
+        }
+
+        count
+    }
+
+    // callers hold the locks of the keys, so each set is read on its own without a writer in
+    // between
+    fn compute_set_op(&self, op: SetOp, keys: &[String]) -> HashSet<String> {
+        match op {
+            SetOp::Inter => {
+                let Some(mut result) = self.smallest_set(keys) else {
+                    return HashSet::new();
+                };
+                for key in keys {
+                    if let Some(set) = self.set.get(key) {
+                        result.retain(|member| set.contains(member));
+                    }
+                }
+                result
+            }
+            SetOp::Union => {
+                let mut result = HashSet::new();
+                for key in keys {
+                    if let Some(set) = self.set.get(key) {
+                        result.extend(set.iter().cloned());
+                    }
+                }
+                result
+            }
+            SetOp::Diff => {
+                let Some((first, rest)) = keys.split_first() else {
+                    return HashSet::new();
+                };
+                let Some(mut result) = self.set.get(first).map(|set| set.clone()) else {
+                    return HashSet::new();
+                };
+                for key in rest {
+                    if let Some(set) = self.set.get(key) {
+                        result.retain(|member| !set.contains(member));
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    // a copy of the smallest of the sets, None when one of them is missing
+    fn smallest_set(&self, keys: &[String]) -> Option<HashSet<String>> {
+        let mut smallest: Option<(&String, usize)> = None;
+        for key in keys {
+            let len = self.scard(key);
+            if len == 0 {
+                return None;
+            }
+            if smallest.is_none_or(|(_, min)| len < min) {
+                smallest = Some((key, len));
+            }
+        }
+
+        let (key, _) = smallest?;
+        self.set.get(key).map(|set| set.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(members: &[&str]) -> Vec<String> {
+        members.iter().map(|m| m.to_string()).collect()
+    }
+
+    fn sorted(set: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut members: Vec<String> = set.into_iter().collect();
+        members.sort();
+        members
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let backend = Backend::new();
+        backend.sadd("a", &members(&["1", "2", "3", "4"])).unwrap();
+        backend.sadd("b", &members(&["3", "4", "5"])).unwrap();
+        let keys = members(&["a", "b"]);
+
+        assert_eq!(
+            sorted(backend.set_op(SetOp::Inter, &keys)),
+            members(&["3", "4"])
+        );
+        assert_eq!(
+            sorted(backend.set_op(SetOp::Union, &keys)),
+            members(&["1", "2", "3", "4", "5"])
+        );
+        assert_eq!(
+            sorted(backend.set_op(SetOp::Diff, &keys)),
+            members(&["1", "2"])
+        );
+        assert!(backend
+            .set_op(SetOp::Inter, &members(&["a", "missing"]))
+            .is_empty());
+
+        assert_eq!(backend.sintercard(&keys, 0), 2);
+        assert_eq!(backend.sintercard(&keys, 1), 1);
+
+        assert_eq!(backend.set_op_store(SetOp::Diff, "c", &keys), 2);
+        assert_eq!(backend.scard("c"), 2);
+        assert_eq!(backend.set_op_store(SetOp::Inter, "c", &members(&["x"])), 0);
+        assert!(backend.smembers("c").is_none());
+    }
+
+    #[test]
+    fn test_set_ops_are_consistent_snapshots() {
+        let backend = Backend::new();
+        let keys = members(&["a", "b"]);
+
+        // every writer moves one member from one set to the other, so the union never shrinks
+        backend.sadd("a", &members(&["1", "2", "3", "4"])).unwrap();
+        let writers: Vec<_> = (0..4)
+            .map(|i| {
+                let backend = backend.clone();
+                std::thread::spawn(move || {
+                    for n in 0..500 {
+                        let member = ((i + n) % 4 + 1).to_string();
+                        if !backend.smove("a", "b", &member).unwrap() {
+                            backend.smove("b", "a", &member).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for _ in 0..500 {
+            assert_eq!(backend.set_op(SetOp::Union, &keys).len(), 4);
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+    }
+}
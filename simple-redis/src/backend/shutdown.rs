@@ -0,0 +1,51 @@
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownMode {
+    /// Save only when the server was configured to persist data.
+    Default,
+    Save,
+    NoSave,
+}
+
+// every listener and connection holds a receiver, a request is seen by all of them
+#[derive(Debug)]
+pub struct Shutdown {
+    sender: watch::Sender<Option<ShutdownMode>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let (sender, _) = watch::channel(None);
+        Self { sender }
+    }
+}
+
+impl Shutdown {
+    pub fn trigger(&self, mode: ShutdownMode) {
+        self.sender.send_replace(Some(mode));
+    }
+
+    // only requests made after subscribing are observed, so a backend can outlive a server
+    pub fn subscribe(&self) -> watch::Receiver<Option<ShutdownMode>> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_trigger() {
+        let shutdown = Shutdown::default();
+        shutdown.trigger(ShutdownMode::Save);
+
+        let mut receiver = shutdown.subscribe();
+        assert!(!receiver.has_changed().unwrap());
+
+        shutdown.trigger(ShutdownMode::NoSave);
+        receiver.changed().await.unwrap();
+        assert_eq!(*receiver.borrow(), Some(ShutdownMode::NoSave));
+    }
+}
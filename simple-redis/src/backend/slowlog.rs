@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::unix_time;
+
+const SLOWLOG_ENTRY_MAX_ARGC: usize = 32;
+const SLOWLOG_ENTRY_MAX_STRING: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowLogEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub duration: u64,
+    pub args: Vec<String>,
+    pub client: String,
+}
+
+#[derive(Debug)]
+pub struct SlowLog {
+    entries: Mutex<VecDeque<SlowLogEntry>>,
+    next_id: AtomicU64,
+    log_slower_than: AtomicI64,
+    max_len: AtomicUsize,
+}
+
+impl SlowLog {
+    pub fn new(log_slower_than: i64, max_len: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            next_id: AtomicU64::new(0),
+            log_slower_than: AtomicI64::new(log_slower_than),
+            max_len: AtomicUsize::new(max_len),
+        }
+    }
+
+    pub fn set_log_slower_than(&self, micros: i64) {
+        self.log_slower_than.store(micros, Ordering::Relaxed);
+    }
+
+    pub fn set_max_len(&self, max_len: usize) {
+        self.max_len.store(max_len, Ordering::Relaxed);
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.truncate(max_len);
+    }
+
+    // record the command if it ran longer than the threshold, newest entries first
+    pub fn record(&self, args: &[String], client: Option<SocketAddr>, elapsed: Duration) {
+        let threshold = self.log_slower_than.load(Ordering::Relaxed);
+        let duration = elapsed.as_micros() as u64;
+
+        if threshold < 0 || duration < threshold as u64 {
+            return;
+        }
+
+        let entry = SlowLogEntry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            timestamp: unix_time().as_secs(),
+            duration,
+            args: truncate_args(args),
+            client: client.map(|addr| addr.to_string()).unwrap_or_default(),
+        };
+
+        let max_len = self.max_len.load(Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        entries.push_front(entry);
+        entries.truncate(max_len);
+    }
+
+    pub fn get(&self, count: Option<usize>) -> Vec<SlowLogEntry> {
+        let entries = self.entries.lock().unwrap();
+        let count = count.unwrap_or(entries.len());
+        entries.iter().take(count).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn reset(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+fn truncate_args(args: &[String]) -> Vec<String> {
+    let mut result = Vec::with_capacity(args.len().min(SLOWLOG_ENTRY_MAX_ARGC));
+
+    for (i, arg) in args.iter().enumerate() {
+        if i == SLOWLOG_ENTRY_MAX_ARGC - 1 && args.len() > SLOWLOG_ENTRY_MAX_ARGC {
+            let more = args.len() - SLOWLOG_ENTRY_MAX_ARGC + 1;
+            result.push(format!("... ({} more arguments)", more));
+            break;
+        }
+
+        if arg.len() > SLOWLOG_ENTRY_MAX_STRING {
+            let mut end = SLOWLOG_ENTRY_MAX_STRING;
+            while !arg.is_char_boundary(end) {
+                end -= 1;
+            }
+            let more = arg.len() - end;
+            result.push(format!("{}... ({} more bytes)", &arg[..end], more));
+        } else {
+            result.push(arg.clone());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_slowlog_record_threshold() {
+        let slowlog = SlowLog::new(100, 10);
+
+        slowlog.record(&args(&["get", "key"]), None, Duration::from_micros(50));
+        assert!(slowlog.is_empty());
+
+        slowlog.record(&args(&["get", "key"]), None, Duration::from_micros(150));
+        assert_eq!(slowlog.len(), 1);
+
+        slowlog.set_log_slower_than(-1);
+        slowlog.record(&args(&["get", "key"]), None, Duration::from_secs(1));
+        assert_eq!(slowlog.len(), 1);
+    }
+
+    #[test]
+    fn test_slowlog_max_len_and_order() {
+        let slowlog = SlowLog::new(0, 2);
+
+        for key in ["a", "b", "c"] {
+            slowlog.record(&args(&["get", key]), None, Duration::from_micros(1));
+        }
+
+        let entries = slowlog.get(None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, 2);
+        assert_eq!(entries[0].args, args(&["get", "c"]));
+        assert_eq!(entries[1].id, 1);
+
+        assert_eq!(slowlog.get(Some(1)).len(), 1);
+
+        slowlog.reset();
+        assert!(slowlog.is_empty());
+    }
+
+    #[test]
+    fn test_slowlog_truncate_args() {
+        let long = "x".repeat(200);
+        let result = truncate_args(&[long]);
+        assert_eq!(result[0], format!("{}... (72 more bytes)", "x".repeat(128)));
+
+        let many = (0..40).map(|i| i.to_string()).collect::<Vec<_>>();
+        let result = truncate_args(&many);
+        assert_eq!(result.len(), 32);
+        assert_eq!(result[31], "... (9 more arguments)");
+    }
+}
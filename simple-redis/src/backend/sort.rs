@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use super::{Backend, BackendError};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    // elements are weighted by the key this names, see `sort_lookup`, one without `*` skips
+    // sorting
+    pub by: Option<String>,
+    // offset and count, a negative count takes everything after the offset
+    pub limit: Option<(i64, i64)>,
+    // looked up for every element and returned in its place, `#` is the element itself
+    pub get: Vec<String>,
+    pub desc: bool,
+    // compare as binary strings instead of as numbers
+    pub alpha: bool,
+}
+
+struct Weighted {
+    element: Vec<u8>,
+    score: f64,
+    key: Option<Vec<u8>>,
+}
+
+impl Backend {
+    // the elements of the list, set or sorted set at `key` in order, or what GET looks up for
+    // them; the keys BY and GET read are tracked for the client, the command itself only names
+    // `key`
+    pub fn sort(
+        &self,
+        key: &str,
+        options: &SortOptions,
+    ) -> Result<Vec<Option<Vec<u8>>>, BackendError> {
+        let guard = self.lock_key(key);
+        let mut reads = Vec::new();
+        let sorted = self.sort_locked(key, options, &mut reads)?;
+        drop(guard);
+
+        self.track_reads(&reads.iter().collect::<Vec<_>>());
+        Ok(sorted)
+    }
+
+    // sorts like `sort` and stores the result as a list at `destination` whatever it held, a GET
+    // that finds nothing stores an empty string and an empty result deletes it; the length of
+    // the list
+    pub fn sort_store(
+        &self,
+        key: &str,
+        options: &SortOptions,
+        destination: &str,
+    ) -> Result<usize, BackendError> {
+        let guard = self.lock_keys([key, destination]);
+        let mut reads = Vec::new();
+        let list: VecDeque<Vec<u8>> = self
+            .sort_locked(key, options, &mut reads)?
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+        let len = list.len();
+        self.list_replace(destination, list);
+        drop(guard);
+
+        self.track_reads(&reads.iter().collect::<Vec<_>>());
+        Ok(len)
+    }
+
+    fn sort_locked(
+        &self,
+        key: &str,
+        options: &SortOptions,
+        reads: &mut Vec<String>,
+    ) -> Result<Vec<Option<Vec<u8>>>, BackendError> {
+        let mut elements = self.sort_elements(key)?;
+
+        match &options.by {
+            // a sorted set keeps its own order, redis reverses it for DESC
+            Some(by) if !by.contains('*') => {
+                if options.desc && self.zset.contains_key(key) {
+                    elements.reverse();
+                }
+            }
+            by => {
+                let mut weighted = Vec::with_capacity(elements.len());
+                for element in elements {
+                    let weight = match by {
+                        Some(by) => self.sort_lookup(by, &element, reads),
+                        None => Some(element.clone()),
+                    };
+                    let score = match options.alpha {
+                        true => 0.0,
+                        false => parse_score(weight.as_deref()).ok_or_else(|| {
+                            BackendError::Other(
+                                "One or more scores can't be converted into double".to_string(),
+                            )
+                        })?,
+                    };
+                    weighted.push(Weighted {
+                        element,
+                        score,
+                        key: weight,
+                    });
+                }
+
+                weighted.sort_by(|a, b| {
+                    let order = match options.alpha {
+                        true => a.key.cmp(&b.key),
+                        false => a
+                            .score
+                            .partial_cmp(&b.score)
+                            .unwrap_or(Ordering::Equal)
+                            .then_with(|| a.element.cmp(&b.element)),
+                    };
+                    match options.desc {
+                        true => order.reverse(),
+                        false => order,
+                    }
+                });
+                elements = weighted.into_iter().map(|w| w.element).collect();
+            }
+        }
+
+        let range = limit_range(elements.len(), options.limit);
+        Ok(elements[range]
+            .iter()
+            .flat_map(|element| match options.get.is_empty() {
+                true => vec![Some(element.clone())],
+                false => options
+                    .get
+                    .iter()
+                    .map(|pattern| self.sort_lookup(pattern, element, reads))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    // a list keeps its order
+    fn sort_elements(&self, key: &str) -> Result<Vec<Vec<u8>>, BackendError> {
+        if let Some(list) = self.list.get(key) {
+            return Ok(list.iter().cloned().collect());
+        }
+        if let Some(set) = self.set.get(key) {
+            return Ok(set
+                .iter()
+                .map(|member| member.as_bytes().to_vec())
+                .collect());
+        }
+        if let Some(zset) = self.zset.get(key) {
+            return Ok(zset
+                .iter()
+                .map(|(member, _)| member.as_bytes().to_vec())
+                .collect());
+        }
+        match self.contains_key(key) {
+            true => Err(BackendError::WrongType),
+            false => Ok(Vec::new()),
+        }
+    }
+
+    // the first `*` of the pattern is replaced by the element to name a string key, or a hash
+    // field when followed by `->field`, a missing key or one of another type is None; the key is
+    // added to `reads`
+    fn sort_lookup(
+        &self,
+        pattern: &str,
+        element: &[u8],
+        reads: &mut Vec<String>,
+    ) -> Option<Vec<u8>> {
+        if pattern == "#" {
+            return Some(element.to_vec());
+        }
+        let star = pattern.find('*')?;
+        let (prefix, rest) = (&pattern[..star], &pattern[star + 1..]);
+        let (suffix, field) = match rest.find("->") {
+            Some(arrow) if arrow + 2 < rest.len() => (&rest[..arrow], Some(&rest[arrow + 2..])),
+            _ => (rest, None),
+        };
+
+        let element = String::from_utf8_lossy(element);
+        let key = format!("{}{}{}", prefix, element, suffix);
+        let value = match field {
+            Some(field) => self.hget(&key, field),
+            None => self.get_string(&key),
+        };
+        reads.push(key);
+        value.map(|value| value.as_bytes().into_owned())
+    }
+}
+
+// a missing or empty weight counts as 0, like redis
+fn parse_score(weight: Option<&[u8]>) -> Option<f64> {
+    match weight {
+        None | Some(b"") => Some(0.0),
+        Some(weight) => std::str::from_utf8(weight)
+            .ok()?
+            .parse::<f64>()
+            .ok()
+            .filter(|score| !score.is_nan()),
+    }
+}
+
+fn limit_range(len: usize, limit: Option<(i64, i64)>) -> Range<usize> {
+    let Some((offset, count)) = limit else {
+        return 0..len;
+    };
+    let start = (offset.max(0) as u64).min(len as u64) as usize;
+    let end = match count < 0 {
+        true => len,
+        false => start.saturating_add(count as usize).min(len),
+    };
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(backend: &Backend, key: &str, options: &SortOptions) -> Vec<String> {
+        backend
+            .sort(key, options)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.map_or("nil".to_string(), |v| String::from_utf8(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let backend = Backend::new();
+        backend
+            .sadd("s", &["3".into(), "10".into(), "1".into(), "2".into()])
+            .unwrap();
+
+        let mut options = SortOptions::default();
+        assert_eq!(sorted(&backend, "s", &options), ["1", "2", "3", "10"]);
+
+        options.alpha = true;
+        assert_eq!(sorted(&backend, "s", &options), ["1", "10", "2", "3"]);
+
+        options.alpha = false;
+        options.desc = true;
+        options.limit = Some((1, 2));
+        assert_eq!(sorted(&backend, "s", &options), ["3", "2"]);
+
+        options.limit = Some((3, -1));
+        assert_eq!(sorted(&backend, "s", &options), ["1"]);
+        options.limit = Some((9, 1));
+        assert!(sorted(&backend, "s", &options).is_empty());
+
+        assert!(sorted(&backend, "missing", &SortOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_get() {
+        let backend = Backend::new();
+        backend
+            .sadd("s", &["a".into(), "b".into(), "c".into()])
+            .unwrap();
+        backend.set("w_a", "3");
+        backend.set("w_b", "1");
+        backend.hset("h_a", "name", "A").unwrap();
+        backend.hset("h_c", "name", "C").unwrap();
+
+        let options = SortOptions {
+            by: Some("w_*".into()),
+            get: vec!["#".into(), "h_*->name".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted(&backend, "s", &options),
+            ["c", "C", "b", "nil", "a", "A"]
+        );
+
+        let options = SortOptions {
+            by: Some("h_*->name".into()),
+            alpha: true,
+            ..Default::default()
+        };
+        assert_eq!(sorted(&backend, "s", &options), ["b", "a", "c"]);
+
+        backend.set("w_c", "x");
+        assert_eq!(
+            backend.sort(
+                "s",
+                &SortOptions {
+                    by: Some("w_*".into()),
+                    ..Default::default()
+                }
+            ),
+            Err(BackendError::Other(
+                "One or more scores can't be converted into double".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sort_nosort() {
+        let backend = Backend::new();
+        let mut zset = crate::backend::SortedSet::new();
+        zset.insert("b", 1.0);
+        zset.insert("a", 2.0);
+        zset.insert("c", 3.0);
+        backend.zset_replace("z", zset);
+
+        let mut options = SortOptions {
+            by: Some("nosort".into()),
+            ..Default::default()
+        };
+        assert_eq!(sorted(&backend, "z", &options), ["b", "a", "c"]);
+        options.desc = true;
+        options.limit = Some((0, 2));
+        assert_eq!(sorted(&backend, "z", &options), ["c", "a"]);
+
+        backend.set("str", "1");
+        assert_eq!(
+            backend.sort("str", &SortOptions::default()),
+            Err(BackendError::WrongType)
+        );
+    }
+}
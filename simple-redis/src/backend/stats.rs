@@ -0,0 +1,272 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+use super::{unix_time, Backend};
+
+// upper bounds of the command latency histograms in microseconds
+pub const LATENCY_BUCKETS: [u64; 12] = [
+    10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 100_000, 1_000_000,
+];
+
+#[derive(Debug, Default)]
+pub struct CommandStats {
+    calls: AtomicU64,
+    usec: AtomicU64,
+    // calls per bucket, the ones slower than the last bound only show up in `calls`
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+}
+
+impl CommandStats {
+    fn record(&self, usec: u64) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.usec.fetch_add(usec, Ordering::Relaxed);
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| usec <= *bound) {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn usec(&self) -> u64 {
+        self.usec.load(Ordering::Relaxed)
+    }
+
+    // cumulative counts per upper bound, like a prometheus histogram reports them
+    pub fn buckets(&self) -> Vec<(u64, u64)> {
+        let mut total = 0;
+        LATENCY_BUCKETS
+            .iter()
+            .zip(&self.buckets)
+            .map(|(bound, count)| {
+                total += count.load(Ordering::Relaxed);
+                (*bound, total)
+            })
+            .collect()
+    }
+}
+
+// server wide counters, cheap enough to be updated on every request
+#[derive(Debug)]
+pub struct Stats {
+    commands: DashMap<&'static str, Arc<CommandStats>>,
+    net_input_bytes: AtomicU64,
+    net_output_bytes: AtomicU64,
+    expired_keys: AtomicU64,
+    expired_subkeys: AtomicU64,
+    // there is no maxmemory policy yet, so nothing is evicted
+    evicted_keys: AtomicU64,
+    // connections refused for `maxclients` and clients closed for their output buffer limit
+    rejected_connections: AtomicU64,
+    output_limit_disconnections: AtomicU64,
+    // writes since the last save, unix time in seconds of that save and whether it succeeded
+    dirty: AtomicU64,
+    last_save: AtomicU64,
+    last_save_ok: AtomicBool,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            commands: DashMap::new(),
+            net_input_bytes: AtomicU64::new(0),
+            net_output_bytes: AtomicU64::new(0),
+            expired_keys: AtomicU64::new(0),
+            expired_subkeys: AtomicU64::new(0),
+            evicted_keys: AtomicU64::new(0),
+            rejected_connections: AtomicU64::new(0),
+            output_limit_disconnections: AtomicU64::new(0),
+            dirty: AtomicU64::new(0),
+            // like redis, a server that never saved reports its start
+            last_save: AtomicU64::new(unix_time().as_secs()),
+            last_save_ok: AtomicBool::new(true),
+        }
+    }
+}
+
+impl Stats {
+    pub fn record_command(&self, name: &'static str, elapsed: Duration, write: bool) {
+        let usec = elapsed.as_micros() as u64;
+        match self.commands.get(name) {
+            Some(stats) => stats.record(usec),
+            None => self.commands.entry(name).or_default().record(usec),
+        }
+        if write {
+            self.dirty.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // commands that ran at least once, ordered by name
+    pub fn commands(&self) -> Vec<(&'static str, Arc<CommandStats>)> {
+        let mut commands: Vec<_> = self
+            .commands
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        commands.sort_by_key(|(name, _)| *name);
+        commands
+    }
+
+    pub fn commands_processed(&self) -> u64 {
+        self.commands.iter().map(|entry| entry.calls()).sum()
+    }
+
+    pub fn add_net_input(&self, bytes: usize) {
+        self.net_input_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn add_net_output(&self, bytes: usize) {
+        self.net_output_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn net_input_bytes(&self) -> u64 {
+        self.net_input_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn net_output_bytes(&self) -> u64 {
+        self.net_output_bytes.load(Ordering::Relaxed)
+    }
+
+    // `fields` of a hash expired, `key` when the key went away with them or its own deadline
+    // passed
+    pub fn record_expired(&self, fields: usize, key: bool) {
+        self.expired_subkeys
+            .fetch_add(fields as u64, Ordering::Relaxed);
+        if key {
+            self.expired_keys.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn expired_keys(&self) -> u64 {
+        self.expired_keys.load(Ordering::Relaxed)
+    }
+
+    pub fn expired_subkeys(&self) -> u64 {
+        self.expired_subkeys.load(Ordering::Relaxed)
+    }
+
+    pub fn evicted_keys(&self) -> u64 {
+        self.evicted_keys.load(Ordering::Relaxed)
+    }
+
+    pub fn record_rejected_connection(&self) {
+        self.rejected_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn rejected_connections(&self) -> u64 {
+        self.rejected_connections.load(Ordering::Relaxed)
+    }
+
+    pub fn record_output_limit_disconnection(&self) {
+        self.output_limit_disconnections
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn output_limit_disconnections(&self) -> u64 {
+        self.output_limit_disconnections.load(Ordering::Relaxed)
+    }
+
+    pub fn record_save(&self, ok: bool) {
+        if ok {
+            self.dirty.store(0, Ordering::Relaxed);
+            self.last_save
+                .store(unix_time().as_secs(), Ordering::Relaxed);
+        }
+        self.last_save_ok.store(ok, Ordering::Relaxed);
+    }
+
+    pub fn changes_since_last_save(&self) -> u64 {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    pub fn last_save(&self) -> u64 {
+        self.last_save.load(Ordering::Relaxed)
+    }
+
+    pub fn last_save_ok(&self) -> bool {
+        self.last_save_ok.load(Ordering::Relaxed)
+    }
+}
+
+impl Backend {
+    pub fn stats(&self) -> &Stats {
+        &self.inner.stats
+    }
+
+    // keys per database and value type, counting the ones currently moved to the partitions of
+    // the thread-per-core engine too
+    pub fn key_types(&self) -> Vec<(usize, &'static str, usize)> {
+        let backends = self.with_partitions();
+        let mut types = Vec::new();
+        for index in 0..self.databases() {
+            let mut counts = [("string", 0), ("hash", 0), ("set", 0), ("zset", 0)];
+            for backend in &backends {
+                let db = backend.db(index);
+                counts[0].1 += db.map.len();
+                counts[1].1 += db.hmap.len();
+                counts[2].1 += db.set.len();
+                counts[3].1 += db.zset.len();
+            }
+            types.extend(counts.into_iter().map(|(kind, keys)| (index, kind, keys)));
+        }
+        types
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_record_command() {
+        let stats = Stats::default();
+        stats.record_command("get", Duration::from_micros(5), false);
+        stats.record_command("set", Duration::from_micros(300), true);
+        stats.record_command("set", Duration::from_secs(2), true);
+
+        assert_eq!(stats.commands_processed(), 3);
+        assert_eq!(stats.changes_since_last_save(), 2);
+
+        let commands = stats.commands();
+        assert_eq!(commands.len(), 2);
+        let (name, set) = &commands[1];
+        assert_eq!(*name, "set");
+        assert_eq!(set.calls(), 2);
+        assert_eq!(set.usec(), 2_000_300);
+        let buckets = set.buckets();
+        assert_eq!(buckets[4], (250, 0));
+        assert_eq!(buckets[5], (500, 1));
+        // slower than every bound
+        assert_eq!(buckets.last(), Some(&(1_000_000, 1)));
+
+        stats.record_save(false);
+        assert!(!stats.last_save_ok());
+        assert_eq!(stats.changes_since_last_save(), 2);
+        stats.record_save(true);
+        assert!(stats.last_save_ok());
+        assert_eq!(stats.changes_since_last_save(), 0);
+    }
+
+    #[test]
+    fn test_backend_key_types() {
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.hset("b", "field", "value").unwrap();
+        let partition = backend.partition();
+        partition.set("c", "2");
+
+        let types = backend.key_types();
+        assert_eq!(types.len(), backend.databases() * 4);
+        assert_eq!(&types[..2], &[(0, "string", 2), (0, "hash", 1)]);
+        assert_eq!(types[4], (1, "string", 0));
+
+        drop(partition);
+        assert_eq!(backend.key_types()[0], (0, "string", 1));
+    }
+}
@@ -0,0 +1,478 @@
+use std::borrow::Cow;
+
+use dashmap::mapref::entry::Entry;
+
+use super::{Backend, BackendError};
+use crate::resp::frame::Frame;
+use crate::resp::BulkString;
+
+// same limit as redis' default proto-max-bulk-len
+pub const STRING_MAX_LEN: usize = 512 * 1024 * 1024;
+
+// numeric strings are kept as integers, like redis' int encoding, so INCR does not need to reparse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StringValue {
+    Int(i64),
+    Raw(Vec<u8>),
+}
+
+impl StringValue {
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Self {
+        let bytes = bytes.into();
+
+        match parse_canonical_int(&bytes) {
+            Some(i) => StringValue::Int(i),
+            None => StringValue::Raw(bytes),
+        }
+    }
+
+    pub fn as_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            StringValue::Int(i) => Cow::Owned(i.to_string().into_bytes()),
+            StringValue::Raw(bytes) => Cow::Borrowed(bytes),
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            StringValue::Int(i) => i.to_string().into_bytes(),
+            StringValue::Raw(bytes) => bytes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            StringValue::Int(i) => i.to_string().len(),
+            StringValue::Raw(bytes) => bytes.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            StringValue::Int(_) => "int",
+            StringValue::Raw(_) => "raw",
+        }
+    }
+
+    pub fn to_int(&self) -> Option<i64> {
+        match self {
+            StringValue::Int(i) => Some(*i),
+            StringValue::Raw(bytes) => parse_canonical_int(bytes),
+        }
+    }
+
+    pub fn to_float(&self) -> Option<f64> {
+        match self {
+            StringValue::Int(i) => Some(*i as f64),
+            StringValue::Raw(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .filter(|s| !s.is_empty() && !s.starts_with(char::is_whitespace))
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|f| !f.is_nan()),
+        }
+    }
+
+    // raw bytes for in-place updates such as APPEND and SETRANGE
+    pub fn make_raw(&mut self) -> &mut Vec<u8> {
+        if let StringValue::Int(i) = self {
+            *self = StringValue::Raw(i.to_string().into_bytes());
+        }
+
+        match self {
+            StringValue::Raw(bytes) => bytes,
+            StringValue::Int(_) => unreachable!(),
+        }
+    }
+}
+
+// only the form i64 would print itself as counts, so "007" or "+1" stay raw
+fn parse_canonical_int(bytes: &[u8]) -> Option<i64> {
+    if bytes.is_empty() || bytes.len() > 20 {
+        return None;
+    }
+
+    let s = std::str::from_utf8(bytes).ok()?;
+    let i = s.parse::<i64>().ok()?;
+
+    (i.to_string() == s).then_some(i)
+}
+
+impl From<&str> for StringValue {
+    fn from(s: &str) -> Self {
+        StringValue::new(s.as_bytes())
+    }
+}
+
+impl From<String> for StringValue {
+    fn from(s: String) -> Self {
+        StringValue::new(s.into_bytes())
+    }
+}
+
+impl From<Vec<u8>> for StringValue {
+    fn from(bytes: Vec<u8>) -> Self {
+        StringValue::new(bytes)
+    }
+}
+
+impl From<&[u8]> for StringValue {
+    fn from(bytes: &[u8]) -> Self {
+        StringValue::new(bytes)
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for StringValue {
+    fn from(bytes: &[u8; N]) -> Self {
+        StringValue::new(bytes.as_slice())
+    }
+}
+
+impl From<i64> for StringValue {
+    fn from(i: i64) -> Self {
+        StringValue::Int(i)
+    }
+}
+
+impl From<StringValue> for Frame {
+    fn from(value: StringValue) -> Self {
+        Frame::BulkString(BulkString::new(value.into_bytes()))
+    }
+}
+
+impl Backend {
+    pub fn get_string(&self, key: &str) -> Option<StringValue> {
+        self.map.get(key).map(|v| v.value().clone())
+    }
+
+    pub fn incr_by(&self, key: &str, delta: i64) -> Result<i64, BackendError> {
+        self.update_string(key, |current| {
+            let current = match current {
+                Some(value) => value.to_int().ok_or(BackendError::NotInteger)?,
+                None => 0,
+            };
+            let result = current.checked_add(delta).ok_or(BackendError::Overflow)?;
+
+            Ok(StringValue::Int(result))
+        })
+        .map(|value| value.to_int().unwrap_or_default())
+    }
+
+    pub fn incr_by_float(&self, key: &str, delta: f64) -> Result<StringValue, BackendError> {
+        self.update_string(key, |current| {
+            let current = match current {
+                Some(value) => value.to_float().ok_or(BackendError::NotFloat)?,
+                None => 0.0,
+            };
+            let result = current + delta;
+
+            if !result.is_finite() {
+                return Err(BackendError::NanOrInfinity);
+            }
+
+            // Display prints the shortest form that round trips, so 10.5 + 0.1 is stored as "10.6"
+            Ok(StringValue::new(result.to_string()))
+        })
+    }
+
+    // read-modify-write under the key lock, a failed update leaves the key untouched
+    fn update_string(
+        &self,
+        key: &str,
+        f: impl FnOnce(Option<&StringValue>) -> Result<StringValue, BackendError>,
+    ) -> Result<StringValue, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        match self.map.entry(key.to_string()) {
+            Entry::Occupied(mut entry) => {
+                let value = f(Some(entry.get()))?;
+                entry.insert(value.clone());
+                Ok(value)
+            }
+            Entry::Vacant(entry) => {
+                let value = f(None)?;
+                entry.insert(value.clone());
+                Ok(value)
+            }
+        }
+    }
+
+    pub fn append(&self, key: &str, bytes: &[u8]) -> Result<usize, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        match self.map.entry(key.to_string()) {
+            Entry::Occupied(mut entry) => {
+                let value = entry.get_mut();
+
+                if value.len() + bytes.len() > STRING_MAX_LEN {
+                    return Err(BackendError::StringTooLong);
+                }
+
+                let raw = value.make_raw();
+                raw.extend_from_slice(bytes);
+                Ok(raw.len())
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(StringValue::new(bytes));
+                Ok(bytes.len())
+            }
+        }
+    }
+
+    pub fn strlen(&self, key: &str) -> usize {
+        self.map.get(key).map_or(0, |v| v.len())
+    }
+
+    // negative indexes count from the end, both ends are inclusive and clamped to the string
+    pub fn getrange(&self, key: &str, start: i64, end: i64) -> Vec<u8> {
+        let Some(value) = self.map.get(key) else {
+            return Vec::new();
+        };
+
+        let bytes = value.as_bytes();
+        let len = bytes.len() as i64;
+
+        let start = if start < 0 {
+            (len + start).max(0)
+        } else {
+            start
+        };
+        let end = if end < 0 { len + end } else { end.min(len - 1) };
+
+        if len == 0 || start > end || end < 0 {
+            return Vec::new();
+        }
+
+        bytes[start as usize..=end as usize].to_vec()
+    }
+
+    pub fn setrange(&self, key: &str, offset: i64, bytes: &[u8]) -> Result<usize, BackendError> {
+        if offset < 0 {
+            return Err(BackendError::OffsetOutOfRange);
+        }
+
+        let offset = offset as usize;
+        if !bytes.is_empty() && offset + bytes.len() > STRING_MAX_LEN {
+            return Err(BackendError::StringTooLong);
+        }
+
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        match self.map.entry(key.to_string()) {
+            // an empty value never creates the key
+            Entry::Vacant(_) if bytes.is_empty() => Ok(0),
+            entry => {
+                let mut value = entry.or_insert(StringValue::Raw(Vec::new()));
+                if bytes.is_empty() {
+                    return Ok(value.len());
+                }
+
+                let raw = value.make_raw();
+                if raw.len() < offset + bytes.len() {
+                    raw.resize(offset + bytes.len(), 0);
+                }
+                raw[offset..offset + bytes.len()].copy_from_slice(bytes);
+                Ok(raw.len())
+            }
+        }
+    }
+
+    pub fn mget(&self, keys: &[String]) -> Vec<Option<StringValue>> {
+        let _guard = self.lock_keys(keys.iter().map(String::as_str));
+        keys.iter().map(|key| self.get_string(key)).collect()
+    }
+
+    pub fn mset(&self, pairs: &[(String, StringValue)]) {
+        let _guard = self.lock_keys(pairs.iter().map(|(key, _)| key.as_str()));
+        self.insert_strings(pairs);
+    }
+
+    // sets nothing when any of the keys already exists, whatever its type
+    pub fn msetnx(&self, pairs: &[(String, StringValue)]) -> bool {
+        let _guard = self.lock_keys(pairs.iter().map(|(key, _)| key.as_str()));
+        if pairs.iter().any(|(key, _)| self.contains_key(key)) {
+            return false;
+        }
+
+        self.insert_strings(pairs);
+        true
+    }
+
+    // replaces the keys whatever they held, like SET
+    fn insert_strings(&self, pairs: &[(String, StringValue)]) {
+        for (key, value) in pairs {
+            self.overwrite(key, "string");
+            self.map.insert(key.clone(), value.clone());
+        }
+    }
+
+    pub fn getset(
+        &self,
+        key: &str,
+        value: impl Into<StringValue>,
+    ) -> Result<Option<StringValue>, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        self.set_expire_at(key, None);
+        Ok(self.map.insert(key.to_string(), value.into()))
+    }
+
+    pub fn getdel(&self, key: &str) -> Result<Option<StringValue>, BackendError> {
+        let _guard = self.lock_key(key);
+        self.check_kind(key, "string")?;
+        self.set_expire_at(key, None);
+        Ok(self.map.remove(key).map(|(_, value)| value))
+    }
+
+    // a key of any type counts as existing
+    pub fn setnx(&self, key: &str, value: impl Into<StringValue>) -> bool {
+        let _guard = self.lock_key(key);
+        if self.contains_key(key) {
+            return false;
+        }
+        self.map.insert(key.to_string(), value.into());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_value_encoding() {
+        assert_eq!(StringValue::from("123"), StringValue::Int(123));
+        assert_eq!(StringValue::from("-5"), StringValue::Int(-5));
+        assert_eq!(StringValue::from("007"), StringValue::Raw(b"007".to_vec()));
+        assert_eq!(StringValue::from("+1"), StringValue::Raw(b"+1".to_vec()));
+        assert_eq!(
+            StringValue::from("99999999999999999999"),
+            StringValue::Raw(b"99999999999999999999".to_vec())
+        );
+        assert_eq!(StringValue::from("abc").encoding(), "raw");
+    }
+
+    #[test]
+    fn test_string_value_conversions() {
+        let value = StringValue::Int(42);
+        assert_eq!(value.as_bytes().as_ref(), b"42");
+        assert_eq!(value.len(), 2);
+        assert_eq!(value.to_float(), Some(42.0));
+
+        assert_eq!(StringValue::from("1.5").to_float(), Some(1.5));
+        assert_eq!(StringValue::from(" 1.5").to_float(), None);
+        assert_eq!(StringValue::from("abc").to_int(), None);
+
+        let mut value = StringValue::Int(42);
+        value.make_raw().push(b'!');
+        assert_eq!(value, StringValue::Raw(b"42!".to_vec()));
+
+        let frame: Frame = StringValue::Int(7).into();
+        assert_eq!(frame, b"7".into());
+    }
+
+    #[test]
+    fn test_backend_incr_by() {
+        let backend = Backend::new();
+        assert_eq!(backend.incr_by("counter", 1), Ok(1));
+        assert_eq!(backend.incr_by("counter", -3), Ok(-2));
+        assert_eq!(backend.get_string("counter"), Some(StringValue::Int(-2)));
+
+        backend.set("max", i64::MAX);
+        assert_eq!(backend.incr_by("max", 1), Err(BackendError::Overflow));
+
+        backend.set("text", "abc");
+        assert_eq!(backend.incr_by("text", 1), Err(BackendError::NotInteger));
+        assert_eq!(backend.get_string("text"), Some("abc".into()));
+    }
+
+    #[test]
+    fn test_backend_incr_by_float() {
+        let backend = Backend::new();
+        backend.set("key", "10.50");
+        assert_eq!(backend.incr_by_float("key", 0.1), Ok("10.6".into()));
+        assert_eq!(backend.incr_by_float("key", -5.6), Ok(StringValue::Int(5)));
+        assert_eq!(
+            backend.incr_by_float("key", f64::INFINITY),
+            Err(BackendError::NanOrInfinity)
+        );
+
+        backend.set("text", "abc");
+        assert_eq!(
+            backend.incr_by_float("text", 1.0),
+            Err(BackendError::NotFloat)
+        );
+    }
+
+    #[test]
+    fn test_backend_append_and_strlen() {
+        let backend = Backend::new();
+        assert_eq!(backend.append("key", b"Hello"), Ok(5));
+        assert_eq!(backend.append("key", b" World"), Ok(11));
+        assert_eq!(backend.strlen("key"), 11);
+        assert_eq!(backend.strlen("missing"), 0);
+
+        backend.set("number", 12);
+        assert_eq!(backend.append("number", b"3"), Ok(3));
+        assert_eq!(backend.incr_by("number", 1), Ok(124));
+    }
+
+    #[test]
+    fn test_backend_getrange() {
+        let backend = Backend::new();
+        backend.set("key", "This is a string");
+        assert_eq!(backend.getrange("key", 0, 3), b"This");
+        assert_eq!(backend.getrange("key", -3, -1), b"ing");
+        assert_eq!(backend.getrange("key", 0, -1), b"This is a string");
+        assert_eq!(backend.getrange("key", 10, 100), b"string");
+        assert!(backend.getrange("key", 5, 3).is_empty());
+        assert!(backend.getrange("key", -100, -50).is_empty());
+        assert!(backend.getrange("missing", 0, -1).is_empty());
+    }
+
+    #[test]
+    fn test_backend_setrange() {
+        let backend = Backend::new();
+        backend.set("key", "Hello World");
+        assert_eq!(backend.setrange("key", 6, b"Redis"), Ok(11));
+        assert_eq!(backend.get_string("key"), Some("Hello Redis".into()));
+
+        assert_eq!(backend.setrange("padded", 3, b"ab"), Ok(5));
+        assert_eq!(backend.get_string("padded"), Some(b"\0\0\0ab".into()));
+
+        assert_eq!(backend.setrange("missing", 0, b""), Ok(0));
+        assert_eq!(backend.get_string("missing"), None);
+        assert_eq!(
+            backend.setrange("key", -1, b"x"),
+            Err(BackendError::OffsetOutOfRange)
+        );
+        assert_eq!(
+            backend.setrange("key", STRING_MAX_LEN as i64, b"x"),
+            Err(BackendError::StringTooLong)
+        );
+    }
+
+    #[test]
+    fn test_backend_multi_and_conditional_set() {
+        let backend = Backend::new();
+        backend.mset(&[("a".to_string(), "1".into()), ("b".to_string(), "2".into())]);
+        assert_eq!(
+            backend.mget(&["a".to_string(), "missing".to_string()]),
+            vec![Some(StringValue::Int(1)), None]
+        );
+
+        assert!(!backend.msetnx(&[("b".to_string(), "3".into()), ("c".to_string(), "3".into())]));
+        assert_eq!(backend.get_string("c"), None);
+        assert!(backend.msetnx(&[("c".to_string(), "3".into())]));
+
+        assert!(!backend.setnx("a", "x"));
+        assert!(backend.setnx("d", "x"));
+
+        assert_eq!(backend.getset("d", "y"), Ok(Some("x".into())));
+        assert_eq!(backend.getdel("d"), Ok(Some("y".into())));
+        assert_eq!(backend.getdel("d"), Ok(None));
+    }
+}
@@ -0,0 +1,555 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use super::{Backend, BackendError, ClientInfo};
+use crate::resp::frame::Frame;
+use crate::resp::{Array, Null, Push};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackingOptions {
+    // the client that receives the invalidations instead of the tracking one
+    pub redirect: Option<u64>,
+    // invalidations for every key matching `prefixes`, whether it was read or not
+    pub bcast: bool,
+    pub prefixes: Vec<String>,
+    // only keys read right after CLIENT CACHING YES are tracked
+    pub optin: bool,
+    // keys read right after CLIENT CACHING NO are not tracked
+    pub optout: bool,
+    // no invalidations for keys the client modified itself
+    pub noloop: bool,
+}
+
+// the tracking state of one client
+#[derive(Debug, Default)]
+pub(crate) struct ClientTracking {
+    options: Option<TrackingOptions>,
+    // set by CLIENT CACHING for the next command only
+    caching: Option<bool>,
+}
+
+// which clients have to be told when a key changes, like the invalidation table of redis it holds
+// key names only, whatever database they are in
+#[derive(Debug)]
+pub struct Tracking {
+    keys: Mutex<HashMap<String, HashSet<u64>>>,
+    prefixes: Mutex<BTreeMap<String, HashSet<u64>>>,
+    max_keys: AtomicUsize,
+    // clients with tracking on, the request path skips tracking while there are none
+    clients: AtomicUsize,
+}
+
+impl Tracking {
+    pub fn new(max_keys: usize) -> Self {
+        Self {
+            keys: Mutex::default(),
+            prefixes: Mutex::default(),
+            max_keys: AtomicUsize::new(max_keys),
+            clients: AtomicUsize::new(0),
+        }
+    }
+
+    // zero lifts the cap, keys over it are invalidated right away to make room
+    pub fn set_max_keys(&self, max_keys: usize) {
+        self.max_keys.store(max_keys, Ordering::Relaxed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.clients.load(Ordering::Relaxed) > 0
+    }
+
+    pub fn clients(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+
+    pub fn keys(&self) -> usize {
+        self.keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    pub fn prefixes(&self) -> usize {
+        self.prefixes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    // a client turned tracking off or went away, the keys it read are dropped once invalidated
+    pub(crate) fn forget(&self, id: u64) {
+        self.clients.fetch_sub(1, Ordering::Relaxed);
+        let mut prefixes = self.prefixes.lock().unwrap_or_else(PoisonError::into_inner);
+        prefixes.retain(|_, clients| {
+            clients.remove(&id);
+            !clients.is_empty()
+        });
+    }
+}
+
+impl ClientTracking {
+    pub(crate) fn is_on(&self) -> bool {
+        self.options.is_some()
+    }
+}
+
+impl ClientInfo {
+    fn tracking(&self) -> MutexGuard<'_, ClientTracking> {
+        self.tracking.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn tracking_options(&self) -> Option<TrackingOptions> {
+        self.tracking().options.clone()
+    }
+
+    // the caching decision of CLIENT CACHING only holds for the command right after it
+    pub fn clear_caching(&self) {
+        self.tracking().caching = None;
+    }
+
+    fn tracks_reads(&self) -> bool {
+        let tracking = self.tracking();
+        match &tracking.options {
+            Some(options) if options.bcast => false,
+            Some(options) if options.optin => tracking.caching == Some(true),
+            Some(options) if options.optout => tracking.caching != Some(false),
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
+
+impl Backend {
+    pub fn tracking(&self) -> &Tracking {
+        &self.inner.tracking
+    }
+
+    // CLIENT TRACKING ON, calling it again keeps the mode and adds the prefixes
+    pub fn enable_tracking(&self, options: TrackingOptions) -> Result<(), BackendError> {
+        let Some(client) = self.client() else {
+            return Ok(());
+        };
+        if !options.bcast && !options.prefixes.is_empty() {
+            return Err(tracking_error(
+                "PREFIX option requires BCAST mode to be enabled",
+            ));
+        }
+        if options.optin && options.optout {
+            return Err(tracking_error("You can't use both OPTIN and OPTOUT"));
+        }
+        if options.bcast && (options.optin || options.optout) {
+            return Err(tracking_error(
+                "OPTIN and OPTOUT are not compatible with BCAST",
+            ));
+        }
+        // RESP2 clients could only take invalidations as messages of a Pub/Sub channel, which the
+        // server does not have
+        if let Some(redirect) = options.redirect {
+            let protocol = match redirect == client.id {
+                true => Some(client.protocol()),
+                false => self.clients().get(redirect).map(|target| target.protocol()),
+            };
+            match protocol {
+                None => {
+                    return Err(tracking_error(
+                        "The client ID you want redirect to does not exist",
+                    ))
+                }
+                Some(protocol) if protocol != 3 => {
+                    return Err(tracking_error(
+                        "The client you want to redirect to must use RESP3",
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut tracking = client.tracking();
+        if let Some(current) = &tracking.options {
+            if current.bcast != options.bcast {
+                return Err(tracking_error(
+                    "You can't switch BCAST mode on/off before disabling tracking for this client, \
+                     and then re-enabling it with a different mode.",
+                ));
+            }
+        } else {
+            self.tracking().clients.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if options.bcast {
+            let mut prefixes = self
+                .tracking()
+                .prefixes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            // without a prefix every key is broadcast
+            let wanted = match options.prefixes.is_empty() {
+                true => vec![String::new()],
+                false => options.prefixes.clone(),
+            };
+            for prefix in wanted {
+                prefixes.entry(prefix).or_default().insert(client.id);
+            }
+        }
+
+        let mut options = options;
+        if let Some(current) = tracking.options.take() {
+            options.prefixes.extend(current.prefixes);
+            options.prefixes.sort();
+            options.prefixes.dedup();
+        }
+        tracking.options = Some(options);
+        tracking.caching = None;
+        Ok(())
+    }
+
+    // CLIENT TRACKING OFF, the keys the client read are forgotten once they are invalidated
+    pub fn disable_tracking(&self) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        if client.tracking().options.take().is_some() {
+            self.tracking().forget(client.id);
+        }
+    }
+
+    // CLIENT CACHING YES|NO
+    pub fn set_caching(&self, caching: bool) -> Result<(), BackendError> {
+        let Some(client) = self.client() else {
+            return Ok(());
+        };
+        let mut tracking = client.tracking();
+        match &tracking.options {
+            Some(options) if options.optin && caching => {}
+            Some(options) if options.optout && !caching => {}
+            Some(options) if options.optin || options.optout => {
+                let error = match caching {
+                    true => {
+                        "CLIENT CACHING YES is only valid when tracking is enabled in OPTIN mode."
+                    }
+                    false => {
+                        "CLIENT CACHING NO is only valid when tracking is enabled in OPTOUT mode."
+                    }
+                };
+                return Err(tracking_error(error));
+            }
+            _ => {
+                return Err(tracking_error(
+                    "CLIENT CACHING can be called only when the client is in tracking mode with \
+                     OPTIN or OPTOUT mode enabled",
+                ))
+            }
+        }
+        tracking.caching = Some(caching);
+        Ok(())
+    }
+
+    // remembers that the client of this handle read `keys`
+    pub fn track_reads(&self, keys: &[&String]) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        if keys.is_empty() || !client.tracks_reads() {
+            return;
+        }
+
+        let evicted: Vec<String> = {
+            let mut table = self
+                .tracking()
+                .keys
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for key in keys {
+                table.entry(key.to_string()).or_default().insert(client.id);
+            }
+
+            let max_keys = self.tracking().max_keys.load(Ordering::Relaxed);
+            let over = match max_keys {
+                0 => 0,
+                max_keys => table.len().saturating_sub(max_keys),
+            };
+            table
+                .keys()
+                .filter(|key| !keys.contains(key))
+                .take(over)
+                .cloned()
+                .collect()
+        };
+
+        // like redis, a key dropped from a full table is invalidated so no client keeps a stale copy
+        if !evicted.is_empty() {
+            self.invalidate_by(&evicted.iter().collect::<Vec<_>>(), None);
+        }
+    }
+
+    // tells every client tracking one of `keys` that it changed, the key is no longer tracked
+    // for the clients that read it
+    pub fn invalidate(&self, keys: &[&String]) {
+        self.invalidate_by(keys, self.client().map(|client| client.id));
+    }
+
+    // `by` is the client that changed the keys, none when they expired or were evicted
+    pub(crate) fn invalidate_by(&self, keys: &[&String], by: Option<u64>) {
+        if !self.tracking().is_active() || keys.is_empty() {
+            return;
+        }
+
+        let mut notify: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+        {
+            let mut table = self
+                .tracking()
+                .keys
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for key in keys {
+                for client in table.remove(key.as_str()).unwrap_or_default() {
+                    notify.entry(client).or_default().push(key.to_string());
+                }
+            }
+        }
+        {
+            let prefixes = self
+                .tracking()
+                .prefixes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for (prefix, clients) in prefixes.iter() {
+                for key in keys.iter().filter(|key| key.starts_with(prefix.as_str())) {
+                    for client in clients {
+                        let keys = notify.entry(*client).or_default();
+                        if !keys.contains(key) {
+                            keys.push(key.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        for (client, keys) in notify {
+            self.notify(client, Some(keys), by);
+        }
+    }
+
+    // every key may have changed, like after FLUSHALL
+    pub fn invalidate_all(&self) {
+        if !self.tracking().is_active() {
+            return;
+        }
+        let by = self.client().map(|client| client.id);
+
+        let mut clients: HashSet<u64> = HashSet::new();
+        {
+            let mut table = self
+                .tracking()
+                .keys
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for (_, readers) in table.drain() {
+                clients.extend(readers);
+            }
+        }
+        {
+            let prefixes = self
+                .tracking()
+                .prefixes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for readers in prefixes.values() {
+                clients.extend(readers);
+            }
+        }
+
+        for client in clients {
+            self.notify(client, None, by);
+        }
+    }
+
+    fn notify(&self, id: u64, keys: Option<Vec<String>>, by: Option<u64>) {
+        let Some(client) = self.clients().get(id) else {
+            return;
+        };
+        let Some(options) = client.tracking_options() else {
+            return;
+        };
+        if options.noloop && by == Some(id) {
+            return;
+        }
+
+        let keys = match keys {
+            Some(keys) => Frame::Array(Array::new(
+                keys.iter().map(|key| key.as_bytes().into()).collect(),
+            )),
+            None => Frame::Null(Null),
+        };
+        let target: Arc<ClientInfo> = match options.redirect {
+            Some(redirect) if redirect != id => match self.clients().get(redirect) {
+                Some(target) => target,
+                None => {
+                    if client.protocol() == 3 {
+                        client.push(Frame::Push(Push::new(vec![
+                            b"tracking-redir-broken".into(),
+                            (redirect as i64).into(),
+                        ])));
+                    }
+                    return;
+                }
+            },
+            _ => client,
+        };
+
+        // the target may have switched back to RESP2 with HELLO since
+        if target.protocol() == 3 {
+            target.push(Frame::Push(Push::new(vec![b"invalidate".into(), keys])));
+        }
+    }
+}
+
+fn tracking_error(message: &str) -> BackendError {
+    BackendError::Other(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::ConnectionKind;
+
+    fn connect(backend: &Backend, protocol: u8) -> Backend {
+        let client = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into())
+            .unwrap();
+        client.client().unwrap().set_protocol(protocol);
+        client
+    }
+
+    fn key(key: &str) -> String {
+        key.to_string()
+    }
+
+    #[test]
+    fn test_tracking_default_mode() {
+        let backend = Backend::new();
+        let reader = connect(&backend, 3);
+        let mut pushes = reader.client().unwrap().push_receiver();
+        let writer = connect(&backend, 2);
+
+        reader.enable_tracking(TrackingOptions::default()).unwrap();
+        reader.track_reads(&[&key("a"), &key("b")]);
+        assert_eq!(backend.tracking().keys(), 2);
+
+        writer.invalidate(&[&key("a"), &key("c")]);
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            Frame::Push(Push::new(vec![
+                b"invalidate".into(),
+                vec![b"a".into()].into()
+            ]))
+        );
+        // once invalidated a key has to be read again to be tracked
+        writer.invalidate(&[&key("a")]);
+        assert!(pushes.try_recv().is_err());
+        assert_eq!(backend.tracking().keys(), 1);
+
+        writer.invalidate_all();
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            Frame::Push(Push::new(vec![b"invalidate".into(), Frame::Null(Null)]))
+        );
+
+        reader.disable_tracking();
+        assert!(!backend.tracking().is_active());
+    }
+
+    #[test]
+    fn test_tracking_bcast_noloop_and_redirect() {
+        let backend = Backend::new();
+        let listener = connect(&backend, 3);
+        let mut pushes = listener.client().unwrap().push_receiver();
+        let tracker = connect(&backend, 2);
+
+        let options = TrackingOptions {
+            redirect: Some(listener.client().unwrap().id),
+            bcast: true,
+            prefixes: vec![key("user:")],
+            noloop: true,
+            ..Default::default()
+        };
+        tracker.enable_tracking(options).unwrap();
+        assert_eq!(backend.tracking().prefixes(), 1);
+
+        backend.invalidate(&[&key("user:1"), &key("item:1")]);
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            Frame::Push(Push::new(vec![
+                b"invalidate".into(),
+                vec![b"user:1".into()].into()
+            ]))
+        );
+
+        // its own writes are not sent back
+        tracker.invalidate(&[&key("user:2")]);
+        assert!(pushes.try_recv().is_err());
+
+        tracker.disable_tracking();
+        assert_eq!(backend.tracking().prefixes(), 0);
+
+        let resp2 = connect(&backend, 2);
+        let options = TrackingOptions {
+            redirect: Some(resp2.client().unwrap().id),
+            ..Default::default()
+        };
+        assert_eq!(
+            tracker.enable_tracking(options),
+            Err(tracking_error(
+                "The client you want to redirect to must use RESP3"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_tracking_optin_and_errors() {
+        let backend = Backend::new();
+        let client = connect(&backend, 3);
+
+        assert!(client.set_caching(true).is_err());
+        let options = TrackingOptions {
+            prefixes: vec![key("a")],
+            ..Default::default()
+        };
+        assert!(client.enable_tracking(options).is_err());
+        let options = TrackingOptions {
+            redirect: Some(42),
+            ..Default::default()
+        };
+        assert!(client.enable_tracking(options).is_err());
+
+        let options = TrackingOptions {
+            optin: true,
+            ..Default::default()
+        };
+        client.enable_tracking(options).unwrap();
+        assert!(client.set_caching(false).is_err());
+
+        client.track_reads(&[&key("a")]);
+        assert_eq!(backend.tracking().keys(), 0);
+        client.set_caching(true).unwrap();
+        client.track_reads(&[&key("b")]);
+        client.client().unwrap().clear_caching();
+        client.track_reads(&[&key("c")]);
+        assert_eq!(backend.tracking().keys(), 1);
+    }
+
+    #[test]
+    fn test_tracking_max_keys() {
+        let backend = Backend::new();
+        backend.tracking().set_max_keys(2);
+        let client = connect(&backend, 3);
+        let mut pushes = client.client().unwrap().push_receiver();
+        client.enable_tracking(TrackingOptions::default()).unwrap();
+
+        client.track_reads(&[&key("a"), &key("b")]);
+        client.track_reads(&[&key("c")]);
+        assert_eq!(backend.tracking().keys(), 2);
+        assert!(matches!(pushes.try_recv().unwrap(), Frame::Push(_)));
+    }
+}
@@ -0,0 +1,111 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound;
+
+// f64 with the total order sorted sets need, scores are never NaN
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score(pub f64);
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// members ordered by score then by member, with a map for score lookups by member
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SortedSet {
+    scores: HashMap<String, f64>,
+    ordered: BTreeSet<(Score, String)>,
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    // returns the previous score of `member`
+    pub fn insert(&mut self, member: impl ToString, score: f64) -> Option<f64> {
+        let member = member.to_string();
+        let old = self.scores.insert(member.clone(), score);
+
+        if let Some(old) = old {
+            self.ordered.remove(&(Score(old), member.clone()));
+        }
+        self.ordered.insert((Score(score), member));
+
+        old
+    }
+
+    pub fn remove(&mut self, member: &str) -> Option<f64> {
+        let score = self.scores.remove(member)?;
+        self.ordered.remove(&(Score(score), member.to_string()));
+        Some(score)
+    }
+
+    // members with `min <= score < max`, in score order
+    pub fn range_by_score(&self, min: f64, max: f64) -> impl Iterator<Item = (&str, f64)> {
+        self.ordered
+            .range((
+                Bound::Included((Score(min), String::new())),
+                Bound::Unbounded,
+            ))
+            .take_while(move |(score, _)| score.0 < max)
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.ordered
+            .iter()
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_set() {
+        let mut zset = SortedSet::new();
+
+        assert_eq!(zset.insert("b", 2.0), None);
+        assert_eq!(zset.insert("a", 1.0), None);
+        assert_eq!(zset.insert("c", 2.0), None);
+        assert_eq!(zset.insert("a", 3.0), Some(1.0));
+        assert_eq!(zset.len(), 3);
+
+        assert_eq!(
+            zset.iter().collect::<Vec<_>>(),
+            vec![("b", 2.0), ("c", 2.0), ("a", 3.0)]
+        );
+        assert_eq!(
+            zset.range_by_score(2.0, 3.0).collect::<Vec<_>>(),
+            vec![("b", 2.0), ("c", 2.0)]
+        );
+
+        assert_eq!(zset.remove("b"), Some(2.0));
+        assert_eq!(zset.remove("b"), None);
+        assert_eq!(zset.score("a"), Some(3.0));
+    }
+}
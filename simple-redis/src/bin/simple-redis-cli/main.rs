@@ -0,0 +1,295 @@
+mod render;
+mod split;
+
+use std::io::{IsTerminal, Read};
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{ArgAction, Parser};
+use render::{render, OutputMode};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use simple_redis::client::{Client, ClientError};
+use simple_redis::resp::frame::Frame;
+use simple_redis::resp::{RespDecode, SimpleError};
+use split::split_args;
+
+const HISTORY_FILE: &str = ".simple_rediscli_history";
+const PIPE_BATCH_SIZE: usize = 1000;
+const SCAN_COUNT: &str = "100";
+
+#[derive(Debug, Parser)]
+#[command(name = "simple-redis-cli", version, disable_help_flag = true)]
+struct Opts {
+    #[arg(short = 'h', long, default_value = "127.0.0.1")]
+    host: String,
+
+    #[arg(short = 'p', long, default_value_t = 6379)]
+    port: u16,
+
+    /// Execute the command this many times, a negative value repeats forever
+    #[arg(short = 'r', long, default_value_t = 1, allow_negative_numbers = true)]
+    repeat: i64,
+
+    /// Seconds to wait between repeated commands, fractions are allowed
+    #[arg(short = 'i', long, default_value_t = 0.0)]
+    interval: f64,
+
+    #[arg(long, conflicts_with_all = ["csv", "json"])]
+    raw: bool,
+
+    #[arg(long, conflicts_with = "json")]
+    csv: bool,
+
+    #[arg(long)]
+    json: bool,
+
+    /// Transfer raw RESP commands from stdin to the server
+    #[arg(long, conflicts_with = "scan")]
+    pipe: bool,
+
+    /// List all keys using the SCAN command
+    #[arg(long)]
+    scan: bool,
+
+    #[arg(long, requires = "scan")]
+    pattern: Option<String>,
+
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+impl Opts {
+    fn output_mode(&self) -> OutputMode {
+        if self.raw {
+            OutputMode::Raw
+        } else if self.csv {
+            OutputMode::Csv
+        } else if self.json {
+            OutputMode::Json
+        } else if std::io::stdout().is_terminal() {
+            OutputMode::Human
+        } else {
+            OutputMode::Raw
+        }
+    }
+}
+
+// connects lazily and reconnects on the next command once the server dropped us
+struct Connection {
+    addr: String,
+    client: Option<Client>,
+}
+
+impl Connection {
+    fn new(addr: String) -> Self {
+        Self { addr, client: None }
+    }
+
+    async fn call(&mut self, args: &[Vec<u8>]) -> Result<Frame, ClientError> {
+        let client = match &mut self.client {
+            Some(client) => client,
+            None => self
+                .client
+                .insert(Client::connect(self.addr.as_str()).await?),
+        };
+
+        let command = args.iter().map(|arg| arg.as_slice().into()).collect();
+        let result = client.execute(command).await;
+
+        if client.is_broken() {
+            self.client = None;
+        }
+
+        match result {
+            Err(ClientError::Server { kind, message }) => {
+                let error = format!("{} {}", kind, message);
+                Ok(Frame::SimpleError(SimpleError::new(error.trim_end())))
+            }
+            result => result,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let addr = format!("{}:{}", opts.host, opts.port);
+    let mode = opts.output_mode();
+
+    if opts.pipe {
+        return pipe_mode(&addr).await;
+    }
+
+    let mut connection = Connection::new(addr);
+
+    if opts.scan {
+        return scan_mode(&mut connection, opts.pattern.as_deref(), mode).await;
+    }
+
+    if !opts.command.is_empty() {
+        let args = opts
+            .command
+            .iter()
+            .map(|arg| arg.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        return repeat_command(&mut connection, &args, opts.repeat, opts.interval, mode).await;
+    }
+
+    repl(&mut connection, mode).await
+}
+
+async fn repeat_command(
+    connection: &mut Connection,
+    args: &[Vec<u8>],
+    repeat: i64,
+    interval: f64,
+    mode: OutputMode,
+) -> Result<()> {
+    let interval = Duration::from_secs_f64(interval.max(0.0));
+    let mut count = 0;
+
+    while repeat < 0 || count < repeat {
+        if count > 0 && !interval.is_zero() {
+            tokio::time::sleep(interval).await;
+        }
+
+        let reply = connection.call(args).await?;
+        println!("{}", render(&reply, mode));
+        count += 1;
+    }
+
+    Ok(())
+}
+
+async fn repl(connection: &mut Connection, mode: OutputMode) -> Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    let history = std::env::var("HOME")
+        .map(|home| format!("{}/{}", home, HISTORY_FILE))
+        .ok();
+
+    if let Some(history) = &history {
+        // a missing history file is expected on the first run
+        let _ = editor.load_history(history);
+    }
+
+    let prompt = format!("{}> ", connection.addr);
+
+    loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut args = match split_args(&line) {
+            Ok(args) if args.is_empty() => continue,
+            Ok(args) => args,
+            Err(_) => {
+                println!("Invalid argument(s)");
+                continue;
+            }
+        };
+        editor.add_history_entry(line.as_str())?;
+
+        let name = String::from_utf8_lossy(&args[0]).to_lowercase();
+        if name == "quit" || name == "exit" {
+            break;
+        }
+
+        // like redis-cli, a leading number repeats the command: "3 incr counter"
+        let mut repeat = 1;
+        if args.len() > 1 {
+            if let Ok(n) = name.parse::<i64>() {
+                repeat = n;
+                args.remove(0);
+            }
+        }
+
+        if let Err(e) = repeat_command(connection, &args, repeat, 0.0, mode).await {
+            println!(
+                "Could not connect to simple-redis at {}: {}",
+                connection.addr, e
+            );
+        }
+    }
+
+    if let Some(history) = &history {
+        editor.save_history(history)?;
+    }
+
+    Ok(())
+}
+
+async fn scan_mode(
+    connection: &mut Connection,
+    pattern: Option<&str>,
+    mode: OutputMode,
+) -> Result<()> {
+    let mut cursor = b"0".to_vec();
+
+    loop {
+        let mut args = vec![b"SCAN".to_vec(), cursor.clone()];
+        if let Some(pattern) = pattern {
+            args.extend([b"MATCH".to_vec(), pattern.as_bytes().to_vec()]);
+        }
+        args.extend([b"COUNT".to_vec(), SCAN_COUNT.as_bytes().to_vec()]);
+
+        let (next, keys) = match connection.call(&args).await? {
+            Frame::Array(reply) if reply.len() == 2 => (reply[0].clone(), reply[1].clone()),
+            reply => anyhow::bail!("Unexpected SCAN reply: {}", render(&reply, mode)),
+        };
+
+        if let Frame::Array(keys) = keys {
+            for key in keys.iter() {
+                println!("{}", render(key, mode));
+            }
+        }
+
+        cursor = match next {
+            Frame::BulkString(next) => next.to_vec(),
+            reply => anyhow::bail!("Unexpected SCAN cursor: {}", render(&reply, mode)),
+        };
+
+        if cursor == b"0" {
+            return Ok(());
+        }
+    }
+}
+
+// stdin is expected to hold RESP encoded commands, e.g. generated by a bulk loading script
+async fn pipe_mode(addr: &str) -> Result<()> {
+    let mut input = Vec::new();
+    std::io::stdin().read_to_end(&mut input)?;
+
+    let mut cursor = std::io::Cursor::new(input.as_slice());
+    let mut commands = Vec::new();
+    while (cursor.position() as usize) < input.len() {
+        commands.push(Frame::decode(&mut cursor)?);
+    }
+
+    let mut client = Client::connect(addr).await?;
+    let (mut errors, mut replies) = (0, 0);
+
+    while !commands.is_empty() {
+        let rest = commands.split_off(commands.len().min(PIPE_BATCH_SIZE));
+        let batch = std::mem::replace(&mut commands, rest);
+
+        for reply in client.execute_pipeline(batch).await? {
+            if let Frame::SimpleError(_) | Frame::BulkError(_) = reply {
+                eprintln!("{}", render(&reply, OutputMode::Raw));
+                errors += 1;
+            }
+            replies += 1;
+        }
+    }
+
+    println!("All data transferred. Waiting for the last reply...");
+    println!("Last reply received from server.");
+    println!("errors: {}, replies: {}", errors, replies);
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use serde_json::Value;
+use simple_redis::resp::frame::Frame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    Human,
+    Raw,
+    Csv,
+    Json,
+}
+
+pub fn render(frame: &Frame, mode: OutputMode) -> String {
+    match mode {
+        OutputMode::Human => render_human(frame),
+        OutputMode::Raw => render_raw(frame),
+        OutputMode::Csv => render_csv(frame),
+        OutputMode::Json => render_json(frame).to_string(),
+    }
+}
+
+fn render_human(frame: &Frame) -> String {
+    match frame {
+        Frame::SimpleString(s) => s.to_string(),
+        Frame::SimpleError(e) => format!("(error) {}", e.as_str()),
+        Frame::Integer(i) => format!("(integer) {}", **i),
+        Frame::BulkString(s) => quote(s),
+        Frame::Null(_) => "(nil)".to_string(),
+        Frame::Boolean(b) => format!("({})", **b),
+        Frame::Double(d) => format!("(double) {}", **d),
+        Frame::BigNumber(n) => format!("(big number) {}", n.as_str()),
+        Frame::BulkError(e) => format!("(error) {}", String::from_utf8_lossy(e)),
+        Frame::Array(array) if array.is_empty() => "(empty array)".to_string(),
+        Frame::Array(array) => render_items(array.iter().map(render_human), ")"),
+        Frame::Set(set) if set.is_empty() => "(empty set)".to_string(),
+        Frame::Set(set) => render_items(set.iter().map(render_human), "~"),
+        Frame::Push(push) => render_items(push.iter().map(render_human), ">"),
+        Frame::Map(map) if map.is_empty() => "(empty hash)".to_string(),
+        Frame::Map(map) => render_items(
+            map.iter().map(|(key, value)| {
+                let key = render_human(key);
+                let indent = " ".repeat(key.len() + 4);
+                format!("{} => {}", key, indent_tail(&render_human(value), &indent))
+            }),
+            "#",
+        ),
+    }
+}
+
+// numbered items, nested replies are indented below their number:
+// 1) 1) "a"
+//    2) "b"
+// 2) "c"
+fn render_items(items: impl ExactSizeIterator<Item = String>, marker: &str) -> String {
+    let width = items.len().to_string().len();
+
+    items
+        .enumerate()
+        .map(|(i, item)| {
+            let prefix = format!("{:>width$}{} ", i + 1, marker, width = width);
+            let indent = " ".repeat(prefix.len());
+            format!("{}{}", prefix, indent_tail(&item, &indent))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent_tail(text: &str, indent: &str) -> String {
+    text.replace('\n', &format!("\n{}", indent))
+}
+
+fn render_raw(frame: &Frame) -> String {
+    match frame {
+        Frame::SimpleString(s) => s.to_string(),
+        Frame::SimpleError(e) => e.to_string(),
+        Frame::Integer(i) => i.to_string(),
+        Frame::BulkString(s) => String::from_utf8_lossy(s).into_owned(),
+        Frame::Null(_) => String::new(),
+        Frame::Boolean(b) => if **b { "1" } else { "0" }.to_string(),
+        Frame::Double(d) => d.to_string(),
+        Frame::BigNumber(n) => n.to_string(),
+        Frame::BulkError(e) => String::from_utf8_lossy(e).into_owned(),
+        Frame::Array(array) => join(array.iter().map(render_raw), "\n"),
+        Frame::Set(set) => join(set.iter().map(render_raw), "\n"),
+        Frame::Push(push) => join(push.iter().map(render_raw), "\n"),
+        Frame::Map(map) => join(
+            map.iter()
+                .flat_map(|(key, value)| [render_raw(key), render_raw(value)]),
+            "\n",
+        ),
+    }
+}
+
+fn render_csv(frame: &Frame) -> String {
+    match frame {
+        Frame::SimpleString(s) => quote(s.as_bytes()),
+        Frame::SimpleError(e) => format!("ERROR,{}", quote(e.as_bytes())),
+        Frame::Integer(i) => i.to_string(),
+        Frame::BulkString(s) => quote(s),
+        Frame::Null(_) => "NULL".to_string(),
+        Frame::Boolean(b) => if **b { "true" } else { "false" }.to_string(),
+        Frame::Double(d) => d.to_string(),
+        Frame::BigNumber(n) => n.to_string(),
+        Frame::BulkError(e) => format!("ERROR,{}", quote(e)),
+        Frame::Array(array) => join(array.iter().map(render_csv), ","),
+        Frame::Set(set) => join(set.iter().map(render_csv), ","),
+        Frame::Push(push) => join(push.iter().map(render_csv), ","),
+        Frame::Map(map) => join(
+            map.iter()
+                .flat_map(|(key, value)| [render_csv(key), render_csv(value)]),
+            ",",
+        ),
+    }
+}
+
+fn render_json(frame: &Frame) -> Value {
+    match frame {
+        Frame::SimpleString(s) => Value::String(s.to_string()),
+        Frame::SimpleError(e) => serde_json::json!({ "error": e.as_str() }),
+        Frame::Integer(i) => Value::from(**i),
+        Frame::BulkString(s) => Value::String(String::from_utf8_lossy(s).into_owned()),
+        Frame::Null(_) => Value::Null,
+        Frame::Boolean(b) => Value::Bool(**b),
+        Frame::Double(d) => Value::from(**d),
+        Frame::BigNumber(n) => Value::String(n.to_string()),
+        Frame::BulkError(e) => serde_json::json!({ "error": String::from_utf8_lossy(e) }),
+        Frame::Array(array) => Value::Array(array.iter().map(render_json).collect()),
+        Frame::Set(set) => Value::Array(set.iter().map(render_json).collect()),
+        Frame::Push(push) => Value::Array(push.iter().map(render_json).collect()),
+        Frame::Map(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (render_raw(key), render_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn join(items: impl Iterator<Item = String>, separator: &str) -> String {
+    items.collect::<Vec<_>>().join(separator)
+}
+
+fn quote(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() + 2);
+    result.push('"');
+
+    for &c in bytes {
+        match c {
+            b'\\' => result.push_str("\\\\"),
+            b'"' => result.push_str("\\\""),
+            b'\n' => result.push_str("\\n"),
+            b'\r' => result.push_str("\\r"),
+            b'\t' => result.push_str("\\t"),
+            0x07 => result.push_str("\\a"),
+            0x08 => result.push_str("\\b"),
+            c if c.is_ascii_graphic() || c == b' ' => result.push(c as char),
+            c => result.push_str(&format!("\\x{:02x}", c)),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simple_redis::resp::{Map, Null, Set, SimpleError};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn test_render_human_scalars() {
+        assert_eq!(render_human(&"OK".into()), "OK");
+        assert_eq!(render_human(&1.into()), "(integer) 1");
+        assert_eq!(render_human(&b"value".into()), "\"value\"");
+        assert_eq!(render_human(&Frame::Null(Null)), "(nil)");
+        assert_eq!(render_human(&true.into()), "(true)");
+        assert_eq!(render_human(&1.5.into()), "(double) 1.5");
+        assert_eq!(
+            render_human(&Frame::SimpleError(SimpleError::new("ERR oops"))),
+            "(error) ERR oops"
+        );
+    }
+
+    #[test]
+    fn test_render_human_nested() {
+        let frame: Frame = vec![
+            vec![b"a".into(), b"b".into()].into(),
+            b"c".into(),
+            Vec::<Frame>::new().into(),
+        ]
+        .into();
+
+        assert_eq!(
+            render_human(&frame),
+            "1) 1) \"a\"\n   2) \"b\"\n2) \"c\"\n3) (empty array)"
+        );
+
+        let items: Frame = (1..=10).map(Frame::from).collect::<Vec<_>>().into();
+        let rendered = render_human(&items);
+        assert!(rendered.starts_with(" 1) (integer) 1\n"));
+        assert!(rendered.ends_with("10) (integer) 10"));
+    }
+
+    #[test]
+    fn test_render_human_map_and_set() {
+        let map = Frame::Map(Map::new(BTreeMap::from([(
+            b"key".into(),
+            vec![1.into(), 2.into()].into(),
+        )])));
+        assert_eq!(
+            render_human(&map),
+            "1# \"key\" => 1) (integer) 1\n            2) (integer) 2"
+        );
+
+        let set = Frame::Set(Set::new(BTreeSet::from([b"a".into()])));
+        assert_eq!(render_human(&set), "1~ \"a\"");
+    }
+
+    #[test]
+    fn test_render_other_modes() {
+        let frame: Frame = vec![b"a".into(), 1.into(), Frame::Null(Null)].into();
+
+        assert_eq!(render(&frame, OutputMode::Raw), "a\n1\n");
+        assert_eq!(render(&frame, OutputMode::Csv), "\"a\",1,NULL");
+        assert_eq!(render(&frame, OutputMode::Json), "[\"a\",1,null]");
+    }
+}
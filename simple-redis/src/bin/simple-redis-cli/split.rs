@@ -0,0 +1,126 @@
+use anyhow::Result;
+
+// split a line the way redis-cli does: whitespace separated, "double quotes" support
+// \n \r \t \b \a \\ \" and \xHH escapes, 'single quotes' only support \'
+pub fn split_args(line: &str) -> Result<Vec<Vec<u8>>> {
+    let bytes = line.as_bytes();
+    let mut args = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        if i == bytes.len() {
+            return Ok(args);
+        }
+
+        let mut current = Vec::new();
+        let mut in_double = false;
+        let mut in_single = false;
+
+        loop {
+            if in_double {
+                match bytes.get(i) {
+                    None => anyhow::bail!("unbalanced quotes"),
+                    Some(b'\\')
+                        if bytes.get(i + 1) == Some(&b'x')
+                            && i + 3 < bytes.len()
+                            && bytes[i + 2].is_ascii_hexdigit()
+                            && bytes[i + 3].is_ascii_hexdigit() =>
+                    {
+                        let hex = std::str::from_utf8(&bytes[i + 2..i + 4])?;
+                        current.push(u8::from_str_radix(hex, 16)?);
+                        i += 3;
+                    }
+                    Some(b'\\') if i + 1 < bytes.len() => {
+                        i += 1;
+                        current.push(match bytes[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 0x08,
+                            b'a' => 0x07,
+                            c => c,
+                        });
+                    }
+                    Some(b'"') => {
+                        // the closing quote must be followed by a space or nothing
+                        if bytes.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+                            anyhow::bail!("closing quote must be followed by a space");
+                        }
+                        in_double = false;
+                    }
+                    Some(&c) => current.push(c),
+                }
+            } else if in_single {
+                match bytes.get(i) {
+                    None => anyhow::bail!("unbalanced quotes"),
+                    Some(b'\\') if bytes.get(i + 1) == Some(&b'\'') => {
+                        i += 1;
+                        current.push(b'\'');
+                    }
+                    Some(b'\'') => {
+                        if bytes.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+                            anyhow::bail!("closing quote must be followed by a space");
+                        }
+                        in_single = false;
+                    }
+                    Some(&c) => current.push(c),
+                }
+            } else {
+                match bytes.get(i) {
+                    None => break,
+                    Some(c) if c.is_ascii_whitespace() => break,
+                    Some(b'"') => in_double = true,
+                    Some(b'\'') => in_single = true,
+                    Some(&c) => current.push(c),
+                }
+            }
+
+            i += 1;
+        }
+
+        args.push(current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Vec<String> {
+        split_args(line)
+            .unwrap()
+            .into_iter()
+            .map(|arg| String::from_utf8(arg).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split("set key value"), vec!["set", "key", "value"]);
+        assert_eq!(split("  get   key  "), vec!["get", "key"]);
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn test_split_args_quotes() {
+        assert_eq!(
+            split(r#"set key "hello world""#),
+            vec!["set", "key", "hello world"]
+        );
+        assert_eq!(split(r#"set key "a\"b\n""#), vec!["set", "key", "a\"b\n"]);
+        assert_eq!(split(r#"set key "\x41\x42""#), vec!["set", "key", "AB"]);
+        assert_eq!(split(r"set key 'it\'s'"), vec!["set", "key", "it's"]);
+        assert_eq!(split(r#"set key 'a\nb'"#), vec!["set", "key", "a\\nb"]);
+    }
+
+    #[test]
+    fn test_split_args_invalid() {
+        assert!(split_args(r#"set key "value"#).is_err());
+        assert!(split_args(r#"set key "value"x"#).is_err());
+        assert!(split_args("set key 'value").is_err());
+    }
+}
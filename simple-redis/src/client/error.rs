@@ -0,0 +1,83 @@
+use thiserror::Error;
+
+use crate::resp::frame::Frame;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("{kind} {message}")]
+    Server { kind: String, message: String },
+
+    #[error("Unexpected reply: {0:?}")]
+    UnexpectedReply(Frame),
+
+    #[error("Connection closed")]
+    ConnectionClosed,
+
+    #[error("Timed out waiting for a pooled connection")]
+    PoolTimeout,
+}
+
+impl ClientError {
+    // error replies look like "ERR message" or "WRONGTYPE message", split off the kind
+    pub(crate) fn server(reply: &str) -> Self {
+        let (kind, message) = reply.split_once(' ').unwrap_or((reply, ""));
+
+        ClientError::Server {
+            kind: kind.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    // whether the connection that produced this error can no longer be used
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            ClientError::Io(_) | ClientError::Protocol(_) | ClientError::ConnectionClosed
+        )
+    }
+}
+
+impl From<anyhow::Error> for ClientError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<std::io::Error>() {
+            Ok(e) => ClientError::Io(e),
+            Err(e) => ClientError::Protocol(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_error_server() {
+        match ClientError::server("WRONGTYPE Operation against a key") {
+            ClientError::Server { kind, message } => {
+                assert_eq!(kind, "WRONGTYPE");
+                assert_eq!(message, "Operation against a key");
+            }
+            _ => panic!("Expected Server"),
+        }
+
+        match ClientError::server("ERR") {
+            ClientError::Server { kind, message } => {
+                assert_eq!(kind, "ERR");
+                assert_eq!(message, "");
+            }
+            _ => panic!("Expected Server"),
+        }
+    }
+
+    #[test]
+    fn test_client_error_is_fatal() {
+        assert!(ClientError::ConnectionClosed.is_fatal());
+        assert!(!ClientError::server("ERR oops").is_fatal());
+    }
+}
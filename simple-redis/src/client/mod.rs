@@ -0,0 +1,382 @@
+mod error;
+mod pipeline;
+mod pool;
+mod stream;
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use futures::SinkExt;
+use stream::ClientStream;
+use tokio::net::{TcpStream, ToSocketAddrs, UnixStream};
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use tokio_stream::StreamExt;
+use tokio_util::codec::Framed;
+
+use crate::network::RespFrameCodec;
+use crate::resp::frame::Frame;
+pub use error::ClientError;
+pub use pipeline::Pipeline;
+pub use pool::{Pool, PoolConfig, PooledClient};
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub struct Client {
+    framed: Framed<ClientStream, RespFrameCodec>,
+    broken: bool,
+    // a request was sent and its reply is not read yet, a request future dropped halfway leaves
+    // it set so the reply can't be taken for the one of the next request
+    in_flight: bool,
+    // pushes that arrived while waiting for a reply
+    pushes: VecDeque<Frame>,
+}
+
+impl Client {
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self::new(ClientStream::Tcp(stream)))
+    }
+
+    // `server_name` is checked against the certificate of the server, see `TlsConfig::connector`
+    pub async fn connect_tls(
+        addr: impl ToSocketAddrs,
+        connector: &TlsConnector,
+        server_name: &str,
+    ) -> Result<Self> {
+        let server_name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| ClientError::Protocol(e.to_string()))?;
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        let stream = connector.connect(server_name, stream).await?;
+
+        Ok(Self::new(ClientStream::Tls(Box::new(stream))))
+    }
+
+    pub async fn connect_unix(path: impl AsRef<Path>) -> Result<Self> {
+        let stream = UnixStream::connect(path).await?;
+
+        Ok(Self::new(ClientStream::Unix(stream)))
+    }
+
+    fn new(stream: ClientStream) -> Self {
+        Self {
+            framed: Framed::new(stream, RespFrameCodec),
+            broken: false,
+            in_flight: false,
+            pushes: VecDeque::new(),
+        }
+    }
+
+    // a client is broken once the stream failed or a request was abandoned before its reply was
+    // read, its replies can no longer be matched to requests
+    pub fn is_broken(&self) -> bool {
+        self.broken || self.in_flight
+    }
+
+    pub fn pipeline(&self) -> Pipeline {
+        Pipeline::new()
+    }
+
+    pub async fn execute(&mut self, command: Vec<Frame>) -> Result<Frame> {
+        self.in_flight = true;
+        let result = self.round_trip(command).await;
+        self.check(result).and_then(into_result)
+    }
+
+    async fn round_trip(&mut self, command: Vec<Frame>) -> Result<Frame> {
+        self.framed.send(command.into()).await?;
+        self.read_reply().await
+    }
+
+    pub async fn execute_pipeline(&mut self, commands: Vec<Frame>) -> Result<Vec<Frame>> {
+        self.in_flight = true;
+        let result = self.pipeline_round_trip(commands).await;
+        self.check(result)
+    }
+
+    async fn pipeline_round_trip(&mut self, commands: Vec<Frame>) -> Result<Vec<Frame>> {
+        let len = commands.len();
+
+        for command in commands {
+            self.framed.feed(command).await?;
+        }
+        self.framed.flush().await?;
+
+        let mut replies = Vec::with_capacity(len);
+        for _ in 0..len {
+            replies.push(self.read_reply().await?);
+        }
+
+        Ok(replies)
+    }
+
+    async fn read_reply(&mut self) -> Result<Frame> {
+        loop {
+            match self.read_frame().await? {
+                Frame::Push(push) => self.pushes.push_back(Frame::Push(push)),
+                frame => return Ok(frame),
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<Frame> {
+        match self.framed.next().await {
+            Some(Ok(frame)) => Ok(frame),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(ClientError::ConnectionClosed),
+        }
+    }
+
+    // waits for the next out of band message, like the invalidations of client side caching, a
+    // RESP2 connection that tracking is redirected to gets them as plain arrays
+    pub async fn next_push(&mut self) -> Result<Frame> {
+        if let Some(push) = self.pushes.pop_front() {
+            return Ok(push);
+        }
+        let result = self.read_frame().await;
+        self.check(result)
+    }
+
+    // called once a request ran to completion, whether it succeeded or not
+    fn check<T>(&mut self, result: Result<T>) -> Result<T> {
+        self.in_flight = false;
+        if let Err(e) = &result {
+            self.broken |= e.is_fatal();
+        }
+        result
+    }
+
+    pub async fn echo(&mut self, message: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        let reply = self.execute(cmd("ECHO", [message.as_ref()])).await?;
+        into_bytes(reply).map(Option::unwrap_or_default)
+    }
+
+    pub async fn get(&mut self, key: &str) -> Result<Option<Vec<u8>>> {
+        let reply = self.execute(cmd("GET", [key.as_bytes()])).await?;
+        into_bytes(reply)
+    }
+
+    pub async fn set(&mut self, key: &str, value: impl AsRef<[u8]>) -> Result<()> {
+        let reply = self
+            .execute(cmd("SET", [key.as_bytes(), value.as_ref()]))
+            .await?;
+        into_ok(reply)
+    }
+
+    pub async fn hget(&mut self, key: &str, field: &str) -> Result<Option<Vec<u8>>> {
+        let reply = self
+            .execute(cmd("HGET", [key.as_bytes(), field.as_bytes()]))
+            .await?;
+        into_bytes(reply)
+    }
+
+    pub async fn hset(&mut self, key: &str, field: &str, value: impl AsRef<[u8]>) -> Result<i64> {
+        let reply = self
+            .execute(cmd(
+                "HSET",
+                [key.as_bytes(), field.as_bytes(), value.as_ref()],
+            ))
+            .await?;
+        into_int(reply)
+    }
+
+    pub async fn hmget(&mut self, key: &str, fields: &[&str]) -> Result<Vec<Option<Vec<u8>>>> {
+        let args = std::iter::once(key).chain(fields.iter().copied());
+        let reply = self.execute(cmd("HMGET", args.map(str::as_bytes))).await?;
+        into_array(reply)?.into_iter().map(into_bytes).collect()
+    }
+
+    pub async fn hgetall(&mut self, key: &str) -> Result<HashMap<String, Vec<u8>>> {
+        let reply = self.execute(cmd("HGETALL", [key.as_bytes()])).await?;
+
+        if let Frame::Null(_) = reply {
+            return Ok(HashMap::new());
+        }
+
+        let mut items = into_array(reply)?.into_iter();
+        let mut result = HashMap::with_capacity(items.len() / 2);
+
+        while let (Some(field), Some(value)) = (items.next(), items.next()) {
+            result.insert(into_string(field)?, into_bytes(value)?.unwrap_or_default());
+        }
+
+        Ok(result)
+    }
+
+    pub async fn sadd(&mut self, key: &str, member: &str) -> Result<bool> {
+        let reply = self
+            .execute(cmd("SADD", [key.as_bytes(), member.as_bytes()]))
+            .await?;
+        Ok(into_int(reply)? == 1)
+    }
+
+    pub async fn smembers(&mut self, key: &str) -> Result<Vec<String>> {
+        let reply = self.execute(cmd("SMEMBERS", [key.as_bytes()])).await?;
+        into_array(reply)?.into_iter().map(into_string).collect()
+    }
+
+    pub async fn sismember(&mut self, key: &str, member: &str) -> Result<bool> {
+        let reply = self
+            .execute(cmd("SISMEMBER", [key.as_bytes(), member.as_bytes()]))
+            .await?;
+        Ok(into_int(reply)? == 1)
+    }
+}
+
+pub(crate) fn cmd<'a>(name: &str, args: impl IntoIterator<Item = &'a [u8]>) -> Vec<Frame> {
+    std::iter::once(name.as_bytes().into())
+        .chain(args.into_iter().map(Frame::from))
+        .collect()
+}
+
+// error frames are turned into `ClientError::Server`, everything else is handed back as is
+pub(crate) fn into_result(frame: Frame) -> Result<Frame> {
+    match frame {
+        Frame::SimpleError(e) => Err(ClientError::server(&e.inner)),
+        Frame::BulkError(e) => Err(ClientError::server(&String::from_utf8_lossy(&e.inner))),
+        frame => Ok(frame),
+    }
+}
+
+fn into_ok(frame: Frame) -> Result<()> {
+    match frame {
+        Frame::SimpleString(s) if s.inner == "OK" => Ok(()),
+        Frame::BulkString(s) if s.inner == b"OK" => Ok(()),
+        frame => Err(ClientError::UnexpectedReply(frame)),
+    }
+}
+
+fn into_int(frame: Frame) -> Result<i64> {
+    match frame {
+        Frame::Integer(i) => Ok(i.inner),
+        frame => Err(ClientError::UnexpectedReply(frame)),
+    }
+}
+
+fn into_bytes(frame: Frame) -> Result<Option<Vec<u8>>> {
+    match frame {
+        Frame::Null(_) => Ok(None),
+        Frame::BulkString(s) => Ok(Some(s.inner)),
+        Frame::SimpleString(s) => Ok(Some(s.inner.into_bytes())),
+        Frame::Integer(i) => Ok(Some(i.inner.to_string().into_bytes())),
+        frame => Err(ClientError::UnexpectedReply(frame)),
+    }
+}
+
+fn into_string(frame: Frame) -> Result<String> {
+    match into_bytes(frame)? {
+        Some(bytes) => String::from_utf8(bytes).map_err(|e| ClientError::Protocol(e.to_string())),
+        None => Ok(String::new()),
+    }
+}
+
+fn into_array(frame: Frame) -> Result<Vec<Frame>> {
+    match frame {
+        Frame::Array(array) => Ok(array.inner),
+        frame => Err(ClientError::UnexpectedReply(frame)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::server::Server;
+    use std::net::SocketAddr;
+
+    pub(crate) async fn spawn_server() -> SocketAddr {
+        let server = Server::builder()
+            .bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .start()
+            .await
+            .unwrap();
+        server.local_addr()
+    }
+
+    #[test]
+    fn test_into_result() {
+        let frame = Frame::SimpleError(crate::resp::SimpleError::new("ERR unknown"));
+        assert!(matches!(
+            into_result(frame),
+            Err(ClientError::Server { .. })
+        ));
+        assert_eq!(into_result(1.into()).unwrap(), 1.into());
+    }
+
+    #[tokio::test]
+    async fn test_client_get_set() {
+        let addr = spawn_server().await;
+        let mut client = Client::connect(addr).await.unwrap();
+
+        assert_eq!(client.get("key").await.unwrap(), None);
+        client.set("key", "value").await.unwrap();
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+        assert_eq!(client.echo("hello").await.unwrap(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_client_hash_and_set() {
+        let addr = spawn_server().await;
+        let mut client = Client::connect(addr).await.unwrap();
+
+        assert_eq!(client.hset("hash", "f1", "v1").await.unwrap(), 1);
+        client.hset("hash", "f2", "v2").await.unwrap();
+        assert_eq!(
+            client.hget("hash", "f1").await.unwrap(),
+            Some(b"v1".to_vec())
+        );
+        assert_eq!(
+            client.hmget("hash", &["f1", "nope"]).await.unwrap(),
+            vec![Some(b"v1".to_vec()), None]
+        );
+        assert_eq!(client.hgetall("hash").await.unwrap().len(), 2);
+        assert!(client.hgetall("nope").await.unwrap().is_empty());
+
+        assert!(client.sadd("set", "a").await.unwrap());
+        assert!(!client.sadd("set", "a").await.unwrap());
+        assert!(client.sismember("set", "a").await.unwrap());
+        assert_eq!(client.smembers("set").await.unwrap(), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_client_command_errors() {
+        let addr = spawn_server().await;
+        let mut client = Client::connect(addr).await.unwrap();
+
+        // the server answers commands it does not know and wrong arities with an error
+        match client.execute(cmd("NOPE", [b"a".as_slice()])).await {
+            Err(ClientError::Server { kind, message }) => {
+                assert_eq!(kind, "ERR");
+                assert_eq!(
+                    message,
+                    "unknown command 'NOPE', with args beginning with: 'a' "
+                );
+            }
+            result => panic!("Expected a server error, got {:?}", result),
+        }
+        match client.execute(cmd("GET", [])).await {
+            Err(ClientError::Server { message, .. }) => {
+                assert_eq!(message, "wrong number of arguments for 'get' command");
+            }
+            result => panic!("Expected a server error, got {:?}", result),
+        }
+        assert!(!client.is_broken());
+        assert_eq!(client.get("key").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_client_connection_closed() {
+        // accepts connections and closes them right away
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { while listener.accept().await.is_ok() {} });
+        let mut client = Client::connect(addr).await.unwrap();
+
+        let result = client.get("key").await;
+        assert!(result.unwrap_err().is_fatal());
+        assert!(client.is_broken());
+    }
+}
@@ -0,0 +1,105 @@
+use super::{cmd, into_result, Client, ClientError, Result};
+use crate::resp::frame::Frame;
+
+// commands are buffered and written in one go, then the replies are read back in order
+#[derive(Debug, Default)]
+pub struct Pipeline {
+    commands: Vec<Frame>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn cmd(&mut self, command: Vec<Frame>) -> &mut Self {
+        self.commands.push(command.into());
+        self
+    }
+
+    pub fn get(&mut self, key: &str) -> &mut Self {
+        self.cmd(cmd("GET", [key.as_bytes()]))
+    }
+
+    pub fn set(&mut self, key: &str, value: impl AsRef<[u8]>) -> &mut Self {
+        self.cmd(cmd("SET", [key.as_bytes(), value.as_ref()]))
+    }
+
+    pub fn hset(&mut self, key: &str, field: &str, value: impl AsRef<[u8]>) -> &mut Self {
+        self.cmd(cmd(
+            "HSET",
+            [key.as_bytes(), field.as_bytes(), value.as_ref()],
+        ))
+    }
+
+    pub fn sadd(&mut self, key: &str, member: &str) -> &mut Self {
+        self.cmd(cmd("SADD", [key.as_bytes(), member.as_bytes()]))
+    }
+
+    // the outer error is a connection failure, the inner ones are per command error replies
+    pub async fn execute(&mut self, client: &mut Client) -> Result<Vec<Result<Frame>>> {
+        let commands = std::mem::take(&mut self.commands);
+
+        if commands.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let replies = client.execute_pipeline(commands).await?;
+        Ok(replies.into_iter().map(into_result).collect())
+    }
+
+    // like `execute`, but fails on the first error reply
+    pub async fn query(&mut self, client: &mut Client) -> Result<Vec<Frame>> {
+        self.execute(client)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, ClientError>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::spawn_server;
+
+    #[tokio::test]
+    async fn test_pipeline_execute() {
+        let addr = spawn_server().await;
+        let mut client = Client::connect(addr).await.unwrap();
+
+        let mut pipeline = client.pipeline();
+        pipeline
+            .set("k1", "v1")
+            .set("k2", "v2")
+            .get("k1")
+            .get("k2")
+            .hset("hash", "field", "value")
+            .sadd("set", "member");
+        assert_eq!(pipeline.len(), 6);
+
+        let replies = pipeline.query(&mut client).await.unwrap();
+        assert_eq!(replies.len(), 6);
+        assert_eq!(replies[2], b"v1".into());
+        assert_eq!(replies[3], b"v2".into());
+        assert_eq!(replies[4], 1.into());
+        assert_eq!(replies[5], 1.into());
+        assert!(pipeline.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_execute_empty() {
+        let addr = spawn_server().await;
+        let mut client = Client::connect(addr).await.unwrap();
+
+        let replies = Pipeline::new().execute(&mut client).await.unwrap();
+        assert!(replies.is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+use super::{Client, ClientError, Result};
+
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    pub max_size: usize,
+    /// How long `Pool::get` waits for a free connection before giving up.
+    pub connection_timeout: Duration,
+    /// Idle connections older than this are checked with an ECHO before being handed out.
+    pub health_check_interval: Duration,
+    pub max_retries: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 16,
+            connection_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    addr: String,
+    config: PoolConfig,
+    idle: Mutex<Vec<IdleClient>>,
+    semaphore: Arc<Semaphore>,
+}
+
+#[derive(Debug)]
+struct IdleClient {
+    client: Client,
+    since: Instant,
+}
+
+impl Pool {
+    pub fn new(addr: impl ToString, config: PoolConfig) -> Self {
+        let semaphore = Arc::new(Semaphore::new(config.max_size));
+
+        let inner = PoolInner {
+            addr: addr.to_string(),
+            config,
+            idle: Mutex::new(Vec::new()),
+            semaphore,
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.inner.idle.lock().unwrap().len()
+    }
+
+    pub fn available(&self) -> usize {
+        self.inner.semaphore.available_permits()
+    }
+
+    pub async fn get(&self) -> Result<PooledClient> {
+        let permit = tokio::time::timeout(
+            self.inner.config.connection_timeout,
+            self.inner.semaphore.clone().acquire_owned(),
+        )
+        .await
+        .map_err(|_| ClientError::PoolTimeout)?
+        .expect("pool semaphore is never closed");
+
+        let client = match self.take_idle().await {
+            Some(client) => client,
+            None => self.connect().await?,
+        };
+
+        Ok(PooledClient {
+            client: Some(client),
+            pool: self.inner.clone(),
+            _permit: permit,
+        })
+    }
+
+    async fn take_idle(&self) -> Option<Client> {
+        loop {
+            let idle = self.inner.idle.lock().unwrap().pop()?;
+            let mut client = idle.client;
+
+            if idle.since.elapsed() < self.inner.config.health_check_interval {
+                return Some(client);
+            }
+
+            match client.echo("PING").await {
+                Ok(_) => return Some(client),
+                Err(e) => warn!("Dropping unhealthy pooled connection: {}", e),
+            }
+        }
+    }
+
+    // retry with exponential backoff, the last error is returned once retries run out
+    async fn connect(&self) -> Result<Client> {
+        let config = &self.inner.config;
+        let mut backoff = config.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            match Client::connect(self.inner.addr.as_str()).await {
+                Ok(client) => return Ok(client),
+                Err(e) if attempt < config.max_retries => {
+                    warn!(
+                        "Connecting to {} failed (attempt {}): {}",
+                        self.inner.addr,
+                        attempt + 1,
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(config.max_backoff);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+// hands the connection back to the pool on drop, unless it is broken
+#[derive(Debug)]
+pub struct PooledClient {
+    client: Option<Client>,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Self::Target {
+        self.client.as_ref().expect("client is only taken on drop")
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.client.as_mut().expect("client is only taken on drop")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            if !client.is_broken() {
+                self.pool.idle.lock().unwrap().push(IdleClient {
+                    client,
+                    since: Instant::now(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::spawn_server;
+
+    #[tokio::test]
+    async fn test_pool_reuses_connections() {
+        let addr = spawn_server().await;
+        let pool = Pool::new(addr, PoolConfig::default());
+
+        {
+            let mut client = pool.get().await.unwrap();
+            client.set("key", "value").await.unwrap();
+            assert_eq!(pool.available(), 15);
+        }
+        assert_eq!(pool.idle_count(), 1);
+
+        let mut client = pool.get().await.unwrap();
+        assert_eq!(pool.idle_count(), 0);
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_pool_timeout() {
+        let addr = spawn_server().await;
+        let config = PoolConfig {
+            max_size: 1,
+            connection_timeout: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let pool = Pool::new(addr, config);
+
+        let _client = pool.get().await.unwrap();
+        assert!(matches!(pool.get().await, Err(ClientError::PoolTimeout)));
+    }
+
+    #[tokio::test]
+    async fn test_pool_discards_broken_connections() {
+        // accepts connections and closes them right away
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { while listener.accept().await.is_ok() {} });
+        let pool = Pool::new(addr, PoolConfig::default());
+
+        {
+            let mut client = pool.get().await.unwrap();
+            let _ = client.get("key").await;
+            assert!(client.is_broken());
+        }
+        assert_eq!(pool.idle_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pool_keeps_connections_after_error_replies() {
+        let addr = spawn_server().await;
+        let pool = Pool::new(addr, PoolConfig::default());
+
+        {
+            let mut client = pool.get().await.unwrap();
+            let _ = client.execute(crate::client::cmd("NOPE", [])).await;
+            assert!(!client.is_broken());
+        }
+        assert_eq!(pool.idle_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pool_discards_cancelled_requests() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // replies to every request, but only after a while
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            while stream.read(&mut buf).await.unwrap_or(0) > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                let _ = stream.write_all(b"$5\r\nvalue\r\n").await;
+            }
+        });
+        let pool = Pool::new(addr, PoolConfig::default());
+
+        {
+            let mut client = pool.get().await.unwrap();
+            let get = tokio::time::timeout(Duration::from_millis(10), client.get("key"));
+            assert!(get.await.is_err());
+            assert!(client.is_broken());
+        }
+        // the reply still on its way must not be read by the next borrower
+        assert_eq!(pool.idle_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pool_connect_retries() {
+        let config = PoolConfig {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        };
+        // nothing listens on port 1
+        let pool = Pool::new("127.0.0.1:1", config);
+        assert!(matches!(pool.get().await, Err(ClientError::Io(_))));
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "append")]
+pub struct Append {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) value: Vec<u8>,
+}
+
+impl CommandExecute for Append {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.append(&self.key, &self.value) {
+            Ok(len) => Ok((len as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_append() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["append", "key", "Hello"]), 5.into());
+        assert_eq!(execute(&backend, &["append", "key", " World"]), 11.into());
+        assert_eq!(backend.get("key"), Some(b"Hello World".into()));
+    }
+}
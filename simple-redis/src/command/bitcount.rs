@@ -0,0 +1,91 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_bit_unit, parse_int, CommandExecute};
+use crate::backend::{Backend, BitUnit};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct BitCount {
+    pub(crate) key: String,
+    pub(crate) range: Option<(String, String)>,
+    pub(crate) unit: BitUnit,
+}
+
+impl CommandExecute for BitCount {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let range = match &self.range {
+            Some((start, end)) => match (parse_int(start), parse_int(end)) {
+                (Ok(start), Ok(end)) => Some((start, end, self.unit)),
+                (Err(e), _) | (_, Err(e)) => return Ok(e.into()),
+            },
+            None => None,
+        };
+
+        Ok((backend.bitcount(&self.key, range) as i64).into())
+    }
+}
+
+impl TryFrom<Frame> for BitCount {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "BITCOUNT" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let mut range = None;
+        let mut unit = BitUnit::Byte;
+
+        if parse.len() > 0 {
+            range = Some((parse.next_string()?, parse.next_string()?));
+        }
+        if parse.len() > 0 {
+            unit = parse_bit_unit(&parse.next_string()?)?;
+        }
+        parse.finish()?;
+
+        Ok(Self { key, range, unit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_bitcount() {
+        let backend = Backend::new();
+        backend.set("key", "foobar");
+
+        assert_eq!(execute(&backend, &["bitcount", "key"]), 26.into());
+        assert_eq!(execute(&backend, &["bitcount", "key", "1", "1"]), 6.into());
+        assert_eq!(
+            execute(&backend, &["bitcount", "key", "5", "30", "bit"]),
+            17.into()
+        );
+    }
+
+    #[test]
+    fn test_bitcount_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"bitcount".into(), b"key".into(), b"1".into()].into();
+        let actual: Result<BitCount> = frame.try_into();
+        assert!(actual.is_err());
+
+        let frame: Frame = vec![
+            b"bitcount".into(),
+            b"key".into(),
+            b"1".into(),
+            b"2".into(),
+            b"word".into(),
+        ]
+        .into();
+        let actual: Result<BitCount> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_bit_offset, parse_int, CommandExecute, NULL};
+use crate::backend::{Backend, BackendError, BitfieldOp, BitfieldOverflow, BitfieldType};
+use crate::resp::frame::Frame;
+
+// subcommands as sent, types and numbers are validated while executing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BitfieldArg {
+    Get(String, String),
+    Set(String, String, String),
+    IncrBy(String, String, String),
+    Overflow(String),
+}
+
+// BITFIELD and BITFIELD_RO, the latter only accepts GET
+#[derive(Debug)]
+pub struct Bitfield {
+    pub(crate) key: String,
+    pub(crate) args: Vec<BitfieldArg>,
+    pub(crate) read_only: bool,
+}
+
+impl Bitfield {
+    fn ops(&self) -> Result<Vec<BitfieldOp>, BackendError> {
+        let mut overflow = BitfieldOverflow::default();
+        let mut ops = Vec::with_capacity(self.args.len());
+
+        for arg in &self.args {
+            let op = match arg {
+                BitfieldArg::Get(ty, offset) => {
+                    let (ty, offset) = type_and_offset(ty, offset)?;
+                    BitfieldOp::Get { ty, offset }
+                }
+                BitfieldArg::Set(ty, offset, value) => {
+                    let (ty, offset) = type_and_offset(ty, offset)?;
+                    let value = parse_int(value)?;
+                    BitfieldOp::Set {
+                        ty,
+                        offset,
+                        value,
+                        overflow,
+                    }
+                }
+                BitfieldArg::IncrBy(ty, offset, increment) => {
+                    let (ty, offset) = type_and_offset(ty, offset)?;
+                    let increment = parse_int(increment)?;
+                    BitfieldOp::IncrBy {
+                        ty,
+                        offset,
+                        increment,
+                        overflow,
+                    }
+                }
+                BitfieldArg::Overflow(kind) => {
+                    overflow = match kind.to_uppercase().as_str() {
+                        "WRAP" => BitfieldOverflow::Wrap,
+                        "SAT" => BitfieldOverflow::Sat,
+                        "FAIL" => BitfieldOverflow::Fail,
+                        _ => {
+                            return Err(BackendError::Other(
+                                "Invalid OVERFLOW type specified".to_string(),
+                            ))
+                        }
+                    };
+                    continue;
+                }
+            };
+
+            if self.read_only && !op.is_read_only() {
+                return Err(BackendError::Other(
+                    "BITFIELD_RO only supports the GET subcommand".to_string(),
+                ));
+            }
+            ops.push(op);
+        }
+
+        Ok(ops)
+    }
+}
+
+// "#2" addresses the third field of the given width instead of a bit offset
+fn type_and_offset(ty: &str, offset: &str) -> Result<(BitfieldType, u64), BackendError> {
+    let ty = BitfieldType::parse(ty)?;
+    let offset = match offset.strip_prefix('#') {
+        Some(index) => parse_bit_offset(index)?
+            .checked_mul(ty.bits as u64)
+            .ok_or(BackendError::BitOffset)?,
+        None => parse_bit_offset(offset)?,
+    };
+
+    Ok((ty, offset))
+}
+
+impl CommandExecute for Bitfield {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = self.ops().and_then(|ops| backend.bitfield(&self.key, &ops));
+
+        match result {
+            Ok(replies) => Ok(replies
+                .into_iter()
+                .map(|reply| reply.map_or_else(|| NULL.clone(), Into::into))
+                .collect::<Vec<Frame>>()
+                .into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for Bitfield {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let read_only = match command.as_str() {
+            "BITFIELD" => false,
+            "BITFIELD_RO" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let mut args = Vec::new();
+
+        while parse.len() > 0 {
+            let arg = match parse.next_string()?.to_uppercase().as_str() {
+                "GET" => BitfieldArg::Get(parse.next_string()?, parse.next_string()?),
+                "SET" => BitfieldArg::Set(
+                    parse.next_string()?,
+                    parse.next_string()?,
+                    parse.next_string()?,
+                ),
+                "INCRBY" => BitfieldArg::IncrBy(
+                    parse.next_string()?,
+                    parse.next_string()?,
+                    parse.next_string()?,
+                ),
+                "OVERFLOW" => BitfieldArg::Overflow(parse.next_string()?),
+                _ => anyhow::bail!("syntax error"),
+            };
+            args.push(arg);
+        }
+
+        Ok(Self {
+            key,
+            args,
+            read_only,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_bitfield() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &["bitfield", "key", "incrby", "i5", "100", "1", "get", "u4", "0"]
+            ),
+            vec![1.into(), 0.into()].into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &["bitfield", "key", "set", "u8", "#1", "255", "get", "u8", "8"]
+            ),
+            vec![0.into(), 255.into()].into()
+        );
+    }
+
+    #[test]
+    fn test_bitfield_overflow() {
+        let backend = Backend::new();
+        let args = [
+            "bitfield", "key", "incrby", "u2", "102", "1", "overflow", "sat", "incrby", "i2",
+            "100", "1",
+        ];
+
+        assert_eq!(execute(&backend, &args), vec![1.into(), 1.into()].into());
+        assert_eq!(execute(&backend, &args), vec![2.into(), 1.into()].into());
+        assert_eq!(execute(&backend, &args), vec![3.into(), 1.into()].into());
+        assert_eq!(execute(&backend, &args), vec![0.into(), 1.into()].into());
+
+        assert_eq!(
+            execute(
+                &backend,
+                &["bitfield", "key", "overflow", "fail", "incrby", "u2", "102", "4"]
+            ),
+            vec![NULL.clone()].into()
+        );
+    }
+
+    #[test]
+    fn test_bitfield_ro() {
+        let backend = Backend::new();
+        backend.set("key", b"\xff");
+
+        assert_eq!(
+            execute(&backend, &["bitfield_ro", "key", "get", "i8", "0"]),
+            vec![(-1).into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["bitfield_ro", "key", "set", "u8", "0", "1"]),
+            BackendError::Other("BITFIELD_RO only supports the GET subcommand".to_string()).into()
+        );
+        assert_eq!(
+            execute(&backend, &["bitfield", "key", "get", "u64", "0"]),
+            BackendError::BitfieldType.into()
+        );
+    }
+}
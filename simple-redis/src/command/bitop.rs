@@ -0,0 +1,92 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::{Backend, BackendError, BitOp as Operation};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct BitOp {
+    pub(crate) operation: Operation,
+    pub(crate) dest: String,
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for BitOp {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        if self.operation == Operation::Not && self.keys.len() != 1 {
+            return Ok(BackendError::Other(
+                "BITOP NOT must be called with a single source key.".to_string(),
+            )
+            .into());
+        }
+
+        let len = backend.bitop(self.operation, &self.dest, &self.keys);
+        Ok((len as i64).into())
+    }
+}
+
+impl TryFrom<Frame> for BitOp {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "BITOP" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let operation = match parse.next_string()?.to_uppercase().as_str() {
+            "AND" => Operation::And,
+            "OR" => Operation::Or,
+            "XOR" => Operation::Xor,
+            "NOT" => Operation::Not,
+            _ => anyhow::bail!("syntax error"),
+        };
+        let dest = parse.next_string()?;
+
+        let mut keys = vec![parse.next_string()?];
+        while parse.len() > 0 {
+            keys.push(parse.next_string()?);
+        }
+
+        Ok(Self {
+            operation,
+            dest,
+            keys,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_bitop() {
+        let backend = Backend::new();
+        backend.set("a", "foobar");
+        backend.set("b", "abcdef");
+
+        assert_eq!(
+            execute(&backend, &["bitop", "and", "dest", "a", "b"]),
+            6.into()
+        );
+        assert_eq!(backend.get("dest"), Some(b"`bc`ab".into()));
+        assert_eq!(
+            execute(&backend, &["bitop", "not", "dest", "a", "b"]),
+            BackendError::Other("BITOP NOT must be called with a single source key.".to_string())
+                .into()
+        );
+    }
+
+    #[test]
+    fn test_bitop_try_from_frame_invalid_parts() {
+        let frame: Frame =
+            vec![b"bitop".into(), b"nand".into(), b"dest".into(), b"a".into()].into();
+        let actual: Result<BitOp> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_bit_unit, parse_int, CommandExecute};
+use crate::backend::{Backend, BackendError, BitUnit};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct BitPos {
+    pub(crate) key: String,
+    pub(crate) bit: String,
+    pub(crate) start: Option<String>,
+    pub(crate) end: Option<String>,
+    pub(crate) unit: BitUnit,
+}
+
+impl BitPos {
+    fn args(&self) -> Result<(bool, i64, Option<i64>), BackendError> {
+        let bit = match self.bit.as_str() {
+            "0" => false,
+            "1" => true,
+            _ => {
+                return Err(BackendError::Other(
+                    "The bit argument must be 1 or 0.".to_string(),
+                ))
+            }
+        };
+        let start = self.start.as_deref().map_or(Ok(0), parse_int)?;
+        let end = self.end.as_deref().map(parse_int).transpose()?;
+
+        Ok((bit, start, end))
+    }
+}
+
+impl CommandExecute for BitPos {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match self.args() {
+            Ok((bit, start, end)) => {
+                Ok(backend.bitpos(&self.key, bit, start, end, self.unit).into())
+            }
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for BitPos {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "BITPOS" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let bit = parse.next_string()?;
+        let mut bitpos = Self {
+            key,
+            bit,
+            start: None,
+            end: None,
+            unit: BitUnit::Byte,
+        };
+
+        if parse.len() > 0 {
+            bitpos.start = Some(parse.next_string()?);
+        }
+        if parse.len() > 0 {
+            bitpos.end = Some(parse.next_string()?);
+        }
+        if parse.len() > 0 {
+            bitpos.unit = parse_bit_unit(&parse.next_string()?)?;
+        }
+        parse.finish()?;
+
+        Ok(bitpos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_bitpos() {
+        let backend = Backend::new();
+        backend.set("key", b"\xff\xf0\x00");
+
+        assert_eq!(execute(&backend, &["bitpos", "key", "0"]), 12.into());
+        assert_eq!(execute(&backend, &["bitpos", "key", "1", "2"]), (-1).into());
+        assert_eq!(
+            execute(&backend, &["bitpos", "key", "1", "7", "15", "bit"]),
+            7.into()
+        );
+        assert_eq!(
+            execute(&backend, &["bitpos", "key", "2"]),
+            BackendError::Other("The bit argument must be 1 or 0.".to_string()).into()
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, NULL, OK};
+use crate::backend::{Backend, BackendError, TrackingOptions};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ClientSubcommand {
+    Id,
+    GetName,
+    SetName(String),
+    List,
+    Info,
+    // none turns tracking off
+    Tracking(Option<TrackingOptions>),
+    Caching(bool),
+    GetRedirect,
+}
+
+#[derive(Debug)]
+pub struct Client {
+    pub(crate) subcommand: ClientSubcommand,
+}
+
+impl CommandExecute for Client {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        if let ClientSubcommand::List = self.subcommand {
+            let list: String = backend
+                .clients()
+                .list()
+                .iter()
+                .map(|client| client.describe() + "\n")
+                .collect();
+            return Ok(list.as_bytes().into());
+        }
+
+        // the rest is about the connection sending it, a backend used directly has none
+        let Some(client) = backend.client() else {
+            return Ok(NULL.clone());
+        };
+
+        match &self.subcommand {
+            ClientSubcommand::Id => Ok((client.id as i64).into()),
+            ClientSubcommand::GetName => match client.name() {
+                name if name.is_empty() => Ok(NULL.clone()),
+                name => Ok(name.as_bytes().into()),
+            },
+            ClientSubcommand::SetName(name) => {
+                if name.chars().any(|c| !c.is_ascii_graphic()) {
+                    return Ok(BackendError::Other(
+                        "Client names cannot contain spaces, newlines or special characters."
+                            .to_string(),
+                    )
+                    .into());
+                }
+                client.set_name(name.as_str());
+                Ok(OK.clone())
+            }
+            ClientSubcommand::Info => Ok((client.describe() + "\n").as_bytes().into()),
+            ClientSubcommand::Tracking(Some(options)) => {
+                match backend.enable_tracking(options.clone()) {
+                    Ok(()) => Ok(OK.clone()),
+                    Err(e) => Ok(e.into()),
+                }
+            }
+            ClientSubcommand::Tracking(None) => {
+                backend.disable_tracking();
+                Ok(OK.clone())
+            }
+            ClientSubcommand::Caching(caching) => match backend.set_caching(*caching) {
+                Ok(()) => Ok(OK.clone()),
+                Err(e) => Ok(e.into()),
+            },
+            // -1 when tracking is off, 0 when it is not redirected
+            ClientSubcommand::GetRedirect => {
+                let redirect = match client.tracking_options() {
+                    Some(options) => options.redirect.map_or(0, |id| id as i64),
+                    None => -1,
+                };
+                Ok(redirect.into())
+            }
+            ClientSubcommand::List => unreachable!(),
+        }
+    }
+}
+
+impl TryFrom<Frame> for Client {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "CLIENT" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let subcommand = match parse.next_string()?.to_uppercase().as_str() {
+            "ID" => ClientSubcommand::Id,
+            "GETNAME" => ClientSubcommand::GetName,
+            "SETNAME" => ClientSubcommand::SetName(parse.next_string()?),
+            "LIST" => ClientSubcommand::List,
+            "INFO" => ClientSubcommand::Info,
+            "TRACKING" => ClientSubcommand::Tracking(parse_tracking(&mut parse)?),
+            "CACHING" => match parse.next_string()?.to_uppercase().as_str() {
+                "YES" => ClientSubcommand::Caching(true),
+                "NO" => ClientSubcommand::Caching(false),
+                _ => anyhow::bail!("syntax error"),
+            },
+            "GETREDIRECT" => ClientSubcommand::GetRedirect,
+            subcommand => anyhow::bail!("Unknown CLIENT subcommand '{}'", subcommand),
+        };
+        parse.finish()?;
+
+        Ok(Self { subcommand })
+    }
+}
+
+// ON|OFF [REDIRECT client-id] [PREFIX prefix ...] [BCAST] [OPTIN] [OPTOUT] [NOLOOP]
+fn parse_tracking(parse: &mut Parse) -> Result<Option<TrackingOptions>> {
+    let on = match parse.next_string()?.to_uppercase().as_str() {
+        "ON" => true,
+        "OFF" => false,
+        _ => anyhow::bail!("syntax error"),
+    };
+
+    let mut options = TrackingOptions::default();
+    while parse.len() > 0 {
+        match parse.next_string()?.to_uppercase().as_str() {
+            "REDIRECT" => options.redirect = Some(parse.next_int()?.try_into()?),
+            "PREFIX" => options.prefixes.push(parse.next_string()?),
+            "BCAST" => options.bcast = true,
+            "OPTIN" => options.optin = true,
+            "OPTOUT" => options.optout = true,
+            "NOLOOP" => options.noloop = true,
+            option => anyhow::bail!("Unknown CLIENT TRACKING option '{}'", option),
+        }
+    }
+
+    Ok(on.then_some(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::ConnectionKind;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_client_subcommands() {
+        let backend = Backend::new();
+        let client = backend
+            .connect(
+                ConnectionKind::Tcp,
+                "127.0.0.1:5000".into(),
+                "127.0.0.1:6379".into(),
+            )
+            .unwrap();
+        backend
+            .connect(
+                ConnectionKind::Unix,
+                "/tmp/redis.sock:0".into(),
+                "/tmp/redis.sock:0".into(),
+            )
+            .unwrap();
+
+        assert_eq!(execute(&client, &["client", "id"]), Frame::from(1i64));
+        assert_eq!(execute(&client, &["client", "getname"]), *NULL);
+        assert_eq!(execute(&client, &["client", "setname", "worker"]), *OK);
+        assert_eq!(
+            execute(&client, &["client", "getname"]),
+            Frame::from(b"worker")
+        );
+        assert!(matches!(
+            execute(&client, &["client", "setname", "a b"]),
+            Frame::SimpleError(_)
+        ));
+
+        let list = match execute(&client, &["client", "list"]) {
+            Frame::BulkString(list) => String::from_utf8(list.inner).unwrap(),
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        };
+        let lines: Vec<&str> = list.lines().collect();
+        // the second connection was already dropped
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0]
+            .starts_with("id=1 addr=127.0.0.1:5000 laddr=127.0.0.1:6379 conn=tcp name=worker"));
+
+        assert_eq!(execute(&backend, &["client", "id"]), *NULL);
+        let frame: Frame = vec![b"client".into(), b"kill".into()].into();
+        assert!(Client::try_from(frame).is_err());
+    }
+
+    #[test]
+    fn test_client_tracking() {
+        let backend = Backend::new();
+        let client = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:5000".into(), "".into())
+            .unwrap();
+
+        assert_eq!(
+            execute(&client, &["client", "getredirect"]),
+            Frame::from(-1i64)
+        );
+        assert!(matches!(
+            execute(&client, &["client", "tracking", "on", "prefix", "user:"]),
+            Frame::SimpleError(_)
+        ));
+        assert_eq!(
+            execute(
+                &client,
+                &["client", "tracking", "on", "bcast", "prefix", "user:", "noloop"]
+            ),
+            *OK
+        );
+        assert_eq!(
+            execute(&client, &["client", "getredirect"]),
+            Frame::from(0i64)
+        );
+        assert!(client.client().unwrap().describe().contains(" flags=t "));
+        assert!(matches!(
+            execute(&client, &["client", "caching", "yes"]),
+            Frame::SimpleError(_)
+        ));
+
+        assert_eq!(execute(&client, &["client", "tracking", "off"]), *OK);
+        assert_eq!(
+            execute(&client, &["client", "tracking", "on", "optin"]),
+            *OK
+        );
+        assert_eq!(execute(&client, &["client", "caching", "yes"]), *OK);
+
+        let frame: Frame = vec![b"client".into(), b"tracking".into(), b"maybe".into()].into();
+        assert!(Client::try_from(frame).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::table::{lookup, CommandFlag, CommandSpec, KeySpec, COMMAND_TABLE};
+use super::{CommandExecute, NULL};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+// COMMAND and its subcommands, all answered from the command table
+#[derive(Debug, PartialEq, Eq)]
+pub enum Commands {
+    All,
+    Count,
+    Info(Vec<String>),
+    Docs(Vec<String>),
+    GetKeys(Vec<String>),
+}
+
+impl CommandExecute for Commands {
+    fn execute(&self, _backend: Backend) -> Result<Frame> {
+        let reply = match self {
+            Commands::All => COMMAND_TABLE
+                .iter()
+                .map(info_frame)
+                .collect::<Vec<_>>()
+                .into(),
+            Commands::Count => (COMMAND_TABLE.len() as i64).into(),
+            Commands::Info(names) if names.is_empty() => COMMAND_TABLE
+                .iter()
+                .map(info_frame)
+                .collect::<Vec<_>>()
+                .into(),
+            Commands::Info(names) => names
+                .iter()
+                .map(|name| lookup(name).map_or_else(|| NULL.clone(), info_frame))
+                .collect::<Vec<_>>()
+                .into(),
+            Commands::Docs(names) => {
+                let specs: Vec<&CommandSpec> = if names.is_empty() {
+                    COMMAND_TABLE.iter().collect()
+                } else {
+                    names.iter().filter_map(|name| lookup(name)).collect()
+                };
+
+                specs
+                    .into_iter()
+                    .flat_map(|spec| [spec.name.as_bytes().into(), docs_frame(spec)])
+                    .collect::<Vec<_>>()
+                    .into()
+            }
+            Commands::GetKeys(args) => get_keys(args),
+        };
+
+        Ok(reply)
+    }
+}
+
+fn get_keys(args: &[String]) -> Frame {
+    let Some(spec) = lookup(&args[0]) else {
+        return BackendError::Other("Invalid command specified".to_string()).into();
+    };
+    if !spec.accepts(args.len()) {
+        return BackendError::Other(
+            "Invalid number of arguments specified for command".to_string(),
+        )
+        .into();
+    }
+    if spec.keys == KeySpec::None {
+        return BackendError::Other("The command has no key arguments".to_string()).into();
+    }
+
+    spec.keys
+        .keys(args)
+        .into_iter()
+        .map(|key| key.as_bytes().into())
+        .collect::<Vec<Frame>>()
+        .into()
+}
+
+// the ten element reply of redis 7: name, arity, flags, first key, last key, step, acl
+// categories, tips, key specs and subcommands
+fn info_frame(spec: &CommandSpec) -> Frame {
+    let mut flags: Vec<Frame> = spec.flags.iter().map(|flag| flag.as_str().into()).collect();
+    if matches!(spec.keys, KeySpec::Counted { .. }) {
+        flags.push("movablekeys".into());
+    }
+    let (first, last, step) = spec.keys.positions();
+
+    vec![
+        spec.name.as_bytes().into(),
+        spec.arity.into(),
+        flags.into(),
+        first.into(),
+        last.into(),
+        step.into(),
+        spec.acl_categories
+            .iter()
+            .map(|category| (*category).into())
+            .collect::<Vec<Frame>>()
+            .into(),
+        Vec::<Frame>::new().into(),
+        key_specs_frame(spec),
+        Vec::<Frame>::new().into(),
+    ]
+    .into()
+}
+
+fn key_specs_frame(spec: &CommandSpec) -> Frame {
+    let flags: Vec<Frame> = if spec.has_flag(CommandFlag::Write) {
+        vec!["RW".into()]
+    } else {
+        vec!["RO".into(), "ACCESS".into()]
+    };
+
+    let range = |lastkey: i64, step: i64| -> Vec<Frame> {
+        vec![
+            b"type".into(),
+            b"range".into(),
+            b"spec".into(),
+            vec![
+                b"lastkey".into(),
+                lastkey.into(),
+                b"keystep".into(),
+                step.into(),
+                b"limit".into(),
+                0.into(),
+            ]
+            .into(),
+        ]
+    };
+    let index = |index: i64| -> Vec<Frame> {
+        vec![
+            b"type".into(),
+            b"index".into(),
+            b"spec".into(),
+            vec![b"index".into(), index.into()].into(),
+        ]
+    };
+
+    let searches = match spec.keys {
+        KeySpec::None => Vec::new(),
+        // redis counts the last key from the first one, negative values from the end
+        KeySpec::Range { first, last, step } => vec![(
+            index(first),
+            range(if last < 0 { last } else { last - first }, step),
+        )],
+        KeySpec::Counted { numkeys } => vec![(
+            index(numkeys as i64),
+            vec![
+                b"type".into(),
+                b"keynum".into(),
+                b"spec".into(),
+                vec![
+                    b"keynumidx".into(),
+                    0.into(),
+                    b"firstkey".into(),
+                    1.into(),
+                    b"keystep".into(),
+                    1.into(),
+                ]
+                .into(),
+            ],
+        )],
+        // the single key, then everything after the keyword
+        KeySpec::Keyword { index: at, keyword } => vec![
+            (index(at as i64), range(0, 1)),
+            (
+                vec![
+                    b"type".into(),
+                    b"keyword".into(),
+                    b"spec".into(),
+                    vec![
+                        b"keyword".into(),
+                        keyword.as_bytes().into(),
+                        b"startfrom".into(),
+                        (at as i64 + 1).into(),
+                    ]
+                    .into(),
+                ],
+                range(-1, 1),
+            ),
+        ],
+        // the single key, then the one after the last keyword, searched from the end
+        KeySpec::Stored { index: at, keyword } => vec![
+            (index(at as i64), range(0, 1)),
+            (
+                vec![
+                    b"type".into(),
+                    b"keyword".into(),
+                    b"spec".into(),
+                    vec![
+                        b"keyword".into(),
+                        keyword.as_bytes().into(),
+                        b"startfrom".into(),
+                        (-1).into(),
+                    ]
+                    .into(),
+                ],
+                range(0, 1),
+            ),
+        ],
+    };
+
+    searches
+        .into_iter()
+        .map(|(begin_search, find_keys)| {
+            vec![
+                b"flags".into(),
+                flags.clone().into(),
+                b"begin_search".into(),
+                begin_search.into(),
+                b"find_keys".into(),
+                find_keys.into(),
+            ]
+            .into()
+        })
+        .collect::<Vec<Frame>>()
+        .into()
+}
+
+fn docs_frame(spec: &CommandSpec) -> Frame {
+    vec![
+        b"summary".into(),
+        spec.summary.as_bytes().into(),
+        b"group".into(),
+        spec.group.as_bytes().into(),
+    ]
+    .into()
+}
+
+impl TryFrom<Frame> for Commands {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "COMMAND" {
+            anyhow::bail!("Invalid command");
+        }
+
+        if parse.len() == 0 {
+            return Ok(Commands::All);
+        }
+
+        let subcommand = parse.next_string()?.to_uppercase();
+        let mut args = Vec::with_capacity(parse.len());
+        while parse.len() > 0 {
+            args.push(parse.next_string()?);
+        }
+
+        match subcommand.as_str() {
+            "COUNT" if args.is_empty() => Ok(Commands::Count),
+            "INFO" => Ok(Commands::Info(args)),
+            "DOCS" => Ok(Commands::Docs(args)),
+            "GETKEYS" if !args.is_empty() => Ok(Commands::GetKeys(args)),
+            _ => anyhow::bail!(
+                "unknown subcommand or wrong number of arguments for '{}'",
+                subcommand
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_command_count() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(&backend, &["command", "count"]),
+            (COMMAND_TABLE.len() as i64).into()
+        );
+        match execute(&backend, &["command"]) {
+            Frame::Array(array) => assert_eq!(array.len(), COMMAND_TABLE.len()),
+            _ => panic!("Expected Array"),
+        }
+    }
+
+    #[test]
+    fn test_command_info() {
+        let backend = Backend::new();
+
+        let reply = execute(&backend, &["command", "info", "get", "nope"]);
+        let Frame::Array(reply) = reply else {
+            panic!("Expected Array");
+        };
+        assert_eq!(reply[1], *NULL);
+
+        let Frame::Array(get) = &reply[0] else {
+            panic!("Expected Array");
+        };
+        assert_eq!(get.len(), 10);
+        assert_eq!(get[0], b"get".into());
+        assert_eq!(get[1], 2.into());
+        assert_eq!(get[2], vec!["readonly".into(), "fast".into()].into());
+        assert_eq!(get[3], 1.into());
+        assert_eq!(get[4], 1.into());
+        assert_eq!(get[5], 1.into());
+    }
+
+    #[test]
+    fn test_command_docs() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(&backend, &["command", "docs", "echo"]),
+            vec![
+                b"echo".into(),
+                vec![
+                    b"summary".into(),
+                    b"Returns the given string.".into(),
+                    b"group".into(),
+                    b"connection".into(),
+                ]
+                .into(),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_command_getkeys() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &["command", "getkeys", "mset", "a", "1", "b", "2"]
+            ),
+            vec![b"a".into(), b"b".into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["command", "getkeys", "echo", "hi"]),
+            BackendError::Other("The command has no key arguments".to_string()).into()
+        );
+        assert_eq!(
+            execute(&backend, &["command", "getkeys", "get"]),
+            BackendError::Other("Invalid number of arguments specified for command".to_string())
+                .into()
+        );
+        assert_eq!(
+            execute(&backend, &["command", "getkeys", "nope"]),
+            BackendError::Other("Invalid command specified".to_string()).into()
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "dbsize")]
+pub struct DbSize {}
+
+impl CommandExecute for DbSize {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.dbsize() as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_dbsize() {
+        let backend = Backend::new();
+        assert_eq!(execute(&backend, &["dbsize"]), 0.into());
+
+        backend.set("a", "1");
+        backend.sadd("b", &["x".to_string()]).unwrap();
+        assert_eq!(execute(&backend, &["dbsize"]), 2.into());
+
+        backend.select(1).unwrap();
+        assert_eq!(execute(&backend, &["dbsize"]), 0.into());
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, OK};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+// the lru clock of redis counts seconds in 24 bits
+const LRU_CLOCK_MAX: u64 = (1 << 24) - 1;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum DebugSubcommand {
+    Object(String),
+    Sleep(Duration),
+    Reload,
+    Jmap,
+    SetActiveExpire(bool),
+}
+
+#[derive(Debug)]
+pub struct Debug {
+    pub(crate) subcommand: DebugSubcommand,
+}
+
+impl CommandExecute for Debug {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match &self.subcommand {
+            DebugSubcommand::Object(key) => {
+                let Some(meta) = backend.key_meta(key) else {
+                    return Ok(BackendError::Other("no such key".to_string()).into());
+                };
+                let (encoding, serialized) = backend
+                    .inspect(key, |value| (value.encoding(), value.serialized_len()))
+                    .unwrap_or_default();
+                Ok(format!(
+                    "refcount:1 encoding:{} serializedlength:{} lru:{} lru_seconds_idle:{} \
+                     lfu_freq:{} memory_usage:{}",
+                    encoding,
+                    serialized,
+                    (meta.access() / 1000) & LRU_CLOCK_MAX,
+                    meta.idle().as_secs(),
+                    meta.freq(),
+                    meta.size()
+                )
+                .into())
+            }
+            // like redis, the whole connection is stuck for that long
+            DebugSubcommand::Sleep(duration) => {
+                std::thread::sleep(*duration);
+                Ok(OK.clone())
+            }
+            DebugSubcommand::Reload => {
+                backend.debug_reload();
+                Ok(OK.clone())
+            }
+            DebugSubcommand::Jmap => {
+                let histogram: String = backend
+                    .key_size_histogram()
+                    .iter()
+                    .map(|(db, kind, bins)| {
+                        let bins: Vec<String> = bins
+                            .iter()
+                            .map(|(bin, keys)| format!("{}={}", bin, keys))
+                            .collect();
+                        format!("db{}_distrib_{}_sizes:{}\r\n", db, kind, bins.join(","))
+                    })
+                    .collect();
+                Ok(histogram.as_bytes().into())
+            }
+            DebugSubcommand::SetActiveExpire(enabled) => {
+                backend.set_active_expire(*enabled);
+                Ok(OK.clone())
+            }
+        }
+    }
+}
+
+impl TryFrom<Frame> for Debug {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "DEBUG" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let subcommand = match parse.next_string()?.to_uppercase().as_str() {
+            "OBJECT" => DebugSubcommand::Object(parse.next_string()?),
+            "SLEEP" => {
+                let seconds = parse.next_string()?;
+                match seconds.parse::<f64>() {
+                    Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+                        DebugSubcommand::Sleep(Duration::from_secs_f64(seconds))
+                    }
+                    _ => anyhow::bail!("value is not a valid float"),
+                }
+            }
+            "RELOAD" => DebugSubcommand::Reload,
+            "JMAP" => DebugSubcommand::Jmap,
+            "SET-ACTIVE-EXPIRE" => DebugSubcommand::SetActiveExpire(parse.next_int()? != 0),
+            subcommand => anyhow::bail!("Unknown DEBUG subcommand '{}'", subcommand),
+        };
+        parse.finish()?;
+
+        Ok(Self { subcommand })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::unix_time;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_debug_try_from_frame() {
+        let frame: Frame = vec![b"debug".into(), b"sleep".into(), b"0.5".into()].into();
+        let cmd: Debug = frame.try_into().unwrap();
+        assert_eq!(
+            cmd.subcommand,
+            DebugSubcommand::Sleep(Duration::from_millis(500))
+        );
+
+        let frame: Frame = vec![b"debug".into(), b"set-active-expire".into(), b"0".into()].into();
+        let cmd: Debug = frame.try_into().unwrap();
+        assert_eq!(cmd.subcommand, DebugSubcommand::SetActiveExpire(false));
+
+        let frame: Frame = vec![b"debug".into(), b"sleep".into(), b"-1".into()].into();
+        let actual: Result<Debug> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_debug_subcommands() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        backend.hset("hash", "field", "value").unwrap();
+
+        let object = match execute(&backend, &["debug", "object", "key"]) {
+            Frame::SimpleString(object) => object.inner,
+            frame => panic!("Expected SimpleString, got {:?}", frame),
+        };
+        assert!(object.starts_with("refcount:1 encoding:embstr serializedlength:5 lru:"));
+        assert!(object.contains(" lru_seconds_idle:0 lfu_freq:5 memory_usage:"));
+        assert!(matches!(
+            execute(&backend, &["debug", "object", "missing"]),
+            Frame::SimpleError(_)
+        ));
+
+        let jmap = match execute(&backend, &["debug", "jmap"]) {
+            Frame::BulkString(jmap) => String::from_utf8(jmap.inner).unwrap(),
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        };
+        let lines: Vec<&str> = jmap.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("db0_distrib_hash_sizes:"));
+        assert!(lines[1].starts_with("db0_distrib_string_sizes:"));
+
+        assert_eq!(execute(&backend, &["debug", "reload"]), *OK);
+        assert_eq!(backend.dbsize(), 2);
+        assert_eq!(execute(&backend, &["debug", "sleep", "0"]), *OK);
+    }
+
+    #[test]
+    fn test_debug_set_active_expire() {
+        let backend = Backend::new();
+        backend.hset("hash", "a", "1").unwrap();
+        backend.hset("hash", "b", "2").unwrap();
+        let at = unix_time().as_millis() as u64 + 1;
+        backend.hexpire("hash", at, None, &["a".to_string()]);
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert_eq!(execute(&backend, &["debug", "set-active-expire", "0"]), *OK);
+        assert!(!backend.active_expire());
+        assert_eq!(execute(&backend, &["debug", "set-active-expire", "1"]), *OK);
+        assert!(backend.active_expire());
+
+        assert_eq!(backend.active_expire_cycle(), 1);
+        assert_eq!(backend.active_expire_cycle(), 0);
+        assert_eq!(backend.stats().expired_subkeys(), 1);
+    }
+}
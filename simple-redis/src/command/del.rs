@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "del")]
+pub struct Del {
+    #[arg(key, variadic)]
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for Del {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.del(&self.keys, false) as i64).into())
+    }
+}
+
+// DEL that leaves freeing big values to the lazyfree thread
+#[derive(Debug, RedisCommand)]
+#[command(name = "unlink")]
+pub struct Unlink {
+    #[arg(key, variadic)]
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for Unlink {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.del(&self.keys, true) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_del_and_unlink() {
+        let backend = Backend::new();
+        execute(&backend, &["set", "a", "1"]);
+        execute(&backend, &["sadd", "b", "1", "2"]);
+        execute(&backend, &["hset", "c", "f", "v"]);
+
+        assert_eq!(execute(&backend, &["del", "a", "missing"]), 1.into());
+        assert_eq!(execute(&backend, &["unlink", "b", "c", "a"]), 2.into());
+        assert_eq!(execute(&backend, &["dbsize"]), 0.into());
+    }
+}
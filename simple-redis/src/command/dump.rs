@@ -0,0 +1,45 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "dump")]
+pub struct Dump {
+    #[arg(key)]
+    key: String,
+}
+
+impl CommandExecute for Dump {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.dump(&self.key) {
+            Some(payload) => Ok(payload.as_slice().into()),
+            None => Ok(NULL.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{crc64, DUMP_VERSION};
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_dump() {
+        let backend = Backend::new();
+        backend.set("key", "hello");
+
+        let payload = match execute(&backend, &["dump", "key"]) {
+            Frame::BulkString(payload) => payload.inner,
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        };
+        let (body, crc) = payload.split_at(payload.len() - 8);
+        assert_eq!(&body[..7], b"\x00\x05hello");
+        assert_eq!(&body[7..], &DUMP_VERSION.to_le_bytes());
+        assert_eq!(crc, &crc64(0, body).to_le_bytes());
+
+        assert_eq!(execute(&backend, &["dump", "missing"]), *NULL);
+    }
+}
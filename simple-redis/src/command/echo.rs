@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::CommandExecute;
+use super::{CommandExecute, RedisCommand};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "echo")]
 pub struct Echo {
+    #[arg(raw)]
     pub(crate) message: Frame,
 }
 
@@ -16,24 +17,6 @@ impl CommandExecute for Echo {
     }
 }
 
-impl TryFrom<Frame> for Echo {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "ECHO" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let message = parse.next()?;
-        parse.finish()?;
-
-        Ok(Self { message })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
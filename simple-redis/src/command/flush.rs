@@ -0,0 +1,77 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, OK};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+// FLUSHDB empties the selected database, FLUSHALL every database
+#[derive(Debug)]
+pub struct Flush {
+    pub(crate) all: bool,
+    pub(crate) lazy: bool,
+}
+
+impl CommandExecute for Flush {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        if self.all {
+            backend.flushall(self.lazy);
+        } else {
+            backend.flushdb(self.lazy);
+        }
+        Ok(OK.clone())
+    }
+}
+
+impl TryFrom<Frame> for Flush {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let all = match command.as_str() {
+            "FLUSHDB" => false,
+            "FLUSHALL" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let mut lazy = false;
+        if parse.len() > 0 {
+            lazy = match parse.next_string()?.to_uppercase().as_str() {
+                "ASYNC" => true,
+                "SYNC" => false,
+                _ => anyhow::bail!("syntax error"),
+            };
+        }
+        parse.finish()?;
+
+        Ok(Self { all, lazy })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_flush() {
+        let backend = Backend::new();
+        let other = backend.session();
+        other.select(1).unwrap();
+        backend.set("a", "1");
+        other.set("b", "2");
+
+        assert_eq!(execute(&backend, &["flushdb", "async"]), *OK);
+        assert_eq!(backend.dbsize(), 0);
+        assert_eq!(other.dbsize(), 1);
+
+        backend.set("a", "1");
+        assert_eq!(execute(&backend, &["flushall"]), *OK);
+        assert!(backend.keyspace().is_empty());
+
+        let frame: Frame = vec![b"flushall".into(), b"later".into()].into();
+        assert!(Flush::try_from(frame).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_float, CommandExecute};
+use crate::backend::{Backend, BackendError, GeoAddOptions};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct GeoAdd {
+    pub(crate) key: String,
+    pub(crate) options: GeoAddOptions,
+    pub(crate) items: Vec<(String, String, String)>,
+}
+
+impl GeoAdd {
+    fn items(&self) -> Result<Vec<(f64, f64, String)>, BackendError> {
+        self.items
+            .iter()
+            .map(|(lon, lat, member)| Ok((parse_float(lon)?, parse_float(lat)?, member.clone())))
+            .collect()
+    }
+}
+
+impl CommandExecute for GeoAdd {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = self
+            .items()
+            .and_then(|items| backend.geoadd(&self.key, &items, self.options));
+
+        match result {
+            Ok(changed) => Ok((changed as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for GeoAdd {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "GEOADD" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let mut options = GeoAddOptions::default();
+
+        // options come first, everything after them is longitude, latitude, member triples
+        while let Ok(option) = parse.peek_string() {
+            match option.to_uppercase().as_str() {
+                "NX" => options.nx = true,
+                "XX" => options.xx = true,
+                "CH" => options.ch = true,
+                _ => break,
+            }
+            parse.next()?;
+        }
+
+        if parse.len() == 0 || parse.len() % 3 != 0 {
+            anyhow::bail!("syntax error. Try GEOADD key [x1] [y1] [name1] [x2] [y2] [name2] ...");
+        }
+
+        let mut items = Vec::with_capacity(parse.len() / 3);
+        while parse.len() > 0 {
+            items.push((
+                parse.next_string()?,
+                parse.next_string()?,
+                parse.next_string()?,
+            ));
+        }
+
+        Ok(Self {
+            key,
+            options,
+            items,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_geoadd() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geoadd",
+                    "Sicily",
+                    "13.361389",
+                    "38.115556",
+                    "Palermo",
+                    "15.087269",
+                    "37.502669",
+                    "Catania"
+                ]
+            ),
+            2.into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &["geoadd", "Sicily", "xx", "ch", "13.5", "38", "Palermo"]
+            ),
+            1.into()
+        );
+        assert_eq!(
+            execute(&backend, &["geoadd", "Sicily", "abc", "38", "Palermo"]),
+            BackendError::NotFloat.into()
+        );
+        assert_eq!(
+            execute(&backend, &["geoadd", "Sicily", "nx", "xx", "13", "38", "x"]),
+            BackendError::Other(
+                "XX and NX options at the same time are not compatible".to_string()
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_geoadd_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"geoadd".into(), b"key".into(), b"nx".into(), b"1".into()].into();
+        let actual: Result<GeoAdd> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, NULL};
+use crate::backend::{Backend, GeoUnit};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct GeoDist {
+    pub(crate) key: String,
+    pub(crate) member1: String,
+    pub(crate) member2: String,
+    pub(crate) unit: Option<String>,
+}
+
+impl CommandExecute for GeoDist {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let unit = match self.unit.as_deref().map(GeoUnit::parse) {
+            Some(Ok(unit)) => unit,
+            Some(Err(e)) => return Ok(e.into()),
+            None => GeoUnit::Meters,
+        };
+
+        match backend.geodist(&self.key, &self.member1, &self.member2) {
+            Some(distance) => Ok(distance_frame(distance, unit)),
+            None => Ok(NULL.clone()),
+        }
+    }
+}
+
+// distances are replied with four decimals
+pub(crate) fn distance_frame(meters: f64, unit: GeoUnit) -> Frame {
+    format!("{:.4}", meters / unit.to_meters())
+        .as_bytes()
+        .into()
+}
+
+impl TryFrom<Frame> for GeoDist {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "GEODIST" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let member1 = parse.next_string()?;
+        let member2 = parse.next_string()?;
+        let unit = if parse.len() > 0 {
+            Some(parse.next_string()?)
+        } else {
+            None
+        };
+        parse.finish()?;
+
+        Ok(Self {
+            key,
+            member1,
+            member2,
+            unit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_geodist() {
+        let backend = Backend::new();
+        execute(
+            &backend,
+            &[
+                "geoadd",
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ],
+        );
+
+        assert_eq!(
+            execute(&backend, &["geodist", "Sicily", "Palermo", "Catania"]),
+            b"166274.1516".into()
+        );
+        assert_eq!(
+            execute(&backend, &["geodist", "Sicily", "Palermo", "Catania", "km"]),
+            b"166.2742".into()
+        );
+        assert_eq!(
+            execute(&backend, &["geodist", "Sicily", "Palermo", "Catania", "mi"]),
+            b"103.3182".into()
+        );
+        assert_eq!(
+            execute(&backend, &["geodist", "Sicily", "Foo", "Bar"]),
+            *NULL
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "geohash")]
+pub struct GeoHash {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic, min = 0)]
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecute for GeoHash {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let hashes = backend
+            .geohash(&self.key, &self.members)
+            .into_iter()
+            .map(|hash| match hash {
+                Some(hash) => hash.as_bytes().into(),
+                None => NULL.clone(),
+            })
+            .collect::<Vec<Frame>>();
+
+        Ok(hashes.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_geohash() {
+        let backend = Backend::new();
+        execute(
+            &backend,
+            &[
+                "geoadd",
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ],
+        );
+
+        assert_eq!(
+            execute(
+                &backend,
+                &["geohash", "Sicily", "Palermo", "Catania", "Rome"]
+            ),
+            vec![b"sqc8b49rny0".into(), b"sqdtr74hyu0".into(), NULL.clone()].into()
+        );
+    }
+}
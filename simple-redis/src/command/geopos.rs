@@ -0,0 +1,70 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "geopos")]
+pub struct GeoPos {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic, min = 0)]
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecute for GeoPos {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let positions = backend
+            .geopos(&self.key, &self.members)
+            .into_iter()
+            .map(|position| match position {
+                Some((lon, lat)) => coordinates_frame(lon, lat),
+                None => NULL.clone(),
+            })
+            .collect::<Vec<Frame>>();
+
+        Ok(positions.into())
+    }
+}
+
+// redis prints coordinates with 17 decimals and drops the trailing zeros
+pub(crate) fn coordinates_frame(lon: f64, lat: f64) -> Frame {
+    let format = |value: f64| {
+        let value = format!("{:.17}", value);
+        value
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    };
+
+    vec![format(lon).as_bytes().into(), format(lat).as_bytes().into()].into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_geopos() {
+        let backend = Backend::new();
+        execute(
+            &backend,
+            &["geoadd", "Sicily", "13.361389", "38.115556", "Palermo"],
+        );
+
+        assert_eq!(
+            execute(&backend, &["geopos", "Sicily", "Palermo", "NonExisting"]),
+            vec![
+                vec![
+                    b"13.36138933897018433".into(),
+                    b"38.11555639549629859".into()
+                ]
+                .into(),
+                NULL.clone(),
+            ]
+            .into()
+        );
+    }
+}
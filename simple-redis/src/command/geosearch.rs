@@ -0,0 +1,530 @@
+use anyhow::Result;
+
+use super::geodist::distance_frame;
+use super::geopos::coordinates_frame;
+use super::parse::Parse;
+use super::{parse_float, parse_int, CommandExecute};
+use crate::backend::{
+    validate_lonlat, Backend, BackendError, GeoFrom, GeoMatch, GeoQuery, GeoShape, GeoSort,
+    GeoUnit, SortedSet,
+};
+use crate::resp::frame::Frame;
+
+// GEOSEARCH and GEOSEARCHSTORE, the options are kept as sent and resolved while executing
+#[derive(Debug)]
+pub struct GeoSearch {
+    pub(crate) key: String,
+    pub(crate) destination: Option<String>,
+    pub(crate) args: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct GeoSearchOptions {
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+    store_dist: bool,
+}
+
+impl GeoSearch {
+    fn command_name(&self) -> &'static str {
+        match self.destination {
+            Some(_) => "GEOSEARCHSTORE",
+            None => "GEOSEARCH",
+        }
+    }
+
+    fn query(&self) -> Result<(GeoQuery, GeoUnit, GeoSearchOptions), BackendError> {
+        let mut from = None;
+        let mut shape = None;
+        let mut sort = None;
+        let mut count = None;
+        let mut any = false;
+        let mut options = GeoSearchOptions::default();
+
+        let mut args = self.args.iter();
+        let mut next = || args.next().ok_or(BackendError::Syntax);
+
+        while let Ok(arg) = next() {
+            match arg.to_uppercase().as_str() {
+                "FROMMEMBER" if from.is_none() => from = Some(GeoFrom::Member(next()?.clone())),
+                "FROMLONLAT" if from.is_none() => {
+                    let lon = parse_float(next()?)?;
+                    let lat = parse_float(next()?)?;
+                    validate_lonlat(lon, lat)?;
+                    from = Some(GeoFrom::LonLat(lon, lat));
+                }
+                "FROMMEMBER" | "FROMLONLAT" => {
+                    return Err(BackendError::Other(format!(
+                        "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+                        self.command_name().to_lowercase()
+                    )))
+                }
+                "BYRADIUS" if shape.is_none() => {
+                    let radius = parse_distance(next()?)?;
+                    let unit = GeoUnit::parse(next()?)?;
+                    shape = Some((GeoShape::Radius(radius * unit.to_meters()), unit));
+                }
+                "BYBOX" if shape.is_none() => {
+                    let width = parse_distance(next()?)?;
+                    let height = parse_distance(next()?)?;
+                    let unit = GeoUnit::parse(next()?)?;
+                    let meters = unit.to_meters();
+                    shape = Some((GeoShape::Box(width * meters, height * meters), unit));
+                }
+                "BYRADIUS" | "BYBOX" => {
+                    return Err(BackendError::Other(format!(
+                        "exactly one of BYRADIUS and BYBOX can be specified for {}",
+                        self.command_name().to_lowercase()
+                    )))
+                }
+                "ASC" => sort = Some(GeoSort::Asc),
+                "DESC" => sort = Some(GeoSort::Desc),
+                "COUNT" => {
+                    let n = parse_int(next()?)?;
+                    if n <= 0 {
+                        return Err(BackendError::Other("COUNT must be > 0".to_string()));
+                    }
+                    count = Some(n as usize);
+                }
+                "ANY" => any = true,
+                "WITHCOORD" if self.destination.is_none() => options.with_coord = true,
+                "WITHDIST" if self.destination.is_none() => options.with_dist = true,
+                "WITHHASH" if self.destination.is_none() => options.with_hash = true,
+                "STOREDIST" if self.destination.is_some() => options.store_dist = true,
+                _ => return Err(BackendError::Syntax),
+            }
+        }
+
+        let Some(from) = from else {
+            return Err(BackendError::Other(format!(
+                "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+                self.command_name().to_lowercase()
+            )));
+        };
+        let Some((shape, unit)) = shape else {
+            return Err(BackendError::Other(format!(
+                "exactly one of BYRADIUS and BYBOX can be specified for {}",
+                self.command_name().to_lowercase()
+            )));
+        };
+        if any && count.is_none() {
+            return Err(BackendError::Other(
+                "the ANY argument requires COUNT argument".to_string(),
+            ));
+        }
+
+        let query = GeoQuery {
+            from,
+            shape,
+            sort,
+            count,
+            any,
+        };
+        Ok((query, unit, options))
+    }
+
+    fn reply(m: &GeoMatch, unit: GeoUnit, options: &GeoSearchOptions) -> Frame {
+        if !(options.with_dist || options.with_hash || options.with_coord) {
+            return m.member.as_bytes().into();
+        }
+
+        let mut item = vec![m.member.as_bytes().into()];
+        if options.with_dist {
+            item.push(distance_frame(m.distance, unit));
+        }
+        if options.with_hash {
+            item.push((m.hash as i64).into());
+        }
+        if options.with_coord {
+            item.push(coordinates_frame(m.lon, m.lat));
+        }
+        item.into()
+    }
+}
+
+fn parse_distance(arg: &str) -> Result<f64, BackendError> {
+    let distance = parse_float(arg)?;
+    if distance < 0.0 {
+        return Err(BackendError::Other("radius cannot be negative".to_string()));
+    }
+    Ok(distance)
+}
+
+impl CommandExecute for GeoSearch {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let (query, unit, options) = match self.query() {
+            Ok(query) => query,
+            Err(e) => return Ok(e.into()),
+        };
+
+        let matches = match backend.geosearch(&self.key, &query) {
+            Ok(matches) => matches,
+            Err(e) => return Ok(e.into()),
+        };
+
+        match &self.destination {
+            Some(destination) => {
+                let mut zset = SortedSet::new();
+                for m in &matches {
+                    let score = if options.store_dist {
+                        m.distance / unit.to_meters()
+                    } else {
+                        m.hash as f64
+                    };
+                    zset.insert(&m.member, score);
+                }
+                backend.zset_replace(destination, zset);
+
+                Ok((matches.len() as i64).into())
+            }
+            None => Ok(matches
+                .iter()
+                .map(|m| Self::reply(m, unit, &options))
+                .collect::<Vec<Frame>>()
+                .into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for GeoSearch {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let destination = match command.as_str() {
+            "GEOSEARCH" => None,
+            "GEOSEARCHSTORE" => Some(parse.next_string()?),
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let mut args = Vec::with_capacity(parse.len());
+        while parse.len() > 0 {
+            args.push(parse.next_string()?);
+        }
+
+        Ok(Self {
+            key,
+            destination,
+            args,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    fn sicily() -> Backend {
+        let backend = Backend::new();
+        execute(
+            &backend,
+            &[
+                "geoadd",
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ],
+        );
+        execute(
+            &backend,
+            &[
+                "geoadd",
+                "Sicily",
+                "12.758489",
+                "38.788135",
+                "edge1",
+                "17.241510",
+                "38.788135",
+                "edge2",
+            ],
+        );
+        backend
+    }
+
+    #[test]
+    fn test_geosearch() {
+        let backend = sicily();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "fromlonlat",
+                    "15",
+                    "37",
+                    "byradius",
+                    "200",
+                    "km",
+                    "asc"
+                ]
+            ),
+            vec![b"Catania".into(), b"Palermo".into()].into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "fromlonlat",
+                    "15",
+                    "37",
+                    "bybox",
+                    "400",
+                    "400",
+                    "km",
+                    "asc",
+                    "withcoord",
+                    "withdist"
+                ]
+            ),
+            vec![
+                vec![
+                    b"Catania".into(),
+                    b"56.4413".into(),
+                    vec![
+                        b"15.08726745843887329".into(),
+                        b"37.50266842333162032".into()
+                    ]
+                    .into(),
+                ]
+                .into(),
+                vec![
+                    b"Palermo".into(),
+                    b"190.4424".into(),
+                    vec![
+                        b"13.36138933897018433".into(),
+                        b"38.11555639549629859".into()
+                    ]
+                    .into(),
+                ]
+                .into(),
+                vec![
+                    b"edge2".into(),
+                    b"279.7403".into(),
+                    vec![
+                        b"17.24151045083999634".into(),
+                        b"38.78813451624225195".into()
+                    ]
+                    .into(),
+                ]
+                .into(),
+                vec![
+                    b"edge1".into(),
+                    b"279.7405".into(),
+                    vec![
+                        b"12.7584877610206604".into(),
+                        b"38.78813451624225195".into()
+                    ]
+                    .into(),
+                ]
+                .into(),
+            ]
+            .into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "frommember",
+                    "Palermo",
+                    "byradius",
+                    "200",
+                    "km",
+                    "desc",
+                    "count",
+                    "1"
+                ]
+            ),
+            vec![b"Catania".into()].into()
+        );
+    }
+
+    #[test]
+    fn test_geosearch_errors() {
+        let backend = sicily();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "byradius",
+                    "1",
+                    "km",
+                    "asc",
+                    "withdist"
+                ]
+            ),
+            BackendError::Other(
+                "exactly one of FROMMEMBER or FROMLONLAT can be specified for geosearch"
+                    .to_string()
+            )
+            .into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "frommember",
+                    "Palermo",
+                    "asc",
+                    "withdist",
+                    "withcoord"
+                ]
+            ),
+            BackendError::Other(
+                "exactly one of BYRADIUS and BYBOX can be specified for geosearch".to_string()
+            )
+            .into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "frommember",
+                    "Palermo",
+                    "byradius",
+                    "1",
+                    "km",
+                    "any"
+                ]
+            ),
+            BackendError::Other("the ANY argument requires COUNT argument".to_string()).into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "frommember",
+                    "Palermo",
+                    "byradius",
+                    "1",
+                    "km",
+                    "count",
+                    "0"
+                ]
+            ),
+            BackendError::Other("COUNT must be > 0".to_string()).into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "Sicily",
+                    "frommember",
+                    "Nope",
+                    "byradius",
+                    "1",
+                    "km"
+                ]
+            ),
+            BackendError::Other("could not decode requested zset member".to_string()).into()
+        );
+    }
+
+    #[test]
+    fn test_geosearchstore() {
+        let backend = sicily();
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearchstore",
+                    "dest",
+                    "Sicily",
+                    "fromlonlat",
+                    "15",
+                    "37",
+                    "byradius",
+                    "200",
+                    "km",
+                    "storedist"
+                ]
+            ),
+            2.into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearch",
+                    "dest",
+                    "fromlonlat",
+                    "15",
+                    "37",
+                    "byradius",
+                    "1",
+                    "km"
+                ]
+            ),
+            vec![].into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearchstore",
+                    "dest",
+                    "Sicily",
+                    "frommember",
+                    "Palermo",
+                    "byradius",
+                    "1",
+                    "km",
+                    "withdist"
+                ]
+            ),
+            BackendError::Syntax.into()
+        );
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "geosearchstore",
+                    "dest",
+                    "Sicily",
+                    "fromlonlat",
+                    "15",
+                    "37",
+                    "byradius",
+                    "200",
+                    "km"
+                ]
+            ),
+            2.into()
+        );
+        assert_eq!(
+            execute(&backend, &["geopos", "dest", "Catania"]),
+            vec![vec![
+                b"15.08726745843887329".into(),
+                b"37.50266842333162032".into()
+            ]
+            .into()]
+            .into()
+        );
+    }
+}
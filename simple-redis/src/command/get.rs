@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::{CommandExecute, NULL};
+use super::{CommandExecute, RedisCommand, NULL};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "get")]
 pub struct Get {
+    #[arg(key)]
     pub(crate) key: String,
 }
 
@@ -19,24 +20,6 @@ impl CommandExecute for Get {
     }
 }
 
-impl TryFrom<Frame> for Get {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "GET" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,39 @@
+use anyhow::Result;
+
+use super::{parse_bit_offset, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "getbit")]
+pub struct GetBit {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) offset: String,
+}
+
+impl CommandExecute for GetBit {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match parse_bit_offset(&self.offset) {
+            Ok(offset) => Ok((backend.getbit(&self.key, offset) as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_getbit() {
+        let backend = Backend::new();
+        backend.set("key", b"\x40");
+
+        assert_eq!(execute(&backend, &["getbit", "key", "1"]), 1.into());
+        assert_eq!(execute(&backend, &["getbit", "key", "0"]), 0.into());
+        assert_eq!(execute(&backend, &["getbit", "key", "100"]), 0.into());
+        assert_eq!(execute(&backend, &["getbit", "missing", "0"]), 0.into());
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "getdel")]
+pub struct GetDel {
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for GetDel {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.getdel(&self.key) {
+            Ok(Some(value)) => Ok(value.into()),
+            Ok(None) => Ok(NULL.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_getdel() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+
+        assert_eq!(execute(&backend, &["getdel", "key"]), b"value".into());
+        assert_eq!(execute(&backend, &["getdel", "key"]), *NULL);
+        assert_eq!(backend.get("key"), None);
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "getrange")]
+pub struct GetRange {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) start: String,
+    pub(crate) end: String,
+}
+
+impl CommandExecute for GetRange {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let range = parse_int(&self.start).and_then(|start| Ok((start, parse_int(&self.end)?)));
+
+        match range {
+            Ok((start, end)) => Ok(backend.getrange(&self.key, start, end).as_slice().into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_getrange() {
+        let backend = Backend::new();
+        backend.set("key", "This is a string");
+
+        assert_eq!(
+            execute(&backend, &["getrange", "key", "0", "3"]),
+            b"This".into()
+        );
+        assert_eq!(
+            execute(&backend, &["getrange", "key", "-3", "-1"]),
+            b"ing".into()
+        );
+        assert_eq!(
+            execute(&backend, &["getrange", "key", "10", "100"]),
+            b"string".into()
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::{Backend, StringValue};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "getset")]
+pub struct GetSet {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) value: StringValue,
+}
+
+impl CommandExecute for GetSet {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.getset(&self.key, self.value.clone()) {
+            Ok(Some(old)) => Ok(old.into()),
+            Ok(None) => Ok(NULL.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_getset() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["getset", "key", "a"]), *NULL);
+        assert_eq!(execute(&backend, &["getset", "key", "b"]), b"a".into());
+        assert_eq!(backend.get("key"), Some(b"b".into()));
+    }
+}
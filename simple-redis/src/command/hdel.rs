@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hdel")]
+pub struct HDel {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic)]
+    pub(crate) fields: Vec<String>,
+}
+
+impl CommandExecute for HDel {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let removed = backend.hdel(&self.key, &self.fields);
+        Ok((removed as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hdel() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2"]);
+
+        assert_eq!(execute(&backend, &["hdel", "key", "a", "c"]), 1.into());
+        assert_eq!(execute(&backend, &["hdel", "key", "b"]), 1.into());
+        assert!(backend.keys().is_empty());
+        assert_eq!(execute(&backend, &["hdel", "key", "b"]), 0.into());
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+use crate::resp::{to_frame, to_frame_resp2};
+
+// HELLO [protover [SETNAME clientname]], switching to RESP3 lets the connection take pushes
+#[derive(Debug)]
+pub struct Hello {
+    pub(crate) protocol: Option<String>,
+    pub(crate) name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct HelloReply {
+    server: &'static str,
+    version: &'static str,
+    proto: u8,
+    id: u64,
+    mode: &'static str,
+    role: &'static str,
+    modules: Vec<String>,
+}
+
+impl CommandExecute for Hello {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let protocol = match self.protocol.as_deref().map(str::parse::<u8>) {
+            None => None,
+            Some(Ok(protocol @ (2 | 3))) => Some(protocol),
+            Some(Ok(_)) => return Ok(BackendError::NoProto.into()),
+            Some(Err(_)) => {
+                return Ok(BackendError::Other(
+                    "Protocol version is not an integer or out of range".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let mut id = 0;
+        let mut proto = 2;
+        if let Some(client) = backend.client() {
+            if let Some(protocol) = protocol {
+                client.set_protocol(protocol);
+            }
+            if let Some(name) = &self.name {
+                client.set_name(name.as_str());
+            }
+            id = client.id;
+            proto = client.protocol();
+        }
+
+        let reply = HelloReply {
+            server: "redis",
+            version: env!("CARGO_PKG_VERSION"),
+            proto,
+            id,
+            mode: "standalone",
+            role: "master",
+            modules: Vec::new(),
+        };
+
+        // a RESP2 connection gets the map flattened into an array, like redis does
+        if proto == 3 {
+            Ok(to_frame(&reply)?)
+        } else {
+            Ok(to_frame_resp2(&reply)?)
+        }
+    }
+}
+
+impl TryFrom<Frame> for Hello {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "HELLO" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let mut hello = Self {
+            protocol: None,
+            name: None,
+        };
+        if parse.len() > 0 {
+            hello.protocol = Some(parse.next_string()?);
+        }
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "SETNAME" => hello.name = Some(parse.next_string()?),
+                option => anyhow::bail!("Unknown HELLO option '{}'", option),
+            }
+        }
+
+        Ok(hello)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::ConnectionKind;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hello() {
+        let backend = Backend::new();
+        let client = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into())
+            .unwrap();
+
+        let reply = execute(&client, &["hello"]);
+        let Frame::Array(fields) = reply else {
+            panic!("Expected Array, got {:?}", reply);
+        };
+        assert_eq!(fields[4], b"proto".into());
+        assert_eq!(fields[5], 2.into());
+
+        let reply = execute(&client, &["hello", "3", "setname", "cache"]);
+        let Frame::Map(fields) = reply else {
+            panic!("Expected Map, got {:?}", reply);
+        };
+        assert_eq!(fields.get(&b"proto".into()), Some(&3.into()));
+        assert_eq!(client.client().unwrap().protocol(), 3);
+        assert_eq!(client.client().unwrap().name(), "cache");
+
+        assert_eq!(
+            execute(&client, &["hello", "4"]),
+            BackendError::NoProto.into()
+        );
+        assert_eq!(client.client().unwrap().protocol(), 3);
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hexists")]
+pub struct HExists {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) field: String,
+}
+
+impl CommandExecute for HExists {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let exists = backend.hexists(&self.key, &self.field);
+        Ok((exists as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hexists() {
+        let backend = Backend::new();
+        backend.hset("key", "field", "value").unwrap();
+
+        assert_eq!(execute(&backend, &["hexists", "key", "field"]), 1.into());
+        assert_eq!(execute(&backend, &["hexists", "key", "other"]), 0.into());
+        assert_eq!(execute(&backend, &["hexists", "nokey", "field"]), 0.into());
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_hash_fields, parse_int, CommandExecute};
+use crate::backend::{unix_time, Backend, BackendError, ExpireCondition};
+use crate::resp::frame::Frame;
+
+// HEXPIRE and HPEXPIRE, the time is relative in seconds or milliseconds
+#[derive(Debug)]
+pub struct HExpire {
+    pub(crate) key: String,
+    pub(crate) time: String,
+    pub(crate) millis: bool,
+    pub(crate) condition: Option<ExpireCondition>,
+    pub(crate) fields: Vec<String>,
+}
+
+impl HExpire {
+    // the deadline as unix time in milliseconds
+    fn expire_at(&self) -> Result<u64, BackendError> {
+        let time = parse_int(&self.time)?;
+        let unit = if self.millis { 1 } else { 1000 };
+        let invalid = || {
+            let command = if self.millis { "hpexpire" } else { "hexpire" };
+            BackendError::Other(format!("invalid expire time in '{}' command", command))
+        };
+
+        u64::try_from(time)
+            .ok()
+            .and_then(|time| time.checked_mul(unit))
+            .and_then(|time| time.checked_add(unix_time().as_millis() as u64))
+            .filter(|at| *at <= i64::MAX as u64)
+            .ok_or_else(invalid)
+    }
+}
+
+impl CommandExecute for HExpire {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let at = match self.expire_at() {
+            Ok(at) => at,
+            Err(e) => return Ok(e.into()),
+        };
+
+        Ok(backend
+            .hexpire(&self.key, at, self.condition, &self.fields)
+            .into_iter()
+            .map(Frame::from)
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for HExpire {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let millis = match command.as_str() {
+            "HEXPIRE" => false,
+            "HPEXPIRE" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let time = parse.next_string()?;
+        let condition = match parse.peek_string()?.to_uppercase().as_str() {
+            "NX" => Some(ExpireCondition::Nx),
+            "XX" => Some(ExpireCondition::Xx),
+            "GT" => Some(ExpireCondition::Gt),
+            "LT" => Some(ExpireCondition::Lt),
+            _ => None,
+        };
+        if condition.is_some() {
+            parse.next()?;
+        }
+        let fields = parse_hash_fields(&mut parse)?;
+
+        Ok(Self {
+            key,
+            time,
+            millis,
+            condition,
+            fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hexpire() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2"]);
+
+        assert_eq!(
+            execute(
+                &backend,
+                &["hexpire", "key", "100", "fields", "2", "a", "c"]
+            ),
+            vec![1.into(), (-2).into()].into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &["hexpire", "key", "200", "nx", "fields", "2", "a", "b"]
+            ),
+            vec![0.into(), 1.into()].into()
+        );
+        assert_eq!(
+            execute(
+                &backend,
+                &["hexpire", "key", "50", "gt", "fields", "1", "a"]
+            ),
+            vec![0.into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["hpexpire", "key", "0", "fields", "1", "a"]),
+            vec![2.into()].into()
+        );
+        assert_eq!(backend.hget("key", "a"), None);
+        assert_eq!(
+            execute(&backend, &["hexpire", "key", "-1", "fields", "1", "b"]),
+            BackendError::Other("invalid expire time in 'hexpire' command".to_string()).into()
+        );
+        assert_eq!(
+            execute(&backend, &["hexpire", "nokey", "10", "fields", "1", "b"]),
+            vec![(-2).into()].into()
+        );
+    }
+
+    #[test]
+    fn test_hexpire_removes_expired_fields() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "session", "token", "t", "user", "u"]);
+
+        execute(
+            &backend,
+            &["hpexpire", "session", "1", "fields", "1", "token"],
+        );
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        assert_eq!(execute(&backend, &["hlen", "session"]), 1.into());
+        assert_eq!(backend.hget("session", "token"), None);
+
+        execute(
+            &backend,
+            &["hpexpire", "session", "1", "fields", "1", "user"],
+        );
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        assert_eq!(execute(&backend, &["hlen", "session"]), 0.into());
+        assert!(backend.keys().is_empty());
+    }
+
+    #[test]
+    fn test_hexpire_try_from_frame_invalid_fields() {
+        for args in [
+            vec!["hexpire", "key", "10", "1", "a"],
+            vec!["hexpire", "key", "10", "fields", "0"],
+            vec!["hexpire", "key", "10", "fields", "2", "a"],
+        ] {
+            let frame: Frame = args
+                .iter()
+                .map(|arg| arg.as_bytes().into())
+                .collect::<Vec<Frame>>()
+                .into();
+            let actual: Result<HExpire> = frame.try_into();
+            assert!(actual.is_err());
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::{CommandExecute, NULL};
+use super::{CommandExecute, RedisCommand, NULL};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "hget")]
 pub struct HGet {
+    #[arg(key)]
     key: String,
     field: String,
 }
@@ -14,31 +15,12 @@ pub struct HGet {
 impl CommandExecute for HGet {
     fn execute(&self, backend: Backend) -> Result<Frame> {
         match backend.hget(&self.key, &self.field) {
-            Some(value) => Ok(value),
+            Some(value) => Ok(value.into()),
             None => Ok(NULL.clone()),
         }
     }
 }
 
-impl TryFrom<Frame> for HGet {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "HGET" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key, field })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
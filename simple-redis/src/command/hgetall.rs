@@ -1,11 +1,13 @@
 use anyhow::Result;
 
-use super::{parse::Parse, CommandExecute, NULL};
+use super::{CommandExecute, RedisCommand, NULL};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "hgetall")]
 pub struct HGetAll {
+    #[arg(key)]
     key: String,
 }
 
@@ -17,7 +19,7 @@ impl CommandExecute for HGetAll {
             Some(hmap) => {
                 for (field, value) in hmap {
                     frame.push(field.as_bytes().into());
-                    frame.push(value);
+                    frame.push(value.into());
                 }
             }
             None => {
@@ -29,24 +31,6 @@ impl CommandExecute for HGetAll {
     }
 }
 
-impl TryFrom<Frame> for HGetAll {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "HGETALL" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
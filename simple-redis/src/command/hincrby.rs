@@ -0,0 +1,60 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hincrby")]
+pub struct HIncrBy {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) field: String,
+    pub(crate) delta: String,
+}
+
+impl CommandExecute for HIncrBy {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result =
+            parse_int(&self.delta).and_then(|delta| backend.hincrby(&self.key, &self.field, delta));
+
+        match result {
+            Ok(value) => Ok(value.into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hincrby() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["hincrby", "key", "n", "5"]), 5.into());
+        assert_eq!(
+            execute(&backend, &["hincrby", "key", "n", "-7"]),
+            (-2).into()
+        );
+        assert_eq!(
+            execute(&backend, &["hincrby", "key", "n", "x"]),
+            BackendError::NotInteger.into()
+        );
+
+        backend.hset("key", "s", "abc").unwrap();
+        assert_eq!(
+            execute(&backend, &["hincrby", "key", "s", "1"]),
+            BackendError::HashNotInteger.into()
+        );
+
+        backend.hset("key", "max", i64::MAX).unwrap();
+        assert_eq!(
+            execute(&backend, &["hincrby", "key", "max", "1"]),
+            BackendError::Overflow.into()
+        );
+    }
+}
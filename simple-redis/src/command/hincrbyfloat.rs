@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use super::{parse_float, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hincrbyfloat")]
+pub struct HIncrByFloat {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) field: String,
+    pub(crate) delta: String,
+}
+
+impl CommandExecute for HIncrByFloat {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = parse_float(&self.delta)
+            .and_then(|delta| backend.hincrbyfloat(&self.key, &self.field, delta));
+
+        match result {
+            Ok(value) => Ok(value.into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hincrbyfloat() {
+        let backend = Backend::new();
+        backend.hset("key", "f", "10.50").unwrap();
+
+        assert_eq!(
+            execute(&backend, &["hincrbyfloat", "key", "f", "0.1"]),
+            b"10.6".into()
+        );
+        assert_eq!(
+            execute(&backend, &["hincrbyfloat", "key", "f", "-5"]),
+            b"5.6".into()
+        );
+        assert_eq!(
+            execute(&backend, &["hincrbyfloat", "key", "new", "3"]),
+            b"3".into()
+        );
+
+        backend.hset("key", "s", "abc").unwrap();
+        assert_eq!(
+            execute(&backend, &["hincrbyfloat", "key", "s", "1"]),
+            BackendError::HashNotFloat.into()
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+// HKEYS and HVALS
+#[derive(Debug)]
+pub struct HKeys {
+    pub(crate) key: String,
+    pub(crate) values: bool,
+}
+
+impl CommandExecute for HKeys {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let pairs = backend.hgetall(&self.key).unwrap_or_default();
+
+        Ok(pairs
+            .into_iter()
+            .map(|(field, value)| {
+                if self.values {
+                    value.into()
+                } else {
+                    field.as_bytes().into()
+                }
+            })
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for HKeys {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let values = match command.as_str() {
+            "HKEYS" => false,
+            "HVALS" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        parse.finish()?;
+
+        Ok(Self { key, values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hkeys_hvals() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "b", "2", "a", "1"]);
+
+        assert_eq!(
+            execute(&backend, &["hkeys", "key"]),
+            vec![b"a".into(), b"b".into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["hvals", "key"]),
+            vec![b"1".into(), b"2".into()].into()
+        );
+        assert_eq!(execute(&backend, &["hkeys", "nokey"]), vec![].into());
+    }
+}
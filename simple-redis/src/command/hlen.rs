@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hlen")]
+pub struct HLen {
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for HLen {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.hlen(&self.key) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hlen() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2"]);
+
+        assert_eq!(execute(&backend, &["hlen", "key"]), 2.into());
+        assert_eq!(execute(&backend, &["hlen", "nokey"]), 0.into());
+    }
+}
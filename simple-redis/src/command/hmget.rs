@@ -1,14 +1,15 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::CommandExecute;
-use super::NULL;
+use super::{CommandExecute, RedisCommand, NULL};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "hmget")]
 pub struct Hmget {
+    #[arg(key)]
     pub(crate) key: String,
+    #[arg(variadic)]
     pub(crate) fields: Vec<String>,
 }
 
@@ -18,7 +19,7 @@ impl CommandExecute for Hmget {
 
         for field in &self.fields {
             match backend.hget(&self.key, field) {
-                Some(value) => result.push(value),
+                Some(value) => result.push(value.into()),
                 None => result.push(NULL.clone()),
             }
         }
@@ -27,32 +28,6 @@ impl CommandExecute for Hmget {
     }
 }
 
-impl TryFrom<Frame> for Hmget {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "HMGET" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        let fields_len = parse.length() - 2;
-        let mut fields = Vec::with_capacity(fields_len);
-
-        for _ in 0..fields_len {
-            let field = parse.next_string()?;
-            fields.push(field);
-        }
-
-        parse.finish()?;
-
-        Ok(Self { key, fields })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,8 +65,8 @@ mod tests {
     fn test_hmget_execute() {
         let backend = Backend::new();
 
-        backend.hset("myhash", "field1", b"value1".into());
-        backend.hset("myhash", "field2", b"value2".into());
+        backend.hset("myhash", "field1", b"value1").unwrap();
+        backend.hset("myhash", "field2", b"value2").unwrap();
 
         let input = b"*5\r\n$5\r\nhmget\r\n$6\r\nmyhash\r\n$6\r\nfield1\r\n$6\r\nfield2\r\n$7\r\nnofield\r\n";
         let cmd = parse_cmd(&input[..]).unwrap();
@@ -0,0 +1,63 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_hash_fields, CommandExecute};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct HPersist {
+    pub(crate) key: String,
+    pub(crate) fields: Vec<String>,
+}
+
+impl CommandExecute for HPersist {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok(backend
+            .hpersist(&self.key, &self.fields)
+            .into_iter()
+            .map(Frame::from)
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for HPersist {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "HPERSIST" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let fields = parse_hash_fields(&mut parse)?;
+
+        Ok(Self { key, fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hpersist() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2"]);
+        execute(&backend, &["hexpire", "key", "100", "fields", "1", "a"]);
+
+        assert_eq!(
+            execute(&backend, &["hpersist", "key", "fields", "3", "a", "b", "c"]),
+            vec![1.into(), (-1).into(), (-2).into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["httl", "key", "fields", "1", "a"]),
+            vec![(-1).into()].into()
+        );
+    }
+}
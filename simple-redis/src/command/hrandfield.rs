@@ -0,0 +1,111 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct HRandField {
+    pub(crate) key: String,
+    pub(crate) count: Option<String>,
+    pub(crate) with_values: bool,
+}
+
+impl CommandExecute for HRandField {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        // without a count the reply is a single field instead of an array
+        let Some(count) = &self.count else {
+            return Ok(match backend.hrandfield(&self.key, 1).pop() {
+                Some((field, _)) => field.as_bytes().into(),
+                None => NULL.clone(),
+            });
+        };
+
+        let count = match parse_int(count) {
+            Ok(count) => count,
+            Err(e) => return Ok(e.into()),
+        };
+
+        let mut reply = Vec::new();
+        for (field, value) in backend.hrandfield(&self.key, count) {
+            reply.push(field.as_bytes().into());
+            if self.with_values {
+                reply.push(value.into());
+            }
+        }
+
+        Ok(reply.into())
+    }
+}
+
+impl TryFrom<Frame> for HRandField {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "HRANDFIELD" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let count = match parse.len() {
+            0 => None,
+            _ => Some(parse.next_string()?),
+        };
+        let with_values = match parse.len() {
+            0 => false,
+            _ if parse.next_string()?.eq_ignore_ascii_case("WITHVALUES") => true,
+            _ => anyhow::bail!("syntax error"),
+        };
+        parse.finish()?;
+
+        Ok(Self {
+            key,
+            count,
+            with_values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    fn array_len(frame: Frame) -> usize {
+        match frame {
+            Frame::Array(array) => array.len(),
+            _ => panic!("Expected Array"),
+        }
+    }
+
+    #[test]
+    fn test_hrandfield() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2", "c", "3"]);
+
+        assert_eq!(execute(&backend, &["hrandfield", "nokey"]), *NULL);
+        assert!([b"a".into(), b"b".into(), b"c".into()]
+            .contains(&execute(&backend, &["hrandfield", "key"])));
+
+        assert_eq!(array_len(execute(&backend, &["hrandfield", "key", "5"])), 3);
+        assert_eq!(array_len(execute(&backend, &["hrandfield", "key", "2"])), 2);
+        assert_eq!(
+            array_len(execute(&backend, &["hrandfield", "key", "-5"])),
+            5
+        );
+        assert_eq!(
+            array_len(execute(&backend, &["hrandfield", "key", "2", "withvalues"])),
+            4
+        );
+
+        backend.hset("single", "a", "1").unwrap();
+        assert_eq!(
+            execute(&backend, &["hrandfield", "single", "-2", "withvalues"]),
+            vec![b"a".into(), b"1".into(), b"a".into(), b"1".into()].into()
+        );
+    }
+}
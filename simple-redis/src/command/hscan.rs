@@ -0,0 +1,115 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::scan::SCAN_DEFAULT_COUNT;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+use crate::utils::glob_match;
+
+#[derive(Debug)]
+pub struct HScan {
+    pub(crate) key: String,
+    pub(crate) cursor: usize,
+    pub(crate) pattern: Option<String>,
+    pub(crate) count: usize,
+    pub(crate) novalues: bool,
+}
+
+impl CommandExecute for HScan {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let pairs = backend.hgetall(&self.key).unwrap_or_default();
+        let start = self.cursor.min(pairs.len());
+        let end = start.saturating_add(self.count).min(pairs.len());
+
+        let mut batch = Vec::new();
+        for (field, value) in &pairs[start..end] {
+            if let Some(pattern) = &self.pattern {
+                if !glob_match(pattern.as_bytes(), field.as_bytes()) {
+                    continue;
+                }
+            }
+
+            batch.push(field.as_bytes().into());
+            if !self.novalues {
+                batch.push(value.clone().into());
+            }
+        }
+
+        let next = if end >= pairs.len() { 0 } else { end };
+
+        Ok(vec![next.to_string().as_bytes().into(), batch.into()].into())
+    }
+}
+
+impl TryFrom<Frame> for HScan {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "HSCAN" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let cursor = parse.next_string()?.parse()?;
+        let mut pattern = None;
+        let mut count = SCAN_DEFAULT_COUNT;
+        let mut novalues = false;
+
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "MATCH" => pattern = Some(parse.next_string()?),
+                "COUNT" => match parse.next_int()? {
+                    n if n < 1 => anyhow::bail!("syntax error"),
+                    n => count = n as usize,
+                },
+                "NOVALUES" => novalues = true,
+                _ => anyhow::bail!("syntax error"),
+            }
+        }
+
+        Ok(Self {
+            key,
+            cursor,
+            pattern,
+            count,
+            novalues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hscan() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a1", "1", "a2", "2", "b1", "3"]);
+
+        assert_eq!(
+            execute(&backend, &["hscan", "key", "0", "count", "2"]),
+            vec![
+                b"2".into(),
+                vec![b"a1".into(), b"1".into(), b"a2".into(), b"2".into()].into()
+            ]
+            .into()
+        );
+        assert_eq!(
+            execute(&backend, &["hscan", "key", "2", "count", "2"]),
+            vec![b"0".into(), vec![b"b1".into(), b"3".into()].into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["hscan", "key", "0", "match", "a*", "novalues"]),
+            vec![b"0".into(), vec![b"a1".into(), b"a2".into()].into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["hscan", "nokey", "0"]),
+            vec![b"0".into(), vec![].into()].into()
+        );
+    }
+}
@@ -1,20 +1,21 @@
 use anyhow::Result;
 
 use super::{parse::Parse, CommandExecute};
-use crate::backend::Backend;
+use crate::backend::{Backend, StringValue};
 use crate::resp::frame::Frame;
 
 #[derive(Debug)]
 pub struct HSet {
     key: String,
-    field: String,
-    value: Frame,
+    pairs: Vec<(String, StringValue)>,
 }
 
 impl CommandExecute for HSet {
     fn execute(&self, backend: Backend) -> Result<Frame> {
-        backend.hset(&self.key, &self.field, self.value.clone());
-        Ok(1.into())
+        match backend.hset_many(&self.key, &self.pairs) {
+            Ok(added) => Ok((added as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
     }
 }
 
@@ -30,17 +31,26 @@ impl TryFrom<Frame> for HSet {
         }
 
         let key = parse.next_string()?;
-        let field = parse.next_string()?;
-        let value = parse.next()?;
-        parse.finish()?;
 
-        Ok(Self { key, field, value })
+        if parse.len() == 0 || parse.len() % 2 != 0 {
+            anyhow::bail!("wrong number of arguments for 'hset' command");
+        }
+
+        let mut pairs = Vec::with_capacity(parse.len() / 2);
+        while parse.len() > 0 {
+            let field = parse.next_string()?;
+            let value = parse.next_bytes()?.into();
+            pairs.push((field, value));
+        }
+
+        Ok(Self { key, pairs })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::tests::execute;
 
     #[test]
     fn test_hget_try_from_frame() {
@@ -56,12 +66,33 @@ mod tests {
 
         let expected = HSet {
             key: "key".to_string(),
-            field: "field".to_string(),
-            value: b"value".into(),
+            pairs: vec![("field".to_string(), "value".into())],
         };
 
         assert_eq!(actual.key, expected.key);
-        assert_eq!(actual.field, expected.field);
-        assert_eq!(actual.value, expected.value);
+        assert_eq!(actual.pairs, expected.pairs);
+    }
+
+    #[test]
+    fn test_hset_multiple_pairs() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(&backend, &["hset", "key", "a", "1", "b", "2"]),
+            2.into()
+        );
+        assert_eq!(
+            execute(&backend, &["hset", "key", "b", "3", "c", "4"]),
+            1.into()
+        );
+        assert_eq!(backend.hget("key", "b"), Some("3".into()));
+    }
+
+    #[test]
+    fn test_hset_try_from_frame_odd_arguments() {
+        let frame: Frame = vec![b"hset".into(), b"key".into(), b"field".into()].into();
+
+        let actual: Result<HSet> = frame.try_into();
+        assert!(actual.is_err());
     }
 }
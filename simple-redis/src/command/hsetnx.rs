@@ -0,0 +1,44 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::{Backend, StringValue};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hsetnx")]
+pub struct HSetNx {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) field: String,
+    pub(crate) value: StringValue,
+}
+
+impl CommandExecute for HSetNx {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.hsetnx(&self.key, &self.field, self.value.clone()) {
+            Ok(set) => Ok((set as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hsetnx() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(&backend, &["hsetnx", "key", "field", "a"]),
+            1.into()
+        );
+        assert_eq!(
+            execute(&backend, &["hsetnx", "key", "field", "b"]),
+            0.into()
+        );
+        assert_eq!(backend.hget("key", "field"), Some("a".into()));
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "hstrlen")]
+pub struct HStrlen {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) field: String,
+}
+
+impl CommandExecute for HStrlen {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.hstrlen(&self.key, &self.field) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_hstrlen() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "f", "hello", "n", "-256"]);
+
+        assert_eq!(execute(&backend, &["hstrlen", "key", "f"]), 5.into());
+        assert_eq!(execute(&backend, &["hstrlen", "key", "n"]), 4.into());
+        assert_eq!(execute(&backend, &["hstrlen", "key", "x"]), 0.into());
+    }
+}
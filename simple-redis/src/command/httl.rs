@@ -0,0 +1,78 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_hash_fields, CommandExecute};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+// HTTL and HPTTL
+#[derive(Debug)]
+pub struct HTtl {
+    pub(crate) key: String,
+    pub(crate) millis: bool,
+    pub(crate) fields: Vec<String>,
+}
+
+impl CommandExecute for HTtl {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok(backend
+            .hpttl(&self.key, &self.fields)
+            .into_iter()
+            .map(|ttl| match ttl {
+                // like redis, seconds are rounded up
+                ttl if ttl >= 0 && !self.millis => (ttl + 999) / 1000,
+                ttl => ttl,
+            })
+            .map(Frame::from)
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for HTtl {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let millis = match command.as_str() {
+            "HTTL" => false,
+            "HPTTL" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let fields = parse_hash_fields(&mut parse)?;
+
+        Ok(Self {
+            key,
+            millis,
+            fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_httl() {
+        let backend = Backend::new();
+        execute(&backend, &["hset", "key", "a", "1", "b", "2"]);
+        execute(&backend, &["hexpire", "key", "100", "fields", "1", "a"]);
+
+        assert_eq!(
+            execute(&backend, &["httl", "key", "fields", "3", "a", "b", "c"]),
+            vec![100.into(), (-1).into(), (-2).into()].into()
+        );
+        match execute(&backend, &["hpttl", "key", "fields", "1", "a"]) {
+            Frame::Array(array) => {
+                assert!(matches!(&array[0], Frame::Integer(ttl) if ttl.inner > 99_000))
+            }
+            _ => panic!("Expected Array"),
+        }
+    }
+}
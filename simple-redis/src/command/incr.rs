@@ -0,0 +1,114 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+// INCR, DECR, INCRBY and DECRBY only differ in where the delta comes from
+#[derive(Debug)]
+pub struct IncrBy {
+    pub(crate) key: String,
+    pub(crate) delta: String,
+    pub(crate) negate: bool,
+}
+
+impl IncrBy {
+    fn delta(&self) -> Result<i64, BackendError> {
+        let delta = parse_int(&self.delta)?;
+
+        if self.negate {
+            delta.checked_neg().ok_or(BackendError::Overflow)
+        } else {
+            Ok(delta)
+        }
+    }
+}
+
+impl CommandExecute for IncrBy {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = self
+            .delta()
+            .and_then(|delta| backend.incr_by(&self.key, delta));
+
+        match result {
+            Ok(value) => Ok(value.into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for IncrBy {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let (by, negate) = match command.as_str() {
+            "INCR" => (false, false),
+            "DECR" => (false, true),
+            "INCRBY" => (true, false),
+            "DECRBY" => (true, true),
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let delta = if by {
+            parse.next_string()?
+        } else {
+            "1".to_string()
+        };
+        parse.finish()?;
+
+        Ok(Self { key, delta, negate })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+    use crate::resp::SimpleError;
+
+    #[test]
+    fn test_incr_family() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["incr", "key"]), 1.into());
+        assert_eq!(execute(&backend, &["incrby", "key", "10"]), 11.into());
+        assert_eq!(execute(&backend, &["decr", "key"]), 10.into());
+        assert_eq!(execute(&backend, &["decrby", "key", "-5"]), 15.into());
+        assert_eq!(backend.get("key"), Some(b"15".into()));
+    }
+
+    #[test]
+    fn test_incr_errors() {
+        let backend = Backend::new();
+        let not_integer = Frame::SimpleError(SimpleError::new(
+            "ERR value is not an integer or out of range",
+        ));
+
+        assert_eq!(execute(&backend, &["incrby", "key", "1.5"]), not_integer);
+        assert_eq!(
+            execute(&backend, &["decrby", "key", &i64::MIN.to_string()]),
+            Frame::SimpleError(SimpleError::new(
+                "ERR increment or decrement would overflow"
+            ))
+        );
+
+        backend.set("text", "abc");
+        assert_eq!(execute(&backend, &["incr", "text"]), not_integer);
+    }
+
+    #[test]
+    fn test_incr_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"incrby".into(), b"key".into()].into();
+        let actual: Result<IncrBy> = frame.try_into();
+        assert!(actual.is_err());
+
+        let frame: Frame = vec![b"incr".into(), b"key".into(), b"1".into()].into();
+        let actual: Result<IncrBy> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
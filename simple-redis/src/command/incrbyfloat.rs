@@ -0,0 +1,67 @@
+use anyhow::Result;
+
+use super::{parse_float, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "incrbyfloat")]
+pub struct IncrByFloat {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) delta: String,
+}
+
+impl CommandExecute for IncrByFloat {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result =
+            parse_float(&self.delta).and_then(|delta| backend.incr_by_float(&self.key, delta));
+
+        match result {
+            Ok(value) => Ok(value.into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+    use crate::resp::SimpleError;
+
+    #[test]
+    fn test_incrbyfloat() {
+        let backend = Backend::new();
+        backend.set("key", "10.50");
+
+        assert_eq!(
+            execute(&backend, &["incrbyfloat", "key", "0.1"]),
+            b"10.6".into()
+        );
+        assert_eq!(
+            execute(&backend, &["incrbyfloat", "key", "-5"]),
+            b"5.6".into()
+        );
+        assert_eq!(
+            execute(&backend, &["incrbyfloat", "new", "5.0e3"]),
+            b"5000".into()
+        );
+    }
+
+    #[test]
+    fn test_incrbyfloat_errors() {
+        let backend = Backend::new();
+        let not_float = Frame::SimpleError(SimpleError::new("ERR value is not a valid float"));
+
+        assert_eq!(execute(&backend, &["incrbyfloat", "key", "abc"]), not_float);
+        assert_eq!(execute(&backend, &["incrbyfloat", "key", "nan"]), not_float);
+        assert_eq!(
+            execute(&backend, &["incrbyfloat", "key", "inf"]),
+            Frame::SimpleError(SimpleError::new(
+                "ERR increment would produce NaN or Infinity"
+            ))
+        );
+        assert_eq!(backend.get("key"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::{Backend, ConnectionKind};
+use crate::resp::frame::Frame;
+use crate::utils::resident_memory;
+
+// sections in the order INFO prints them, all of them are part of the default set
+const SECTIONS: &[&str] = &[
+    "server",
+    "clients",
+    "memory",
+    "persistence",
+    "stats",
+    "keyspace",
+];
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "info")]
+pub struct Info {
+    #[arg(variadic, min = 0)]
+    pub(crate) sections: Vec<String>,
+}
+
+impl Info {
+    fn wants(&self, section: &str) -> bool {
+        self.sections.is_empty()
+            || self.sections.iter().any(|wanted| {
+                wanted.eq_ignore_ascii_case(section)
+                    || ["all", "default", "everything"]
+                        .iter()
+                        .any(|all| wanted.eq_ignore_ascii_case(all))
+            })
+    }
+}
+
+fn section(backend: &Backend, name: &str) -> Vec<String> {
+    match name {
+        "server" => vec![
+            format!("redis_version:{}", env!("CARGO_PKG_VERSION")),
+            "redis_mode:standalone".to_string(),
+            format!("arch_bits:{}", usize::BITS),
+            format!("process_id:{}", std::process::id()),
+        ],
+        "clients" => {
+            let clients = backend.clients().list();
+            let mut lines = vec![format!("connected_clients:{}", clients.len())];
+            for kind in [
+                ConnectionKind::Tcp,
+                ConnectionKind::Tls,
+                ConnectionKind::Unix,
+            ] {
+                let count = clients.iter().filter(|client| client.kind == kind).count();
+                lines.push(format!("connected_clients_{}:{}", kind.as_str(), count));
+            }
+            let output = clients.iter().map(|client| client.output_memory()).max();
+            lines.push(format!("maxclients:{}", backend.clients().maxclients()));
+            lines.push(format!(
+                "client_recent_max_output_buffer:{}",
+                output.unwrap_or_default()
+            ));
+            lines.push(format!("tracking_clients:{}", backend.tracking().clients()));
+            lines
+        }
+        "memory" => vec![
+            format!("used_memory_rss:{}", resident_memory().unwrap_or_default()),
+            format!("lazyfree_pending_objects:{}", backend.lazyfree().pending()),
+            format!("lazyfreed_objects:{}", backend.lazyfree().freed_objects()),
+        ],
+        "persistence" => {
+            let stats = backend.stats();
+            let status = if stats.last_save_ok() { "ok" } else { "err" };
+            vec![
+                format!(
+                    "rdb_changes_since_last_save:{}",
+                    stats.changes_since_last_save()
+                ),
+                format!("rdb_last_save_time:{}", stats.last_save()),
+                format!("rdb_last_bgsave_status:{}", status),
+            ]
+        }
+        "stats" => {
+            let stats = backend.stats();
+            vec![
+                format!("total_commands_processed:{}", stats.commands_processed()),
+                format!("total_net_input_bytes:{}", stats.net_input_bytes()),
+                format!("total_net_output_bytes:{}", stats.net_output_bytes()),
+                format!("expired_keys:{}", stats.expired_keys()),
+                format!("expired_subkeys:{}", stats.expired_subkeys()),
+                format!("evicted_keys:{}", stats.evicted_keys()),
+                format!("rejected_connections:{}", stats.rejected_connections()),
+                format!(
+                    "client_output_buffer_limit_disconnections:{}",
+                    stats.output_limit_disconnections()
+                ),
+                format!("tracking_total_keys:{}", backend.tracking().keys()),
+                format!("tracking_total_prefixes:{}", backend.tracking().prefixes()),
+            ]
+        }
+        "keyspace" => backend
+            .keyspace()
+            .into_iter()
+            .map(|(db, keys, expires)| {
+                format!("db{}:keys={},expires={},avg_ttl=0", db, keys, expires)
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl CommandExecute for Info {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let mut info = String::new();
+
+        for name in SECTIONS.iter().filter(|name| self.wants(name)) {
+            if !info.is_empty() {
+                info.push_str("\r\n");
+            }
+            let mut title = name.to_string();
+            title[..1].make_ascii_uppercase();
+            info.push_str(&format!("# {}\r\n", title));
+            for line in section(&backend, name) {
+                info.push_str(&line);
+                info.push_str("\r\n");
+            }
+        }
+
+        Ok(info.as_bytes().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    fn info(backend: &Backend, args: &[&str]) -> String {
+        match execute(backend, args) {
+            Frame::BulkString(info) => String::from_utf8(info.inner).unwrap(),
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        }
+    }
+
+    #[test]
+    fn test_info_keyspace() {
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.select(2).unwrap();
+        backend.set("b", "2");
+        backend.set("c", "3");
+
+        assert_eq!(
+            info(&backend, &["info", "keyspace"]),
+            "# Keyspace\r\ndb0:keys=1,expires=0,avg_ttl=0\r\ndb2:keys=2,expires=0,avg_ttl=0\r\n"
+        );
+
+        let all = info(&backend, &["info"]);
+        assert!(all.starts_with("# Server\r\n"));
+        assert!(all.contains("\r\n\r\n# Keyspace\r\n"));
+
+        assert_eq!(info(&backend, &["info", "nope"]), "");
+    }
+
+    #[test]
+    fn test_info_clients() {
+        let backend = Backend::new();
+        let _tcp = backend
+            .connect(ConnectionKind::Tcp, "127.0.0.1:1".into(), "".into())
+            .unwrap();
+        let _unix = backend
+            .connect(ConnectionKind::Unix, "/tmp/s:0".into(), "".into())
+            .unwrap();
+
+        assert_eq!(
+            info(&backend, &["info", "clients"]),
+            "# Clients\r\nconnected_clients:2\r\nconnected_clients_tcp:1\r\n\
+             connected_clients_tls:0\r\nconnected_clients_unix:1\r\nmaxclients:10000\r\n\
+             client_recent_max_output_buffer:0\r\ntracking_clients:0\r\n"
+        );
+    }
+}
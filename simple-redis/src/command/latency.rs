@@ -0,0 +1,149 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum LatencySubcommand {
+    Latest,
+    History(String),
+    Reset(Vec<String>),
+}
+
+#[derive(Debug)]
+pub struct Latency {
+    pub(crate) subcommand: LatencySubcommand,
+}
+
+impl CommandExecute for Latency {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match &self.subcommand {
+            LatencySubcommand::Latest => Ok(backend
+                .latency()
+                .latest()
+                .into_iter()
+                .filter_map(|(name, event)| {
+                    event.latest().map(|latest| {
+                        vec![
+                            name.as_bytes().into(),
+                            (latest.timestamp as i64).into(),
+                            (latest.latency as i64).into(),
+                            (event.max as i64).into(),
+                        ]
+                        .into()
+                    })
+                })
+                .collect::<Vec<Frame>>()
+                .into()),
+            LatencySubcommand::History(event) => Ok(backend
+                .latency()
+                .history(event)
+                .into_iter()
+                .map(|sample| {
+                    vec![
+                        (sample.timestamp as i64).into(),
+                        (sample.latency as i64).into(),
+                    ]
+                    .into()
+                })
+                .collect::<Vec<Frame>>()
+                .into()),
+            LatencySubcommand::Reset(events) => Ok((backend.latency().reset(events) as i64).into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for Latency {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "LATENCY" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let subcommand = match parse.next_string()?.to_uppercase().as_str() {
+            "LATEST" => LatencySubcommand::Latest,
+            "HISTORY" => LatencySubcommand::History(parse.next_string()?),
+            "RESET" => {
+                let mut events = Vec::with_capacity(parse.len());
+                while parse.len() > 0 {
+                    events.push(parse.next_string()?);
+                }
+                LatencySubcommand::Reset(events)
+            }
+            subcommand => anyhow::bail!("Unknown LATENCY subcommand '{}'", subcommand),
+        };
+        parse.finish()?;
+
+        Ok(Self { subcommand })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_latency_try_from_frame() {
+        let frame: Frame = vec![b"latency".into(), b"history".into(), b"command".into()].into();
+        let cmd: Latency = frame.try_into().unwrap();
+        assert_eq!(
+            cmd.subcommand,
+            LatencySubcommand::History("command".to_string())
+        );
+
+        let frame: Frame =
+            vec![b"latency".into(), b"reset".into(), b"a".into(), b"b".into()].into();
+        let cmd: Latency = frame.try_into().unwrap();
+        assert_eq!(
+            cmd.subcommand,
+            LatencySubcommand::Reset(vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_latency_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"latency".into(), b"history".into()].into();
+        let actual: Result<Latency> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_latency_execute() {
+        let config = Config {
+            latency_monitor_threshold: 1,
+            ..Default::default()
+        };
+        let backend = Backend::with_config(&config);
+        backend.latency().add_sample("command", 42);
+
+        let cmd = Latency {
+            subcommand: LatencySubcommand::Latest,
+        };
+        match cmd.execute(backend.clone()).unwrap() {
+            Frame::Array(array) => {
+                assert_eq!(array.len(), 1);
+                match &array[0] {
+                    Frame::Array(event) => {
+                        assert_eq!(event[0], b"command".into());
+                        assert_eq!(event[2], 42.into());
+                        assert_eq!(event[3], 42.into());
+                    }
+                    _ => panic!("Expected Array"),
+                }
+            }
+            _ => panic!("Expected Array"),
+        }
+
+        let cmd = Latency {
+            subcommand: LatencySubcommand::Reset(vec![]),
+        };
+        assert_eq!(cmd.execute(backend).unwrap(), 1.into());
+    }
+}
@@ -0,0 +1,311 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct Lcs {
+    pub(crate) key1: String,
+    pub(crate) key2: String,
+    pub(crate) len: bool,
+    pub(crate) idx: bool,
+    pub(crate) min_match_len: Option<String>,
+    pub(crate) with_match_len: bool,
+}
+
+impl CommandExecute for Lcs {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        if self.len && self.idx {
+            return Ok(BackendError::Other(
+                "If you want both the length and indexes, please just use IDX.".to_string(),
+            )
+            .into());
+        }
+
+        let min_match_len = match self.min_match_len.as_deref().map(parse_int) {
+            Some(Ok(n)) => n.max(0) as usize,
+            Some(Err(e)) => return Ok(e.into()),
+            None => 0,
+        };
+
+        let a = backend
+            .get_string(&self.key1)
+            .map(|v| v.into_bytes())
+            .unwrap_or_default();
+        let b = backend
+            .get_string(&self.key2)
+            .map(|v| v.into_bytes())
+            .unwrap_or_default();
+        let table = LcsTable::new(&a, &b);
+
+        if self.len {
+            return Ok((table.len() as i64).into());
+        }
+
+        if !self.idx {
+            return Ok(table.sequence().as_slice().into());
+        }
+
+        let matches = table
+            .matches()
+            .into_iter()
+            .filter(|m| m.len() >= min_match_len)
+            .map(|m| {
+                let mut reply: Vec<Frame> = vec![
+                    vec![(m.a.0 as i64).into(), (m.a.1 as i64).into()].into(),
+                    vec![(m.b.0 as i64).into(), (m.b.1 as i64).into()].into(),
+                ];
+                if self.with_match_len {
+                    reply.push((m.len() as i64).into());
+                }
+                reply.into()
+            })
+            .collect::<Vec<Frame>>();
+
+        Ok(vec![
+            b"matches".into(),
+            matches.into(),
+            b"len".into(),
+            (table.len() as i64).into(),
+        ]
+        .into())
+    }
+}
+
+impl TryFrom<Frame> for Lcs {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "LCS" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key1 = parse.next_string()?;
+        let key2 = parse.next_string()?;
+        let mut lcs = Self {
+            key1,
+            key2,
+            len: false,
+            idx: false,
+            min_match_len: None,
+            with_match_len: false,
+        };
+
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "LEN" => lcs.len = true,
+                "IDX" => lcs.idx = true,
+                "MINMATCHLEN" => lcs.min_match_len = Some(parse.next_string()?),
+                "WITHMATCHLEN" => lcs.with_match_len = true,
+                _ => anyhow::bail!("syntax error"),
+            }
+        }
+
+        Ok(lcs)
+    }
+}
+
+// inclusive ranges of a common run in both strings
+#[derive(Debug, PartialEq, Eq)]
+struct LcsMatch {
+    a: (usize, usize),
+    b: (usize, usize),
+}
+
+impl LcsMatch {
+    fn len(&self) -> usize {
+        self.a.1 - self.a.0 + 1
+    }
+}
+
+// dynamic programming table, cell (i, j) is the lcs length of a[..i] and b[..j]
+struct LcsTable<'a> {
+    a: &'a [u8],
+    b: &'a [u8],
+    cells: Vec<u32>,
+}
+
+impl<'a> LcsTable<'a> {
+    fn new(a: &'a [u8], b: &'a [u8]) -> Self {
+        let width = b.len() + 1;
+        let mut cells = vec![0u32; (a.len() + 1) * width];
+
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                cells[i * width + j] = if a[i - 1] == b[j - 1] {
+                    cells[(i - 1) * width + j - 1] + 1
+                } else {
+                    cells[(i - 1) * width + j].max(cells[i * width + j - 1])
+                };
+            }
+        }
+
+        Self { a, b, cells }
+    }
+
+    fn get(&self, i: usize, j: usize) -> u32 {
+        self.cells[i * (self.b.len() + 1) + j]
+    }
+
+    fn len(&self) -> usize {
+        self.get(self.a.len(), self.b.len()) as usize
+    }
+
+    fn sequence(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.len());
+        let (mut i, mut j) = (self.a.len(), self.b.len());
+
+        while i > 0 && j > 0 {
+            if self.a[i - 1] == self.b[j - 1] {
+                result.push(self.a[i - 1]);
+                i -= 1;
+                j -= 1;
+            } else if self.get(i - 1, j) > self.get(i, j - 1) {
+                i -= 1;
+            } else {
+                j -= 1;
+            }
+        }
+
+        result.reverse();
+        result
+    }
+
+    // walks the table backwards like redis does, so matches come from the end of the strings
+    fn matches(&self) -> Vec<LcsMatch> {
+        let mut matches = Vec::new();
+        let mut current: Option<LcsMatch> = None;
+        let (mut i, mut j) = (self.a.len(), self.b.len());
+
+        while i > 0 && j > 0 {
+            let mut emit = false;
+
+            if self.a[i - 1] == self.b[j - 1] {
+                match &mut current {
+                    None => {
+                        current = Some(LcsMatch {
+                            a: (i - 1, i - 1),
+                            b: (j - 1, j - 1),
+                        })
+                    }
+                    // extend the run backwards while it stays contiguous
+                    Some(m) if m.a.0 == i && m.b.0 == j => {
+                        m.a.0 -= 1;
+                        m.b.0 -= 1;
+                    }
+                    Some(_) => emit = true,
+                }
+
+                if current.as_ref().is_some_and(|m| m.a.0 == 0 || m.b.0 == 0) {
+                    emit = true;
+                }
+
+                i -= 1;
+                j -= 1;
+            } else {
+                if self.get(i - 1, j) > self.get(i, j - 1) {
+                    i -= 1;
+                } else {
+                    j -= 1;
+                }
+
+                emit = current.is_some();
+            }
+
+            if emit {
+                matches.extend(current.take());
+            }
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    fn setup() -> Backend {
+        let backend = Backend::new();
+        backend.set("key1", "ohmytext");
+        backend.set("key2", "mynewtext");
+        backend
+    }
+
+    #[test]
+    fn test_lcs() {
+        let backend = setup();
+
+        assert_eq!(
+            execute(&backend, &["lcs", "key1", "key2"]),
+            b"mytext".into()
+        );
+        assert_eq!(execute(&backend, &["lcs", "key1", "key2", "len"]), 6.into());
+        assert_eq!(
+            execute(&backend, &["lcs", "key1", "missing", "len"]),
+            0.into()
+        );
+    }
+
+    #[test]
+    fn test_lcs_idx() {
+        let backend = setup();
+
+        let range = |a: i64, b: i64| -> Frame { vec![a.into(), b.into()].into() };
+        assert_eq!(
+            execute(&backend, &["lcs", "key1", "key2", "idx"]),
+            vec![
+                b"matches".into(),
+                vec![
+                    vec![range(4, 7), range(5, 8)].into(),
+                    vec![range(2, 3), range(0, 1)].into(),
+                ]
+                .into(),
+                b"len".into(),
+                6.into(),
+            ]
+            .into()
+        );
+
+        assert_eq!(
+            execute(
+                &backend,
+                &[
+                    "lcs",
+                    "key1",
+                    "key2",
+                    "idx",
+                    "minmatchlen",
+                    "4",
+                    "withmatchlen"
+                ]
+            ),
+            vec![
+                b"matches".into(),
+                vec![vec![range(4, 7), range(5, 8), 4.into()].into()].into(),
+                b"len".into(),
+                6.into(),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_lcs_len_and_idx() {
+        let backend = setup();
+
+        assert_eq!(
+            execute(&backend, &["lcs", "key1", "key2", "len", "idx"]),
+            BackendError::Other(
+                "If you want both the length and indexes, please just use IDX.".to_string()
+            )
+            .into()
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "llen")]
+pub struct LLen {
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for LLen {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.llen(&self.key) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_llen() {
+        let backend = Backend::new();
+        execute(&backend, &["rpush", "list", "a", "b"]);
+
+        assert_eq!(execute(&backend, &["llen", "list"]), 2.into());
+        assert_eq!(execute(&backend, &["llen", "missing"]), 0.into());
+    }
+}
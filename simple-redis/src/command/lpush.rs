@@ -0,0 +1,67 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "lpush")]
+pub struct LPush {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic)]
+    pub(crate) elements: Vec<Vec<u8>>,
+}
+
+impl CommandExecute for LPush {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.push(&self.key, &self.elements, false) {
+            Ok(len) => Ok((len as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+// LPUSH at the tail
+#[derive(Debug, RedisCommand)]
+#[command(name = "rpush")]
+pub struct RPush {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic)]
+    pub(crate) elements: Vec<Vec<u8>>,
+}
+
+impl CommandExecute for RPush {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.push(&self.key, &self.elements, true) {
+            Ok(len) => Ok((len as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_lpush_and_rpush() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["lpush", "list", "b", "a"]), 2.into());
+        assert_eq!(execute(&backend, &["rpush", "list", "c"]), 3.into());
+        assert_eq!(
+            execute(&backend, &["lrange", "list", "0", "-1"]),
+            vec![b"a".into(), b"b".into(), b"c".into()].into()
+        );
+
+        execute(&backend, &["set", "str", "x"]);
+        assert_eq!(
+            execute(&backend, &["rpush", "str", "a"]),
+            BackendError::WrongType.into()
+        );
+    }
+}
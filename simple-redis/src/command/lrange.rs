@@ -0,0 +1,56 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "lrange")]
+pub struct LRange {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) start: String,
+    pub(crate) stop: String,
+}
+
+impl CommandExecute for LRange {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let range = parse_int(&self.start).and_then(|start| Ok((start, parse_int(&self.stop)?)));
+
+        match range {
+            Ok((start, stop)) => Ok(backend
+                .lrange(&self.key, start, stop)
+                .iter()
+                .map(|element| element.as_slice().into())
+                .collect::<Vec<Frame>>()
+                .into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_lrange() {
+        let backend = Backend::new();
+        execute(&backend, &["rpush", "list", "a", "b", "c"]);
+
+        assert_eq!(
+            execute(&backend, &["lrange", "list", "-2", "100"]),
+            vec![b"b".into(), b"c".into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["lrange", "missing", "0", "-1"]),
+            Vec::<Frame>::new().into()
+        );
+        assert_eq!(
+            execute(&backend, &["lrange", "list", "x", "1"]),
+            BackendError::NotInteger.into()
+        );
+    }
+}
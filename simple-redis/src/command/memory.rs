@@ -0,0 +1,172 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, NULL};
+use crate::backend::{Backend, MEMORY_USAGE_SAMPLES};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum MemorySubcommand {
+    // the key and how many elements of an aggregate to look at, 0 for all of them
+    Usage(String, usize),
+    Stats,
+    Doctor,
+}
+
+#[derive(Debug)]
+pub struct Memory {
+    pub(crate) subcommand: MemorySubcommand,
+}
+
+impl CommandExecute for Memory {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match &self.subcommand {
+            MemorySubcommand::Usage(key, samples) => Ok(backend
+                .memory_usage(key, *samples)
+                .map_or(NULL.clone(), |bytes| (bytes as i64).into())),
+            MemorySubcommand::Stats => {
+                let stats = backend.memory_stats();
+                let mut frames: Vec<Frame> = Vec::new();
+                if let Some(rss) = stats.rss {
+                    frames.push(b"total.allocated".into());
+                    frames.push((rss as i64).into());
+                }
+                for (db, keys, bytes) in &stats.dbs {
+                    frames.push(format!("db.{}", db).as_bytes().into());
+                    frames.push(
+                        vec![
+                            b"keys.count".into(),
+                            (*keys as i64).into(),
+                            b"dataset.bytes".into(),
+                            (*bytes as i64).into(),
+                        ]
+                        .into(),
+                    );
+                }
+                frames.push(b"keys.count".into());
+                frames.push((stats.keys as i64).into());
+                frames.push(b"keys.bytes-per-key".into());
+                frames.push((stats.dataset.checked_div(stats.keys).unwrap_or(0) as i64).into());
+                frames.push(b"dataset.bytes".into());
+                frames.push((stats.dataset as i64).into());
+                if let Some(rss) = stats.rss.filter(|rss| *rss > 0) {
+                    frames.push(b"dataset.percentage".into());
+                    frames.push(
+                        (stats.dataset as f64 * 100.0 / rss as f64)
+                            .to_string()
+                            .as_bytes()
+                            .into(),
+                    );
+                }
+                Ok(frames.into())
+            }
+            MemorySubcommand::Doctor => Ok(backend.memory_doctor().as_bytes().into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for Memory {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "MEMORY" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let subcommand = match parse.next_string()?.to_uppercase().as_str() {
+            "USAGE" => {
+                let key = parse.next_string()?;
+                let mut samples = MEMORY_USAGE_SAMPLES;
+                if parse.len() > 0 {
+                    if !parse.next_string()?.eq_ignore_ascii_case("SAMPLES") {
+                        anyhow::bail!("syntax error");
+                    }
+                    samples = match parse.next_int()? {
+                        samples if samples < 0 => anyhow::bail!("syntax error"),
+                        samples => samples as usize,
+                    };
+                }
+                MemorySubcommand::Usage(key, samples)
+            }
+            "STATS" => MemorySubcommand::Stats,
+            "DOCTOR" => MemorySubcommand::Doctor,
+            subcommand => anyhow::bail!("Unknown MEMORY subcommand '{}'", subcommand),
+        };
+        parse.finish()?;
+
+        Ok(Self { subcommand })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_memory_try_from_frame() {
+        let frame: Frame = vec![b"memory".into(), b"usage".into(), b"key".into()].into();
+        let cmd: Memory = frame.try_into().unwrap();
+        assert_eq!(
+            cmd.subcommand,
+            MemorySubcommand::Usage("key".to_string(), MEMORY_USAGE_SAMPLES)
+        );
+
+        let frame: Frame = vec![
+            b"memory".into(),
+            b"usage".into(),
+            b"key".into(),
+            b"samples".into(),
+            b"0".into(),
+        ]
+        .into();
+        let cmd: Memory = frame.try_into().unwrap();
+        assert_eq!(
+            cmd.subcommand,
+            MemorySubcommand::Usage("key".to_string(), 0)
+        );
+
+        let frame: Frame = vec![
+            b"memory".into(),
+            b"usage".into(),
+            b"key".into(),
+            b"x".into(),
+        ]
+        .into();
+        let actual: Result<Memory> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_memory_subcommands() {
+        let backend = Backend::new();
+        backend.set("key", "x".repeat(1000));
+
+        let usage = match execute(&backend, &["memory", "usage", "key"]) {
+            Frame::Integer(usage) => usage.inner,
+            frame => panic!("Expected Integer, got {:?}", frame),
+        };
+        assert!(usage > 1000);
+        assert_eq!(execute(&backend, &["memory", "usage", "missing"]), *NULL);
+
+        let stats = match execute(&backend, &["memory", "stats"]) {
+            Frame::Array(stats) => stats.inner,
+            frame => panic!("Expected Array, got {:?}", frame),
+        };
+        let position = stats
+            .iter()
+            .position(|frame| *frame == Frame::from(b"keys.count"))
+            .unwrap();
+        assert_eq!(stats[position + 1], Frame::from(1));
+        assert!(stats.contains(&Frame::from(b"db.0")));
+
+        let doctor = match execute(&backend, &["memory", "doctor"]) {
+            Frame::BulkString(doctor) => String::from_utf8(doctor.inner).unwrap(),
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        };
+        assert!(doctor.starts_with("Hi Sam"));
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "mget")]
+pub struct Mget {
+    #[arg(key, variadic)]
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for Mget {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let values = backend
+            .mget(&self.keys)
+            .into_iter()
+            .map(|value| value.map_or_else(|| NULL.clone(), Into::into))
+            .collect::<Vec<Frame>>();
+
+        Ok(values.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_mget() {
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.set("b", "hello");
+
+        assert_eq!(
+            execute(&backend, &["mget", "a", "missing", "b"]),
+            vec![b"1".into(), NULL.clone(), b"hello".into()].into()
+        );
+    }
+
+    #[test]
+    fn test_mget_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"mget".into()].into();
+        let actual: Result<Mget> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,479 @@
+use std::io::{self, Cursor};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{error::Elapsed, timeout};
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute, OK};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+use crate::resp::{RespDecode, RespEncode, RespError};
+
+// what a timeout of 0 stands for, in milliseconds
+const MIGRATE_DEFAULT_TIMEOUT: i64 = 1000;
+
+#[derive(Debug)]
+pub struct Migrate {
+    pub(crate) host: String,
+    pub(crate) port: String,
+    // empty when the keys follow KEYS
+    pub(crate) key: String,
+    pub(crate) db: String,
+    pub(crate) timeout: String,
+    pub(crate) copy: bool,
+    pub(crate) replace: bool,
+    // the username, if any, and the password the target is authenticated with
+    pub(crate) auth: Option<(Option<String>, String)>,
+    pub(crate) keys: Vec<String>,
+}
+
+// the keys that exist here and the commands that send them to the target
+struct Transfer {
+    port: u16,
+    timeout: Duration,
+    // what each key was dumped as, so a key written meanwhile is not deleted
+    payloads: Vec<(String, Vec<u8>)>,
+    requests: Vec<Frame>,
+}
+
+impl Migrate {
+    fn keys(&self) -> Vec<&String> {
+        if self.key.is_empty() {
+            self.keys.iter().collect()
+        } else {
+            vec![&self.key]
+        }
+    }
+
+    // the commands sent to the target, one RESTORE per key that exists here with the time it has
+    // left to live
+    fn requests(&self, db: i64, payloads: &[(&String, Vec<u8>, i64)]) -> Vec<Frame> {
+        let mut requests = Vec::with_capacity(payloads.len() + 2);
+        match &self.auth {
+            Some((Some(username), password)) => {
+                requests.push(args(&[b"AUTH", username.as_bytes(), password.as_bytes()]))
+            }
+            Some((None, password)) => requests.push(args(&[b"AUTH", password.as_bytes()])),
+            None => {}
+        }
+        requests.push(args(&[b"SELECT", db.to_string().as_bytes()]));
+        for (key, payload, ttl) in payloads {
+            let ttl = ttl.to_string();
+            let mut restore = vec![&b"RESTORE"[..], key.as_bytes(), ttl.as_bytes(), payload];
+            if self.replace {
+                restore.push(b"REPLACE");
+            }
+            requests.push(args(&restore));
+        }
+        requests
+    }
+
+    // the reply comes right away when the arguments are wrong or none of the keys exists
+    fn transfer(&self, backend: &Backend) -> Result<Transfer, Frame> {
+        let numbers = parse_int(&self.port)
+            .and_then(|port| u16::try_from(port).map_err(|_| BackendError::NotInteger))
+            .and_then(|port| Ok((port, parse_int(&self.db)?, parse_int(&self.timeout)?)));
+        let (port, db, timeout) = numbers.map_err(Frame::from)?;
+        let timeout = match timeout {
+            timeout if timeout <= 0 => MIGRATE_DEFAULT_TIMEOUT,
+            timeout => timeout,
+        };
+
+        let keys = self.keys();
+        // like redis, a key about to expire still gets a millisecond on the target
+        let payloads: Vec<(&String, Vec<u8>, i64)> = keys
+            .iter()
+            .filter_map(|key| {
+                let payload = backend.dump(key)?;
+                let ttl = match backend.pttl(key) {
+                    ttl if ttl < 0 => 0,
+                    ttl => ttl.max(1),
+                };
+                Some((*key, payload, ttl))
+            })
+            .collect();
+        if payloads.is_empty() {
+            return Err("NOKEY".into());
+        }
+
+        let requests = self.requests(db, &payloads);
+        Ok(Transfer {
+            port,
+            timeout: Duration::from_millis(timeout as u64),
+            payloads: payloads
+                .into_iter()
+                .map(|(key, payload, _)| (key.to_string(), payload))
+                .collect(),
+            requests,
+        })
+    }
+
+    // like redis, the keys the target restored are deleted here even when another one failed, a
+    // key written while the target was restoring it keeps the new value
+    fn finish(
+        &self,
+        backend: &Backend,
+        payloads: &[(String, Vec<u8>)],
+        replies: &[Frame],
+    ) -> Frame {
+        let (setup, restores) = replies.split_at(replies.len() - payloads.len());
+        let mut error = setup.iter().find_map(target_error);
+        if error.is_none() {
+            for ((key, payload), reply) in payloads.iter().zip(restores) {
+                match target_error(reply) {
+                    Some(e) => {
+                        error.get_or_insert(e);
+                    }
+                    None if !self.copy => {
+                        backend.delete_dumped(key, payload);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        match error {
+            Some(error) => {
+                BackendError::Other(format!("Target instance replied with error: {}", error)).into()
+            }
+            None => OK.clone(),
+        }
+    }
+
+    // the connection to the target is awaited, so the runtime of the client keeps serving others
+    // while it waits
+    pub(crate) async fn execute_async(&self, backend: Backend) -> Result<Frame> {
+        let transfer = match self.transfer(&backend) {
+            Ok(transfer) => transfer,
+            Err(reply) => return Ok(reply),
+        };
+        match exchange(
+            &self.host,
+            transfer.port,
+            transfer.timeout,
+            &transfer.requests,
+        )
+        .await
+        {
+            Ok(replies) => Ok(self.finish(&backend, &transfer.payloads, &replies)),
+            Err(e) => Ok(BackendError::Io(e.to_string()).into()),
+        }
+    }
+}
+
+// MIGRATE waits on the network, which a synchronous execution could only do by blocking its
+// thread, see `RespRequest::execute_async`
+impl CommandExecute for Migrate {
+    fn execute(&self, _backend: Backend) -> Result<Frame> {
+        Ok(BackendError::Other("MIGRATE can only run on a client connection".to_string()).into())
+    }
+}
+
+fn target_error(reply: &Frame) -> Option<&str> {
+    match reply {
+        Frame::SimpleError(error) => Some(&error.inner),
+        _ => None,
+    }
+}
+
+fn timed_out(_: Elapsed) -> io::Error {
+    io::ErrorKind::TimedOut.into()
+}
+
+fn args(args: &[&[u8]]) -> Frame {
+    args.iter()
+        .map(|arg| (*arg).into())
+        .collect::<Vec<Frame>>()
+        .into()
+}
+
+// sends every request in one go, then reads as many replies; like redis, the server waits for
+// the target at most `timeout` for each step
+async fn exchange(
+    host: &str,
+    port: u16,
+    wait: Duration,
+    requests: &[Frame],
+) -> io::Result<Vec<Frame>> {
+    let mut stream = timeout(wait, TcpStream::connect((host, port)))
+        .await
+        .map_err(timed_out)??;
+
+    let buf: Vec<u8> = requests
+        .iter()
+        .flat_map(|request| request.encode())
+        .collect();
+    timeout(wait, stream.write_all(&buf))
+        .await
+        .map_err(timed_out)??;
+
+    let mut buf = Vec::new();
+    let mut replies = Vec::with_capacity(requests.len());
+    while replies.len() < requests.len() {
+        replies.push(read_reply(&mut stream, &mut buf, wait).await?);
+    }
+    Ok(replies)
+}
+
+async fn read_reply(
+    stream: &mut TcpStream,
+    buf: &mut Vec<u8>,
+    wait: Duration,
+) -> io::Result<Frame> {
+    loop {
+        let mut cursor = Cursor::new(&buf[..]);
+        match Frame::decode(&mut cursor) {
+            Ok(frame) => {
+                let len = cursor.position() as usize;
+                buf.drain(..len);
+                return Ok(frame);
+            }
+            Err(RespError::Incomplete) => {}
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+
+        let mut chunk = [0; 4096];
+        let n = timeout(wait, stream.read(&mut chunk))
+            .await
+            .map_err(timed_out)??;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+impl TryFrom<Frame> for Migrate {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "MIGRATE" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let mut migrate = Self {
+            host: parse.next_string()?,
+            port: parse.next_string()?,
+            key: parse.next_string()?,
+            db: parse.next_string()?,
+            timeout: parse.next_string()?,
+            copy: false,
+            replace: false,
+            auth: None,
+            keys: Vec::new(),
+        };
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "COPY" => migrate.copy = true,
+                "REPLACE" => migrate.replace = true,
+                "AUTH" => migrate.auth = Some((None, parse.next_string()?)),
+                "AUTH2" => {
+                    let username = parse.next_string()?;
+                    migrate.auth = Some((Some(username), parse.next_string()?));
+                }
+                "KEYS" => {
+                    if !migrate.key.is_empty() {
+                        anyhow::bail!(
+                            "When using MIGRATE KEYS option, the key argument must be set to the empty string"
+                        );
+                    }
+                    while parse.len() > 0 {
+                        migrate.keys.push(parse.next_string()?);
+                    }
+                }
+                _ => anyhow::bail!("syntax error"),
+            }
+        }
+
+        Ok(migrate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_migrate_try_from_frame() {
+        let frame: Frame = [
+            "migrate",
+            "localhost",
+            "6380",
+            "",
+            "0",
+            "100",
+            "copy",
+            "keys",
+            "a",
+            "b",
+        ]
+        .iter()
+        .map(|arg| arg.as_bytes().into())
+        .collect::<Vec<Frame>>()
+        .into();
+        let cmd: Migrate = frame.try_into().unwrap();
+        assert!(cmd.copy);
+        assert!(!cmd.replace);
+        assert_eq!(cmd.keys(), vec!["a", "b"]);
+
+        let frame: Frame = [
+            "migrate",
+            "localhost",
+            "6380",
+            "key",
+            "0",
+            "100",
+            "keys",
+            "a",
+        ]
+        .iter()
+        .map(|arg| arg.as_bytes().into())
+        .collect::<Vec<Frame>>()
+        .into();
+        let actual: Result<Migrate> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_migrate_sends_ttl() {
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.set("b", "2");
+        backend.set_expire_at("b", Some(u64::MAX));
+        let frame: Frame = [
+            "migrate",
+            "localhost",
+            "6380",
+            "",
+            "0",
+            "100",
+            "keys",
+            "a",
+            "b",
+        ]
+        .iter()
+        .map(|arg| arg.as_bytes().into())
+        .collect::<Vec<Frame>>()
+        .into();
+        let cmd: Migrate = frame.try_into().unwrap();
+
+        let transfer = cmd.transfer(&backend).unwrap();
+        let ttls: Vec<Frame> = transfer.requests[1..]
+            .iter()
+            .map(|request| match request {
+                Frame::Array(args) => args[2].clone(),
+                frame => panic!("Expected Array, got {:?}", frame),
+            })
+            .collect();
+        assert_eq!(ttls[0], Frame::from(&b"0"[..]));
+        assert_ne!(ttls[1], Frame::from(&b"0"[..]));
+    }
+
+    async fn migrate(backend: &Backend, args: &[&str]) -> Frame {
+        let frame: Frame = ["migrate"]
+            .iter()
+            .chain(args)
+            .map(|arg| arg.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into();
+        let migrate: Migrate = frame.try_into().unwrap();
+        migrate.execute_async(backend.clone()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migrate_without_keys_or_target() {
+        let backend = Backend::new();
+        assert_eq!(
+            migrate(&backend, &["127.0.0.1", "1", "missing", "0", "100"]).await,
+            Frame::from("NOKEY")
+        );
+
+        backend.set("key", "value");
+        // nothing listens on port 1, the key stays here
+        assert!(matches!(
+            migrate(&backend, &["127.0.0.1", "1", "key", "0", "100"]).await,
+            Frame::SimpleError(error) if error.inner.starts_with("IOERR")
+        ));
+        assert!(backend.contains_key("key"));
+        assert!(matches!(
+            execute(&backend, &["migrate", "127.0.0.1", "1", "key", "0", "100"]),
+            Frame::SimpleError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_deletes_restored_keys_on_error() {
+        // a target that takes the SELECT and the first RESTORE and refuses the second
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port().to_string();
+        let target = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            for _ in 0..3 {
+                read_reply(&mut stream, &mut buf, Duration::from_secs(5))
+                    .await
+                    .unwrap();
+            }
+            stream
+                .write_all(b"+OK\r\n+OK\r\n-BUSYKEY Target key name already exists.\r\n")
+                .await
+                .unwrap();
+        });
+
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.set("b", "2");
+        let reply = migrate(
+            &backend,
+            &["127.0.0.1", &port, "", "0", "1000", "KEYS", "a", "b"],
+        )
+        .await;
+        target.await.unwrap();
+        assert!(matches!(
+            reply,
+            Frame::SimpleError(error) if error.inner.contains("BUSYKEY")
+        ));
+        assert!(!backend.contains_key("a"));
+        assert!(backend.contains_key("b"));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_keeps_keys_written_meanwhile() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port().to_string();
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.set("b", "2");
+
+        // "a" is written after it was dumped, before the target replies
+        let writer = backend.clone();
+        let target = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            for _ in 0..3 {
+                read_reply(&mut stream, &mut buf, Duration::from_secs(5))
+                    .await
+                    .unwrap();
+            }
+            writer.set("a", "changed");
+            stream.write_all(b"+OK\r\n+OK\r\n+OK\r\n").await.unwrap();
+        });
+
+        let reply = migrate(
+            &backend,
+            &["127.0.0.1", &port, "", "0", "1000", "KEYS", "a", "b"],
+        )
+        .await;
+        target.await.unwrap();
+        assert_eq!(reply, OK.clone());
+        assert_eq!(backend.get_string("a").unwrap().as_bytes(), &b"changed"[..]);
+        assert!(!backend.contains_key("b"));
+    }
+}
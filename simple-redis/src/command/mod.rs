@@ -1,22 +1,100 @@
+mod append;
+mod bitcount;
+mod bitfield;
+mod bitop;
+mod bitpos;
+mod client;
+mod commands;
+mod dbsize;
+mod debug;
+mod del;
+mod dump;
 mod echo;
+mod flush;
+mod geoadd;
+mod geodist;
+mod geohash;
+mod geopos;
+mod geosearch;
 mod get;
+mod getbit;
+mod getdel;
+mod getrange;
+mod getset;
+mod hdel;
+mod hello;
+mod hexists;
+mod hexpire;
 mod hget;
 mod hgetall;
+mod hincrby;
+mod hincrbyfloat;
+mod hkeys;
+mod hlen;
 mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hscan;
 mod hset;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+mod incr;
+mod incrbyfloat;
+mod info;
+mod latency;
+mod lcs;
+mod llen;
+mod lpush;
+mod lrange;
+mod memory;
+mod mget;
+mod migrate;
+mod monitor;
+mod move_key;
+mod mset;
+mod object;
 mod parse;
+mod pfadd;
+mod pfcount;
+mod pfmerge;
+mod restore;
 mod sadd;
+mod scan;
+mod scard;
+mod select;
 mod set;
+mod setbit;
+mod setnx;
+mod setop;
+mod setrange;
+mod shutdown;
+mod sintercard;
 mod sismember;
+mod slowlog;
 mod smembers;
+mod smismember;
+mod smove;
+mod sort;
+mod spop;
+mod srandmember;
+mod srem;
+mod sscan;
+mod strlen;
+mod swapdb;
+mod table;
+mod ttl;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, BackendError, BitUnit, BIT_OFFSET_MAX};
 use crate::resp::frame::Frame;
 use crate::resp::null::Null;
 use anyhow::Result;
 use enum_dispatch::enum_dispatch;
 use lazy_static::lazy_static;
 use parse::Parse;
+pub use simple_redis_derive::RedisCommand;
+use table::check_arity;
+pub use table::{lookup, CommandFlag, CommandSpec, KeySpec, KeyType, RedisCommand, COMMAND_TABLE};
 
 lazy_static! {
     static ref OK: Frame = b"OK".into();
@@ -41,39 +119,215 @@ pub enum Command {
     Sadd(sadd::Sadd),
     Smembers(smembers::Smembers),
     Sismember(sismember::Sismember),
+    Slowlog(slowlog::Slowlog),
+    Latency(latency::Latency),
+    Monitor(monitor::Monitor),
+    Scan(scan::Scan),
+    Shutdown(shutdown::Shutdown),
+    IncrBy(incr::IncrBy),
+    IncrByFloat(incrbyfloat::IncrByFloat),
+    Append(append::Append),
+    Strlen(strlen::Strlen),
+    GetRange(getrange::GetRange),
+    SetRange(setrange::SetRange),
+    Mget(mget::Mget),
+    Mset(mset::Mset),
+    GetSet(getset::GetSet),
+    GetDel(getdel::GetDel),
+    SetNx(setnx::SetNx),
+    Lcs(lcs::Lcs),
+    SetBit(setbit::SetBit),
+    GetBit(getbit::GetBit),
+    BitCount(bitcount::BitCount),
+    BitPos(bitpos::BitPos),
+    BitOp(bitop::BitOp),
+    Bitfield(bitfield::Bitfield),
+    PfAdd(pfadd::PfAdd),
+    PfCount(pfcount::PfCount),
+    PfMerge(pfmerge::PfMerge),
+    GeoAdd(geoadd::GeoAdd),
+    GeoPos(geopos::GeoPos),
+    GeoDist(geodist::GeoDist),
+    GeoHash(geohash::GeoHash),
+    GeoSearch(geosearch::GeoSearch),
+    HDel(hdel::HDel),
+    HExists(hexists::HExists),
+    HLen(hlen::HLen),
+    HKeys(hkeys::HKeys),
+    HSetNx(hsetnx::HSetNx),
+    HIncrBy(hincrby::HIncrBy),
+    HIncrByFloat(hincrbyfloat::HIncrByFloat),
+    HStrlen(hstrlen::HStrlen),
+    HRandField(hrandfield::HRandField),
+    HScan(hscan::HScan),
+    HExpire(hexpire::HExpire),
+    HTtl(httl::HTtl),
+    HPersist(hpersist::HPersist),
+    Srem(srem::Srem),
+    Scard(scard::Scard),
+    Smismember(smismember::Smismember),
+    Spop(spop::Spop),
+    Srandmember(srandmember::Srandmember),
+    Smove(smove::Smove),
+    SetOp(setop::SetOp),
+    Sintercard(sintercard::Sintercard),
+    Sscan(sscan::Sscan),
+    LPush(lpush::LPush),
+    RPush(lpush::RPush),
+    LRange(lrange::LRange),
+    LLen(llen::LLen),
+    Commands(commands::Commands),
+    Select(select::Select),
+    Move(move_key::Move),
+    SwapDb(swapdb::SwapDb),
+    Flush(flush::Flush),
+    DbSize(dbsize::DbSize),
+    Info(info::Info),
+    Client(client::Client),
+    Hello(hello::Hello),
+    Object(object::Object),
+    Memory(memory::Memory),
+    Debug(debug::Debug),
+    Dump(dump::Dump),
+    Restore(restore::Restore),
+    Migrate(migrate::Migrate),
+    Ttl(ttl::Ttl),
+    Sort(sort::Sort),
+    Del(del::Del),
+    Unlink(del::Unlink),
 }
 
 impl TryFrom<Frame> for Command {
     type Error = anyhow::Error;
 
+    // the command table finds the parser and checks the arity before any argument is parsed
     fn try_from(frame: Frame) -> Result<Self> {
         let mut parse = Parse::try_new(frame.clone())?;
 
-        match parse.peek_string() {
-            Ok(command) => match command.to_uppercase().as_str() {
-                "GET" => Ok(Command::Get(frame.try_into()?)),
-                "SET" => Ok(Command::Set(frame.try_into()?)),
-                "HGET" => Ok(Command::HGet(frame.try_into()?)),
-                "HSET" => Ok(Command::HSet(frame.try_into()?)),
-                "HGETALL" => Ok(Command::HGetAll(frame.try_into()?)),
-                "ECHO" => Ok(Command::Echo(frame.try_into()?)),
-                "HMGET" => Ok(Command::Hmget(frame.try_into()?)),
-                "SADD" => Ok(Command::Sadd(frame.try_into()?)),
-                "SMEMBERS" => Ok(Command::Smembers(frame.try_into()?)),
-                "SISMEMBER" => Ok(Command::Sismember(frame.try_into()?)),
-                _ => anyhow::bail!("Invalid command"),
-            },
-            Err(_) => anyhow::bail!("Invalid command"),
-        }
+        let Some(spec) = parse
+            .peek_string()
+            .ok()
+            .and_then(|name| table::lookup(&name))
+        else {
+            let name = parse.next_string().unwrap_or_default();
+            let mut args = String::new();
+            while let Ok(arg) = parse.next_string() {
+                args.push_str(&format!("'{}' ", arg));
+            }
+            anyhow::bail!(
+                "unknown command '{}', with args beginning with: {}",
+                name,
+                args
+            );
+        };
+        check_arity(spec.name, spec.arity, parse.length())?;
+
+        (spec.parse)(frame)
+    }
+}
+
+// a request that can't be parsed gets its error as the reply, whatever went wrong, the same as
+// an error the command reports itself
+pub(crate) fn parse_error(error: &anyhow::Error) -> Frame {
+    BackendError::Other(error.to_string()).into()
+}
+
+// a command on a key holding another type than the one it works on gets WRONGTYPE instead of
+// running, the writers check again under their key lock
+pub(crate) fn check_key_kinds(args: &[String], backend: &Backend) -> Result<(), BackendError> {
+    let Some(spec) = args.first().and_then(|name| lookup(name)) else {
+        return Ok(());
+    };
+    let Some((kind, keys)) = spec.typed_keys(args) else {
+        return Ok(());
+    };
+    keys.into_iter().try_for_each(|key| {
+        let _guard = backend.lock_key(key);
+        backend.check_kind(key, kind)
+    })
+}
+
+// the keys of a command whose time to live ran out are deleted before it runs, so it finds them
+// missing
+pub(crate) fn expire_keys(args: &[String], backend: &Backend) {
+    if let Some(spec) = args.first().and_then(|name| lookup(name)) {
+        backend.expire_keys(&spec.keys.keys(args));
+    }
+}
+
+// numeric arguments are checked while executing, so a bad one gets an error reply instead of
+// closing the connection
+fn parse_int(arg: &str) -> Result<i64, BackendError> {
+    arg.parse().map_err(|_| BackendError::NotInteger)
+}
+
+fn parse_float(arg: &str) -> Result<f64, BackendError> {
+    arg.parse::<f64>()
+        .ok()
+        .filter(|f| !f.is_nan())
+        .ok_or(BackendError::NotFloat)
+}
+
+fn parse_bit_offset(arg: &str) -> Result<u64, BackendError> {
+    arg.parse::<u64>()
+        .ok()
+        .filter(|offset| *offset <= BIT_OFFSET_MAX)
+        .ok_or(BackendError::BitOffset)
+}
+
+fn parse_bit_unit(arg: &str) -> Result<BitUnit> {
+    match arg.to_uppercase().as_str() {
+        "BYTE" => Ok(BitUnit::Byte),
+        "BIT" => Ok(BitUnit::Bit),
+        _ => anyhow::bail!("syntax error"),
+    }
+}
+
+// the `FIELDS numfields field ...` tail of the hash field expiry commands
+fn parse_hash_fields(parse: &mut Parse) -> Result<Vec<String>> {
+    if !parse.next_string()?.eq_ignore_ascii_case("FIELDS") {
+        anyhow::bail!("Mandatory argument FIELDS is missing or not at the right position");
+    }
+
+    let numfields = parse.next_int()?;
+    if numfields <= 0 {
+        anyhow::bail!("Parameter `numFields` should be greater than 0");
+    }
+    if numfields as usize != parse.len() {
+        anyhow::bail!("The `numfields` parameter must match the number of arguments");
     }
+
+    let mut fields = Vec::with_capacity(parse.len());
+    while parse.len() > 0 {
+        fields.push(parse.next_string()?);
+    }
+
+    Ok(fields)
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::resp::frame::Frame;
     use std::convert::TryInto;
 
+    // run a command given as plain arguments, e.g. &["incrby", "key", "10"], with the expiry and
+    // type check a connection does first
+    pub(crate) fn execute(backend: &Backend, args: &[&str]) -> Frame {
+        let frame: Frame = args
+            .iter()
+            .map(|arg| arg.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into();
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        expire_keys(&args, backend);
+        if let Err(e) = check_key_kinds(&args, backend) {
+            return e.into();
+        }
+        let command: Command = frame.try_into().unwrap();
+        command.execute(backend.clone()).unwrap()
+    }
+
     #[test]
     fn test_command_try_from_frame() {
         let frame: Frame = vec!["get".into(), "key".into()].into();
@@ -180,4 +434,35 @@ mod tests {
             _ => panic!("Expected Array"),
         }
     }
+
+    #[test]
+    fn test_command_wrong_type() {
+        let backend = Backend::new();
+        execute(&backend, &["set", "str", "value"]);
+        execute(&backend, &["sadd", "set", "a"]);
+        let wrong_type: Frame = BackendError::WrongType.into();
+
+        assert_eq!(execute(&backend, &["sadd", "str", "a"]), wrong_type);
+        assert_eq!(execute(&backend, &["hset", "str", "f", "v"]), wrong_type);
+        assert_eq!(execute(&backend, &["smembers", "str"]), wrong_type);
+        assert_eq!(execute(&backend, &["hget", "set", "f"]), wrong_type);
+        assert_eq!(execute(&backend, &["get", "set"]), wrong_type);
+        assert_eq!(execute(&backend, &["incr", "set"]), wrong_type);
+        assert_eq!(execute(&backend, &["sunion", "set", "str"]), wrong_type);
+        assert_eq!(
+            execute(&backend, &["mget", "str", "set"]),
+            vec![b"value".into(), NULL.clone()].into()
+        );
+        assert_eq!(execute(&backend, &["setnx", "set", "value"]), 0.into());
+
+        // SET and the destination of a STORE replace whatever the key held
+        assert_eq!(execute(&backend, &["sunionstore", "str", "set"]), 1.into());
+        assert_eq!(
+            execute(&backend, &["smembers", "str"]),
+            vec![b"a".into()].into()
+        );
+        assert_eq!(execute(&backend, &["set", "set", "value"]), *OK);
+        assert_eq!(execute(&backend, &["get", "set"]), b"value".into());
+        assert_eq!(execute(&backend, &["dbsize"]), 2.into());
+    }
 }
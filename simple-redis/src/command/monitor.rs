@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, OK};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+// the connection switches to streaming the command feed after replying, see `network::stream_handle`
+#[derive(Debug, RedisCommand)]
+#[command(name = "monitor")]
+pub struct Monitor {}
+
+impl CommandExecute for Monitor {
+    fn execute(&self, _backend: Backend) -> Result<Frame> {
+        Ok(OK.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_try_from_frame() {
+        let frame: Frame = vec![b"monitor".into()].into();
+        let actual: Result<Monitor> = frame.try_into();
+        assert!(actual.is_ok());
+
+        let frame: Frame = vec![b"monitor".into(), b"extra".into()].into();
+        let actual: Result<Monitor> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
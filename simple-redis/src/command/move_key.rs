@@ -0,0 +1,47 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "move")]
+pub struct Move {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) db: String,
+}
+
+impl CommandExecute for Move {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = parse_int(&self.db).and_then(|db| backend.move_key(&self.key, db));
+
+        match result {
+            Ok(moved) => Ok((moved as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_move() {
+        let backend = Backend::new();
+        backend.hset("key", "field", "value").unwrap();
+
+        assert_eq!(execute(&backend, &["move", "key", "1"]), 1.into());
+        assert_eq!(execute(&backend, &["move", "key", "1"]), 0.into());
+        assert_eq!(
+            execute(&backend, &["move", "key", "0"]),
+            BackendError::SameObject.into()
+        );
+
+        backend.select(1).unwrap();
+        assert_eq!(backend.hget("key", "field"), Some("value".into()));
+    }
+}
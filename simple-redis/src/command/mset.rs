@@ -0,0 +1,90 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, OK};
+use crate::backend::{Backend, StringValue};
+use crate::resp::frame::Frame;
+
+// MSET and MSETNX, the latter sets nothing at all when one of the keys exists
+#[derive(Debug)]
+pub struct Mset {
+    pub(crate) pairs: Vec<(String, StringValue)>,
+    pub(crate) nx: bool,
+}
+
+impl CommandExecute for Mset {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        if self.nx {
+            Ok((backend.msetnx(&self.pairs) as i64).into())
+        } else {
+            backend.mset(&self.pairs);
+            Ok(OK.clone())
+        }
+    }
+}
+
+impl TryFrom<Frame> for Mset {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let nx = match command.as_str() {
+            "MSET" => false,
+            "MSETNX" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        if parse.len() == 0 || parse.len() % 2 != 0 {
+            anyhow::bail!(
+                "wrong number of arguments for '{}' command",
+                command.to_lowercase()
+            );
+        }
+
+        let mut pairs = Vec::with_capacity(parse.len() / 2);
+        while parse.len() > 0 {
+            let key = parse.next_string()?;
+            let value = parse.next_bytes()?.into();
+            pairs.push((key, value));
+        }
+
+        Ok(Self { pairs, nx })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_mset() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["mset", "a", "1", "b", "2"]), *OK);
+        assert_eq!(backend.get("a"), Some(b"1".into()));
+        assert_eq!(backend.get("b"), Some(b"2".into()));
+    }
+
+    #[test]
+    fn test_msetnx() {
+        let backend = Backend::new();
+        backend.set("b", "old");
+
+        assert_eq!(execute(&backend, &["msetnx", "a", "1", "b", "2"]), 0.into());
+        assert_eq!(backend.get("a"), None);
+        assert_eq!(backend.get("b"), Some(b"old".into()));
+
+        assert_eq!(execute(&backend, &["msetnx", "a", "1", "c", "3"]), 1.into());
+        assert_eq!(backend.get("c"), Some(b"3".into()));
+    }
+
+    #[test]
+    fn test_mset_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"mset".into(), b"a".into()].into();
+        let actual: Result<Mset> = frame.try_into();
+        assert!(actual.is_err());
+    }
+}
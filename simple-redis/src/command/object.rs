@@ -0,0 +1,103 @@
+use anyhow::Result;
+
+use super::parse::{FromArg, Parse, ParseError};
+use super::{CommandExecute, RedisCommand, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ObjectSubcommand {
+    Encoding,
+    IdleTime,
+    Freq,
+    RefCount,
+}
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "object")]
+pub struct Object {
+    pub(crate) subcommand: ObjectSubcommand,
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for Object {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let Some(meta) = backend.key_meta(&self.key) else {
+            return Ok(NULL.clone());
+        };
+
+        match self.subcommand {
+            ObjectSubcommand::Encoding => Ok(backend
+                .inspect(&self.key, |value| value.encoding())
+                .map_or(NULL.clone(), |encoding| encoding.as_bytes().into())),
+            ObjectSubcommand::IdleTime => Ok((meta.idle().as_secs() as i64).into()),
+            ObjectSubcommand::Freq => Ok((meta.freq() as i64).into()),
+            // values are never shared between keys
+            ObjectSubcommand::RefCount => Ok(1.into()),
+        }
+    }
+}
+
+impl FromArg for ObjectSubcommand {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError> {
+        match parse.next_string()?.to_uppercase().as_str() {
+            "ENCODING" => Ok(ObjectSubcommand::Encoding),
+            "IDLETIME" => Ok(ObjectSubcommand::IdleTime),
+            "FREQ" => Ok(ObjectSubcommand::Freq),
+            "REFCOUNT" => Ok(ObjectSubcommand::RefCount),
+            subcommand => Err(ParseError::UnknownSubcommand {
+                command: "OBJECT",
+                subcommand: subcommand.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::LFU_INIT_VAL;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_object_try_from_frame() {
+        let frame: Frame = vec![b"object".into(), b"freq".into(), b"key".into()].into();
+        let cmd: Object = frame.try_into().unwrap();
+        assert_eq!(cmd.subcommand, ObjectSubcommand::Freq);
+        assert_eq!(cmd.key, "key");
+
+        let frame: Frame = vec![b"object".into(), b"foo".into(), b"key".into()].into();
+        let actual: Result<Object> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_object_subcommands() {
+        let backend = Backend::new();
+        backend.set("counter", 10);
+        backend.set("name", "simple-redis");
+
+        assert_eq!(
+            execute(&backend, &["object", "encoding", "counter"]),
+            Frame::from(b"int")
+        );
+        assert_eq!(
+            execute(&backend, &["object", "encoding", "name"]),
+            Frame::from(b"embstr")
+        );
+        assert_eq!(
+            execute(&backend, &["object", "idletime", "name"]),
+            Frame::from(0)
+        );
+        assert_eq!(
+            execute(&backend, &["object", "freq", "name"]),
+            Frame::from(LFU_INIT_VAL as i64)
+        );
+        assert_eq!(
+            execute(&backend, &["object", "refcount", "name"]),
+            Frame::from(1)
+        );
+        assert_eq!(execute(&backend, &["object", "encoding", "missing"]), *NULL);
+    }
+}
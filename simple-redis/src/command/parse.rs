@@ -1,3 +1,4 @@
+use crate::backend::StringValue;
 use crate::resp::frame::Frame;
 use anyhow::Result;
 use std::ops::Deref;
@@ -17,6 +18,15 @@ pub enum ParseError {
 
     #[error("From utf8 error: {0}")]
     FromUtf8Error(#[from] std::string::FromUtf8Error),
+
+    #[error("Parse int error: {0}")]
+    ParseIntError(#[from] std::num::ParseIntError),
+
+    #[error("Unknown {command} subcommand '{subcommand}'")]
+    UnknownSubcommand {
+        command: &'static str,
+        subcommand: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +77,26 @@ impl Parse {
         }
     }
 
+    pub fn next_bytes(&mut self) -> Result<Vec<u8>, ParseError> {
+        let frame = self.next()?;
+        match frame {
+            Frame::SimpleString(s) => Ok(s.inner.into_bytes()),
+            Frame::BulkString(s) => Ok(s.inner),
+            Frame::Integer(i) => Ok(i.inner.to_string().into_bytes()),
+            _ => Err(ParseError::InvalidType(format!("for bytes {:?}", frame))),
+        }
+    }
+
+    pub fn next_int(&mut self) -> Result<i64, ParseError> {
+        let frame = self.next()?;
+        match frame {
+            Frame::Integer(i) => Ok(i.inner),
+            Frame::SimpleString(s) => Ok(s.inner.parse()?),
+            Frame::BulkString(s) => Ok(String::from_utf8(s.inner)?.parse()?),
+            _ => Err(ParseError::InvalidType(format!("for int {:?}", frame))),
+        }
+    }
+
     pub fn finish(&mut self) -> Result<(), ParseError> {
         if self.parts.next().is_none() {
             Ok(())
@@ -76,6 +106,35 @@ impl Parse {
     }
 }
 
+// how `#[derive(RedisCommand)]` reads an argument of a given type
+pub trait FromArg: Sized {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError>;
+}
+
+impl FromArg for String {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError> {
+        parse.next_string()
+    }
+}
+
+impl FromArg for Vec<u8> {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError> {
+        parse.next_bytes()
+    }
+}
+
+impl FromArg for StringValue {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError> {
+        parse.next_bytes().map(Into::into)
+    }
+}
+
+impl FromArg for i64 {
+    fn from_arg(parse: &mut Parse) -> Result<Self, ParseError> {
+        parse.next_int()
+    }
+}
+
 impl TryFrom<Frame> for Parse {
     type Error = anyhow::Error;
 
@@ -103,4 +162,28 @@ mod tests {
             expected.parts.collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn test_parse_next_int() {
+        let frame: Frame = vec![b"get".into(), b"10".into(), 20.into(), b"x".into()].into();
+        let mut parse = Parse::try_new(frame).unwrap();
+        parse.next_string().unwrap();
+
+        assert_eq!(parse.next_int().unwrap(), 10);
+        assert_eq!(parse.next_int().unwrap(), 20);
+        assert_eq!(parse.len(), 1);
+        assert!(parse.next_int().is_err());
+        parse.finish().unwrap();
+    }
+
+    #[test]
+    fn test_parse_next_bytes() {
+        let frame: Frame = vec![b"\xff\x00".into(), "ok".into(), 7.into()].into();
+        let mut parse = Parse::try_new(frame).unwrap();
+
+        assert_eq!(parse.next_bytes().unwrap(), b"\xff\x00");
+        assert_eq!(parse.next_bytes().unwrap(), b"ok");
+        assert_eq!(parse.next_bytes().unwrap(), b"7");
+        assert!(parse.next_bytes().is_err());
+    }
 }
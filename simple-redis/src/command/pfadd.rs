@@ -0,0 +1,48 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "pfadd")]
+pub struct PfAdd {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic, min = 0)]
+    pub(crate) elements: Vec<Vec<u8>>,
+}
+
+impl CommandExecute for PfAdd {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.pfadd(&self.key, &self.elements) {
+            Ok(changed) => Ok((changed as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_pfadd() {
+        let backend = Backend::new();
+
+        assert_eq!(
+            execute(&backend, &["pfadd", "hll", "a", "b", "c"]),
+            1.into()
+        );
+        assert_eq!(execute(&backend, &["pfadd", "hll", "a"]), 0.into());
+        assert_eq!(execute(&backend, &["pfadd", "empty"]), 1.into());
+
+        backend.set("text", "abc");
+        assert_eq!(
+            execute(&backend, &["pfadd", "text", "a"]),
+            BackendError::InvalidHll.into()
+        );
+    }
+}
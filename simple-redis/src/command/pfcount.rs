@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "pfcount")]
+pub struct PfCount {
+    #[arg(key, variadic)]
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for PfCount {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.pfcount(&self.keys) {
+            Ok(count) => Ok((count as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_pfcount() {
+        let backend = Backend::new();
+        execute(&backend, &["pfadd", "a", "foo", "bar", "zap"]);
+        execute(&backend, &["pfadd", "b", "zap", "zap", "zap", "kaz"]);
+
+        assert_eq!(execute(&backend, &["pfcount", "a"]), 3.into());
+        assert_eq!(execute(&backend, &["pfcount", "a", "b"]), 4.into());
+        assert_eq!(execute(&backend, &["pfcount", "missing"]), 0.into());
+    }
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand, OK};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "pfmerge")]
+pub struct PfMerge {
+    #[arg(key)]
+    pub(crate) dest: String,
+    #[arg(key, variadic, min = 0)]
+    pub(crate) sources: Vec<String>,
+}
+
+impl CommandExecute for PfMerge {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.pfmerge(&self.dest, &self.sources) {
+            Ok(()) => Ok(OK.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_pfmerge() {
+        let backend = Backend::new();
+        execute(&backend, &["pfadd", "a", "foo", "bar", "zap", "a"]);
+        execute(&backend, &["pfadd", "b", "a", "b", "c", "foo"]);
+
+        assert_eq!(execute(&backend, &["pfmerge", "dest", "a", "b"]), *OK);
+        assert_eq!(execute(&backend, &["pfcount", "dest"]), 6.into());
+
+        // the destination takes part in the union
+        execute(&backend, &["pfadd", "c", "d"]);
+        assert_eq!(execute(&backend, &["pfmerge", "dest", "c"]), *OK);
+        assert_eq!(execute(&backend, &["pfcount", "dest"]), 7.into());
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand, OK};
+use crate::backend::{unix_time, Backend, BackendError};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "restore")]
+pub struct Restore {
+    #[arg(key)]
+    key: String,
+    ttl: String,
+    payload: Vec<u8>,
+    #[arg(flag)]
+    replace: bool,
+    #[arg(flag)]
+    absttl: bool,
+    #[arg(option)]
+    idletime: Option<String>,
+    #[arg(option)]
+    freq: Option<String>,
+}
+
+impl Restore {
+    // the deadline in unix milliseconds, none for a key that never expires
+    fn expire_at(&self) -> Result<Option<u64>, BackendError> {
+        let ttl = parse_int(&self.ttl)?;
+        if ttl < 0 {
+            return Err(BackendError::Other(
+                "Invalid TTL value, must be >= 0".to_string(),
+            ));
+        }
+        Ok(match (ttl, self.absttl) {
+            (0, _) => None,
+            (ttl, true) => Some(ttl as u64),
+            (ttl, false) => Some(unix_time().as_millis() as u64 + ttl as u64),
+        })
+    }
+
+    fn idle(&self) -> Result<Option<Duration>, BackendError> {
+        let Some(idletime) = &self.idletime else {
+            return Ok(None);
+        };
+        match parse_int(idletime)? {
+            idle if idle < 0 => Err(BackendError::Other(
+                "Invalid IDLETIME value, must be >= 0".to_string(),
+            )),
+            idle => Ok(Some(Duration::from_secs(idle as u64))),
+        }
+    }
+
+    fn freq(&self) -> Result<Option<u8>, BackendError> {
+        let Some(freq) = &self.freq else {
+            return Ok(None);
+        };
+        match u8::try_from(parse_int(freq)?) {
+            Ok(freq) => Ok(Some(freq)),
+            Err(_) => Err(BackendError::Other(
+                "Invalid FREQ value, must be >= 0 and <= 255".to_string(),
+            )),
+        }
+    }
+}
+
+impl CommandExecute for Restore {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        // a key is either tracked by its idle time or by its access frequency, like in redis
+        if self.idletime.is_some() && self.freq.is_some() {
+            return Ok(BackendError::Syntax.into());
+        }
+
+        let result = self.expire_at().and_then(|expire_at| {
+            backend.restore(
+                &self.key,
+                &self.payload,
+                self.replace,
+                expire_at,
+                self.idle()?,
+                self.freq()?,
+            )
+        });
+        match result {
+            Ok(()) => Ok(OK.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    // the payload is binary, so the frame is built by hand
+    fn restore(backend: &Backend, key: &str, ttl: &str, payload: &[u8], options: &[&str]) -> Frame {
+        let mut frame: Vec<Frame> = vec![
+            b"restore".into(),
+            key.as_bytes().into(),
+            ttl.as_bytes().into(),
+            payload.into(),
+        ];
+        frame.extend(options.iter().map(|option| option.as_bytes().into()));
+        let command: crate::command::Command = Frame::from(frame).try_into().unwrap();
+        command.execute(backend.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_restore() {
+        let backend = Backend::new();
+        backend.hset("hash", "field", "value").unwrap();
+        let payload = backend.dump("hash").unwrap();
+
+        assert_eq!(restore(&backend, "copy", "0", &payload, &[]), *OK);
+        assert_eq!(backend.value("copy"), backend.value("hash"));
+        assert_eq!(
+            restore(&backend, "copy", "0", &payload, &[]),
+            Frame::from(BackendError::BusyKey)
+        );
+        assert_eq!(
+            restore(
+                &backend,
+                "copy",
+                "0",
+                &payload,
+                &["replace", "idletime", "60"]
+            ),
+            *OK
+        );
+        assert_eq!(
+            execute(&backend, &["object", "idletime", "copy"]),
+            Frame::from(60)
+        );
+        assert_eq!(
+            restore(&backend, "copy", "0", &payload, &["replace", "freq", "100"]),
+            *OK
+        );
+        assert_eq!(
+            execute(&backend, &["object", "freq", "copy"]),
+            Frame::from(100)
+        );
+        assert_eq!(
+            restore(&backend, "copy", "0", &payload, &["replace", "freq", "256"]),
+            Frame::from(BackendError::Other(
+                "Invalid FREQ value, must be >= 0 and <= 255".to_string()
+            ))
+        );
+        assert_eq!(
+            restore(&backend, "other", "0", b"garbage that is long", &[]),
+            Frame::from(BackendError::BadDump)
+        );
+    }
+
+    #[test]
+    fn test_restore_ttl() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        let payload = backend.dump("key").unwrap();
+
+        assert_eq!(
+            execute(&backend, &["restore", "copy", "-1", "x"]),
+            Frame::from(BackendError::Other(
+                "Invalid TTL value, must be >= 0".to_string()
+            ))
+        );
+
+        assert_eq!(restore(&backend, "copy", "60000", &payload, &[]), *OK);
+        match execute(&backend, &["pttl", "copy"]) {
+            Frame::Integer(ttl) => assert!(ttl.inner > 59_000 && ttl.inner <= 60_000),
+            frame => panic!("Expected Integer, got {:?}", frame),
+        }
+
+        assert_eq!(
+            restore(&backend, "key", "1", &payload, &["replace", "absttl"]),
+            *OK
+        );
+        assert!(!backend.contains_key("key"));
+    }
+}
@@ -1,48 +1,32 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::CommandExecute;
+use super::{CommandExecute, RedisCommand};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "sadd")]
 pub struct Sadd {
+    #[arg(key)]
     pub(crate) key: String,
-    pub(crate) field: String,
+    #[arg(variadic)]
+    pub(crate) members: Vec<String>,
 }
 
 impl CommandExecute for Sadd {
     fn execute(&self, backend: Backend) -> Result<Frame> {
-        match backend.sadd(&self.key, &self.field) {
-            true => Ok(1.into()),
-            false => Ok(0.into()),
+        match backend.sadd(&self.key, &self.members) {
+            Ok(added) => Ok((added as i64).into()),
+            Err(e) => Ok(e.into()),
         }
     }
 }
 
-impl TryFrom<Frame> for Sadd {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "SADD" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key, field })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::backend::Backend;
+    use crate::command::tests::execute;
     use crate::resp::RespDecode;
     use std::io::Cursor;
 
@@ -58,7 +42,7 @@ mod tests {
         let cmd = parse_cmd(input).unwrap();
 
         assert_eq!(cmd.key, "key");
-        assert_eq!(cmd.field, "value");
+        assert_eq!(cmd.members, vec!["value"]);
     }
 
     #[test]
@@ -79,4 +63,13 @@ mod tests {
 
         assert_eq!(result.unwrap(), 1.into());
     }
+
+    #[test]
+    fn test_sadd_multiple_members() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["sadd", "key", "a", "b", "a"]), 2.into());
+        assert_eq!(execute(&backend, &["sadd", "key", "b", "c"]), 1.into());
+        assert_eq!(backend.scard("key"), 3);
+    }
 }
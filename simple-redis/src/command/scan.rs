@@ -0,0 +1,129 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+use crate::utils::glob_match;
+
+pub(crate) const SCAN_DEFAULT_COUNT: usize = 10;
+
+#[derive(Debug)]
+pub struct Scan {
+    pub(crate) cursor: usize,
+    pub(crate) pattern: Option<String>,
+    pub(crate) count: usize,
+}
+
+impl CommandExecute for Scan {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let keys = backend.keys();
+        let start = self.cursor.min(keys.len());
+        let end = start.saturating_add(self.count).min(keys.len());
+
+        let batch = keys[start..end]
+            .iter()
+            .filter(|key| match &self.pattern {
+                Some(pattern) => glob_match(pattern.as_bytes(), key.as_bytes()),
+                None => true,
+            })
+            .map(|key| key.as_bytes().into())
+            .collect::<Vec<Frame>>();
+
+        let next = if end >= keys.len() { 0 } else { end };
+
+        Ok(vec![next.to_string().as_bytes().into(), batch.into()].into())
+    }
+}
+
+impl TryFrom<Frame> for Scan {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SCAN" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let cursor = parse.next_string()?.parse()?;
+        let mut pattern = None;
+        let mut count = SCAN_DEFAULT_COUNT;
+
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "MATCH" => pattern = Some(parse.next_string()?),
+                "COUNT" => match parse.next_int()? {
+                    n if n < 1 => anyhow::bail!("syntax error"),
+                    n => count = n as usize,
+                },
+                _ => anyhow::bail!("syntax error"),
+            }
+        }
+
+        Ok(Self {
+            cursor,
+            pattern,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_try_from_frame() {
+        let frame: Frame = vec![
+            b"scan".into(),
+            b"0".into(),
+            b"match".into(),
+            b"user:*".into(),
+            b"count".into(),
+            b"100".into(),
+        ]
+        .into();
+
+        let actual: Scan = frame.try_into().unwrap();
+        assert_eq!(actual.cursor, 0);
+        assert_eq!(actual.pattern, Some("user:*".to_string()));
+        assert_eq!(actual.count, 100);
+    }
+
+    #[test]
+    fn test_scan_try_from_frame_invalid_parts() {
+        let frame: Frame = vec![b"scan".into(), b"0".into(), b"match".into()].into();
+        let actual: Result<Scan> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_scan_execute() {
+        let backend = Backend::new();
+        for key in ["user:1", "user:2", "order:1"] {
+            backend.set(key, b"value");
+        }
+
+        let cmd = Scan {
+            cursor: 0,
+            pattern: Some("user:*".to_string()),
+            count: 2,
+        };
+        assert_eq!(
+            cmd.execute(backend.clone()).unwrap(),
+            vec![b"2".into(), vec![b"user:1".into()].into()].into()
+        );
+
+        let cmd = Scan {
+            cursor: 2,
+            pattern: Some("user:*".to_string()),
+            count: 2,
+        };
+        assert_eq!(
+            cmd.execute(backend).unwrap(),
+            vec![b"0".into(), vec![b"user:2".into()].into()].into()
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "scard")]
+pub struct Scard {
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for Scard {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.scard(&self.key) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_scard() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "a", "b"]);
+
+        assert_eq!(execute(&backend, &["scard", "key"]), 2.into());
+        assert_eq!(execute(&backend, &["scard", "nokey"]), 0.into());
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand, OK};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "select")]
+pub struct Select {
+    pub(crate) index: String,
+}
+
+impl CommandExecute for Select {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = parse_int(&self.index).and_then(|index| backend.select(index));
+
+        match result {
+            Ok(()) => Ok(OK.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::BackendError;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_select() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+
+        assert_eq!(execute(&backend, &["select", "3"]), *OK);
+        assert_eq!(backend.selected_db(), 3);
+        assert_eq!(execute(&backend, &["get", "key"]), *crate::command::NULL);
+
+        assert_eq!(
+            execute(&backend, &["select", "16"]),
+            BackendError::DbIndexOutOfRange.into()
+        );
+        assert_eq!(
+            execute(&backend, &["select", "x"]),
+            BackendError::NotInteger.into()
+        );
+        assert_eq!(backend.selected_db(), 3);
+    }
+}
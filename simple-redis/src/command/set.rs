@@ -1,13 +1,15 @@
 use anyhow::Result;
 
-use super::{parse::Parse, CommandExecute, OK};
-use crate::backend::Backend;
+use super::{CommandExecute, RedisCommand, OK};
+use crate::backend::{Backend, StringValue};
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "set")]
 pub struct Set {
+    #[arg(key)]
     key: String,
-    value: Frame,
+    value: StringValue,
 }
 
 impl CommandExecute for Set {
@@ -17,25 +19,6 @@ impl CommandExecute for Set {
     }
 }
 
-impl TryFrom<Frame> for Set {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "SET" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        let value = parse.next()?;
-        parse.finish()?;
-
-        Ok(Self { key, value })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,7 +32,7 @@ mod tests {
         let actual: Set = frame.try_into().unwrap();
         let expected = Set {
             key: "key".to_string(),
-            value: "value".into(),
+            value: StringValue::from("value"),
         };
 
         assert_eq!(actual.key, expected.key);
@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use super::{parse_bit_offset, CommandExecute, RedisCommand};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "setbit")]
+pub struct SetBit {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) offset: String,
+    pub(crate) value: String,
+}
+
+impl CommandExecute for SetBit {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = parse_bit_offset(&self.offset).and_then(|offset| {
+            let bit = match self.value.as_str() {
+                "0" => false,
+                "1" => true,
+                _ => return Err(BackendError::BitValue),
+            };
+            backend.setbit(&self.key, offset, bit)
+        });
+
+        match result {
+            Ok(old) => Ok((old as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_setbit() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["setbit", "key", "7", "1"]), 0.into());
+        assert_eq!(execute(&backend, &["setbit", "key", "7", "0"]), 1.into());
+        assert_eq!(
+            execute(&backend, &["setbit", "key", "7", "2"]),
+            BackendError::BitValue.into()
+        );
+        assert_eq!(
+            execute(&backend, &["setbit", "key", "-1", "1"]),
+            BackendError::BitOffset.into()
+        );
+        assert_eq!(
+            execute(&backend, &["setbit", "key", "4294967296", "1"]),
+            BackendError::BitOffset.into()
+        );
+    }
+}
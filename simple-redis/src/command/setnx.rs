@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::{Backend, StringValue};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "setnx")]
+pub struct SetNx {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) value: StringValue,
+}
+
+impl CommandExecute for SetNx {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let set = backend.setnx(&self.key, self.value.clone());
+        Ok((set as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_setnx() {
+        let backend = Backend::new();
+
+        assert_eq!(execute(&backend, &["setnx", "key", "a"]), 1.into());
+        assert_eq!(execute(&backend, &["setnx", "key", "b"]), 0.into());
+        assert_eq!(backend.get("key"), Some(b"a".into()));
+    }
+}
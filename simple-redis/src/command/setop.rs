@@ -0,0 +1,129 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::{Backend, SetOp as Operation};
+use crate::resp::frame::Frame;
+
+// SINTER, SUNION, SDIFF and their STORE variants
+#[derive(Debug)]
+pub struct SetOp {
+    pub(crate) operation: Operation,
+    pub(crate) destination: Option<String>,
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecute for SetOp {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match &self.destination {
+            Some(destination) => {
+                let len = backend.set_op_store(self.operation, destination, &self.keys);
+                Ok((len as i64).into())
+            }
+            None => Ok(backend
+                .set_op(self.operation, &self.keys)
+                .into_iter()
+                .map(|member| member.as_bytes().into())
+                .collect::<Vec<Frame>>()
+                .into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for SetOp {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let (operation, store) = match command.as_str() {
+            "SINTER" => (Operation::Inter, false),
+            "SUNION" => (Operation::Union, false),
+            "SDIFF" => (Operation::Diff, false),
+            "SINTERSTORE" => (Operation::Inter, true),
+            "SUNIONSTORE" => (Operation::Union, true),
+            "SDIFFSTORE" => (Operation::Diff, true),
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let destination = match store {
+            true => Some(parse.next_string()?),
+            false => None,
+        };
+        let mut keys = vec![parse.next_string()?];
+        while parse.len() > 0 {
+            keys.push(parse.next_string()?);
+        }
+
+        Ok(Self {
+            operation,
+            destination,
+            keys,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    fn sorted(frame: Frame) -> Vec<Frame> {
+        match frame {
+            Frame::Array(array) => {
+                let mut array = array.to_vec();
+                array.sort_by_key(|frame| format!("{:?}", frame));
+                array
+            }
+            _ => panic!("Expected Array"),
+        }
+    }
+
+    #[test]
+    fn test_setop() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "a", "1", "2", "3"]);
+        execute(&backend, &["sadd", "b", "2", "3", "4"]);
+
+        assert_eq!(
+            sorted(execute(&backend, &["sinter", "a", "b"])),
+            vec![b"2".into(), b"3".into()]
+        );
+        assert_eq!(
+            sorted(execute(&backend, &["sunion", "a", "b"])),
+            vec![b"1".into(), b"2".into(), b"3".into(), b"4".into()]
+        );
+        assert_eq!(
+            sorted(execute(&backend, &["sdiff", "a", "b"])),
+            vec![b"1".into()]
+        );
+        assert_eq!(execute(&backend, &["sinter", "a", "nokey"]), vec![].into());
+    }
+
+    #[test]
+    fn test_setop_store() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "a", "1", "2", "3"]);
+        execute(&backend, &["sadd", "b", "2", "3", "4"]);
+
+        assert_eq!(
+            execute(&backend, &["sunionstore", "dst", "a", "b"]),
+            4.into()
+        );
+        assert_eq!(backend.scard("dst"), 4);
+
+        // the destination may also be a source
+        assert_eq!(
+            execute(&backend, &["sdiffstore", "dst", "dst", "a"]),
+            1.into()
+        );
+        assert!(backend.sismember("dst", "4"));
+
+        assert_eq!(
+            execute(&backend, &["sinterstore", "dst", "a", "nokey"]),
+            0.into()
+        );
+        assert!(backend.smembers("dst").is_none());
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "setrange")]
+pub struct SetRange {
+    #[arg(key)]
+    pub(crate) key: String,
+    pub(crate) offset: String,
+    pub(crate) value: Vec<u8>,
+}
+
+impl CommandExecute for SetRange {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = parse_int(&self.offset)
+            .and_then(|offset| backend.setrange(&self.key, offset, &self.value));
+
+        match result {
+            Ok(len) => Ok((len as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+    use crate::resp::SimpleError;
+
+    #[test]
+    fn test_setrange() {
+        let backend = Backend::new();
+        backend.set("key", "Hello World");
+
+        assert_eq!(
+            execute(&backend, &["setrange", "key", "6", "Redis"]),
+            11.into()
+        );
+        assert_eq!(backend.get("key"), Some(b"Hello Redis".into()));
+
+        assert_eq!(
+            execute(&backend, &["setrange", "key", "-1", "x"]),
+            Frame::SimpleError(SimpleError::new("ERR offset is out of range"))
+        );
+        assert_eq!(
+            execute(&backend, &["setrange", "key", "abc", "x"]),
+            Frame::SimpleError(SimpleError::new(
+                "ERR value is not an integer or out of range"
+            ))
+        );
+    }
+}
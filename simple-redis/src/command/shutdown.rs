@@ -0,0 +1,78 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, OK};
+use crate::backend::{Backend, ShutdownMode};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct Shutdown {
+    pub(crate) mode: ShutdownMode,
+}
+
+impl CommandExecute for Shutdown {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        backend.shutdown().trigger(self.mode);
+        Ok(OK.clone())
+    }
+}
+
+impl TryFrom<Frame> for Shutdown {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SHUTDOWN" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let mode = if parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "SAVE" => ShutdownMode::Save,
+                "NOSAVE" => ShutdownMode::NoSave,
+                _ => anyhow::bail!("syntax error"),
+            }
+        } else {
+            ShutdownMode::Default
+        };
+        parse.finish()?;
+
+        Ok(Self { mode })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shutdown_try_from_frame() {
+        let frame: Frame = vec![b"shutdown".into()].into();
+        let cmd: Shutdown = frame.try_into().unwrap();
+        assert_eq!(cmd.mode, ShutdownMode::Default);
+
+        let frame: Frame = vec![b"shutdown".into(), b"nosave".into()].into();
+        let cmd: Shutdown = frame.try_into().unwrap();
+        assert_eq!(cmd.mode, ShutdownMode::NoSave);
+
+        let frame: Frame = vec![b"shutdown".into(), b"later".into()].into();
+        let actual: Result<Shutdown> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_execute() {
+        let backend = Backend::new();
+        let mut receiver = backend.shutdown().subscribe();
+
+        let cmd = Shutdown {
+            mode: ShutdownMode::Save,
+        };
+        assert_eq!(cmd.execute(backend).unwrap(), *OK);
+
+        receiver.changed().await.unwrap();
+        assert_eq!(*receiver.borrow(), Some(ShutdownMode::Save));
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct Sintercard {
+    pub(crate) keys: Vec<String>,
+    pub(crate) limit: usize,
+}
+
+impl CommandExecute for Sintercard {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let count = backend.sintercard(&self.keys, self.limit);
+        Ok((count as i64).into())
+    }
+}
+
+impl TryFrom<Frame> for Sintercard {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SINTERCARD" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let numkeys = parse.next_int()?;
+        if numkeys <= 0 {
+            anyhow::bail!("numkeys should be greater than 0");
+        }
+        if numkeys as usize > parse.len() {
+            anyhow::bail!("Number of keys can't be greater than number of args");
+        }
+
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_string()?);
+        }
+
+        // a limit of 0 means no limit
+        let mut limit = 0;
+        if parse.len() > 0 {
+            if !parse.next_string()?.eq_ignore_ascii_case("LIMIT") {
+                anyhow::bail!("syntax error");
+            }
+            limit = match parse.next_int()? {
+                n if n < 0 => anyhow::bail!("LIMIT can't be negative"),
+                n => n as usize,
+            };
+        }
+        parse.finish()?;
+
+        Ok(Self { keys, limit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_sintercard() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "a", "1", "2", "3", "4"]);
+        execute(&backend, &["sadd", "b", "2", "3", "4", "5"]);
+
+        assert_eq!(execute(&backend, &["sintercard", "2", "a", "b"]), 3.into());
+        assert_eq!(
+            execute(&backend, &["sintercard", "2", "a", "b", "limit", "2"]),
+            2.into()
+        );
+        assert_eq!(
+            execute(&backend, &["sintercard", "2", "a", "b", "limit", "0"]),
+            3.into()
+        );
+        assert_eq!(execute(&backend, &["sintercard", "2", "a", "c"]), 0.into());
+    }
+
+    #[test]
+    fn test_sintercard_try_from_frame_invalid_numkeys() {
+        for args in [
+            vec!["sintercard", "0", "a"],
+            vec!["sintercard", "3", "a", "b"],
+            vec!["sintercard", "1", "a", "limit", "-1"],
+        ] {
+            let frame: Frame = args
+                .iter()
+                .map(|arg| arg.as_bytes().into())
+                .collect::<Vec<Frame>>()
+                .into();
+            let actual: Result<Sintercard> = frame.try_into();
+            assert!(actual.is_err());
+        }
+    }
+}
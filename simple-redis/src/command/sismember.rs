@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::CommandExecute;
+use super::{CommandExecute, RedisCommand};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "sismember")]
 pub struct Sismember {
+    #[arg(key)]
     key: String,
     field: String,
 }
@@ -20,25 +21,6 @@ impl CommandExecute for Sismember {
     }
 }
 
-impl TryFrom<Frame> for Sismember {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "SISMEMBER" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key, field })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,150 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{CommandExecute, OK};
+use crate::backend::{Backend, SlowLogEntry};
+use crate::resp::frame::Frame;
+
+const SLOWLOG_DEFAULT_GET_COUNT: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum SlowlogSubcommand {
+    Get(Option<usize>),
+    Len,
+    Reset,
+}
+
+#[derive(Debug)]
+pub struct Slowlog {
+    pub(crate) subcommand: SlowlogSubcommand,
+}
+
+impl CommandExecute for Slowlog {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match self.subcommand {
+            SlowlogSubcommand::Get(count) => {
+                let entries = backend.slowlog().get(count);
+                Ok(entries
+                    .iter()
+                    .map(entry_to_frame)
+                    .collect::<Vec<Frame>>()
+                    .into())
+            }
+            SlowlogSubcommand::Len => Ok((backend.slowlog().len() as i64).into()),
+            SlowlogSubcommand::Reset => {
+                backend.slowlog().reset();
+                Ok(OK.clone())
+            }
+        }
+    }
+}
+
+fn entry_to_frame(entry: &SlowLogEntry) -> Frame {
+    vec![
+        (entry.id as i64).into(),
+        (entry.timestamp as i64).into(),
+        (entry.duration as i64).into(),
+        entry
+            .args
+            .iter()
+            .map(|arg| arg.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into(),
+        entry.client.as_bytes().into(),
+        b"".into(),
+    ]
+    .into()
+}
+
+impl TryFrom<Frame> for Slowlog {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SLOWLOG" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let subcommand = match parse.next_string()?.to_uppercase().as_str() {
+            "GET" => {
+                let count = if parse.len() > 0 {
+                    match parse.next_int()? {
+                        count if count < 0 => None,
+                        count => Some(count as usize),
+                    }
+                } else {
+                    Some(SLOWLOG_DEFAULT_GET_COUNT)
+                };
+                SlowlogSubcommand::Get(count)
+            }
+            "LEN" => SlowlogSubcommand::Len,
+            "RESET" => SlowlogSubcommand::Reset,
+            subcommand => anyhow::bail!("Unknown SLOWLOG subcommand '{}'", subcommand),
+        };
+        parse.finish()?;
+
+        Ok(Self { subcommand })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::time::Duration;
+
+    #[test]
+    fn test_slowlog_try_from_frame() {
+        let frame: Frame = vec![b"slowlog".into(), b"get".into()].into();
+        let cmd: Slowlog = frame.try_into().unwrap();
+        assert_eq!(cmd.subcommand, SlowlogSubcommand::Get(Some(10)));
+
+        let frame: Frame = vec![b"slowlog".into(), b"get".into(), b"-1".into()].into();
+        let cmd: Slowlog = frame.try_into().unwrap();
+        assert_eq!(cmd.subcommand, SlowlogSubcommand::Get(None));
+
+        let frame: Frame = vec![b"slowlog".into(), b"len".into()].into();
+        let cmd: Slowlog = frame.try_into().unwrap();
+        assert_eq!(cmd.subcommand, SlowlogSubcommand::Len);
+    }
+
+    #[test]
+    fn test_slowlog_try_from_frame_invalid_subcommand() {
+        let frame: Frame = vec![b"slowlog".into(), b"foo".into()].into();
+        let actual: Result<Slowlog> = frame.try_into();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_slowlog_execute() {
+        let config = Config {
+            slowlog_log_slower_than: 0,
+            ..Default::default()
+        };
+        let backend = Backend::with_config(&config);
+        backend
+            .slowlog()
+            .record(&["get".to_string()], None, Duration::from_micros(5));
+
+        let cmd = Slowlog {
+            subcommand: SlowlogSubcommand::Len,
+        };
+        assert_eq!(cmd.execute(backend.clone()).unwrap(), 1.into());
+
+        let cmd = Slowlog {
+            subcommand: SlowlogSubcommand::Get(None),
+        };
+        match cmd.execute(backend.clone()).unwrap() {
+            Frame::Array(array) => assert_eq!(array.len(), 1),
+            _ => panic!("Expected Array"),
+        }
+
+        let cmd = Slowlog {
+            subcommand: SlowlogSubcommand::Reset,
+        };
+        assert_eq!(cmd.execute(backend.clone()).unwrap(), *OK);
+        assert!(backend.slowlog().is_empty());
+    }
+}
@@ -1,12 +1,13 @@
 use anyhow::Result;
 
-use super::parse::Parse;
-use super::CommandExecute;
+use super::{CommandExecute, RedisCommand};
 use crate::backend::Backend;
 use crate::resp::frame::Frame;
 
-#[derive(Debug)]
+#[derive(Debug, RedisCommand)]
+#[command(name = "smembers")]
 pub struct Smembers {
+    #[arg(key)]
     pub(crate) key: String,
 }
 
@@ -25,24 +26,6 @@ impl CommandExecute for Smembers {
     }
 }
 
-impl TryFrom<Frame> for Smembers {
-    type Error = anyhow::Error;
-
-    fn try_from(frame: Frame) -> Result<Self> {
-        let mut parse = Parse::try_new(frame)?;
-        let command = parse.next_string()?.to_uppercase();
-
-        if command != "SMEMBERS" {
-            anyhow::bail!("Invalid command");
-        }
-
-        let key = parse.next_string()?;
-        parse.finish()?;
-
-        Ok(Self { key })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "smismember")]
+pub struct Smismember {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic)]
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecute for Smismember {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok(backend
+            .smismember(&self.key, &self.members)
+            .into_iter()
+            .map(|found| (found as i64).into())
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_smismember() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "a", "b"]);
+
+        assert_eq!(
+            execute(&backend, &["smismember", "key", "a", "x", "b"]),
+            vec![1.into(), 0.into(), 1.into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["smismember", "nokey", "a"]),
+            vec![0.into()].into()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "smove")]
+pub struct Smove {
+    #[arg(key)]
+    pub(crate) source: String,
+    #[arg(key)]
+    pub(crate) destination: String,
+    pub(crate) member: String,
+}
+
+impl CommandExecute for Smove {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        match backend.smove(&self.source, &self.destination, &self.member) {
+            Ok(moved) => Ok((moved as i64).into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_smove() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "src", "a", "b"]);
+
+        assert_eq!(execute(&backend, &["smove", "src", "dst", "a"]), 1.into());
+        assert_eq!(execute(&backend, &["smove", "src", "dst", "a"]), 0.into());
+        assert!(backend.sismember("dst", "a"));
+
+        assert_eq!(execute(&backend, &["smove", "src", "dst", "b"]), 1.into());
+        assert_eq!(backend.keys(), vec!["dst"]);
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute, NULL};
+use crate::backend::{Backend, BackendError, SortOptions};
+use crate::resp::frame::Frame;
+
+// SORT and SORT_RO, the options are kept as sent and resolved while executing
+#[derive(Debug)]
+pub struct Sort {
+    pub(crate) key: String,
+    pub(crate) args: Vec<String>,
+    pub(crate) read_only: bool,
+}
+
+impl Sort {
+    // the options and the STORE destination, which SORT_RO does not take
+    fn options(&self) -> Result<(SortOptions, Option<String>), BackendError> {
+        let mut options = SortOptions::default();
+        let mut store = None;
+
+        let mut args = self.args.iter();
+        let mut next = || args.next().ok_or(BackendError::Syntax);
+
+        while let Ok(arg) = next() {
+            match arg.to_uppercase().as_str() {
+                "ASC" => options.desc = false,
+                "DESC" => options.desc = true,
+                "ALPHA" => options.alpha = true,
+                "LIMIT" => {
+                    let offset = parse_int(next()?)?;
+                    let count = parse_int(next()?)?;
+                    options.limit = Some((offset, count));
+                }
+                "BY" => options.by = Some(next()?.clone()),
+                "GET" => options.get.push(next()?.clone()),
+                "STORE" if !self.read_only => store = Some(next()?.clone()),
+                _ => return Err(BackendError::Syntax),
+            }
+        }
+
+        Ok((options, store))
+    }
+}
+
+impl CommandExecute for Sort {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let (options, store) = match self.options() {
+            Ok(options) => options,
+            Err(e) => return Ok(e.into()),
+        };
+        if let Some(destination) = store {
+            return Ok(
+                match backend.sort_store(&self.key, &options, &destination) {
+                    Ok(len) => (len as i64).into(),
+                    Err(e) => e.into(),
+                },
+            );
+        }
+
+        match backend.sort(&self.key, &options) {
+            Ok(values) => Ok(values
+                .iter()
+                .map(|value| match value {
+                    Some(value) => value.as_slice().into(),
+                    None => NULL.clone(),
+                })
+                .collect::<Vec<Frame>>()
+                .into()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+impl TryFrom<Frame> for Sort {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let read_only = match command.as_str() {
+            "SORT" => false,
+            "SORT_RO" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        let mut args = Vec::with_capacity(parse.len());
+        while parse.len() > 0 {
+            args.push(parse.next_string()?);
+        }
+
+        Ok(Self {
+            key,
+            args,
+            read_only,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_sort() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "s", "3", "1", "2"]);
+        execute(&backend, &["hset", "h_1", "name", "one"]);
+
+        assert_eq!(
+            execute(&backend, &["sort", "s", "desc", "limit", "0", "2"]),
+            vec![b"3".into(), b"2".into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["sort_ro", "s", "get", "h_*->name", "get", "#"]),
+            vec![
+                b"one".into(),
+                b"1".into(),
+                NULL.clone(),
+                b"2".into(),
+                NULL.clone(),
+                b"3".into()
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_sort_errors() {
+        let backend = Backend::new();
+        execute(&backend, &["set", "str", "a"]);
+
+        assert_eq!(
+            execute(&backend, &["sort", "str"]),
+            BackendError::WrongType.into()
+        );
+        assert_eq!(
+            execute(&backend, &["sort", "s", "limit", "x", "1"]),
+            BackendError::NotInteger.into()
+        );
+        assert_eq!(
+            execute(&backend, &["sort", "s", "limit", "0"]),
+            BackendError::Syntax.into()
+        );
+        assert_eq!(
+            execute(&backend, &["sort_ro", "s", "store", "dst"]),
+            BackendError::Syntax.into()
+        );
+        assert_eq!(
+            execute(&backend, &["sort", "str", "store", "dst"]),
+            BackendError::WrongType.into()
+        );
+    }
+
+    #[test]
+    fn test_sort_store() {
+        let backend = Backend::new();
+        execute(&backend, &["rpush", "list", "3", "1", "2"]);
+        execute(&backend, &["set", "w_1", "x"]);
+        execute(&backend, &["set", "dst", "old"]);
+
+        assert_eq!(
+            execute(&backend, &["sort", "list", "desc", "store", "dst"]),
+            3.into()
+        );
+        assert_eq!(
+            execute(&backend, &["lrange", "dst", "0", "-1"]),
+            vec![b"3".into(), b"2".into(), b"1".into()].into()
+        );
+
+        // a list sorted by nothing keeps its order, a missing lookup is stored empty
+        assert_eq!(
+            execute(
+                &backend,
+                &["sort", "list", "by", "nosort", "get", "w_*", "store", "dst"]
+            ),
+            3.into()
+        );
+        assert_eq!(
+            execute(&backend, &["lrange", "dst", "0", "-1"]),
+            vec![b"".into(), b"x".into(), b"".into()].into()
+        );
+
+        // an empty result deletes the destination
+        assert_eq!(
+            execute(&backend, &["sort", "missing", "store", "dst"]),
+            0.into()
+        );
+        assert!(!backend.contains_key("dst"));
+    }
+}
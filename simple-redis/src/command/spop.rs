@@ -0,0 +1,93 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute, NULL};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct Spop {
+    pub(crate) key: String,
+    pub(crate) count: Option<String>,
+}
+
+impl CommandExecute for Spop {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        // without a count the reply is a single member instead of an array
+        let Some(count) = &self.count else {
+            return Ok(match backend.spop(&self.key, 1).pop() {
+                Some(member) => member.as_bytes().into(),
+                None => NULL.clone(),
+            });
+        };
+
+        let count = match parse_int(count) {
+            Ok(count) if count >= 0 => count as usize,
+            Ok(_) => {
+                return Ok(BackendError::Other(
+                    "value is out of range, must be positive".to_string(),
+                )
+                .into())
+            }
+            Err(e) => return Ok(e.into()),
+        };
+
+        Ok(backend
+            .spop(&self.key, count)
+            .into_iter()
+            .map(|member| member.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for Spop {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SPOP" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let count = match parse.len() {
+            0 => None,
+            _ => Some(parse.next_string()?),
+        };
+        parse.finish()?;
+
+        Ok(Self { key, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_spop() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "a", "b", "c"]);
+
+        let popped = execute(&backend, &["spop", "key"]);
+        assert!([b"a".into(), b"b".into(), b"c".into()].contains(&popped));
+        assert_eq!(backend.scard("key"), 2);
+
+        match execute(&backend, &["spop", "key", "5"]) {
+            Frame::Array(array) => assert_eq!(array.len(), 2),
+            _ => panic!("Expected Array"),
+        }
+        assert!(backend.keys().is_empty());
+
+        assert_eq!(execute(&backend, &["spop", "key"]), *NULL);
+        assert_eq!(execute(&backend, &["spop", "key", "1"]), vec![].into());
+        assert_eq!(
+            execute(&backend, &["spop", "key", "-1"]),
+            BackendError::Other("value is out of range, must be positive".to_string()).into()
+        );
+    }
+}
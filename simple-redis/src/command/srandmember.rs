@@ -0,0 +1,84 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::{parse_int, CommandExecute, NULL};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug)]
+pub struct Srandmember {
+    pub(crate) key: String,
+    pub(crate) count: Option<String>,
+}
+
+impl CommandExecute for Srandmember {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        // without a count the reply is a single member instead of an array
+        let Some(count) = &self.count else {
+            return Ok(match backend.srandmember(&self.key, 1).pop() {
+                Some(member) => member.as_bytes().into(),
+                None => NULL.clone(),
+            });
+        };
+
+        let count = match parse_int(count) {
+            Ok(count) => count,
+            Err(e) => return Ok(e.into()),
+        };
+
+        Ok(backend
+            .srandmember(&self.key, count)
+            .into_iter()
+            .map(|member| member.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into())
+    }
+}
+
+impl TryFrom<Frame> for Srandmember {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SRANDMEMBER" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let count = match parse.len() {
+            0 => None,
+            _ => Some(parse.next_string()?),
+        };
+        parse.finish()?;
+
+        Ok(Self { key, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_srandmember() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "a", "b", "c"]);
+
+        let member = execute(&backend, &["srandmember", "key"]);
+        assert!([b"a".into(), b"b".into(), b"c".into()].contains(&member));
+        assert_eq!(backend.scard("key"), 3);
+
+        match execute(&backend, &["srandmember", "key", "5"]) {
+            Frame::Array(array) => assert_eq!(array.len(), 3),
+            _ => panic!("Expected Array"),
+        }
+        match execute(&backend, &["srandmember", "key", "-5"]) {
+            Frame::Array(array) => assert_eq!(array.len(), 5),
+            _ => panic!("Expected Array"),
+        }
+        assert_eq!(execute(&backend, &["srandmember", "nokey"]), *NULL);
+    }
+}
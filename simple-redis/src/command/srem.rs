@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "srem")]
+pub struct Srem {
+    #[arg(key)]
+    pub(crate) key: String,
+    #[arg(variadic)]
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecute for Srem {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let removed = backend.srem(&self.key, &self.members);
+        Ok((removed as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_srem() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "a", "b", "c"]);
+
+        assert_eq!(execute(&backend, &["srem", "key", "a", "x"]), 1.into());
+        assert_eq!(execute(&backend, &["srem", "key", "b", "c"]), 2.into());
+        assert!(backend.keys().is_empty());
+        assert_eq!(execute(&backend, &["srem", "key", "a"]), 0.into());
+    }
+}
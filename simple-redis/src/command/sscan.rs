@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::scan::SCAN_DEFAULT_COUNT;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+use crate::utils::glob_match;
+
+#[derive(Debug)]
+pub struct Sscan {
+    pub(crate) key: String,
+    pub(crate) cursor: usize,
+    pub(crate) pattern: Option<String>,
+    pub(crate) count: usize,
+}
+
+impl CommandExecute for Sscan {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        // sorted so an index into the members can serve as the cursor
+        let mut members = backend.smembers(&self.key).unwrap_or_default();
+        members.sort();
+
+        let start = self.cursor.min(members.len());
+        let end = start.saturating_add(self.count).min(members.len());
+
+        let batch = members[start..end]
+            .iter()
+            .filter(|member| match &self.pattern {
+                Some(pattern) => glob_match(pattern.as_bytes(), member.as_bytes()),
+                None => true,
+            })
+            .map(|member| member.as_bytes().into())
+            .collect::<Vec<Frame>>();
+
+        let next = if end >= members.len() { 0 } else { end };
+
+        Ok(vec![next.to_string().as_bytes().into(), batch.into()].into())
+    }
+}
+
+impl TryFrom<Frame> for Sscan {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        if command != "SSCAN" {
+            anyhow::bail!("Invalid command");
+        }
+
+        let key = parse.next_string()?;
+        let cursor = parse.next_string()?.parse()?;
+        let mut pattern = None;
+        let mut count = SCAN_DEFAULT_COUNT;
+
+        while parse.len() > 0 {
+            match parse.next_string()?.to_uppercase().as_str() {
+                "MATCH" => pattern = Some(parse.next_string()?),
+                "COUNT" => match parse.next_int()? {
+                    n if n < 1 => anyhow::bail!("syntax error"),
+                    n => count = n as usize,
+                },
+                _ => anyhow::bail!("syntax error"),
+            }
+        }
+
+        Ok(Self {
+            key,
+            cursor,
+            pattern,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_sscan() {
+        let backend = Backend::new();
+        execute(&backend, &["sadd", "key", "b1", "a1", "a2"]);
+
+        assert_eq!(
+            execute(&backend, &["sscan", "key", "0", "count", "2"]),
+            vec![b"2".into(), vec![b"a1".into(), b"a2".into()].into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["sscan", "key", "2"]),
+            vec![b"0".into(), vec![b"b1".into()].into()].into()
+        );
+        assert_eq!(
+            execute(&backend, &["sscan", "key", "0", "match", "b*"]),
+            vec![b"0".into(), vec![b"b1".into()].into()].into()
+        );
+    }
+}
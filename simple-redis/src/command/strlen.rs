@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use super::{CommandExecute, RedisCommand};
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "strlen")]
+pub struct Strlen {
+    #[arg(key)]
+    pub(crate) key: String,
+}
+
+impl CommandExecute for Strlen {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok((backend.strlen(&self.key) as i64).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_strlen() {
+        let backend = Backend::new();
+        backend.set("key", "Hello world");
+        backend.set("number", -100);
+
+        assert_eq!(execute(&backend, &["strlen", "key"]), 11.into());
+        assert_eq!(execute(&backend, &["strlen", "number"]), 4.into());
+        assert_eq!(execute(&backend, &["strlen", "missing"]), 0.into());
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+
+use super::{parse_int, CommandExecute, RedisCommand, OK};
+use crate::backend::{Backend, BackendError};
+use crate::resp::frame::Frame;
+
+#[derive(Debug, RedisCommand)]
+#[command(name = "swapdb")]
+pub struct SwapDb {
+    pub(crate) index1: String,
+    pub(crate) index2: String,
+}
+
+impl SwapDb {
+    fn indexes(&self) -> Result<(i64, i64), BackendError> {
+        let invalid = |which| BackendError::Other(format!("invalid {} DB index", which));
+        let first = parse_int(&self.index1).map_err(|_| invalid("first"))?;
+        let second = parse_int(&self.index2).map_err(|_| invalid("second"))?;
+        Ok((first, second))
+    }
+}
+
+impl CommandExecute for SwapDb {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        let result = self
+            .indexes()
+            .and_then(|(first, second)| backend.swapdb(first, second));
+
+        match result {
+            Ok(()) => Ok(OK.clone()),
+            Err(e) => Ok(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_swapdb() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+
+        assert_eq!(execute(&backend, &["swapdb", "0", "1"]), *OK);
+        assert_eq!(backend.dbsize(), 0);
+        backend.select(1).unwrap();
+        assert_eq!(backend.get("key"), Some(b"value".into()));
+
+        assert_eq!(
+            execute(&backend, &["swapdb", "x", "1"]),
+            BackendError::Other("invalid first DB index".to_string()).into()
+        );
+        assert_eq!(
+            execute(&backend, &["swapdb", "0", "99"]),
+            BackendError::DbIndexOutOfRange.into()
+        );
+    }
+}
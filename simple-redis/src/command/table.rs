@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+
+use super::Command;
+use crate::resp::frame::Frame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandFlag {
+    Write,
+    ReadOnly,
+    DenyOom,
+    Fast,
+    Blocking,
+    Admin,
+}
+
+impl CommandFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandFlag::Write => "write",
+            CommandFlag::ReadOnly => "readonly",
+            CommandFlag::DenyOom => "denyoom",
+            CommandFlag::Fast => "fast",
+            CommandFlag::Blocking => "blocking",
+            CommandFlag::Admin => "admin",
+        }
+    }
+}
+
+// where the key names are among the arguments, positions count the command name as 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySpec {
+    None,
+    // a negative `last` counts from the end, -1 being the last argument
+    Range { first: i64, last: i64, step: i64 },
+    // the argument at `numkeys` says how many keys directly follow it, like SINTERCARD
+    Counted { numkeys: usize },
+    // the argument at `index`, or every argument after `keyword` when that one is empty, like
+    // MIGRATE
+    Keyword { index: usize, keyword: &'static str },
+    // the argument at `index`, and the one after the last `keyword` when it is given, like SORT
+    // with STORE
+    Stored { index: usize, keyword: &'static str },
+}
+
+impl KeySpec {
+    // first, last and step as COMMAND reports them, keys that move around report zeros
+    pub fn positions(&self) -> (i64, i64, i64) {
+        match *self {
+            KeySpec::Range { first, last, step } => (first, last, step),
+            KeySpec::Stored { index, .. } => (index as i64, index as i64, 1),
+            KeySpec::None | KeySpec::Counted { .. } | KeySpec::Keyword { .. } => (0, 0, 0),
+        }
+    }
+
+    pub fn keys<'a>(&self, args: &'a [String]) -> Vec<&'a String> {
+        match *self {
+            KeySpec::None => Vec::new(),
+            KeySpec::Range { first, last, step } => {
+                let last = if last < 0 {
+                    args.len() as i64 + last
+                } else {
+                    last.min(args.len() as i64 - 1)
+                };
+
+                (first..=last)
+                    .step_by(step.max(1) as usize)
+                    .filter_map(|i| args.get(i as usize))
+                    .collect()
+            }
+            KeySpec::Counted { numkeys } => {
+                let count = args
+                    .get(numkeys)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .unwrap_or_default();
+                args.iter().skip(numkeys + 1).take(count).collect()
+            }
+            KeySpec::Keyword { index, keyword } => match args.get(index) {
+                Some(key) if !key.is_empty() => vec![key],
+                Some(_) => args
+                    .iter()
+                    .skip(index + 1)
+                    .skip_while(|arg| !arg.eq_ignore_ascii_case(keyword))
+                    .skip(1)
+                    .collect(),
+                None => Vec::new(),
+            },
+            KeySpec::Stored { index, keyword } => {
+                let Some(key) = args.get(index) else {
+                    return Vec::new();
+                };
+                let stored = args
+                    .iter()
+                    .skip(index + 1)
+                    .rposition(|arg| arg.eq_ignore_ascii_case(keyword))
+                    .and_then(|position| args.get(index + position + 2));
+                std::iter::once(key).chain(stored).collect()
+            }
+        }
+    }
+}
+
+// the type the keys of a command must hold, a key holding another one gets WRONGTYPE before the
+// command runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    // any type, or whatever the command replaces, like SET or DEL
+    Any,
+    // every key, like HGET or SINTER
+    Is(&'static str),
+    // the first key is a destination the command replaces whatever it held, the others hold the
+    // type, like SINTERSTORE
+    Store(&'static str),
+}
+
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    // like redis, a positive arity is exact and a negative one is a minimum, both count the name
+    pub arity: i64,
+    pub flags: &'static [CommandFlag],
+    pub acl_categories: &'static [&'static str],
+    pub keys: KeySpec,
+    pub key_type: KeyType,
+    pub group: &'static str,
+    pub summary: &'static str,
+    pub(crate) parse: fn(Frame) -> Result<Command>,
+}
+
+impl CommandSpec {
+    pub fn accepts(&self, argc: usize) -> bool {
+        accepts(self.arity, argc)
+    }
+
+    pub fn has_flag(&self, flag: CommandFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    // the keys whose type is checked before the command runs and the type they must hold
+    pub fn typed_keys<'a>(&self, args: &'a [String]) -> Option<(&'static str, Vec<&'a String>)> {
+        match self.key_type {
+            KeyType::Any => None,
+            KeyType::Is(kind) => Some((kind, self.keys.keys(args))),
+            KeyType::Store(kind) => {
+                Some((kind, self.keys.keys(args).into_iter().skip(1).collect()))
+            }
+        }
+    }
+}
+
+// the metadata `#[derive(RedisCommand)]` generates next to the parser, so the table entry of a
+// derived command does not repeat its arity and key positions
+pub trait RedisCommand {
+    const NAME: &'static str;
+    const ARITY: i64;
+    const KEYS: KeySpec;
+}
+
+fn accepts(arity: i64, argc: usize) -> bool {
+    let argc = argc as i64;
+    if arity >= 0 {
+        argc == arity
+    } else {
+        argc >= -arity
+    }
+}
+
+pub(crate) fn check_arity(name: &str, arity: i64, argc: usize) -> Result<()> {
+    if !accepts(arity, argc) {
+        anyhow::bail!("wrong number of arguments for '{}' command", name);
+    }
+    Ok(())
+}
+
+const fn keys(first: i64, last: i64, step: i64) -> KeySpec {
+    KeySpec::Range { first, last, step }
+}
+
+const ONE_KEY: KeySpec = keys(1, 1, 1);
+const ALL_KEYS: KeySpec = keys(1, -1, 1);
+
+const ANY: KeyType = KeyType::Any;
+const STRING: KeyType = KeyType::Is("string");
+const HASH: KeyType = KeyType::Is("hash");
+const SET: KeyType = KeyType::Is("set");
+const ZSET: KeyType = KeyType::Is("zset");
+const LIST: KeyType = KeyType::Is("list");
+
+macro_rules! command {
+    ($name:literal, $variant:ident, $arity:expr, [$($flag:ident),*], [$($acl:literal),*], $keys:expr, $key_type:expr, $group:literal, $summary:literal) => {
+        CommandSpec {
+            name: $name,
+            arity: $arity,
+            flags: &[$(CommandFlag::$flag),*],
+            acl_categories: &[$($acl),*],
+            keys: $keys,
+            key_type: $key_type,
+            group: $group,
+            summary: $summary,
+            parse: |frame| Ok(Command::$variant(frame.try_into()?)),
+        }
+    };
+    ($variant:ident($ty:path), [$($flag:ident),*], [$($acl:literal),*], $key_type:expr, $group:literal, $summary:literal) => {
+        CommandSpec {
+            name: <$ty as RedisCommand>::NAME,
+            arity: <$ty as RedisCommand>::ARITY,
+            flags: &[$(CommandFlag::$flag),*],
+            acl_categories: &[$($acl),*],
+            keys: <$ty as RedisCommand>::KEYS,
+            key_type: $key_type,
+            group: $group,
+            summary: $summary,
+            parse: |frame| Ok(Command::$variant(frame.try_into()?)),
+        }
+    };
+}
+
+// every command the server knows, dispatch and COMMAND both read from here
+#[rustfmt::skip]
+pub static COMMAND_TABLE: &[CommandSpec] = &[
+    // strings
+    command!(Get(super::get::Get), [ReadOnly, Fast], ["@read", "@string", "@fast"], STRING, "string", "Returns the string value of a key."),
+    command!(Set(super::set::Set), [Write, DenyOom], ["@write", "@string", "@slow"], ANY, "string", "Sets the string value of a key, ignoring its type."),
+    command!("incr", IncrBy, 2, [Write, DenyOom, Fast], ["@write", "@string", "@fast"], ONE_KEY, STRING, "string", "Increments the integer value of a key by one."),
+    command!("decr", IncrBy, 2, [Write, DenyOom, Fast], ["@write", "@string", "@fast"], ONE_KEY, STRING, "string", "Decrements the integer value of a key by one."),
+    command!("incrby", IncrBy, 3, [Write, DenyOom, Fast], ["@write", "@string", "@fast"], ONE_KEY, STRING, "string", "Increments the integer value of a key by a number."),
+    command!("decrby", IncrBy, 3, [Write, DenyOom, Fast], ["@write", "@string", "@fast"], ONE_KEY, STRING, "string", "Decrements a number from the integer value of a key."),
+    command!(IncrByFloat(super::incrbyfloat::IncrByFloat), [Write, DenyOom, Fast], ["@write", "@string", "@fast"], STRING, "string", "Increment the floating point value of a key by a number."),
+    command!(Append(super::append::Append), [Write, DenyOom, Fast], ["@write", "@string", "@fast"], STRING, "string", "Appends a string to the value of a key."),
+    command!(Strlen(super::strlen::Strlen), [ReadOnly, Fast], ["@read", "@string", "@fast"], STRING, "string", "Returns the length of a string value."),
+    command!(GetRange(super::getrange::GetRange), [ReadOnly], ["@read", "@string", "@slow"], STRING, "string", "Returns a substring of the string stored at a key."),
+    command!(SetRange(super::setrange::SetRange), [Write, DenyOom], ["@write", "@string", "@slow"], STRING, "string", "Overwrites a part of a string value with another by an offset."),
+    command!(Mget(super::mget::Mget), [ReadOnly, Fast], ["@read", "@string", "@fast"], ANY, "string", "Atomically returns the string values of one or more keys."),
+    command!("mset", Mset, -3, [Write, DenyOom], ["@write", "@string", "@slow"], keys(1, -1, 2), ANY, "string", "Atomically creates or modifies the string values of one or more keys."),
+    command!("msetnx", Mset, -3, [Write, DenyOom], ["@write", "@string", "@slow"], keys(1, -1, 2), ANY, "string", "Atomically modifies the string values of one or more keys only when all keys don't exist."),
+    command!(GetSet(super::getset::GetSet), [Write, DenyOom, Fast], ["@write", "@string", "@fast"], STRING, "string", "Returns the previous string value of a key after setting it to a new value."),
+    command!(GetDel(super::getdel::GetDel), [Write, Fast], ["@write", "@string", "@fast"], STRING, "string", "Returns the string value of a key after deleting the key."),
+    command!(SetNx(super::setnx::SetNx), [Write, DenyOom, Fast], ["@write", "@string", "@fast"], ANY, "string", "Set the string value of a key only when the key doesn't exist."),
+    command!("lcs", Lcs, -3, [ReadOnly], ["@read", "@string", "@slow"], keys(1, 2, 1), STRING, "string", "Finds the longest common substring."),
+    // bitmaps
+    command!(SetBit(super::setbit::SetBit), [Write, DenyOom], ["@write", "@bitmap", "@slow"], STRING, "bitmap", "Sets or clears the bit at offset of the string value. Creates the key if it doesn't exist."),
+    command!(GetBit(super::getbit::GetBit), [ReadOnly, Fast], ["@read", "@bitmap", "@fast"], STRING, "bitmap", "Returns a bit value by offset."),
+    command!("bitcount", BitCount, -2, [ReadOnly], ["@read", "@bitmap", "@slow"], ONE_KEY, STRING, "bitmap", "Counts the number of set bits (population counting) in a string."),
+    command!("bitpos", BitPos, -3, [ReadOnly], ["@read", "@bitmap", "@slow"], ONE_KEY, STRING, "bitmap", "Finds the first set (1) or clear (0) bit in a string."),
+    command!("bitop", BitOp, -4, [Write, DenyOom], ["@write", "@bitmap", "@slow"], keys(2, -1, 1), KeyType::Store("string"), "bitmap", "Performs bitwise operations on multiple strings, and stores the result."),
+    command!("bitfield", Bitfield, -2, [Write, DenyOom], ["@write", "@bitmap", "@slow"], ONE_KEY, STRING, "bitmap", "Performs arbitrary bitfield integer operations on strings."),
+    command!("bitfield_ro", Bitfield, -2, [ReadOnly, Fast], ["@read", "@bitmap", "@fast"], ONE_KEY, STRING, "bitmap", "Performs arbitrary read-only bitfield integer operations on strings."),
+    // hyperloglog
+    command!(PfAdd(super::pfadd::PfAdd), [Write, DenyOom, Fast], ["@write", "@hyperloglog", "@fast"], STRING, "hyperloglog", "Adds elements to a HyperLogLog key. Creates the key if it doesn't exist."),
+    command!(PfCount(super::pfcount::PfCount), [ReadOnly], ["@read", "@hyperloglog", "@slow"], STRING, "hyperloglog", "Returns the approximated cardinality of the set(s) observed by the HyperLogLog key(s)."),
+    command!(PfMerge(super::pfmerge::PfMerge), [Write, DenyOom], ["@write", "@hyperloglog", "@slow"], STRING, "hyperloglog", "Merges one or more HyperLogLog values into a single key."),
+    // geo
+    command!("geoadd", GeoAdd, -5, [Write, DenyOom], ["@write", "@geo", "@slow"], ONE_KEY, ZSET, "geo", "Adds one or more members to a geospatial index. The key is created if it doesn't exist."),
+    command!(GeoPos(super::geopos::GeoPos), [ReadOnly], ["@read", "@geo", "@slow"], ZSET, "geo", "Returns the longitude and latitude of members from a geospatial index."),
+    command!("geodist", GeoDist, -4, [ReadOnly], ["@read", "@geo", "@slow"], ONE_KEY, ZSET, "geo", "Returns the distance between two members of a geospatial index."),
+    command!(GeoHash(super::geohash::GeoHash), [ReadOnly], ["@read", "@geo", "@slow"], ZSET, "geo", "Returns members from a geospatial index as geohash strings."),
+    command!("geosearch", GeoSearch, -7, [ReadOnly], ["@read", "@geo", "@slow"], ONE_KEY, ZSET, "geo", "Queries a geospatial index for members inside an area of a box or a circle."),
+    command!("geosearchstore", GeoSearch, -8, [Write, DenyOom], ["@write", "@geo", "@slow"], keys(1, 2, 1), KeyType::Store("zset"), "geo", "Queries a geospatial index for members inside an area of a box or a circle, optionally stores the result."),
+    // hashes
+    command!(HGet(super::hget::HGet), [ReadOnly, Fast], ["@read", "@hash", "@fast"], HASH, "hash", "Returns the value of a field in a hash."),
+    command!("hset", HSet, -4, [Write, DenyOom, Fast], ["@write", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Creates or modifies the value of a field in a hash."),
+    command!(HSetNx(super::hsetnx::HSetNx), [Write, DenyOom, Fast], ["@write", "@hash", "@fast"], HASH, "hash", "Sets the value of a field in a hash only when the field doesn't exist."),
+    command!(HGetAll(super::hgetall::HGetAll), [ReadOnly], ["@read", "@hash", "@slow"], HASH, "hash", "Returns all fields and values in a hash."),
+    command!(Hmget(super::hmget::Hmget), [ReadOnly, Fast], ["@read", "@hash", "@fast"], HASH, "hash", "Returns the values of all fields in a hash."),
+    command!(HDel(super::hdel::HDel), [Write, Fast], ["@write", "@hash", "@fast"], HASH, "hash", "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain."),
+    command!(HExists(super::hexists::HExists), [ReadOnly, Fast], ["@read", "@hash", "@fast"], HASH, "hash", "Determines whether a field exists in a hash."),
+    command!(HLen(super::hlen::HLen), [ReadOnly, Fast], ["@read", "@hash", "@fast"], HASH, "hash", "Returns the number of fields in a hash."),
+    command!("hkeys", HKeys, 2, [ReadOnly], ["@read", "@hash", "@slow"], ONE_KEY, HASH, "hash", "Returns all fields in a hash."),
+    command!("hvals", HKeys, 2, [ReadOnly], ["@read", "@hash", "@slow"], ONE_KEY, HASH, "hash", "Returns all values in a hash."),
+    command!(HIncrBy(super::hincrby::HIncrBy), [Write, DenyOom, Fast], ["@write", "@hash", "@fast"], HASH, "hash", "Increments the integer value of a field in a hash by a number."),
+    command!(HIncrByFloat(super::hincrbyfloat::HIncrByFloat), [Write, DenyOom, Fast], ["@write", "@hash", "@fast"], HASH, "hash", "Increments the floating point value of a field by a number."),
+    command!(HStrlen(super::hstrlen::HStrlen), [ReadOnly, Fast], ["@read", "@hash", "@fast"], HASH, "hash", "Returns the length of the value of a field."),
+    command!("hrandfield", HRandField, -2, [ReadOnly], ["@read", "@hash", "@slow"], ONE_KEY, HASH, "hash", "Returns one or more random fields from a hash."),
+    command!("hscan", HScan, -3, [ReadOnly], ["@read", "@hash", "@slow"], ONE_KEY, HASH, "hash", "Iterates over fields and values of a hash."),
+    command!("hexpire", HExpire, -6, [Write, Fast], ["@write", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Set expiry for hash field using relative time to expire (seconds)."),
+    command!("hpexpire", HExpire, -6, [Write, Fast], ["@write", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Set expiry for hash field using relative time to expire (milliseconds)."),
+    command!("httl", HTtl, -5, [ReadOnly, Fast], ["@read", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Returns the TTL in seconds of a hash field."),
+    command!("hpttl", HTtl, -5, [ReadOnly, Fast], ["@read", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Returns the TTL in milliseconds of a hash field."),
+    command!("hpersist", HPersist, -5, [Write, Fast], ["@write", "@hash", "@fast"], ONE_KEY, HASH, "hash", "Removes the expiration time for each specified field."),
+    // sets
+    command!(Sadd(super::sadd::Sadd), [Write, DenyOom, Fast], ["@write", "@set", "@fast"], SET, "set", "Adds one or more members to a set. Creates the key if it doesn't exist."),
+    command!(Srem(super::srem::Srem), [Write, Fast], ["@write", "@set", "@fast"], SET, "set", "Removes one or more members from a set. Deletes the set if the last member was removed."),
+    command!(Smembers(super::smembers::Smembers), [ReadOnly], ["@read", "@set", "@slow"], SET, "set", "Returns all members of a set."),
+    command!(Sismember(super::sismember::Sismember), [ReadOnly, Fast], ["@read", "@set", "@fast"], SET, "set", "Determines whether a member belongs to a set."),
+    command!(Smismember(super::smismember::Smismember), [ReadOnly, Fast], ["@read", "@set", "@fast"], SET, "set", "Determines whether multiple members belong to a set."),
+    command!(Scard(super::scard::Scard), [ReadOnly, Fast], ["@read", "@set", "@fast"], SET, "set", "Returns the number of members in a set."),
+    command!("spop", Spop, -2, [Write, Fast], ["@write", "@set", "@fast"], ONE_KEY, SET, "set", "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped."),
+    command!("srandmember", Srandmember, -2, [ReadOnly], ["@read", "@set", "@slow"], ONE_KEY, SET, "set", "Get one or multiple random members from a set."),
+    command!(Smove(super::smove::Smove), [Write, Fast], ["@write", "@set", "@fast"], SET, "set", "Moves a member from one set to another."),
+    command!("sinter", SetOp, -2, [ReadOnly], ["@read", "@set", "@slow"], ALL_KEYS, SET, "set", "Returns the intersect of multiple sets."),
+    command!("sunion", SetOp, -2, [ReadOnly], ["@read", "@set", "@slow"], ALL_KEYS, SET, "set", "Returns the union of multiple sets."),
+    command!("sdiff", SetOp, -2, [ReadOnly], ["@read", "@set", "@slow"], ALL_KEYS, SET, "set", "Returns the difference of multiple sets."),
+    command!("sinterstore", SetOp, -3, [Write, DenyOom], ["@write", "@set", "@slow"], ALL_KEYS, KeyType::Store("set"), "set", "Stores the intersect of multiple sets in a key."),
+    command!("sunionstore", SetOp, -3, [Write, DenyOom], ["@write", "@set", "@slow"], ALL_KEYS, KeyType::Store("set"), "set", "Stores the union of multiple sets in a key."),
+    command!("sdiffstore", SetOp, -3, [Write, DenyOom], ["@write", "@set", "@slow"], ALL_KEYS, KeyType::Store("set"), "set", "Stores the difference of multiple sets in a key."),
+    command!("sintercard", Sintercard, -3, [ReadOnly], ["@read", "@set", "@slow"], KeySpec::Counted { numkeys: 1 }, SET, "set", "Returns the number of members of the intersect of multiple sets."),
+    command!("sscan", Sscan, -3, [ReadOnly], ["@read", "@set", "@slow"], ONE_KEY, SET, "set", "Iterates over members of a set."),
+    // lists
+    command!(LPush(super::lpush::LPush), [Write, DenyOom, Fast], ["@write", "@list", "@fast"], LIST, "list", "Prepends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!(RPush(super::lpush::RPush), [Write, DenyOom, Fast], ["@write", "@list", "@fast"], LIST, "list", "Appends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!(LRange(super::lrange::LRange), [ReadOnly], ["@read", "@list", "@slow"], LIST, "list", "Returns a range of elements from a list."),
+    command!(LLen(super::llen::LLen), [ReadOnly, Fast], ["@read", "@list", "@fast"], LIST, "list", "Returns the length of a list."),
+    // keyspace, connection and server
+    command!("scan", Scan, -2, [ReadOnly], ["@keyspace", "@read", "@slow"], KeySpec::None, ANY, "generic", "Iterates over the key names in the database."),
+    command!(Del(super::del::Del), [Write], ["@keyspace", "@write", "@slow"], ANY, "generic", "Deletes one or more keys."),
+    command!(Unlink(super::del::Unlink), [Write, Fast], ["@keyspace", "@write", "@fast"], ANY, "generic", "Asynchronously deletes one or more keys."),
+    command!(Move(super::move_key::Move), [Write, Fast], ["@keyspace", "@write", "@fast"], ANY, "generic", "Moves a key to another database."),
+    command!(Dump(super::dump::Dump), [ReadOnly], ["@keyspace", "@read", "@slow"], ANY, "generic", "Returns a serialized representation of the value stored at a key."),
+    command!(Restore(super::restore::Restore), [Write, DenyOom], ["@keyspace", "@write", "@slow", "@dangerous"], ANY, "generic", "Creates a key from the serialized representation of a value."),
+    command!("ttl", Ttl, 2, [ReadOnly, Fast], ["@keyspace", "@read", "@fast"], ONE_KEY, ANY, "generic", "Returns the expiration time in seconds of a key."),
+    command!("pttl", Ttl, 2, [ReadOnly, Fast], ["@keyspace", "@read", "@fast"], ONE_KEY, ANY, "generic", "Returns the expiration time in milliseconds of a key."),
+    command!("migrate", Migrate, -6, [Write], ["@keyspace", "@write", "@slow", "@dangerous"], KeySpec::Keyword { index: 3, keyword: "KEYS" }, ANY, "generic", "Atomically transfers a key from one Redis instance to another."),
+    command!("sort", Sort, -2, [Write, DenyOom], ["@write", "@set", "@sortedset", "@list", "@slow", "@dangerous"], KeySpec::Stored { index: 1, keyword: "STORE" }, ANY, "generic", "Sorts the elements in a list, a set, or a sorted set, optionally storing the result."),
+    command!("sort_ro", Sort, -2, [ReadOnly], ["@read", "@set", "@sortedset", "@list", "@slow", "@dangerous"], ONE_KEY, ANY, "generic", "Returns the sorted elements of a list, a set, or a sorted set."),
+    command!(Select(super::select::Select), [Fast], ["@fast", "@connection"], ANY, "connection", "Changes the selected database."),
+    command!(SwapDb(super::swapdb::SwapDb), [Write, Fast], ["@keyspace", "@write", "@fast", "@dangerous"], ANY, "server", "Swaps two Redis databases."),
+    command!("flushdb", Flush, -1, [Write], ["@keyspace", "@write", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "Remove all keys from the current database."),
+    command!("flushall", Flush, -1, [Write], ["@keyspace", "@write", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "Removes all keys from all databases."),
+    command!(DbSize(super::dbsize::DbSize), [ReadOnly, Fast], ["@keyspace", "@read", "@fast"], ANY, "server", "Returns the number of keys in the database."),
+    command!(Info(super::info::Info), [], ["@slow", "@dangerous"], ANY, "server", "Returns information and statistics about the server."),
+    command!("client", Client, -2, [], ["@slow", "@connection"], KeySpec::None, ANY, "connection", "A container for client connection commands."),
+    command!(Echo(super::echo::Echo), [Fast], ["@fast", "@connection"], ANY, "connection", "Returns the given string."),
+    command!("hello", Hello, -1, [Fast], ["@fast", "@connection"], KeySpec::None, ANY, "connection", "Handshakes with the Redis server."),
+    command!("command", Commands, -1, [], ["@slow", "@connection"], KeySpec::None, ANY, "server", "Returns detailed information about all commands."),
+    command!("slowlog", Slowlog, -2, [Admin], ["@admin", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "A container for slow log commands."),
+    command!("latency", Latency, -2, [Admin], ["@admin", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "A container for latency diagnostics commands."),
+    command!(Object(super::object::Object), [ReadOnly], ["@keyspace", "@read", "@slow"], ANY, "generic", "A container for object introspection commands."),
+    command!("memory", Memory, -2, [ReadOnly], ["@slow"], keys(2, 2, 1), ANY, "server", "A container for memory diagnostics commands."),
+    command!("debug", Debug, -2, [Admin], ["@admin", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "A container for debugging commands."),
+    command!(Monitor(super::monitor::Monitor), [Admin], ["@admin", "@slow", "@dangerous"], ANY, "server", "Listens for all requests received by the server in real-time."),
+    command!("shutdown", Shutdown, -1, [Admin], ["@admin", "@slow", "@dangerous"], KeySpec::None, ANY, "server", "Synchronously saves the database(s) to disk and shuts down the Redis server."),
+];
+
+lazy_static! {
+    static ref COMMANDS_BY_NAME: HashMap<&'static str, &'static CommandSpec> =
+        COMMAND_TABLE.iter().map(|spec| (spec.name, spec)).collect();
+}
+
+pub fn lookup(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS_BY_NAME.get(name.to_lowercase().as_str()).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::RedisCommand;
+
+    #[derive(Debug, RedisCommand)]
+    #[command(name = "example")]
+    struct Example {
+        #[arg(key)]
+        key: String,
+        #[arg(raw)]
+        value: Frame,
+        #[arg(flag, token = "WITHSCORES")]
+        scores: bool,
+        #[arg(option)]
+        ex: Option<i64>,
+    }
+
+    fn frame(args: &[&str]) -> Frame {
+        args.iter()
+            .map(|arg| arg.as_bytes().into())
+            .collect::<Vec<Frame>>()
+            .into()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_command_table_names_are_unique() {
+        assert_eq!(COMMANDS_BY_NAME.len(), COMMAND_TABLE.len());
+        assert!(lookup("GET").is_some());
+        assert!(lookup("nope").is_none());
+    }
+
+    #[test]
+    fn test_command_spec_accepts() {
+        let get = lookup("get").unwrap();
+        assert!(get.accepts(2));
+        assert!(!get.accepts(3));
+
+        let set = lookup("set").unwrap();
+        assert!(!set.accepts(2));
+        assert!(!set.accepts(5));
+
+        let sadd = lookup("sadd").unwrap();
+        assert!(!sadd.accepts(2));
+        assert!(sadd.accepts(5));
+    }
+
+    #[test]
+    fn test_key_spec_keys() {
+        let mset = args(&["mset", "a", "1", "b", "2"]);
+        assert_eq!(lookup("mset").unwrap().keys.keys(&mset), vec!["a", "b"]);
+
+        let bitop = args(&["bitop", "and", "dest", "x", "y"]);
+        assert_eq!(
+            lookup("bitop").unwrap().keys.keys(&bitop),
+            vec!["dest", "x", "y"]
+        );
+
+        let sintercard = args(&["sintercard", "2", "a", "b", "limit", "1"]);
+        assert_eq!(
+            lookup("sintercard").unwrap().keys.keys(&sintercard),
+            vec!["a", "b"]
+        );
+
+        let migrate = lookup("migrate").unwrap();
+        let single = args(&["migrate", "host", "6379", "key", "0", "100", "copy"]);
+        assert_eq!(migrate.keys.keys(&single), vec!["key"]);
+        let many = args(&["migrate", "host", "6379", "", "0", "100", "KEYS", "a", "b"]);
+        assert_eq!(migrate.keys.keys(&many), vec!["a", "b"]);
+
+        let sort = lookup("sort").unwrap();
+        let stored = args(&["sort", "key", "get", "store", "store", "dst"]);
+        assert_eq!(sort.keys.keys(&stored), vec!["key", "dst"]);
+        assert_eq!(sort.keys.keys(&args(&["sort", "key", "desc"])), vec!["key"]);
+        assert_eq!(
+            sort.keys.keys(&args(&["sort", "key", "store"])),
+            vec!["key"]
+        );
+    }
+
+    #[test]
+    fn test_command_spec_typed_keys() {
+        let sunion = args(&["sunion", "a", "b"]);
+        assert_eq!(
+            lookup("sunion").unwrap().typed_keys(&sunion),
+            Some(("set", vec![&sunion[1], &sunion[2]]))
+        );
+        // the destination is replaced whatever it held
+        let store = args(&["sunionstore", "dest", "a"]);
+        assert_eq!(
+            lookup("sunionstore").unwrap().typed_keys(&store),
+            Some(("set", vec![&store[2]]))
+        );
+        let set = args(&["set", "key", "value"]);
+        assert_eq!(lookup("set").unwrap().typed_keys(&set), None);
+        assert_eq!(lookup("geoadd").unwrap().key_type, ZSET);
+    }
+
+    #[test]
+    fn test_derived_command_metadata() {
+        assert_eq!(Example::NAME, "example");
+        assert_eq!(Example::ARITY, -3);
+        assert_eq!(Example::KEYS, keys(1, 1, 1));
+
+        let hmget = lookup("hmget").unwrap();
+        assert_eq!(hmget.arity, -3);
+        assert_eq!(hmget.keys, ONE_KEY);
+        assert_eq!(lookup("echo").unwrap().keys, KeySpec::None);
+    }
+
+    #[test]
+    fn test_derived_command_parser() {
+        let example = Example::try_from(frame(&["EXAMPLE", "key", "v", "ex", "10", "withscores"]));
+        let example = example.unwrap();
+        assert_eq!(example.key, "key");
+        assert_eq!(example.value, b"v".into());
+        assert!(example.scores);
+        assert_eq!(example.ex, Some(10));
+
+        let example = Example::try_from(frame(&["example", "key", "v"])).unwrap();
+        assert!(!example.scores);
+        assert_eq!(example.ex, None);
+
+        for args in [
+            &["get", "key", "v"][..],
+            &["example", "key"],
+            &["example", "key", "v", "ex"],
+            &["example", "key", "v", "nope"],
+        ] {
+            assert!(Example::try_from(frame(args)).is_err());
+        }
+    }
+}
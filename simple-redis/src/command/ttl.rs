@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+use super::parse::Parse;
+use super::CommandExecute;
+use crate::backend::Backend;
+use crate::resp::frame::Frame;
+
+// TTL and PTTL
+#[derive(Debug)]
+pub struct Ttl {
+    pub(crate) key: String,
+    pub(crate) millis: bool,
+}
+
+impl CommandExecute for Ttl {
+    fn execute(&self, backend: Backend) -> Result<Frame> {
+        Ok(match backend.pttl(&self.key) {
+            // like redis, seconds are rounded up
+            ttl if ttl >= 0 && !self.millis => (ttl as u64).div_ceil(1000) as i64,
+            ttl => ttl,
+        }
+        .into())
+    }
+}
+
+impl TryFrom<Frame> for Ttl {
+    type Error = anyhow::Error;
+
+    fn try_from(frame: Frame) -> Result<Self> {
+        let mut parse = Parse::try_new(frame)?;
+        let command = parse.next_string()?.to_uppercase();
+
+        let millis = match command.as_str() {
+            "TTL" => false,
+            "PTTL" => true,
+            _ => anyhow::bail!("Invalid command"),
+        };
+
+        let key = parse.next_string()?;
+        parse.finish()?;
+
+        Ok(Self { key, millis })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::tests::execute;
+
+    #[test]
+    fn test_ttl() {
+        let backend = Backend::new();
+        backend.set("key", "value");
+        backend.set("expiring", "value");
+        backend.set_expire_at("expiring", Some(u64::MAX));
+
+        assert_eq!(execute(&backend, &["ttl", "missing"]), (-2).into());
+        assert_eq!(execute(&backend, &["pttl", "key"]), (-1).into());
+        match execute(&backend, &["ttl", "expiring"]) {
+            Frame::Integer(ttl) => assert!(ttl.inner > 1_000_000),
+            frame => panic!("Expected Integer, got {:?}", frame),
+        }
+    }
+}
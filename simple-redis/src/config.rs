@@ -0,0 +1,57 @@
+use crate::backend::OutputBufferLimits;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Commands slower than this many microseconds are written to the slow log.
+    /// Zero logs every command, a negative value disables the slow log.
+    pub slowlog_log_slower_than: i64,
+    pub slowlog_max_len: usize,
+    /// Latency events of at least this many milliseconds are sampled by the
+    /// latency monitor. Zero disables it.
+    pub latency_monitor_threshold: u64,
+    /// Number of logical databases, SELECT takes an index below it.
+    pub databases: usize,
+    /// Keys remembered for client side caching, reading more invalidates the
+    /// oldest ones. Zero means no limit.
+    pub tracking_table_max_keys: usize,
+    /// Pending output per client class that gets a client closed, see
+    /// `OutputBufferLimits::set` for the `client-output-buffer-limit` syntax.
+    pub client_output_buffer_limits: OutputBufferLimits,
+    /// Clients idle for this many seconds are closed. Zero keeps them open.
+    pub timeout: u64,
+    /// Seconds between TCP keepalive probes of idle client sockets. Zero
+    /// disables keepalive.
+    pub tcp_keepalive: u64,
+    /// Connections beyond this many are rejected.
+    pub maxclients: usize,
+    /// Whether keys deleted for each reason are freed on the lazyfree thread
+    /// instead of by the command. Expiry covers hashes emptied by their
+    /// expiring fields. There is no eviction yet, so the first one only takes
+    /// effect once there is.
+    pub lazyfree_lazy_eviction: bool,
+    pub lazyfree_lazy_expire: bool,
+    /// Deletes and overwrites the server does itself, like RESTORE REPLACE.
+    pub lazyfree_lazy_server_del: bool,
+    /// DEL behaves like UNLINK.
+    pub lazyfree_lazy_user_del: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            slowlog_log_slower_than: 10_000,
+            slowlog_max_len: 128,
+            latency_monitor_threshold: 0,
+            databases: 16,
+            tracking_table_max_keys: 1_000_000,
+            client_output_buffer_limits: OutputBufferLimits::default(),
+            timeout: 0,
+            tcp_keepalive: 300,
+            maxclients: 10_000,
+            lazyfree_lazy_eviction: false,
+            lazyfree_lazy_expire: false,
+            lazyfree_lazy_server_del: false,
+            lazyfree_lazy_user_del: false,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+mod partition;
+mod router;
+
+use std::thread::JoinHandle;
+
+use anyhow::{anyhow, Result};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio::task::LocalSet;
+use tracing::{info, warn, Instrument, Span};
+
+use crate::backend::{Backend, ConnectionKind, Db, ShutdownMode};
+use crate::network::{shutdown_aware_stream_handle, Connection};
+pub use partition::partition_of;
+pub(crate) use router::Router;
+
+// how the server runs commands, picked when it starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Engine {
+    // every connection is a task on the work-stealing runtime and all of them share one keyspace
+    #[default]
+    Shared,
+    // one single-threaded runtime pinned to each core, owning the keys that hash to it, 0 starts
+    // one per available core
+    ThreadPerCore(usize),
+}
+
+// the cores of the thread-per-core engine, connections are handed to them in turn
+pub(crate) struct ThreadPerCore {
+    router: Router,
+    cores: Vec<mpsc::UnboundedSender<std::net::TcpStream>>,
+    next: usize,
+    workers: Vec<JoinHandle<Result<Backend>>>,
+}
+
+impl ThreadPerCore {
+    // moves the keys of `backend` into the partitions, it stays empty until `stop`
+    pub(crate) fn start(
+        backend: &Backend,
+        cores: usize,
+        shutdown: watch::Receiver<Option<ShutdownMode>>,
+        span: Span,
+    ) -> Result<Self> {
+        let cores = match cores {
+            0 => std::thread::available_parallelism().map_or(1, usize::from),
+            cores => cores,
+        };
+        let core_ids = core_affinity::get_core_ids().unwrap_or_default();
+
+        let partitions: Vec<Backend> = (0..cores).map(|_| backend.partition()).collect();
+        for index in 0..backend.databases() {
+            transfer(backend.db(index), |key| {
+                partitions[partition_of(key, cores)].db(index)
+            });
+        }
+
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..cores).map(|_| mpsc::unbounded_channel()).unzip();
+        let router = Router::new(senders);
+
+        let mut connections = Vec::with_capacity(cores);
+        let mut workers = Vec::with_capacity(cores);
+        for (core, (partition, jobs)) in partitions.into_iter().zip(receivers).enumerate() {
+            let (sender, mut accepted) = mpsc::unbounded_channel::<std::net::TcpStream>();
+            connections.push(sender);
+
+            let core_id = (!core_ids.is_empty()).then(|| core_ids[core % core_ids.len()]);
+            let template = backend.clone();
+            let router = router.clone();
+            let shutdown = shutdown.clone();
+            let span = span.clone();
+            let worker = std::thread::Builder::new()
+                .name(format!("simple-redis-core-{}", core))
+                .spawn(move || {
+                    if let Some(core_id) = core_id {
+                        core_affinity::set_for_current(core_id);
+                    }
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()?;
+
+                    let accept = async move {
+                        while let Some(stream) = accepted.recv().await {
+                            let stream = match TcpStream::from_std(stream) {
+                                Ok(stream) => stream,
+                                Err(e) => {
+                                    warn!("Failed to register connection: {}", e);
+                                    continue;
+                                }
+                            };
+                            // commands without keys run on the shared template
+                            let connection = Connection::tcp(&stream, ConnectionKind::Tcp);
+                            let backend = template.clone();
+                            let router = router.clone();
+                            let shutdown = shutdown.clone();
+                            tokio::task::spawn_local(
+                                async move {
+                                    if let Err(e) = shutdown_aware_stream_handle(
+                                        stream,
+                                        connection,
+                                        backend,
+                                        shutdown,
+                                        Some(router),
+                                    )
+                                    .await
+                                    {
+                                        info!("Error: {:?}", e);
+                                    }
+                                }
+                                .instrument(span.clone()),
+                            );
+                        }
+                    };
+
+                    let local = LocalSet::new();
+                    let (_, partition) = local.block_on(&runtime, async {
+                        tokio::join!(accept, partition::run_partition(partition, jobs))
+                    });
+                    Ok(partition)
+                })?;
+            workers.push(worker);
+        }
+
+        info!("Started the thread-per-core engine on {} cores", cores);
+        Ok(Self {
+            router,
+            cores: connections,
+            next: 0,
+            workers,
+        })
+    }
+
+    pub(crate) fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    pub(crate) fn accept(&mut self, stream: TcpStream) -> Result<()> {
+        let stream = stream.into_std()?;
+        self.cores[self.next]
+            .send(stream)
+            .map_err(|_| anyhow!("core {} stopped", self.next))?;
+        self.next = (self.next + 1) % self.cores.len();
+        Ok(())
+    }
+
+    // waits for the connections of every core to finish, then moves the keys back into `backend`
+    pub(crate) async fn stop(self, backend: &Backend) -> Result<()> {
+        let Self {
+            router,
+            cores,
+            workers,
+            ..
+        } = self;
+        drop(cores);
+        drop(router);
+
+        let partitions = tokio::task::spawn_blocking(move || {
+            workers
+                .into_iter()
+                .map(|worker| worker.join().map_err(|_| anyhow!("core thread panicked"))?)
+                .collect::<Result<Vec<_>>>()
+        })
+        .await??;
+
+        for partition in partitions {
+            for index in 0..backend.databases() {
+                transfer(partition.db(index), |_| backend.db(index));
+            }
+        }
+        Ok(())
+    }
+}
+
+// moves every key of `from` into the database `to` picks for it
+fn transfer<'a>(from: &Db, to: impl Fn(&str) -> &'a Db) {
+    for key in from.keys() {
+        if let Some((value, expire_at)) = from.take_expiring(&key) {
+            to(&key).insert_expiring(key, value, expire_at);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::backend::{Backend, Value};
+use crate::command::{parse_error, Command, CommandExecute};
+use crate::network::RespRequest;
+use crate::resp::frame::Frame;
+
+// like redis cluster, only the part between the first `{` and the next `}` is hashed when it is
+// not empty, so keys sharing a tag always live on the same partition
+pub fn partition_of(key: &str, partitions: usize) -> usize {
+    let tag = key
+        .find('{')
+        .and_then(|open| {
+            let rest = &key[open + 1..];
+            rest.find('}')
+                .filter(|len| *len > 0)
+                .map(|len| &rest[..len])
+        })
+        .unwrap_or(key);
+
+    let mut hasher = DefaultHasher::new();
+    tag.hash(&mut hasher);
+    (hasher.finish() % partitions as u64) as usize
+}
+
+// a value and the unix time in milliseconds it expires at, as it moves between backends
+pub(crate) type Expiring = (Value, Option<u64>);
+
+#[derive(Debug)]
+pub(crate) enum Job {
+    Execute(Execute),
+    Lend(Lend),
+}
+
+// a command that only touches keys of the partition running it, when it is sent to several
+// partitions only one of them `record`s it in the monitor feed, slow log and latency samples
+#[derive(Debug)]
+pub(crate) struct Execute {
+    pub(crate) frame: Frame,
+    pub(crate) db: usize,
+    pub(crate) client: Option<SocketAddr>,
+    pub(crate) record: bool,
+    pub(crate) reply: oneshot::Sender<Result<Frame>>,
+}
+
+// hands copies of `keys` and their deadlines to a coordinator and stops the partition until it
+// releases them
+#[derive(Debug)]
+pub(crate) struct Lend {
+    pub(crate) db: usize,
+    pub(crate) keys: Vec<String>,
+    pub(crate) reply: oneshot::Sender<Vec<(String, Value, Option<u64>)>>,
+    pub(crate) release: oneshot::Receiver<Release>,
+}
+
+// what the coordinator changed, a `None` value deletes the key, `then` runs before the partition
+// serves anything else
+#[derive(Debug, Default)]
+pub(crate) struct Release {
+    pub(crate) updates: Vec<(String, Option<Expiring>)>,
+    pub(crate) then: Option<Execute>,
+}
+
+impl Execute {
+    pub(crate) fn new(
+        frame: Frame,
+        db: usize,
+        client: Option<SocketAddr>,
+        record: bool,
+    ) -> (Self, oneshot::Receiver<Result<Frame>>) {
+        let (reply, receiver) = oneshot::channel();
+        let execute = Self {
+            frame,
+            db,
+            client,
+            record,
+            reply,
+        };
+        (execute, receiver)
+    }
+
+    fn run(self, backend: &Backend) {
+        // the index was checked by the SELECT of the connection sending it
+        backend.select(self.db as i64).ok();
+        let result = if self.record {
+            RespRequest::new(self.frame, backend.clone(), self.client).execute()
+        } else {
+            match Command::try_from(self.frame) {
+                Ok(command) => command.execute(backend.clone()),
+                Err(e) => Ok(parse_error(&e)),
+            }
+        };
+        self.reply.send(result).ok();
+    }
+}
+
+// serves the jobs of one partition one at a time, then hands the partition back
+pub(crate) async fn run_partition(
+    backend: Backend,
+    mut jobs: mpsc::UnboundedReceiver<Job>,
+) -> Backend {
+    while let Some(job) = jobs.recv().await {
+        match job {
+            Job::Execute(execute) => execute.run(&backend),
+            Job::Lend(lend) => {
+                backend.select(lend.db as i64).ok();
+                let values = lend
+                    .keys
+                    .into_iter()
+                    .filter_map(|key| {
+                        let value = backend.value(&key)?;
+                        let expire_at = backend.expire_at(&key);
+                        Some((key, value, expire_at))
+                    })
+                    .collect();
+                if lend.reply.send(values).is_err() {
+                    continue;
+                }
+
+                // a coordinator that went away leaves the keys untouched
+                let Ok(release) = lend.release.await else {
+                    continue;
+                };
+                for (key, value) in release.updates {
+                    backend.take(&key);
+                    if let Some((value, expire_at)) = value {
+                        backend.insert_expiring(key, value, expire_at);
+                    }
+                }
+                if let Some(execute) = release.then {
+                    execute.run(&backend);
+                }
+            }
+        }
+    }
+
+    backend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_of_hash_tags() {
+        assert!(partition_of("key", 4) < 4);
+        assert_eq!(partition_of("anything", 1), 0);
+
+        for n in 2..16 {
+            assert_eq!(partition_of("{user:1}.name", n), partition_of("user:1", n));
+            assert_eq!(partition_of("x{user:1}y", n), partition_of("{user:1}", n));
+        }
+    }
+}
@@ -0,0 +1,544 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, Result};
+use tokio::sync::{mpsc, oneshot};
+
+use super::partition::{partition_of, Execute, Job, Lend, Release};
+use crate::backend::{Backend, BackendError, Value};
+use crate::command::{lookup, CommandFlag};
+use crate::network::{command_args, RespRequest};
+use crate::resp::frame::Frame;
+
+// sends every command to the partitions owning its keys
+#[derive(Debug, Clone)]
+pub(crate) struct Router {
+    partitions: Arc<Vec<mpsc::UnboundedSender<Job>>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    // no key involved, runs on the connection's own backend
+    Local,
+    Partition(usize),
+    // keys on several partitions, lent to the connection and written back in one step
+    Coordinated {
+        owners: BTreeMap<usize, BTreeSet<String>>,
+        read_only: bool,
+    },
+    // runs on every partition, the replies are merged
+    Broadcast,
+    // SCAN goes through the partitions one after the other, `cursor` is the one of `partition`
+    Scan {
+        partition: usize,
+        cursor: usize,
+    },
+    // holds every partition while it runs on each of them
+    Exclusive,
+    // a SORT option reading keys that are only known while sorting, they may live on any
+    // partition
+    Denied(&'static str),
+}
+
+struct Lent {
+    values: Vec<(String, Value, Option<u64>)>,
+    releases: Vec<(usize, oneshot::Sender<Release>)>,
+}
+
+impl Router {
+    pub(crate) fn new(partitions: Vec<mpsc::UnboundedSender<Job>>) -> Self {
+        Self {
+            partitions: Arc::new(partitions),
+        }
+    }
+
+    fn route(&self, args: &[String]) -> Route {
+        // unknown commands and wrong arities get the same error reply they do without the engine
+        let Some(spec) = args
+            .first()
+            .and_then(|name| lookup(name))
+            .filter(|spec| spec.accepts(args.len()))
+        else {
+            return Route::Local;
+        };
+
+        match spec.name {
+            "dbsize" | "info" => return Route::Broadcast,
+            "scan" => {
+                return match args[1].parse::<usize>() {
+                    Ok(cursor) => Route::Scan {
+                        partition: cursor % self.partitions.len(),
+                        cursor: cursor / self.partitions.len(),
+                    },
+                    // the invalid cursor error
+                    Err(_) => Route::Local,
+                };
+            }
+            "swapdb" | "flushdb" | "flushall" => return Route::Exclusive,
+            // DEBUG OBJECT reads a key of one partition, a reload rewrites all of them
+            "debug" => {
+                return match args[1].to_lowercase().as_str() {
+                    "object" if args.len() > 2 => {
+                        Route::Partition(partition_of(&args[2], self.partitions.len()))
+                    }
+                    "reload" => Route::Exclusive,
+                    _ => Route::Local,
+                }
+            }
+            // like redis cluster does, instead of reading them from the wrong partition
+            "sort" | "sort_ro" => {
+                if let Some(option) = sort_pattern_option(&args[2..]) {
+                    return Route::Denied(option);
+                }
+            }
+            _ => {}
+        }
+
+        let mut owners: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+        for key in spec.keys.keys(args) {
+            let partition = partition_of(key, self.partitions.len());
+            owners.entry(partition).or_default().insert(key.clone());
+        }
+
+        match owners.len() {
+            0 => Route::Local,
+            // MIGRATE awaits its target on the connection with the keys lent, not on the partition
+            1 if spec.name != "migrate" => Route::Partition(*owners.keys().next().unwrap()),
+            _ => Route::Coordinated {
+                owners,
+                read_only: spec.has_flag(CommandFlag::ReadOnly),
+            },
+        }
+    }
+
+    // the reply, and whether the connection switched to monitor mode; `scratch` holds the keys
+    // lent to the connection, it is made the first time it is needed and reused after that
+    pub(crate) async fn dispatch(
+        &self,
+        frame: Frame,
+        session: &Backend,
+        scratch: &OnceLock<Backend>,
+        client: Option<SocketAddr>,
+    ) -> Result<(Frame, bool)> {
+        let args = command_args(&frame);
+        let db = session.selected_db();
+
+        let reply = match self.route(&args) {
+            Route::Local => {
+                let request = RespRequest::new(frame, session.clone(), client);
+                return Ok((request.execute_async().await?, request.is_monitor()));
+            }
+            Route::Partition(partition) => {
+                let (execute, reply) = Execute::new(frame, db, client, true);
+                self.send(partition, Job::Execute(execute))?;
+                receive(reply).await?
+            }
+            Route::Coordinated { owners, read_only } => {
+                let lent = self
+                    .lend(
+                        owners
+                            .iter()
+                            .map(|(partition, keys)| (*partition, keys.iter().cloned().collect())),
+                        db,
+                    )
+                    .await?;
+                let scratch = scratch.get_or_init(|| session.scratch());
+                scratch.select(db as i64)?;
+                self.coordinate(frame, scratch, client, lent, owners, read_only)
+                    .await?
+            }
+            Route::Broadcast => {
+                let mut replies = Vec::with_capacity(self.partitions.len());
+                for partition in 0..self.partitions.len() {
+                    let (execute, reply) = Execute::new(frame.clone(), db, client, partition == 0);
+                    self.send(partition, Job::Execute(execute))?;
+                    replies.push(reply);
+                }
+                let mut frames = Vec::with_capacity(replies.len());
+                for reply in replies {
+                    frames.push(receive(reply).await?);
+                }
+                merge(&args[0], frames)
+            }
+            Route::Scan { partition, cursor } => {
+                let (execute, reply) = Execute::new(with_cursor(frame, cursor), db, client, true);
+                self.send(partition, Job::Execute(execute))?;
+                let reply = receive(reply).await?;
+                scan_reply(reply, partition, self.partitions.len())
+            }
+            Route::Denied(option) => BackendError::Other(format!(
+                "{} option of SORT denied with the thread-per-core engine",
+                option
+            ))
+            .into(),
+            Route::Exclusive => {
+                let lent = self
+                    .lend(
+                        (0..self.partitions.len()).map(|partition| (partition, Vec::new())),
+                        db,
+                    )
+                    .await?;
+                let mut replies = Vec::with_capacity(lent.releases.len());
+                for (partition, release) in lent.releases {
+                    let (execute, reply) = Execute::new(frame.clone(), db, client, partition == 0);
+                    let then = Some(execute);
+                    release
+                        .send(Release {
+                            then,
+                            ..Default::default()
+                        })
+                        .map_err(|_| stopped(partition))?;
+                    replies.push(reply);
+                }
+                let mut frames = Vec::with_capacity(replies.len());
+                for reply in replies {
+                    frames.push(receive(reply).await?);
+                }
+                frames.swap_remove(0)
+            }
+        };
+
+        Ok((reply, false))
+    }
+
+    // partitions are always held in ascending order, so two coordinators never wait on each other
+    async fn lend(
+        &self,
+        partitions: impl Iterator<Item = (usize, Vec<String>)>,
+        db: usize,
+    ) -> Result<Lent> {
+        let mut lent = Lent {
+            values: Vec::new(),
+            releases: Vec::new(),
+        };
+
+        for (partition, keys) in partitions {
+            let (reply, values) = oneshot::channel();
+            let (release, released) = oneshot::channel();
+            let job = Job::Lend(Lend {
+                db,
+                keys,
+                reply,
+                release: released,
+            });
+            self.send(partition, job)?;
+            lent.values
+                .extend(values.await.map_err(|_| stopped(partition))?);
+            lent.releases.push((partition, release));
+        }
+
+        Ok(lent)
+    }
+
+    // runs the command on the scratch backend holding the lent values, a successful write hands
+    // the keys of each partition back with their new values, the scratch is left empty
+    async fn coordinate(
+        &self,
+        frame: Frame,
+        scratch: &Backend,
+        client: Option<SocketAddr>,
+        lent: Lent,
+        owners: BTreeMap<usize, BTreeSet<String>>,
+        read_only: bool,
+    ) -> Result<Frame> {
+        for (key, value, expire_at) in lent.values {
+            scratch.insert_expiring(key, value, expire_at);
+        }
+
+        // a reader holds the partitions only long enough to copy the values
+        let mut releases = lent.releases;
+        if read_only {
+            releases.clear();
+        }
+
+        let result = RespRequest::new(frame, scratch.clone(), client)
+            .execute_async()
+            .await;
+        for (partition, release) in releases {
+            let updates = match (&result, owners.get(&partition)) {
+                (Ok(_), Some(keys)) => keys
+                    .iter()
+                    .map(|key| (key.clone(), scratch.take_expiring(key)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            release
+                .send(Release {
+                    updates,
+                    then: None,
+                })
+                .map_err(|_| stopped(partition))?;
+        }
+        // what a reader or a failed write leaves behind are copies
+        drop(scratch.drain());
+
+        result
+    }
+
+    fn send(&self, partition: usize, job: Job) -> Result<()> {
+        self.partitions[partition]
+            .send(job)
+            .map_err(|_| stopped(partition))
+    }
+}
+
+async fn receive(reply: oneshot::Receiver<Result<Frame>>) -> Result<Frame> {
+    reply.await.map_err(|_| anyhow!("partition stopped"))?
+}
+
+fn stopped(partition: usize) -> anyhow::Error {
+    anyhow!("partition {} stopped", partition)
+}
+
+// the first BY or GET option of a SORT whose pattern names other keys, `#` is the element itself
+// and a BY without `*` skips sorting
+fn sort_pattern_option(options: &[String]) -> Option<&'static str> {
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.to_uppercase().as_str() {
+            "BY" if options.next().is_some_and(|pattern| pattern.contains('*')) => {
+                return Some("BY")
+            }
+            "GET" if options.next().is_some_and(|pattern| pattern != "#") => return Some("GET"),
+            // their values are skipped so none is taken for an option
+            "LIMIT" => {
+                options.nth(1);
+            }
+            "STORE" => {
+                options.next();
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// the SCAN of a partition starting at `cursor`
+fn with_cursor(frame: Frame, cursor: usize) -> Frame {
+    match frame {
+        Frame::Array(mut array) => {
+            array.inner[1] = cursor.to_string().as_bytes().into();
+            Frame::Array(array)
+        }
+        frame => frame,
+    }
+}
+
+// the cursor a partition replies with points into it, once it is done the next partition starts
+// from its beginning, the last one ends the iteration
+fn scan_reply(frame: Frame, partition: usize, partitions: usize) -> Frame {
+    let Frame::Array(mut reply) = frame else {
+        return frame;
+    };
+    let next = match reply.inner.first() {
+        Some(Frame::BulkString(next)) => std::str::from_utf8(&next.inner)
+            .ok()
+            .and_then(|next| next.parse::<usize>().ok()),
+        _ => None,
+    };
+    let next = match next {
+        Some(0) if partition + 1 < partitions => partition + 1,
+        Some(0) => 0,
+        Some(next) => next * partitions + partition,
+        None => return Frame::Array(reply),
+    };
+    reply.inner[0] = next.to_string().as_bytes().into();
+    Frame::Array(reply)
+}
+
+// DBSIZE adds the counts up, INFO adds up the keyspace lines of the partitions
+fn merge(name: &str, frames: Vec<Frame>) -> Frame {
+    if let Some(error) = frames
+        .iter()
+        .find(|frame| !matches!(frame, Frame::Integer(_) | Frame::BulkString(_)))
+    {
+        return error.clone();
+    }
+
+    if name.eq_ignore_ascii_case("dbsize") {
+        let total: i64 = frames
+            .iter()
+            .map(|frame| match frame {
+                Frame::Integer(count) => count.inner,
+                _ => 0,
+            })
+            .sum();
+        return total.into();
+    }
+
+    let infos: Vec<String> = frames
+        .iter()
+        .filter_map(|frame| match frame {
+            Frame::BulkString(info) => Some(String::from_utf8_lossy(&info.inner).into_owned()),
+            _ => None,
+        })
+        .collect();
+    let mut keyspace: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for line in infos.iter().flat_map(|info| info.lines()) {
+        if let Some((db, keys, expires)) = keyspace_line(line) {
+            let total = keyspace.entry(db).or_default();
+            total.0 += keys;
+            total.1 += expires;
+        }
+    }
+
+    let mut merged = String::new();
+    for line in infos
+        .first()
+        .map(String::as_str)
+        .unwrap_or_default()
+        .split_inclusive("\r\n")
+    {
+        if keyspace_line(line.trim_end()).is_some() {
+            continue;
+        }
+        merged.push_str(line);
+        if line.starts_with("# Keyspace") {
+            for (db, (keys, expires)) in &keyspace {
+                merged.push_str(&format!(
+                    "db{}:keys={},expires={},avg_ttl=0\r\n",
+                    db, keys, expires
+                ));
+            }
+        }
+    }
+    merged.as_bytes().into()
+}
+
+// `db0:keys=1,expires=0,avg_ttl=0` as the database index, its number of keys and of keys with
+// a deadline
+fn keyspace_line(line: &str) -> Option<(usize, usize, usize)> {
+    let (db, fields) = line.strip_prefix("db")?.split_once(':')?;
+    let mut fields = fields.split(',');
+    let keys = fields.next()?.strip_prefix("keys=")?;
+    let expires = fields.next()?.strip_prefix("expires=")?;
+    Some((db.parse().ok()?, keys.parse().ok()?, expires.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_route() {
+        let router = Router::new((0..4).map(|_| mpsc::unbounded_channel().0).collect());
+
+        assert_eq!(router.route(&args(&["ping"])), Route::Local);
+        assert_eq!(router.route(&args(&["get"])), Route::Local);
+        assert_eq!(
+            router.route(&args(&["get", "key"])),
+            Route::Partition(partition_of("key", 4))
+        );
+        assert_eq!(
+            router.route(&args(&["mset", "{a}1", "x", "{a}2", "y"])),
+            Route::Partition(partition_of("a", 4))
+        );
+        assert_eq!(router.route(&args(&["dbsize"])), Route::Broadcast);
+        assert_eq!(
+            router.route(&args(&["scan", "0"])),
+            Route::Scan {
+                partition: 0,
+                cursor: 0
+            }
+        );
+        assert_eq!(
+            router.route(&args(&["scan", "9", "count", "5"])),
+            Route::Scan {
+                partition: 1,
+                cursor: 2
+            }
+        );
+        assert_eq!(router.route(&args(&["scan", "x"])), Route::Local);
+        assert_eq!(router.route(&args(&["flushall"])), Route::Exclusive);
+        assert_eq!(router.route(&args(&["debug", "reload"])), Route::Exclusive);
+        assert_eq!(router.route(&args(&["debug", "sleep", "0"])), Route::Local);
+        assert_eq!(
+            router.route(&args(&["debug", "object", "key"])),
+            Route::Partition(partition_of("key", 4))
+        );
+        assert_eq!(
+            router.route(&args(&["object", "freq", "key"])),
+            Route::Partition(partition_of("key", 4))
+        );
+        assert_eq!(
+            router.route(&args(&["sort", "key", "by", "nosort", "get", "#"])),
+            Route::Partition(partition_of("key", 4))
+        );
+        assert_eq!(
+            router.route(&args(&["sort", "key", "limit", "0", "1", "by", "w_*"])),
+            Route::Denied("BY")
+        );
+        assert_eq!(
+            router.route(&args(&["sort_ro", "key", "get", "#", "get", "h_*->f"])),
+            Route::Denied("GET")
+        );
+
+        let keys = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut mget = vec!["mget"];
+        mget.extend(keys);
+        match router.route(&args(&mget)) {
+            Route::Coordinated { owners, read_only } => {
+                assert!(read_only);
+                assert!(owners.len() > 1);
+                assert_eq!(
+                    owners.values().map(BTreeSet::len).sum::<usize>(),
+                    keys.len()
+                );
+            }
+            route => panic!("Expected Coordinated, got {:?}", route),
+        }
+    }
+
+    #[test]
+    fn test_scan_cursor() {
+        let reply = |next: &str| {
+            Frame::from(vec![
+                next.as_bytes().into(),
+                Frame::from(Vec::<Frame>::new()),
+            ])
+        };
+
+        assert_eq!(
+            with_cursor(Frame::from(vec![b"scan".into(), b"9".into()]), 2),
+            Frame::from(vec![b"scan".into(), b"2".into()])
+        );
+        // the next cursor keeps pointing at the partition until it is done
+        assert_eq!(scan_reply(reply("3"), 1, 4), reply("13"));
+        assert_eq!(scan_reply(reply("0"), 1, 4), reply("2"));
+        assert_eq!(scan_reply(reply("0"), 3, 4), reply("0"));
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(
+            merge("dbsize", vec![1i64.into(), 2i64.into()]),
+            Frame::from(3i64)
+        );
+
+        let info = |lines: &str| {
+            Frame::from(
+                format!(
+                    "# Server\r\nredis_mode:standalone\r\n\r\n# Keyspace\r\n{}",
+                    lines
+                )
+                .as_bytes(),
+            )
+        };
+        let merged = merge(
+            "info",
+            vec![
+                info("db0:keys=1,expires=1,avg_ttl=0\r\n"),
+                info("db0:keys=2,expires=1,avg_ttl=0\r\ndb3:keys=1,expires=0,avg_ttl=0\r\n"),
+            ],
+        );
+        assert_eq!(
+            merged,
+            info("db0:keys=3,expires=2,avg_ttl=0\r\ndb3:keys=1,expires=0,avg_ttl=0\r\n")
+        );
+    }
+}
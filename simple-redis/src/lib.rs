@@ -1,4 +1,10 @@
 pub mod backend;
+pub mod client;
 pub mod command;
+pub mod config;
+pub mod engine;
 pub mod network;
+pub mod rdb;
 pub mod resp;
+pub mod server;
+pub mod utils;
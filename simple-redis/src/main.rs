@@ -1,31 +1,277 @@
 use anyhow::Result;
-use simple_redis::{backend::Backend, network::stream_handle};
+use clap::{Parser, Subcommand, ValueEnum};
+use simple_redis::backend::{Backend, ShutdownMode};
+use simple_redis::config::Config;
+use simple_redis::engine::Engine;
+use simple_redis::network::{TlsAuthClients, TlsConfig};
+use simple_redis::rdb::{self, RdbReader, RdbRecord};
+use simple_redis::server::Server;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
-use tokio::net::TcpListener;
-use tracing::info;
+use std::path::{Path, PathBuf};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EngineKind {
+    Shared,
+    ThreadPerCore,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AuthClients {
+    Yes,
+    No,
+    Optional,
+}
+
+#[derive(Debug, Subcommand)]
+enum Tool {
+    /// Print the keys of an RDB file with their types and sizes, then exit
+    Rdb { path: PathBuf },
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "simple-redis", version)]
+struct Opts {
+    /// Port 0 only listens on the TLS port and the unix socket
+    #[arg(short = 'p', long, default_value_t = 6379)]
+    port: u16,
+
+    /// Also accept connections on a unix socket at this path
+    #[arg(long)]
+    unixsocket: Option<PathBuf>,
+
+    /// Permissions of the unix socket file in octal, like 700
+    #[arg(long, value_parser = parse_perm, requires = "unixsocket")]
+    unixsocketperm: Option<u32>,
+
+    /// How commands are executed, thread-per-core partitions the keys over pinned cores
+    #[arg(long, value_enum, default_value = "shared")]
+    engine: EngineKind,
+
+    /// Cores used by the thread-per-core engine, 0 uses all of them
+    #[arg(long, default_value_t = 0)]
+    cores: usize,
+
+    /// Serve prometheus metrics over http on this port at /metrics
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
+    /// Also accept TLS connections on this port, SIGHUP reloads the certificates
+    #[arg(long, requires_all = ["tls_cert_file", "tls_key_file"])]
+    tls_port: Option<u16>,
+
+    #[arg(long)]
+    tls_cert_file: Option<PathBuf>,
+
+    #[arg(long)]
+    tls_key_file: Option<PathBuf>,
+
+    /// CA certificate that client certificates are verified against
+    #[arg(long)]
+    tls_ca_cert_file: Option<PathBuf>,
+
+    /// Whether TLS clients have to present a certificate signed by the CA
+    #[arg(long, value_enum, default_value = "yes")]
+    tls_auth_clients: AuthClients,
+
+    /// RDB file, from Redis or an earlier run, loaded at startup and written back on shutdown
+    #[arg(long)]
+    dbfilename: Option<PathBuf>,
+
+    /// Close clients idle for this many seconds, 0 never does
+    #[arg(long, default_value_t = 0)]
+    timeout: u64,
+
+    /// Seconds between TCP keepalive probes of idle clients, 0 disables them
+    #[arg(long, default_value_t = 300)]
+    tcp_keepalive: u64,
+
+    /// Reject connections beyond this many clients
+    #[arg(long, default_value_t = 10_000)]
+    maxclients: usize,
+
+    /// Close clients whose unread output reaches the hard limit or stays above the soft one,
+    /// like "pubsub 32mb 8mb 60", can be repeated
+    #[arg(long, value_name = "CLASS HARD SOFT SECONDS")]
+    client_output_buffer_limit: Vec<String>,
+
+    /// Free evicted keys on the lazyfree thread
+    #[arg(long)]
+    lazyfree_lazy_eviction: bool,
+
+    /// Free expired keys on the lazyfree thread
+    #[arg(long)]
+    lazyfree_lazy_expire: bool,
+
+    /// Free keys the server deletes or overwrites itself on the lazyfree thread
+    #[arg(long)]
+    lazyfree_lazy_server_del: bool,
+
+    /// Make DEL free keys on the lazyfree thread like UNLINK
+    #[arg(long)]
+    lazyfree_lazy_user_del: bool,
+
+    #[command(subcommand)]
+    tool: Option<Tool>,
+}
+
+fn parse_perm(perm: &str) -> Result<u32, String> {
+    u32::from_str_radix(perm, 8).map_err(|e| format!("invalid octal permissions: {}", e))
+}
+
+impl Opts {
+    fn config(&self) -> Result<Config> {
+        let mut config = Config {
+            timeout: self.timeout,
+            tcp_keepalive: self.tcp_keepalive,
+            maxclients: self.maxclients,
+            lazyfree_lazy_eviction: self.lazyfree_lazy_eviction,
+            lazyfree_lazy_expire: self.lazyfree_lazy_expire,
+            lazyfree_lazy_server_del: self.lazyfree_lazy_server_del,
+            lazyfree_lazy_user_del: self.lazyfree_lazy_user_del,
+            ..Config::default()
+        };
+        for spec in &self.client_output_buffer_limit {
+            config
+                .client_output_buffer_limits
+                .set(spec)
+                .map_err(anyhow::Error::msg)?;
+        }
+        Ok(config)
+    }
+
+    fn tls(&self) -> Option<(SocketAddr, TlsConfig)> {
+        let port = self.tls_port?;
+        let mut config = TlsConfig::new(self.tls_cert_file.clone()?, self.tls_key_file.clone()?)
+            .auth_clients(match self.tls_auth_clients {
+                AuthClients::Yes => TlsAuthClients::Yes,
+                AuthClients::No => TlsAuthClients::No,
+                AuthClients::Optional => TlsAuthClients::Optional,
+            });
+        if let Some(ca_cert_file) = &self.tls_ca_cert_file {
+            config = config.ca_cert_file(ca_cert_file);
+        }
+        Some((SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)), config))
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
+    let opts = Opts::parse();
+    if let Some(Tool::Rdb { path }) = &opts.tool {
+        return inspect_rdb(path);
+    }
 
-    let backend = Backend::new();
+    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, opts.port));
+    let engine = match opts.engine {
+        EngineKind::Shared => Engine::Shared,
+        EngineKind::ThreadPerCore => Engine::ThreadPerCore(opts.cores),
+    };
+    let mut builder = Server::builder()
+        .bind(addr)
+        .engine(engine)
+        .config(opts.config()?);
+    // like redis, port 0 disables the plain listener
+    if opts.port == 0 {
+        builder = builder.without_tcp();
+    }
+    if let Some(path) = &opts.unixsocket {
+        builder = builder.unix_socket(path, opts.unixsocketperm);
+    }
+    if let Some(port) = opts.metrics_port {
+        builder = builder.metrics(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)));
+    }
+    if let Some((addr, config)) = opts.tls() {
+        builder = builder.tls(addr, config);
+    }
+    if let Some(path) = opts.dbfilename.clone() {
+        builder = builder
+            .backend(load_rdb(&path)?)
+            .on_save(move |backend| Ok(rdb::save(backend, &path)?));
+    }
+    let server = builder.start().await?;
 
-    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 6379));
-    info!("Listening on {}", addr);
+    // signals go through the same shutdown path as the SHUTDOWN command
+    let backend = server.backend().clone();
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+            _ = terminate.recv() => info!("Received SIGTERM"),
+        }
+        backend.shutdown().trigger(ShutdownMode::Default);
+    });
 
-    let listener = TcpListener::bind(addr).await?;
+    // new certificates are picked up without a restart
+    if let Some(tls) = server.tls().cloned() {
+        let mut hangup = signal(SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                info!("Received SIGHUP");
+                if let Err(e) = tls.reload() {
+                    warn!("Failed to reload TLS certificates: {}", e);
+                }
+            }
+        });
+    }
 
-    loop {
-        let (stream, raddr) = listener.accept().await?;
-        info!("Accepted connection from {}", raddr);
+    server.wait().await
+}
 
-        let backend = backend.clone();
+// a missing file starts out empty, like redis
+fn load_rdb(path: &Path) -> Result<Backend> {
+    let backend = Backend::new();
+    if !path.exists() {
+        return Ok(backend);
+    }
+    let load = rdb::load(&backend, path)?;
+    info!("Loaded {} keys from {}", load.keys, path.display());
+    if load.expired > 0 {
+        info!("Left out {} keys that already expired", load.expired);
+    }
+    if load.functions > 0 {
+        warn!(
+            "Left out {} function libraries, functions are not supported",
+            load.functions
+        );
+    }
+    Ok(backend)
+}
 
-        tokio::spawn(async move {
-            if let Err(e) = stream_handle(stream, backend).await {
-                info!("Error: {:?}", e);
+// one tab separated line per key, the aux fields and totals as comments
+fn inspect_rdb(path: &Path) -> Result<()> {
+    let bytes = std::fs::read(path)?;
+    let reader = RdbReader::new(&bytes)?;
+    println!("# rdb version {}", reader.version());
+    let (mut keys, mut size) = (0, 0);
+    for record in reader {
+        match record? {
+            RdbRecord::Aux(name, value) => println!("# {} {}", name, value),
+            RdbRecord::Function(code) => println!("# function library of {} bytes", code.len()),
+            RdbRecord::Entry(entry) => {
+                if keys == 0 {
+                    println!("db\ttype\tkey\tlen\tbytes\texpire_at");
+                }
+                let expire_at = entry
+                    .expire_at
+                    .map_or_else(|| "-".to_string(), |at| at.to_string());
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    entry.db,
+                    entry.value.kind(),
+                    entry.key,
+                    entry.value.len(),
+                    entry.size,
+                    expire_at
+                );
+                keys += 1;
+                size += entry.size;
             }
-        });
+        }
     }
+    println!("# {} keys, {} bytes", keys, size);
+    Ok(())
 }
@@ -0,0 +1,295 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::backend::{Backend, ConnectionKind};
+use crate::utils::resident_memory;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+// a scrape is a single short GET, anything longer is not one
+const MAX_REQUEST_LEN: usize = 8 * 1024;
+// the server drains its connections on shutdown, an idle scraper must not hold it up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// the server internals in the prometheus text format
+pub fn render_metrics(backend: &Backend) -> String {
+    let mut metrics = Metrics::default();
+    let stats = backend.stats();
+
+    metrics.family(
+        "connected_clients",
+        "gauge",
+        "Client connections by connection type.",
+    );
+    let clients = backend.clients().list();
+    for kind in [
+        ConnectionKind::Tcp,
+        ConnectionKind::Tls,
+        ConnectionKind::Unix,
+    ] {
+        let count = clients.iter().filter(|client| client.kind == kind).count();
+        metrics.sample("connected_clients", &[("conn", kind.as_str())], count);
+    }
+
+    metrics.family(
+        "rejected_connections_total",
+        "counter",
+        "Connections rejected because of maxclients.",
+    );
+    metrics.sample(
+        "rejected_connections_total",
+        &[],
+        stats.rejected_connections(),
+    );
+    metrics.family(
+        "client_output_buffer_limit_disconnections_total",
+        "counter",
+        "Clients closed for going over their output buffer limit.",
+    );
+    metrics.sample(
+        "client_output_buffer_limit_disconnections_total",
+        &[],
+        stats.output_limit_disconnections(),
+    );
+
+    let commands = stats.commands();
+    metrics.family("commands_processed_total", "counter", "Commands processed.");
+    metrics.sample("commands_processed_total", &[], stats.commands_processed());
+    metrics.family(
+        "command_duration_seconds",
+        "histogram",
+        "Time spent executing commands, by command name.",
+    );
+    for (name, command) in &commands {
+        for (bound, count) in command.buckets() {
+            let le = (bound as f64 / 1_000_000.0).to_string();
+            metrics.sample(
+                "command_duration_seconds_bucket",
+                &[("cmd", name), ("le", &le)],
+                count,
+            );
+        }
+        metrics.sample(
+            "command_duration_seconds_bucket",
+            &[("cmd", name), ("le", "+Inf")],
+            command.calls(),
+        );
+        metrics.sample(
+            "command_duration_seconds_sum",
+            &[("cmd", name)],
+            command.usec() as f64 / 1_000_000.0,
+        );
+        metrics.sample(
+            "command_duration_seconds_count",
+            &[("cmd", name)],
+            command.calls(),
+        );
+    }
+
+    metrics.family(
+        "net_input_bytes_total",
+        "counter",
+        "Bytes read from client connections.",
+    );
+    metrics.sample("net_input_bytes_total", &[], stats.net_input_bytes());
+    metrics.family(
+        "net_output_bytes_total",
+        "counter",
+        "Bytes written to client connections.",
+    );
+    metrics.sample("net_output_bytes_total", &[], stats.net_output_bytes());
+
+    metrics.family("keys", "gauge", "Keys by database and value type.");
+    for (db, kind, keys) in backend.key_types() {
+        metrics.sample("keys", &[("db", &db.to_string()), ("type", kind)], keys);
+    }
+    metrics.family(
+        "expired_keys_total",
+        "counter",
+        "Keys removed because they expired.",
+    );
+    metrics.sample("expired_keys_total", &[], stats.expired_keys());
+    metrics.family(
+        "expired_subkeys_total",
+        "counter",
+        "Hash fields removed because they expired.",
+    );
+    metrics.sample("expired_subkeys_total", &[], stats.expired_subkeys());
+    metrics.family(
+        "evicted_keys_total",
+        "counter",
+        "Keys evicted to stay below the memory limit.",
+    );
+    metrics.sample("evicted_keys_total", &[], stats.evicted_keys());
+
+    metrics.family(
+        "lazyfree_pending_objects",
+        "gauge",
+        "Values waiting to be freed by the lazyfree thread.",
+    );
+    metrics.sample(
+        "lazyfree_pending_objects",
+        &[],
+        backend.lazyfree().pending(),
+    );
+    metrics.family(
+        "lazyfreed_objects_total",
+        "counter",
+        "Values freed by the lazyfree thread.",
+    );
+    metrics.sample(
+        "lazyfreed_objects_total",
+        &[],
+        backend.lazyfree().freed_objects(),
+    );
+
+    if let Some(rss) = resident_memory() {
+        metrics.family(
+            "memory_rss_bytes",
+            "gauge",
+            "Resident memory of the process.",
+        );
+        metrics.sample("memory_rss_bytes", &[], rss);
+    }
+
+    metrics.family(
+        "rdb_changes_since_last_save",
+        "gauge",
+        "Writes since the data was last saved.",
+    );
+    metrics.sample(
+        "rdb_changes_since_last_save",
+        &[],
+        stats.changes_since_last_save(),
+    );
+    metrics.family(
+        "rdb_last_save_timestamp_seconds",
+        "gauge",
+        "Unix time of the last successful save.",
+    );
+    metrics.sample("rdb_last_save_timestamp_seconds", &[], stats.last_save());
+    metrics.family(
+        "rdb_last_save_status",
+        "gauge",
+        "Whether the last save succeeded.",
+    );
+    metrics.sample("rdb_last_save_status", &[], stats.last_save_ok() as u8);
+
+    metrics.text
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    text: String,
+}
+
+impl Metrics {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP simple_redis_{} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE simple_redis_{} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl ToString) {
+        let _ = write!(self.text, "simple_redis_{}", name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value.to_string());
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// answers a single http request on the metrics listener and closes the connection
+pub(crate) async fn serve_metrics(mut stream: TcpStream, backend: Backend) -> Result<()> {
+    let Some(request) = timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await?? else {
+        return Ok(());
+    };
+
+    let line = String::from_utf8_lossy(&request);
+    let mut parts = line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", render_metrics(&backend)),
+        (Some("GET"), _) => ("404 Not Found", "Not Found\n".to_string()),
+        _ => ("405 Method Not Allowed", "Method Not Allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+// the request head, none when the client went away before sending it
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Vec<u8>>> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_LEN {
+            anyhow::bail!("metrics request too long");
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    Ok(Some(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let backend = Backend::new();
+        backend.set("a", "1");
+        backend.hset("b", "field", "value").unwrap();
+        let _client = backend
+            .connect(ConnectionKind::Unix, "/tmp/s:0".into(), "".into())
+            .unwrap();
+        backend
+            .stats()
+            .record_command("set", Duration::from_micros(30), true);
+        backend.stats().add_net_input(27);
+
+        let metrics = render_metrics(&backend);
+        for line in [
+            "# TYPE simple_redis_command_duration_seconds histogram",
+            "simple_redis_connected_clients{conn=\"unix\"} 1",
+            "simple_redis_connected_clients{conn=\"tcp\"} 0",
+            "simple_redis_commands_processed_total 1",
+            "simple_redis_command_duration_seconds_bucket{cmd=\"set\",le=\"0.000025\"} 0",
+            "simple_redis_command_duration_seconds_bucket{cmd=\"set\",le=\"0.00005\"} 1",
+            "simple_redis_command_duration_seconds_bucket{cmd=\"set\",le=\"+Inf\"} 1",
+            "simple_redis_command_duration_seconds_count{cmd=\"set\"} 1",
+            "simple_redis_net_input_bytes_total 27",
+            "simple_redis_keys{db=\"0\",type=\"string\"} 1",
+            "simple_redis_keys{db=\"0\",type=\"hash\"} 1",
+            "simple_redis_rdb_changes_since_last_save 1",
+            "simple_redis_rdb_last_save_status 1",
+        ] {
+            assert!(metrics.lines().any(|l| l == line), "{} missing", line);
+        }
+    }
+}
@@ -1,27 +1,183 @@
 mod codec;
+mod counted;
+mod metrics;
 mod request;
+mod tls;
 
-use crate::backend::Backend;
-use crate::command::Command;
+use crate::backend::{Backend, ConnectionKind, ShutdownMode};
+use crate::command::{lookup, CommandFlag, CommandSpec};
+use crate::engine::Router;
 use crate::resp::frame::Frame;
 use anyhow::Result;
-use codec::RespFrameCodec;
-use futures::SinkExt;
-use request::RespRequest;
+pub use codec::RespFrameCodec;
+use counted::CountedStream;
+use futures::{FutureExt, SinkExt};
+pub use metrics::render_metrics;
+pub(crate) use metrics::serve_metrics;
+pub(crate) use request::{command_args, command_name, RespRequest};
+use socket2::{SockRef, TcpKeepalive};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+pub use tls::{Tls, TlsAuthClients, TlsConfig};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
-use tracing::info;
+use tracing::{debug, warn};
 
-pub async fn stream_handle(stream: TcpStream, backend: Backend) -> Result<()> {
-    let mut framed = Framed::new(stream, RespFrameCodec);
+// how often a reply stuck on a client that does not read is checked against its output limits
+const OUTPUT_LIMIT_CHECK_PERIOD: Duration = Duration::from_millis(100);
+
+// the other end of a stream as the listener that accepted it sees it
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub kind: ConnectionKind,
+    // reported to the monitor feed and the slow log, unix sockets have none
+    pub peer: Option<SocketAddr>,
+    pub addr: String,
+    pub laddr: String,
+}
+
+impl Connection {
+    pub fn tcp(stream: &TcpStream, kind: ConnectionKind) -> Self {
+        let peer = stream.peer_addr().ok();
+        let laddr = stream.local_addr().ok();
+        let describe =
+            |addr: Option<SocketAddr>| addr.map(|addr| addr.to_string()).unwrap_or_default();
+
+        Self {
+            kind,
+            peer,
+            addr: describe(peer),
+            laddr: describe(laddr),
+        }
+    }
+
+    // like redis, both ends of a unix socket are reported as `path:0`
+    pub fn unix(path: &Path) -> Self {
+        let addr = format!("{}:0", path.display());
+
+        Self {
+            kind: ConnectionKind::Unix,
+            peer: None,
+            addr: addr.clone(),
+            laddr: addr,
+        }
+    }
+}
+
+// like redis, probes start once a socket was idle for `tcp-keepalive` and repeat every third of it
+pub(crate) fn set_keepalive(stream: &TcpStream, backend: &Backend) {
+    let Some(time) = backend.clients().tcp_keepalive() else {
+        return;
+    };
+    let keepalive = TcpKeepalive::new()
+        .with_time(time)
+        .with_interval((time / 3).max(Duration::from_secs(1)));
+    if let Err(e) = SockRef::from(stream).set_tcp_keepalive(&keepalive) {
+        warn!("Failed to enable TCP keepalive: {}", e);
+    }
+}
+
+// any byte stream carries the protocol, a plain, tls or unix socket alike
+pub async fn stream_handle<S>(stream: S, connection: Connection, backend: Backend) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let shutdown = backend.shutdown().subscribe();
+    shutdown_aware_stream_handle(stream, connection, backend, shutdown, None).await
+}
+
+// the server hands in a receiver cloned from its own, so a shutdown requested while the
+// connection was being accepted is not missed, with a router the requests run on the partitions
+// of the thread-per-core engine instead of on `backend`
+pub(crate) async fn shutdown_aware_stream_handle<S>(
+    stream: S,
+    connection: Connection,
+    backend: Backend,
+    mut shutdown: watch::Receiver<Option<ShutdownMode>>,
+    router: Option<Router>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // SELECT only changes the database of this connection
+    let client = connection.peer;
+    let backend = match backend.connect(connection.kind, connection.addr, connection.laddr) {
+        Ok(session) => session,
+        // like redis, a connection over `maxclients` is told so and closed right away
+        Err(e) => {
+            backend.stats().record_rejected_connection();
+            let mut framed = Framed::new(CountedStream::new(stream, backend), RespFrameCodec);
+            framed.send(e.into()).await?;
+            return Ok(());
+        }
+    };
+    let mut pushes = backend
+        .client()
+        .expect("a connected backend has a client")
+        .push_receiver();
+    let mut framed = Framed::new(CountedStream::new(stream, backend.clone()), RespFrameCodec);
+    // the keys a command of the thread-per-core engine borrows from several partitions
+    let scratch = OnceLock::new();
 
     loop {
-        match framed.next().await {
+        // shutdown only interrupts waiting for the next command, a running one always completes
+        let frame = tokio::select! {
+            frame = framed.next() => frame,
+            Some(push) = pushes.recv() => {
+                if !write(&mut framed, push, &backend, true).await? {
+                    return Ok(());
+                }
+                continue;
+            }
+            _ = idle(&backend) => {
+                debug!("Closing idle client");
+                return Ok(());
+            }
+            _ = shutdown.changed() => return Ok(()),
+        };
+
+        match frame {
             Some(Ok(frame)) => {
-                info!("Received frame: {:?}", frame);
-                let response = request_handle(frame, backend.clone()).await?;
-                framed.send(response).await?;
+                debug!("Received frame: {:?}", frame);
+                let name = command_name(&frame);
+                if let Some(info) = backend.client() {
+                    info.touch(&name);
+                    info.set_query_buffer(framed.read_buffer().len());
+                }
+                let spec = lookup(&name);
+                // the arguments are only copied while some client is tracking keys
+                let tracked = backend.tracking().is_active().then(|| command_args(&frame));
+                let start = Instant::now();
+                let (response, monitor) = match &router {
+                    Some(router) => router.dispatch(frame, &backend, &scratch, client).await?,
+                    None => {
+                        let request = RespRequest::new(frame, backend.clone(), client);
+                        (request.execute_async().await?, request.is_monitor())
+                    }
+                };
+                // unknown commands were answered with an error, only the ones of the table count
+                if let Some(spec) = spec {
+                    let write = spec.has_flag(CommandFlag::Write);
+                    backend
+                        .stats()
+                        .record_command(spec.name, start.elapsed(), write);
+                    if let Some(args) = &tracked {
+                        track(&backend, spec, args);
+                    }
+                }
+                if !write(&mut framed, response, &backend, false).await? {
+                    return Ok(());
+                }
+
+                if monitor {
+                    return monitor_handle(&mut framed, &backend, &mut shutdown).await;
+                }
             }
             Some(Err(e)) => return Err(e),
             None => return Ok(()),
@@ -29,9 +185,127 @@ pub async fn stream_handle(stream: TcpStream, backend: Backend) -> Result<()> {
     }
 }
 
+// resolves once the client sent no command for longer than `timeout`, never when there is none;
+// it counts from the last command so pushes written in between do not keep the client alive
+async fn idle(backend: &Backend) {
+    match (backend.clients().timeout(), backend.client()) {
+        (Some(timeout), Some(client)) => {
+            let deadline = client.last_interaction() + timeout;
+            tokio::time::sleep_until(deadline.into()).await
+        }
+        _ => std::future::pending().await,
+    }
+}
+
+// encodes `frame` and waits until it is written, false when the client went over its output
+// buffer limit on the way and has to be closed, `push` frames come from its push queue
+async fn write<S>(
+    framed: &mut Framed<S, RespFrameCodec>,
+    frame: Frame,
+    backend: &Backend,
+    push: bool,
+) -> Result<bool>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let buffered = framed.write_buffer().len();
+    framed.feed(frame).await?;
+    if let Some(client) = backend.client() {
+        client.buffer_output(framed.write_buffer().len().saturating_sub(buffered), push);
+    }
+    if over_output_limit(backend) {
+        return Ok(false);
+    }
+
+    let mut flush = std::pin::pin!(framed.flush());
+    // most replies are written right away, the timer is only needed for a client that lags
+    if let Some(flushed) = flush.as_mut().now_or_never() {
+        flushed?;
+        return Ok(true);
+    }
+    let start = tokio::time::Instant::now() + OUTPUT_LIMIT_CHECK_PERIOD;
+    let mut check = tokio::time::interval_at(start, OUTPUT_LIMIT_CHECK_PERIOD);
+    loop {
+        tokio::select! {
+            flushed = &mut flush => {
+                flushed?;
+                return Ok(true);
+            }
+            _ = check.tick() => if over_output_limit(backend) {
+                return Ok(false);
+            },
+        }
+    }
+}
+
+fn over_output_limit(backend: &Backend) -> bool {
+    if !backend.over_output_limit() {
+        return false;
+    }
+    if let Some(client) = backend.client() {
+        warn!(
+            "Client id={} addr={} closed for overcoming of output buffer limits",
+            client.id, client.addr
+        );
+    }
+    true
+}
+
+// client side caching, reads are remembered for the client and writes invalidate the keys,
+// a write without keys may have changed any of them
+fn track(backend: &Backend, spec: &CommandSpec, args: &[String]) {
+    let keys = spec.keys.keys(args);
+    if spec.has_flag(CommandFlag::Write) {
+        match keys.is_empty() {
+            true => backend.invalidate_all(),
+            false => backend.invalidate(&keys),
+        }
+    } else if spec.has_flag(CommandFlag::ReadOnly) {
+        backend.track_reads(&keys);
+    }
+
+    // CLIENT CACHING only decides for the command that follows it
+    let caching = spec.name == "client"
+        && args
+            .get(1)
+            .is_some_and(|arg| arg.eq_ignore_ascii_case("caching"));
+    if let Some(client) = backend.client().filter(|_| !caching) {
+        client.clear_caching();
+    }
+}
+
 pub async fn request_handle(frame: Frame, backend: Backend) -> Result<Frame> {
-    let command = Command::try_from(frame)?;
-    let request = RespRequest::new(command, backend);
-    let response = request.execute()?;
+    let request = RespRequest::new(frame, backend, None);
+    let response = request.execute_async().await?;
     Ok(response)
 }
+
+// once in monitor mode the connection only streams the command feed until it is closed
+async fn monitor_handle<S>(
+    framed: &mut Framed<S, RespFrameCodec>,
+    backend: &Backend,
+    shutdown: &mut watch::Receiver<Option<ShutdownMode>>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut receiver = backend.monitor().subscribe();
+
+    loop {
+        tokio::select! {
+            line = receiver.recv() => match line {
+                Ok(line) => if !write(framed, line.into(), backend, false).await? {
+                    return Ok(());
+                },
+                Err(RecvError::Lagged(skipped)) => warn!("Monitor lagged, skipped {} lines", skipped),
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            frame = framed.next() => match frame {
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            },
+            _ = shutdown.changed() => return Ok(()),
+        }
+    }
+}
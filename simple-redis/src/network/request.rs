@@ -1,20 +1,172 @@
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
 use crate::backend::Backend;
-use crate::command::{Command, CommandExecute};
+use crate::command::{
+    check_key_kinds, expire_keys, lookup, parse_error, Command, CommandExecute, CommandFlag,
+};
 use crate::resp::frame::Frame;
 use anyhow::Result;
 
 #[derive(Debug)]
 pub struct RespRequest {
-    command: Command,
+    // a request that doesn't parse is answered with this error instead of being executed, the
+    // connection stays open like it does with redis
+    command: Result<Command, Frame>,
+    args: Vec<String>,
     backend: Backend,
+    client: Option<SocketAddr>,
 }
 
 impl RespRequest {
-    pub fn new(command: Command, backend: Backend) -> Self {
-        Self { command, backend }
+    pub fn new(frame: Frame, backend: Backend, client: Option<SocketAddr>) -> Self {
+        let args = command_args(&frame);
+        let command = Command::try_from(frame).map_err(|e| parse_error(&e));
+        Self {
+            command,
+            args,
+            backend,
+            client,
+        }
+    }
+
+    pub fn is_monitor(&self) -> bool {
+        matches!(self.command, Ok(Command::Monitor(_)))
     }
 
     pub fn execute(&self) -> Result<Frame> {
-        self.command.execute(self.backend.clone())
+        let command = match &self.command {
+            Ok(command) => command,
+            Err(reply) => return Ok(reply.clone()),
+        };
+        self.feed();
+
+        let start = Instant::now();
+        expire_keys(&self.args, &self.backend);
+        let result = match check_key_kinds(&self.args, &self.backend) {
+            Ok(()) => command.execute(self.backend.clone()),
+            Err(e) => Ok(e.into()),
+        };
+        self.record(start.elapsed());
+
+        result
+    }
+
+    // like `execute`, but MIGRATE awaits its target instead of holding up the runtime
+    pub async fn execute_async(&self) -> Result<Frame> {
+        let Ok(Command::Migrate(migrate)) = &self.command else {
+            return self.execute();
+        };
+        self.feed();
+
+        let start = Instant::now();
+        expire_keys(&self.args, &self.backend);
+        let result = migrate.execute_async(self.backend.clone()).await;
+        self.record(start.elapsed());
+
+        result
+    }
+
+    // the database the command runs against, a SELECT is shown with the one it leaves
+    fn feed(&self) {
+        self.backend
+            .monitor()
+            .feed(&self.args, self.client, self.backend.selected_db());
+    }
+
+    fn record(&self, elapsed: Duration) {
+        self.touch();
+
+        self.backend
+            .slowlog()
+            .record(&self.args, self.client, elapsed);
+        self.backend
+            .latency()
+            .add_sample("command", elapsed.as_millis() as u64);
+    }
+
+    // the introspection commands look at the metadata of a key without counting as an access,
+    // RESTORE sets it up itself
+    fn touch(&self) {
+        let Some(spec) = self.args.first().and_then(|name| lookup(name)) else {
+            return;
+        };
+        if matches!(spec.name, "object" | "memory" | "debug" | "restore") {
+            return;
+        }
+        let keys = spec.keys.keys(&self.args);
+        if !keys.is_empty() {
+            self.backend.touch(&keys, spec.has_flag(CommandFlag::Write));
+        }
+    }
+}
+
+pub(crate) fn command_args(frame: &Frame) -> Vec<String> {
+    match frame {
+        Frame::Array(array) => array.iter().map(frame_to_arg).collect(),
+        _ => vec![],
+    }
+}
+
+pub(crate) fn command_name(frame: &Frame) -> String {
+    match frame {
+        Frame::Array(array) => array.first().map(frame_to_arg).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn frame_to_arg(frame: &Frame) -> String {
+    match frame {
+        Frame::BulkString(s) => String::from_utf8_lossy(&s.inner).into_owned(),
+        Frame::SimpleString(s) => s.inner.clone(),
+        Frame::Integer(i) => i.inner.to_string(),
+        frame => format!("{:?}", frame),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_execute_records_slowlog() {
+        let config = crate::config::Config {
+            slowlog_log_slower_than: 0,
+            ..Default::default()
+        };
+        let backend = Backend::with_config(&config);
+
+        let frame: Frame = vec![b"set".into(), b"key".into(), b"value".into()].into();
+        let request = RespRequest::new(frame, backend.clone(), None);
+        request.execute().unwrap();
+
+        let entries = backend.slowlog().get(None);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].args, vec!["set", "key", "value"]);
+        assert!(backend.key_meta("key").unwrap().size() > "keyvalue".len());
+    }
+
+    #[test]
+    fn test_request_execute_feeds_monitor_with_db() {
+        let backend = Backend::new();
+        let mut receiver = backend.monitor().subscribe();
+
+        let frame: Frame = vec![b"select".into(), b"3".into()].into();
+        RespRequest::new(frame, backend.clone(), None)
+            .execute()
+            .unwrap();
+        let frame: Frame = vec![b"get".into(), b"key".into()].into();
+        RespRequest::new(frame, backend.clone(), None)
+            .execute()
+            .unwrap();
+
+        assert!(receiver
+            .try_recv()
+            .unwrap()
+            .ends_with(" [0] \"select\" \"3\""));
+        assert!(receiver
+            .try_recv()
+            .unwrap()
+            .ends_with(" [3] \"get\" \"key\""));
     }
 }
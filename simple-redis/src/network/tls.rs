@@ -0,0 +1,141 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use anyhow::{Context, Result};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tracing::info;
+
+// like tls-auth-clients in redis, whether clients have to present a certificate signed by the CA
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TlsAuthClients {
+    #[default]
+    Yes,
+    No,
+    // a certificate is verified when one is sent, clients without one are accepted too
+    Optional,
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_file: PathBuf,
+    pub key_file: PathBuf,
+    pub ca_cert_file: Option<PathBuf>,
+    pub auth_clients: TlsAuthClients,
+}
+
+impl TlsConfig {
+    pub fn new(cert_file: impl Into<PathBuf>, key_file: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_file: cert_file.into(),
+            key_file: key_file.into(),
+            ca_cert_file: None,
+            auth_clients: TlsAuthClients::default(),
+        }
+    }
+
+    pub fn ca_cert_file(mut self, ca_cert_file: impl Into<PathBuf>) -> Self {
+        self.ca_cert_file = Some(ca_cert_file.into());
+        self
+    }
+
+    pub fn auth_clients(mut self, auth_clients: TlsAuthClients) -> Self {
+        self.auth_clients = auth_clients;
+        self
+    }
+
+    // reads the files again every time, so a reload picks up replaced certificates
+    fn server_config(&self) -> Result<ServerConfig> {
+        let builder = ServerConfig::builder();
+        let builder = match (self.auth_clients, &self.ca_cert_file) {
+            (TlsAuthClients::No, _) => builder.with_no_client_auth(),
+            (auth, Some(ca_cert_file)) => {
+                let verifier = WebPkiClientVerifier::builder(Arc::new(roots(ca_cert_file)?));
+                let verifier = match auth {
+                    TlsAuthClients::Optional => verifier.allow_unauthenticated(),
+                    _ => verifier,
+                };
+                builder.with_client_cert_verifier(verifier.build()?)
+            }
+            (_, None) => anyhow::bail!("verifying client certificates requires a CA certificate"),
+        };
+
+        Ok(builder.with_single_cert(certs(&self.cert_file)?, key(&self.key_file)?)?)
+    }
+
+    // for outgoing links, like a replica connecting to its primary, it trusts the CA and presents
+    // the certificate as its client certificate
+    pub fn connector(&self) -> Result<TlsConnector> {
+        let ca_cert_file = self
+            .ca_cert_file
+            .as_ref()
+            .context("verifying the server certificate requires a CA certificate")?;
+        let config = ClientConfig::builder()
+            .with_root_certificates(roots(ca_cert_file)?)
+            .with_client_auth_cert(certs(&self.cert_file)?, key(&self.key_file)?)?;
+
+        Ok(TlsConnector::from(Arc::new(config)))
+    }
+}
+
+// the certificates of the tls listener, connections accepted after a reload use the new ones
+#[derive(Debug, Clone)]
+pub struct Tls {
+    config: TlsConfig,
+    server: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl Tls {
+    pub fn new(config: TlsConfig) -> Result<Self> {
+        let server = Arc::new(config.server_config()?);
+
+        Ok(Self {
+            config,
+            server: Arc::new(RwLock::new(server)),
+        })
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        let server = self.server.read().unwrap_or_else(PoisonError::into_inner);
+        TlsAcceptor::from(server.clone())
+    }
+
+    // the previous certificates stay in use when the new files can not be loaded
+    pub fn reload(&self) -> Result<()> {
+        let server = Arc::new(self.config.server_config()?);
+        *self.server.write().unwrap_or_else(PoisonError::into_inner) = server;
+        info!("Reloaded TLS certificates from {:?}", self.config.cert_file);
+        Ok(())
+    }
+}
+
+fn certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificate found in {:?}", path);
+    }
+    Ok(certs)
+}
+
+fn key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(open(path)?);
+    rustls_pemfile::private_key(&mut reader)?
+        .with_context(|| format!("no private key found in {:?}", path))
+}
+
+fn roots(path: &Path) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in certs(path)? {
+        roots.add(cert)?;
+    }
+    Ok(roots)
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("failed to open {:?}", path))
+}
@@ -0,0 +1,621 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use super::*;
+use crate::backend::{crc64, HashValue, SortedSet, Value};
+
+impl<'a> RdbReader<'a> {
+    // checks the magic string and the version, the records follow
+    pub fn new(bytes: &'a [u8]) -> Result<Self, RdbError> {
+        let header = bytes.get(..9).ok_or(RdbError::NotRdb)?;
+        if &header[..5] != b"REDIS" {
+            return Err(RdbError::NotRdb);
+        }
+        let version = std::str::from_utf8(&header[5..])
+            .ok()
+            .and_then(|version| version.parse::<u16>().ok())
+            .ok_or(RdbError::NotRdb)?;
+        if version == 0 || version > RDB_VERSION {
+            return Err(RdbError::Version(version));
+        }
+        Ok(Self {
+            bytes,
+            input: &bytes[9..],
+            version,
+            db: 0,
+            done: false,
+        })
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn read(&mut self) -> Result<Option<RdbRecord>, RdbError> {
+        let mut expire_at = None;
+        let mut idle = None;
+        let mut freq = None;
+        loop {
+            let input = &mut self.input;
+            match take_u8(input)? {
+                OPCODE_EOF => {
+                    self.check_crc()?;
+                    return Ok(None);
+                }
+                OPCODE_SELECTDB => self.db = decode_len(input)? as usize,
+                // only a hint for sizing the tables
+                OPCODE_RESIZEDB => {
+                    decode_len(input)?;
+                    decode_len(input)?;
+                }
+                OPCODE_EXPIRETIME_MS => expire_at = Some(u64::from_le_bytes(take_array(input)?)),
+                OPCODE_EXPIRETIME => {
+                    expire_at = Some(u32::from_le_bytes(take_array(input)?) as u64 * 1000)
+                }
+                OPCODE_IDLE => idle = Some(Duration::from_secs(decode_len(input)?)),
+                OPCODE_FREQ => freq = Some(take_u8(input)?),
+                OPCODE_AUX => {
+                    let name = decode_lossy(input)?;
+                    let value = decode_lossy(input)?;
+                    return Ok(Some(RdbRecord::Aux(name, value)));
+                }
+                OPCODE_FUNCTION2 => return Ok(Some(RdbRecord::Function(decode_string(input)?))),
+                OPCODE_FUNCTION_PRE_GA => {
+                    return Err(RdbError::Unsupported(
+                        "functions of redis 7.0 release candidates",
+                    ))
+                }
+                OPCODE_MODULE_AUX => return Err(RdbError::Unsupported("modules")),
+                kind => {
+                    let len = input.len();
+                    let key = decode_lossy(input)?;
+                    let value = decode_typed(kind, input)?;
+                    return Ok(Some(RdbRecord::Entry(RdbEntry {
+                        db: self.db,
+                        key,
+                        value,
+                        expire_at,
+                        idle,
+                        freq,
+                        size: len - input.len(),
+                    })));
+                }
+            }
+        }
+    }
+
+    // files of version 5 and later end in the checksum of everything before it, 0 when redis
+    // was told not to compute it
+    fn check_crc(&mut self) -> Result<(), RdbError> {
+        if self.version < 5 {
+            return Ok(());
+        }
+        let len = self.bytes.len() - self.input.len();
+        let crc = u64::from_le_bytes(take_array(&mut self.input)?);
+        if crc != 0 && crc != crc64(0, &self.bytes[..len]) {
+            return Err(RdbError::Checksum);
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for RdbReader<'_> {
+    type Item = Result<RdbRecord, RdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.read().transpose();
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+// the type byte and the value after it, the way DUMP payloads start
+pub(crate) fn decode_value(input: &mut &[u8]) -> Result<Value, RdbError> {
+    let kind = take_u8(input)?;
+    decode_typed(kind, input)
+}
+
+fn decode_typed(kind: u8, input: &mut &[u8]) -> Result<Value, RdbError> {
+    let value = match kind {
+        TYPE_STRING => Value::String(decode_string(input)?.into()),
+        TYPE_LIST => {
+            let len = decode_len(input)?;
+            let list = (0..len)
+                .map(|_| decode_string(input))
+                .collect::<Result<_, _>>()?;
+            Value::List(list)
+        }
+        TYPE_SET => {
+            let len = decode_len(input)?;
+            let members = (0..len)
+                .map(|_| decode_string(input))
+                .collect::<Result<_, _>>()?;
+            set(members)
+        }
+        TYPE_ZSET | TYPE_ZSET_2 => {
+            let len = decode_len(input)?;
+            let mut zset = SortedSet::new();
+            for _ in 0..len {
+                let member = decode_lossy(input)?;
+                let score = match kind {
+                    TYPE_ZSET => decode_text_double(input)?,
+                    _ => f64::from_le_bytes(take_array(input)?),
+                };
+                if score.is_nan() {
+                    return Err(RdbError::Corrupt("sorted set score"));
+                }
+                zset.insert(member, score);
+            }
+            Value::ZSet(zset)
+        }
+        TYPE_HASH => {
+            let len = decode_len(input)?;
+            let mut hash = HashValue::default();
+            for _ in 0..len {
+                let field = decode_lossy(input)?;
+                hash.insert(field, decode_string(input)?);
+            }
+            Value::Hash(hash)
+        }
+        TYPE_HASH_ZIPMAP => hash(ziplist::zipmap(&decode_string(input)?)?)?,
+        TYPE_LIST_ZIPLIST => Value::List(ziplist::ziplist(&decode_string(input)?)?.into()),
+        TYPE_SET_INTSET => set(ziplist::intset(&decode_string(input)?)?),
+        TYPE_ZSET_ZIPLIST => zset(ziplist::ziplist(&decode_string(input)?)?)?,
+        TYPE_HASH_ZIPLIST => hash(ziplist::ziplist(&decode_string(input)?)?)?,
+        TYPE_LIST_QUICKLIST => {
+            let len = decode_len(input)?;
+            let mut list = VecDeque::new();
+            for _ in 0..len {
+                list.extend(ziplist::ziplist(&decode_string(input)?)?);
+            }
+            Value::List(list)
+        }
+        TYPE_HASH_LISTPACK => hash(ziplist::listpack(&decode_string(input)?)?)?,
+        TYPE_ZSET_LISTPACK => zset(ziplist::listpack(&decode_string(input)?)?)?,
+        // nodes hold a listpack, or a single large element as it is
+        TYPE_LIST_QUICKLIST_2 => {
+            let len = decode_len(input)?;
+            let mut list = VecDeque::new();
+            for _ in 0..len {
+                let container = decode_len(input)?;
+                let node = decode_string(input)?;
+                match container {
+                    QUICKLIST_NODE_PLAIN => list.push_back(node),
+                    QUICKLIST_NODE_PACKED => list.extend(ziplist::listpack(&node)?),
+                    _ => return Err(RdbError::Corrupt("quicklist node")),
+                }
+            }
+            Value::List(list)
+        }
+        TYPE_SET_LISTPACK => set(ziplist::listpack(&decode_string(input)?)?),
+        // deadlines are stored relative to the earliest one, 0 for a field that never expires
+        TYPE_HASH_METADATA => {
+            let min = u64::from_le_bytes(take_array(input)?);
+            let len = decode_len(input)?;
+            let mut hash = HashValue::default();
+            for _ in 0..len {
+                let ttl = decode_len(input)?;
+                let field = decode_lossy(input)?;
+                hash.insert(&field, decode_string(input)?);
+                if ttl > 0 {
+                    hash.set_expire(&field, min + ttl - 1);
+                }
+            }
+            Value::Hash(hash)
+        }
+        // triples of field, value and deadline, 0 for a field that never expires
+        TYPE_HASH_LISTPACK_EX => {
+            take_array::<8>(input)?;
+            let entries = ziplist::listpack(&decode_string(input)?)?;
+            if !entries.len().is_multiple_of(3) {
+                return Err(RdbError::Corrupt("hash"));
+            }
+            let mut hash = HashValue::default();
+            for entry in entries.chunks(3) {
+                let field = String::from_utf8_lossy(&entry[0]).into_owned();
+                let at = std::str::from_utf8(&entry[2])
+                    .ok()
+                    .and_then(|at| at.parse::<u64>().ok())
+                    .ok_or(RdbError::Corrupt("hash field deadline"))?;
+                hash.insert(&field, entry[1].clone());
+                if at > 0 {
+                    hash.set_expire(&field, at);
+                }
+            }
+            Value::Hash(hash)
+        }
+        TYPE_MODULE | TYPE_MODULE_2 => return Err(RdbError::Unsupported("modules")),
+        TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+            return Err(RdbError::Unsupported("streams"))
+        }
+        TYPE_HASH_METADATA_PRE_GA | TYPE_HASH_LISTPACK_EX_PRE_GA => {
+            return Err(RdbError::Unsupported(
+                "hashes of redis 7.4 release candidates",
+            ))
+        }
+        kind => return Err(RdbError::Type(kind)),
+    };
+    Ok(value)
+}
+
+fn set(members: Vec<Vec<u8>>) -> Value {
+    let set: HashSet<String> = members
+        .iter()
+        .map(|member| String::from_utf8_lossy(member).into_owned())
+        .collect();
+    Value::Set(set)
+}
+
+// alternating fields and values
+fn hash(entries: Vec<Vec<u8>>) -> Result<Value, RdbError> {
+    if !entries.len().is_multiple_of(2) {
+        return Err(RdbError::Corrupt("hash"));
+    }
+    let mut hash = HashValue::default();
+    let mut entries = entries.into_iter();
+    while let (Some(field), Some(value)) = (entries.next(), entries.next()) {
+        hash.insert(String::from_utf8_lossy(&field), value);
+    }
+    Ok(Value::Hash(hash))
+}
+
+// alternating members and scores as text
+fn zset(entries: Vec<Vec<u8>>) -> Result<Value, RdbError> {
+    if !entries.len().is_multiple_of(2) {
+        return Err(RdbError::Corrupt("sorted set"));
+    }
+    let mut zset = SortedSet::new();
+    for pair in entries.chunks(2) {
+        let score = std::str::from_utf8(&pair[1])
+            .ok()
+            .and_then(|score| score.parse::<f64>().ok())
+            .filter(|score| !score.is_nan())
+            .ok_or(RdbError::Corrupt("sorted set score"))?;
+        zset.insert(String::from_utf8_lossy(&pair[0]), score);
+    }
+    Ok(Value::ZSet(zset))
+}
+
+// scores of redis before 4 were written as text after a length, with three lengths set aside
+fn decode_text_double(input: &mut &[u8]) -> Result<f64, RdbError> {
+    match take_u8(input)? {
+        253 => Ok(f64::NAN),
+        254 => Ok(f64::INFINITY),
+        255 => Ok(f64::NEG_INFINITY),
+        len => {
+            let text = take(input, len as usize)?;
+            std::str::from_utf8(text)
+                .ok()
+                .and_then(|text| text.parse().ok())
+                .ok_or(RdbError::Corrupt("sorted set score"))
+        }
+    }
+}
+
+pub(super) fn decode_len(input: &mut &[u8]) -> Result<u64, RdbError> {
+    let first = take_u8(input)?;
+    match first >> 6 {
+        LEN_6BIT => Ok((first & 0x3f) as u64),
+        LEN_14BIT => Ok((((first & 0x3f) as u64) << 8) | take_u8(input)? as u64),
+        _ if first == LEN_32BIT => Ok(u32::from_be_bytes(take_array(input)?) as u64),
+        _ if first == LEN_64BIT => Ok(u64::from_be_bytes(take_array(input)?)),
+        _ => Err(RdbError::Corrupt("length")),
+    }
+}
+
+// a string after its length, or an integer or compressed string after the encoding marker
+pub(super) fn decode_string(input: &mut &[u8]) -> Result<Vec<u8>, RdbError> {
+    let int = match input.first() {
+        Some(&ENC_INT8) => i8::from_le_bytes(take_array(&mut &input[1..])?) as i64,
+        Some(&ENC_INT16) => i16::from_le_bytes(take_array(&mut &input[1..])?) as i64,
+        Some(&ENC_INT32) => i32::from_le_bytes(take_array(&mut &input[1..])?) as i64,
+        Some(&ENC_LZF) => {
+            *input = &input[1..];
+            let compressed = decode_len(input)? as usize;
+            let len = decode_len(input)? as usize;
+            let compressed = take(input, compressed)?;
+            return lzf::decompress(compressed, len).ok_or(RdbError::Corrupt("compressed string"));
+        }
+        _ => {
+            let len = decode_len(input)? as usize;
+            return Ok(take(input, len)?.to_vec());
+        }
+    };
+    let width = match input[0] {
+        ENC_INT8 => 1,
+        ENC_INT16 => 2,
+        _ => 4,
+    };
+    *input = &input[1 + width..];
+    Ok(int.to_string().into_bytes())
+}
+
+// keys, fields and members are kept as strings
+fn decode_lossy(input: &mut &[u8]) -> Result<String, RdbError> {
+    let bytes = decode_string(input)?;
+    Ok(String::from_utf8(bytes)
+        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
+fn take_u8(input: &mut &[u8]) -> Result<u8, RdbError> {
+    Ok(take(input, 1)?[0])
+}
+
+fn take_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], RdbError> {
+    Ok(take(input, N)?.try_into().unwrap_or([0; N]))
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], RdbError> {
+    if input.len() < len {
+        return Err(RdbError::Truncated);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::StringValue;
+
+    // the records between the header and the end marker, with the checksum redis would write
+    fn rdb(version: &str, records: &[u8]) -> Vec<u8> {
+        let mut bytes = format!("REDIS{}", version).into_bytes();
+        bytes.extend_from_slice(records);
+        bytes.push(OPCODE_EOF);
+        let crc = crc64(0, &bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    fn string(bytes: &[u8]) -> Vec<u8> {
+        let mut buf = vec![bytes.len() as u8];
+        buf.extend_from_slice(bytes);
+        buf
+    }
+
+    fn listpack(entries: &[&[u8]]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for entry in entries {
+            body.push(0x80 | entry.len() as u8);
+            body.extend_from_slice(entry);
+            body.push(entry.len() as u8 + 1);
+        }
+        let mut lp = ((body.len() + 7) as u32).to_le_bytes().to_vec();
+        lp.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        lp.extend_from_slice(&body);
+        lp.push(0xff);
+        string(&lp)
+    }
+
+    fn ziplist(entries: &[&[u8]]) -> Vec<u8> {
+        let mut zl = vec![0; 10];
+        let mut prev = 0;
+        for entry in entries {
+            zl.extend_from_slice(&[prev, entry.len() as u8]);
+            zl.extend_from_slice(entry);
+            prev = entry.len() as u8 + 2;
+        }
+        zl.push(0xff);
+        string(&zl)
+    }
+
+    fn entries(bytes: &[u8]) -> Vec<RdbEntry> {
+        RdbReader::new(bytes)
+            .unwrap()
+            .filter_map(|record| match record.unwrap() {
+                RdbRecord::Entry(entry) => Some(entry),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn hash(pairs: &[(&str, &str)]) -> Value {
+        let mut hash = HashValue::default();
+        for (field, value) in pairs {
+            hash.insert(field, *value);
+        }
+        Value::Hash(hash)
+    }
+
+    fn zset(pairs: &[(&str, f64)]) -> Value {
+        let mut zset = SortedSet::new();
+        for (member, score) in pairs {
+            zset.insert(member, *score);
+        }
+        Value::ZSet(zset)
+    }
+
+    fn set(members: &[&str]) -> Value {
+        Value::Set(members.iter().map(|m| m.to_string()).collect())
+    }
+
+    fn list(elements: &[&str]) -> Value {
+        Value::List(elements.iter().map(|e| e.as_bytes().to_vec()).collect())
+    }
+
+    #[test]
+    fn test_read_redis_7() {
+        let mut records = vec![OPCODE_AUX];
+        records.extend(string(b"redis-ver"));
+        records.extend(string(b"7.2.4"));
+        records.push(OPCODE_AUX);
+        records.extend(string(b"redis-bits"));
+        records.extend_from_slice(&[ENC_INT8, 64]);
+        records.extend_from_slice(&[OPCODE_SELECTDB, 0, OPCODE_RESIZEDB, 8, 1]);
+        // a compressed string, an integer and a string with a deadline
+        records.push(TYPE_STRING);
+        records.extend(string(b"lzf"));
+        records.extend_from_slice(b"\xc3\x07\x0c\x02abc\xe0\x00\x02");
+        records.extend_from_slice(&[OPCODE_FREQ, 3, OPCODE_IDLE, 0x40, 0x78, TYPE_STRING]);
+        records.extend(string(b"int"));
+        records.extend_from_slice(&[ENC_INT16, 0xe8, 0x03]);
+        records.push(OPCODE_EXPIRETIME_MS);
+        records.extend_from_slice(&4_000_000_000_000u64.to_le_bytes());
+        records.push(TYPE_STRING);
+        records.extend(string(b"ttl"));
+        records.extend(string(b"v"));
+        // the listpack encodings of redis 7
+        records.push(TYPE_SET_LISTPACK);
+        records.extend(string(b"set"));
+        records.extend(listpack(&[b"a", b"b"]));
+        records.push(TYPE_SET_INTSET);
+        records.extend(string(b"ints"));
+        records.extend(string(b"\x02\x00\x00\x00\x02\x00\x00\x00\x01\x00\x02\x00"));
+        records.push(TYPE_HASH_LISTPACK);
+        records.extend(string(b"hash"));
+        records.extend(listpack(&[b"f", b"v", b"g", b"w"]));
+        records.push(TYPE_ZSET_LISTPACK);
+        records.extend(string(b"zset"));
+        records.extend(listpack(&[b"m", b"1.5", b"n", b"-2"]));
+        // a packed node and one holding a single large element
+        records.push(TYPE_LIST_QUICKLIST_2);
+        records.extend(string(b"list"));
+        records.push(2);
+        records.push(QUICKLIST_NODE_PACKED as u8);
+        records.extend(listpack(&[b"x", b"y"]));
+        records.push(QUICKLIST_NODE_PLAIN as u8);
+        records.extend(string(b"z"));
+        let bytes = rdb("0011", &records);
+
+        let mut reader = RdbReader::new(&bytes).unwrap();
+        assert_eq!(reader.version(), 11);
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            RdbRecord::Aux("redis-ver".to_string(), "7.2.4".to_string())
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            RdbRecord::Aux("redis-bits".to_string(), "64".to_string())
+        );
+
+        let entries = entries(&bytes);
+        let values: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.value.clone()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("lzf", Value::String("abcabcabcabc".into())),
+                ("int", Value::String(StringValue::Int(1000))),
+                ("ttl", Value::String("v".into())),
+                ("set", set(&["a", "b"])),
+                ("ints", set(&["1", "2"])),
+                ("hash", hash(&[("f", "v"), ("g", "w")])),
+                ("zset", zset(&[("m", 1.5), ("n", -2.0)])),
+                ("list", list(&["x", "y", "z"])),
+            ]
+        );
+        assert_eq!(entries[1].freq, Some(3));
+        assert_eq!(entries[1].idle, Some(Duration::from_secs(120)));
+        assert_eq!(entries[1].size, 7);
+        assert_eq!(entries[2].expire_at, Some(4_000_000_000_000));
+        assert_eq!(entries[3].expire_at, None);
+    }
+
+    #[test]
+    fn test_read_redis_3() {
+        let mut records = vec![OPCODE_SELECTDB, 2];
+        records.push(TYPE_HASH_ZIPLIST);
+        records.extend(string(b"hash"));
+        records.extend(ziplist(&[b"f", b"v"]));
+        records.push(TYPE_ZSET_ZIPLIST);
+        records.extend(string(b"zset"));
+        records.extend(ziplist(&[b"m", b"3"]));
+        records.push(TYPE_HASH_ZIPMAP);
+        records.extend(string(b"zipmap"));
+        records.extend(string(b"\x01\x01a\x01\x00b\xff"));
+        // scores as text, the length 254 stands for infinity
+        records.push(TYPE_ZSET);
+        records.extend(string(b"scores"));
+        records.push(2);
+        records.extend(string(b"a"));
+        records.extend(string(b"0.5"));
+        records.extend(string(b"b"));
+        records.push(254);
+        records.push(OPCODE_EXPIRETIME);
+        records.extend_from_slice(&1_000_000u32.to_le_bytes());
+        records.push(TYPE_LIST_QUICKLIST);
+        records.extend(string(b"list"));
+        records.push(1);
+        records.extend(ziplist(&[b"x"]));
+        records.push(TYPE_LIST);
+        records.extend(string(b"plain"));
+        records.push(1);
+        records.extend(string(b"y"));
+        let bytes = rdb("0006", &records);
+
+        let entries = entries(&bytes);
+        assert!(entries.iter().all(|entry| entry.db == 2));
+        let values: Vec<_> = entries.iter().map(|entry| entry.value.clone()).collect();
+        assert_eq!(
+            values,
+            vec![
+                hash(&[("f", "v")]),
+                zset(&[("m", 3.0)]),
+                hash(&[("a", "b")]),
+                zset(&[("a", 0.5), ("b", f64::INFINITY)]),
+                list(&["x"]),
+                list(&["y"]),
+            ]
+        );
+        assert_eq!(entries[4].expire_at, Some(1_000_000_000));
+    }
+
+    #[test]
+    fn test_read_hash_field_deadlines() {
+        let mut records = vec![TYPE_HASH_LISTPACK_EX];
+        records.extend(string(b"hash"));
+        records.extend_from_slice(&1_900_000_000_000u64.to_le_bytes());
+        records.extend(listpack(&[b"f", b"v", b"1900000000000", b"g", b"w", b"0"]));
+        let bytes = rdb("0012", &records);
+
+        let Value::Hash(hash) = &entries(&bytes)[0].value else {
+            panic!("not a hash");
+        };
+        assert_eq!(hash.expire_at("f"), Some(1_900_000_000_000));
+        assert_eq!(hash.expire_at("g"), None);
+        assert_eq!(hash.len(), 2);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(matches!(RdbReader::new(b"REDIS"), Err(RdbError::NotRdb)));
+        assert!(matches!(
+            RdbReader::new(b"MEMCACHE1"),
+            Err(RdbError::NotRdb)
+        ));
+        assert!(matches!(
+            RdbReader::new(b"REDIS0013"),
+            Err(RdbError::Version(13))
+        ));
+
+        let mut records = vec![TYPE_STRING];
+        records.extend(string(b"key"));
+        records.extend(string(b"value"));
+        let mut bytes = rdb("0011", &records);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let result: Result<Vec<_>, _> = RdbReader::new(&bytes).unwrap().collect();
+        assert!(matches!(result, Err(RdbError::Checksum)));
+        // redis writes a zero checksum when it was told not to compute one
+        bytes[last - 7..].fill(0);
+        assert_eq!(entries(&bytes).len(), 1);
+
+        let mut reader = RdbReader::new(&bytes[..14]).unwrap();
+        assert!(matches!(reader.next(), Some(Err(RdbError::Truncated))));
+        assert!(reader.next().is_none());
+
+        let bytes = rdb("0011", &[TYPE_STREAM_LISTPACKS_3, 1, b's']);
+        let result: Result<Vec<_>, _> = RdbReader::new(&bytes).unwrap().collect();
+        assert!(matches!(result, Err(RdbError::Unsupported("streams"))));
+        let bytes = rdb("0011", &[8, 1, b'k']);
+        let result: Result<Vec<_>, _> = RdbReader::new(&bytes).unwrap().collect();
+        assert!(matches!(result, Err(RdbError::Type(8))));
+    }
+}
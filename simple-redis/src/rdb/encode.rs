@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use super::*;
+use crate::backend::{crc64, StringValue, ValueRef};
+
+// strings this short are never compressed, like redis
+const COMPRESS_MIN_LEN: usize = 21;
+
+impl RdbWriter {
+    pub(crate) fn new() -> Self {
+        let mut buf = b"REDIS".to_vec();
+        buf.extend_from_slice(format!("{:04}", RDB_COMPAT_VERSION).as_bytes());
+        Self {
+            buf,
+            version: RDB_COMPAT_VERSION,
+        }
+    }
+
+    pub(crate) fn aux(&mut self, name: &str, value: &str) {
+        self.buf.push(OPCODE_AUX);
+        encode_string(name.as_bytes(), &mut self.buf);
+        encode_string_value(&value.into(), &mut self.buf);
+    }
+
+    // the keys of database `db` follow, `len` of them and `expires` of those with a deadline
+    pub(crate) fn select_db(&mut self, db: usize, len: usize, expires: usize) {
+        self.buf.push(OPCODE_SELECTDB);
+        encode_len(db as u64, &mut self.buf);
+        self.buf.push(OPCODE_RESIZEDB);
+        encode_len(len as u64, &mut self.buf);
+        encode_len(expires as u64, &mut self.buf);
+    }
+
+    // `expire_at` is in unix milliseconds. redis keeps whichever of the idle time and the access
+    // counter its eviction policy uses
+    pub(crate) fn entry(
+        &mut self,
+        key: &str,
+        value: ValueRef<'_>,
+        expire_at: Option<u64>,
+        idle: Option<Duration>,
+        freq: Option<u8>,
+    ) {
+        if let Some(at) = expire_at {
+            self.buf.push(OPCODE_EXPIRETIME_MS);
+            self.buf.extend_from_slice(&at.to_le_bytes());
+        }
+        if let Some(idle) = idle {
+            self.buf.push(OPCODE_IDLE);
+            encode_len(idle.as_secs(), &mut self.buf);
+        }
+        if let Some(freq) = freq {
+            self.buf.push(OPCODE_FREQ);
+            self.buf.push(freq);
+        }
+        let kind = value_type(value);
+        self.buf.push(kind);
+        encode_string(key.as_bytes(), &mut self.buf);
+        encode_body(value, &mut self.buf);
+        // hash field deadlines only load on redis 7.4 and later
+        if kind == TYPE_HASH_METADATA {
+            self.version = RDB_VERSION;
+        }
+    }
+
+    // the end marker and the checksum of the whole file
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let version = format!("{:04}", self.version);
+        self.buf[5..9].copy_from_slice(version.as_bytes());
+        self.buf.push(OPCODE_EOF);
+        let crc = crc64(0, &self.buf);
+        self.buf.extend_from_slice(&crc.to_le_bytes());
+        self.buf
+    }
+}
+
+// the type byte and the value the way an rdb file holds it
+pub(crate) fn encode_value(value: ValueRef<'_>, buf: &mut Vec<u8>) {
+    buf.push(value_type(value));
+    encode_body(value, buf);
+}
+
+fn value_type(value: ValueRef<'_>) -> u8 {
+    match value {
+        ValueRef::String(_) => TYPE_STRING,
+        ValueRef::Set(_) => TYPE_SET,
+        ValueRef::ZSet(_) => TYPE_ZSET_2,
+        // the plain encoding redis still loads, it converts it to a quicklist
+        ValueRef::List(_) => TYPE_LIST,
+        ValueRef::Hash(hash)
+            if hash
+                .iter()
+                .any(|(field, _)| hash.expire_at(field).is_some()) =>
+        {
+            TYPE_HASH_METADATA
+        }
+        ValueRef::Hash(_) => TYPE_HASH,
+    }
+}
+
+fn encode_body(value: ValueRef<'_>, buf: &mut Vec<u8>) {
+    match value {
+        ValueRef::String(value) => encode_string_value(value, buf),
+        ValueRef::Set(set) => {
+            encode_len(set.len() as u64, buf);
+            for member in set {
+                encode_string(member.as_bytes(), buf);
+            }
+        }
+        ValueRef::List(list) => {
+            encode_len(list.len() as u64, buf);
+            for element in list {
+                encode_string(element, buf);
+            }
+        }
+        ValueRef::ZSet(zset) => {
+            encode_len(zset.len() as u64, buf);
+            for (member, score) in zset.iter() {
+                encode_string(member.as_bytes(), buf);
+                buf.extend_from_slice(&score.to_le_bytes());
+            }
+        }
+        ValueRef::Hash(hash) => {
+            let deadlines = hash.iter().filter_map(|(field, _)| hash.expire_at(field));
+            match deadlines.min() {
+                None => {
+                    encode_len(hash.len() as u64, buf);
+                    for (field, value) in hash.iter() {
+                        encode_string(field.as_bytes(), buf);
+                        encode_string_value(value, buf);
+                    }
+                }
+                // deadlines are stored relative to the earliest one, 0 for a field that never
+                // expires
+                Some(min) => {
+                    buf.extend_from_slice(&min.to_le_bytes());
+                    encode_len(hash.len() as u64, buf);
+                    for (field, value) in hash.iter() {
+                        let ttl = hash.expire_at(field).map_or(0, |at| at - min + 1);
+                        encode_len(ttl, buf);
+                        encode_string(field.as_bytes(), buf);
+                        encode_string_value(value, buf);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn encode_len(len: u64, buf: &mut Vec<u8>) {
+    if len < 1 << 6 {
+        buf.push((LEN_6BIT << 6) | len as u8);
+    } else if len < 1 << 14 {
+        buf.push((LEN_14BIT << 6) | (len >> 8) as u8);
+        buf.push(len as u8);
+    } else if len <= u32::MAX as u64 {
+        buf.push(LEN_32BIT);
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        buf.push(LEN_64BIT);
+        buf.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+// longer strings are compressed when that saves more than the two lengths it costs
+pub(super) fn encode_string(bytes: &[u8], buf: &mut Vec<u8>) {
+    if bytes.len() >= COMPRESS_MIN_LEN {
+        if let Some(compressed) = lzf::compress(bytes, bytes.len() - 4) {
+            buf.push(ENC_LZF);
+            encode_len(compressed.len() as u64, buf);
+            encode_len(bytes.len() as u64, buf);
+            buf.extend_from_slice(&compressed);
+            return;
+        }
+    }
+    encode_len(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+// small integers take the compact integer encoding, like redis writes them
+fn encode_string_value(value: &StringValue, buf: &mut Vec<u8>) {
+    match *value {
+        StringValue::Int(i) if i8::try_from(i).is_ok() => {
+            buf.push(ENC_INT8);
+            buf.extend_from_slice(&(i as i8).to_le_bytes());
+        }
+        StringValue::Int(i) if i16::try_from(i).is_ok() => {
+            buf.push(ENC_INT16);
+            buf.extend_from_slice(&(i as i16).to_le_bytes());
+        }
+        StringValue::Int(i) if i32::try_from(i).is_ok() => {
+            buf.push(ENC_INT32);
+            buf.extend_from_slice(&(i as i32).to_le_bytes());
+        }
+        _ => encode_string(&value.as_bytes(), buf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::HashValue;
+
+    #[test]
+    fn test_writer_matches_redis() {
+        // what redis 6 writes for "SET key hello" in database 0, between the aux fields and the
+        // checksum
+        let mut writer = RdbWriter::new();
+        writer.select_db(0, 1, 0);
+        writer.entry("key", ValueRef::String(&"hello".into()), None, None, None);
+        let bytes = writer.finish();
+        assert_eq!(
+            &bytes[..bytes.len() - 8],
+            b"REDIS0009\xfe\x00\xfb\x01\x00\x00\x03key\x05hello\xff"
+        );
+
+        let mut hash = HashValue::default();
+        hash.insert("field", "value");
+        hash.set_expire("field", 1_900_000_000_000);
+        let mut writer = RdbWriter::new();
+        writer.entry("hash", ValueRef::Hash(&hash), None, None, None);
+        assert!(writer.finish().starts_with(b"REDIS0012"));
+    }
+
+    #[test]
+    fn test_encode_string_compresses() {
+        let mut buf = Vec::new();
+        encode_string(&[b'a'; 100], &mut buf);
+        assert_eq!(buf[0], ENC_LZF);
+        assert!(buf.len() < 20);
+        assert_eq!(
+            decode::decode_string(&mut &buf[..]).unwrap(),
+            vec![b'a'; 100]
+        );
+
+        // too short to be worth it
+        let mut buf = Vec::new();
+        encode_string(&[b'a'; 20], &mut buf);
+        assert_eq!(buf[0], 20);
+    }
+}
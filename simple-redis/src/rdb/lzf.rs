@@ -0,0 +1,122 @@
+// the LZF variant redis compresses long strings with: a control byte below 32 starts a run of
+// that many literals plus one, anything above copies from earlier output, its top three bits
+// hold the length minus two (7 means another length byte follows) and the rest plus the next
+// byte the distance back minus one
+
+const MAX_LITERALS: usize = 32;
+const MAX_OFFSET: usize = 1 << 13;
+const MAX_MATCH: usize = 7 + 255 + 2;
+const HASH_LOG: u32 = 14;
+
+// `None` unless the data shrinks to at most `max` bytes
+pub(super) fn compress(input: &[u8], max: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(max);
+    let mut table = vec![usize::MAX; 1 << HASH_LOG];
+    let mut literals = 0;
+    let mut i = 0;
+    while i + 2 < input.len() {
+        let hash = hash(&input[i..i + 3]);
+        let candidate = std::mem::replace(&mut table[hash], i);
+        let found = candidate != usize::MAX
+            && i - candidate <= MAX_OFFSET
+            && input[candidate..candidate + 3] == input[i..i + 3];
+        if !found {
+            i += 1;
+            continue;
+        }
+
+        let max_len = (input.len() - i).min(MAX_MATCH);
+        let mut len = 3;
+        while len < max_len && input[candidate + len] == input[i + len] {
+            len += 1;
+        }
+        push_literals(&input[literals..i], &mut out);
+        let offset = i - candidate - 1;
+        let short = len - 2;
+        if short < 7 {
+            out.push(((short as u8) << 5) | (offset >> 8) as u8);
+        } else {
+            out.push((7 << 5) | (offset >> 8) as u8);
+            out.push((short - 7) as u8);
+        }
+        out.push(offset as u8);
+        i += len;
+        literals = i;
+        if out.len() > max {
+            return None;
+        }
+    }
+    push_literals(&input[literals..], &mut out);
+    (out.len() <= max).then_some(out)
+}
+
+// `None` when the data is corrupt or does not decompress to exactly `len` bytes
+pub(super) fn decompress(input: &[u8], len: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(len);
+    let mut input = input.iter().copied();
+    while let Some(control) = input.next() {
+        let control = control as usize;
+        if control < MAX_LITERALS {
+            for _ in 0..=control {
+                out.push(input.next()?);
+            }
+            continue;
+        }
+
+        let mut run = control >> 5;
+        if run == 7 {
+            run += input.next()? as usize;
+        }
+        let offset = ((control & 0x1f) << 8 | input.next()? as usize) + 1;
+        let start = out.len().checked_sub(offset)?;
+        // the run may overlap what it produces, so it is copied byte by byte
+        for i in start..start + run + 2 {
+            out.push(out[i]);
+        }
+    }
+    (out.len() == len).then_some(out)
+}
+
+fn push_literals(literals: &[u8], out: &mut Vec<u8>) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        out.push(chunk.len() as u8 - 1);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let v = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (v.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lzf_decompress() {
+        // "abcabcabcabc" the way liblzf compresses it: three literals, then a run of nine
+        // starting three bytes back
+        let compressed = b"\x02abc\xe0\x00\x02";
+        assert_eq!(decompress(compressed, 12), Some(b"abcabcabcabc".to_vec()));
+        assert_eq!(decompress(compressed, 11), None);
+        assert_eq!(decompress(b"\x02ab", 3), None);
+        assert_eq!(decompress(b"\x20\x05", 2), None);
+    }
+
+    #[test]
+    fn test_lzf_round_trip() {
+        let repeated = "simple-redis ".repeat(100).into_bytes();
+        let compressed = compress(&repeated, repeated.len()).unwrap();
+        assert!(compressed.len() < repeated.len() / 10);
+        assert_eq!(decompress(&compressed, repeated.len()), Some(repeated));
+
+        let long: Vec<u8> = (0..5000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let compressed = compress(&long, long.len()).unwrap();
+        assert_eq!(decompress(&compressed, long.len()), Some(long));
+
+        // random looking data does not shrink
+        let noise: Vec<u8> = (0..64u32).map(|i| (i * 97 + 13) as u8).collect();
+        assert_eq!(compress(&noise, noise.len() - 4), None);
+    }
+}
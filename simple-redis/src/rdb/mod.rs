@@ -0,0 +1,213 @@
+// the file format redis persists its keys in, read from the files of redis 2 to 7.4 and written
+// so that redis 6 and later load it
+mod decode;
+mod encode;
+mod lzf;
+mod ziplist;
+
+use std::path::Path;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::backend::{Backend, BackendError, Value};
+pub(crate) use decode::decode_value;
+pub(crate) use encode::encode_value;
+
+// the newest format this reads, the one of redis 7.4
+pub const RDB_VERSION: u16 = 12;
+// the format written unless a hash has field deadlines, the one of redis 6
+const RDB_COMPAT_VERSION: u16 = 9;
+
+// records other than keys start with one of these
+const OPCODE_FUNCTION_PRE_GA: u8 = 0xf5;
+const OPCODE_FUNCTION2: u8 = 0xf6;
+const OPCODE_MODULE_AUX: u8 = 0xf7;
+const OPCODE_IDLE: u8 = 0xf8;
+const OPCODE_FREQ: u8 = 0xf9;
+const OPCODE_AUX: u8 = 0xfa;
+const OPCODE_RESIZEDB: u8 = 0xfb;
+const OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const OPCODE_EXPIRETIME: u8 = 0xfd;
+const OPCODE_SELECTDB: u8 = 0xfe;
+const OPCODE_EOF: u8 = 0xff;
+
+// value types, each encoding redis ever wrote has its own
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_MODULE: u8 = 6;
+const TYPE_MODULE_2: u8 = 7;
+const TYPE_HASH_ZIPMAP: u8 = 9;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+const TYPE_HASH_METADATA_PRE_GA: u8 = 22;
+const TYPE_HASH_LISTPACK_EX_PRE_GA: u8 = 23;
+// a hash with field deadlines
+const TYPE_HASH_METADATA: u8 = 24;
+const TYPE_HASH_LISTPACK_EX: u8 = 25;
+
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+const QUICKLIST_NODE_PACKED: u64 = 2;
+
+// the first two bits of a length say how many bytes it takes, 0b11 marks an encoded string
+const LEN_6BIT: u8 = 0;
+const LEN_14BIT: u8 = 1;
+const LEN_32BIT: u8 = 0x80;
+const LEN_64BIT: u8 = 0x81;
+const ENC_INT8: u8 = 0xc0;
+const ENC_INT16: u8 = 0xc1;
+const ENC_INT32: u8 = 0xc2;
+const ENC_LZF: u8 = 0xc3;
+
+#[derive(Debug, Error)]
+pub enum RdbError {
+    #[error("not an rdb file")]
+    NotRdb,
+
+    #[error("rdb version {0} is not supported")]
+    Version(u16),
+
+    #[error("unexpected end of the rdb data")]
+    Truncated,
+
+    #[error("wrong rdb checksum")]
+    Checksum,
+
+    #[error("corrupt {0} in the rdb data")]
+    Corrupt(&'static str),
+
+    #[error("unknown value type {0} in the rdb data")]
+    Type(u8),
+
+    #[error("{0} are not supported")]
+    Unsupported(&'static str),
+
+    #[error("the rdb data uses database {0}, which is out of range")]
+    Database(usize),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+// RESTORE reports what it can not hold and a generic error for anything else, like redis
+impl From<RdbError> for BackendError {
+    fn from(error: RdbError) -> Self {
+        match error {
+            RdbError::Unsupported(_) => BackendError::Other(error.to_string()),
+            _ => BackendError::BadData,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RdbEntry {
+    pub db: usize,
+    pub key: String,
+    pub value: Value,
+    // unix time in milliseconds
+    pub expire_at: Option<u64>,
+    pub idle: Option<Duration>,
+    pub freq: Option<u8>,
+    // bytes the key and the value take in the file
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RdbRecord {
+    Aux(String, String),
+    // the code of a function library
+    Function(Vec<u8>),
+    Entry(RdbEntry),
+}
+
+// walks the records of rdb data, the checksum is verified once the end is reached
+#[derive(Debug)]
+pub struct RdbReader<'a> {
+    bytes: &'a [u8],
+    input: &'a [u8],
+    version: u16,
+    db: usize,
+    done: bool,
+}
+
+// builds rdb data key by key, see `Backend::save_rdb`
+#[derive(Debug)]
+pub(crate) struct RdbWriter {
+    buf: Vec<u8>,
+    version: u16,
+}
+
+// what loading rdb data created and what it had to leave out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RdbLoad {
+    pub keys: usize,
+    // keys whose deadline passed, they are not created
+    pub expired: usize,
+    pub functions: usize,
+}
+
+// adds the keys of the rdb file at `path` to `backend`
+pub fn load(backend: &Backend, path: &Path) -> Result<RdbLoad, RdbError> {
+    let bytes = std::fs::read(path)?;
+    backend.load_rdb(&bytes)
+}
+
+// writes every key of `backend` to a temporary file next to `path` and renames it over `path`,
+// so a failed save leaves the previous file intact
+pub fn save(backend: &Backend, path: &Path) -> Result<(), RdbError> {
+    let temp = path.with_file_name(format!("temp-{}.rdb", std::process::id()));
+    std::fs::write(&temp, backend.save_rdb())?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_file() {
+        let dir = std::env::temp_dir().join(format!("simple-redis-rdb-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dump.rdb");
+
+        let backend = Backend::new();
+        backend.set("key", "value".repeat(10));
+        save(&backend, &path).unwrap();
+        // the temporary file was renamed over the target
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let copy = Backend::new();
+        assert_eq!(load(&copy, &path).unwrap().keys, 1);
+        assert_eq!(copy.get_string("key"), Some("value".repeat(10).into()));
+        assert!(matches!(
+            load(&copy, &dir.join("missing.rdb")),
+            Err(RdbError::Io(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rdb_error() {
+        let error: BackendError = RdbError::Unsupported("streams").into();
+        assert_eq!(error.to_string(), "ERR streams are not supported");
+        assert_eq!(
+            BackendError::from(RdbError::Truncated),
+            BackendError::BadData
+        );
+    }
+}
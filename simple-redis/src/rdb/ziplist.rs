@@ -0,0 +1,251 @@
+// the compact encodings redis stores small collections in, each flattened to its entries
+
+use super::RdbError;
+
+const END: u8 = 0xff;
+
+// ziplists of redis before 7: a header of total bytes, tail offset and count, then entries of
+// the previous entry's length, an encoding and the data
+pub(super) fn ziplist(bytes: &[u8]) -> Result<Vec<Vec<u8>>, RdbError> {
+    let corrupt = || RdbError::Corrupt("ziplist");
+    let mut input = bytes.get(10..).ok_or_else(corrupt)?;
+    let mut entries = Vec::new();
+    loop {
+        let first = take(&mut input, 1).ok_or_else(corrupt)?[0];
+        if first == END {
+            return Ok(entries);
+        }
+        // the previous length is 1 byte, or 0xfe and 4 more
+        if first == 0xfe {
+            take(&mut input, 4).ok_or_else(corrupt)?;
+        }
+
+        let encoding = take(&mut input, 1).ok_or_else(corrupt)?[0];
+        let entry = match encoding >> 6 {
+            0 => take_string(&mut input, (encoding & 0x3f) as usize),
+            1 => {
+                let next = take(&mut input, 1).ok_or_else(corrupt)?[0];
+                take_string(
+                    &mut input,
+                    ((encoding & 0x3f) as usize) << 8 | next as usize,
+                )
+            }
+            2 => {
+                let len = take(&mut input, 4).ok_or_else(corrupt)?;
+                let len = u32::from_be_bytes(len.try_into().unwrap_or_default());
+                take_string(&mut input, len as usize)
+            }
+            _ => match encoding {
+                0xc0 => take_int(&mut input, 2),
+                0xd0 => take_int(&mut input, 4),
+                0xe0 => take_int(&mut input, 8),
+                0xf0 => take_int(&mut input, 3),
+                0xfe => take_int(&mut input, 1),
+                // 4 bits holding 0 to 12, offset by one
+                0xf1..=0xfd => Some(((encoding & 0x0f) as i64 - 1).to_string().into_bytes()),
+                _ => None,
+            },
+        };
+        entries.push(entry.ok_or_else(corrupt)?);
+    }
+}
+
+// listpacks of redis 7: a header of total bytes and count, then entries of an encoding, the data
+// and the length of both written backwards
+pub(super) fn listpack(bytes: &[u8]) -> Result<Vec<Vec<u8>>, RdbError> {
+    let corrupt = || RdbError::Corrupt("listpack");
+    let mut input = bytes.get(6..).ok_or_else(corrupt)?;
+    let mut entries = Vec::new();
+    loop {
+        let before = input.len();
+        let encoding = take(&mut input, 1).ok_or_else(corrupt)?[0];
+        let entry = match encoding {
+            END => return Ok(entries),
+            0x00..=0x7f => Some(encoding.to_string().into_bytes()),
+            0x80..=0xbf => take_string(&mut input, (encoding & 0x3f) as usize),
+            0xc0..=0xdf => take(&mut input, 1).map(|next| {
+                let value = ((encoding & 0x1f) as i64) << 8 | next[0] as i64;
+                // 13 bits, two's complement
+                let value = if value >= 1 << 12 {
+                    value - (1 << 13)
+                } else {
+                    value
+                };
+                value.to_string().into_bytes()
+            }),
+            0xe0..=0xef => take(&mut input, 1).and_then(|next| {
+                take_string(
+                    &mut input,
+                    ((encoding & 0x0f) as usize) << 8 | next[0] as usize,
+                )
+            }),
+            0xf0 => take(&mut input, 4).and_then(|len| {
+                let len = u32::from_le_bytes(len.try_into().unwrap_or_default());
+                take_string(&mut input, len as usize)
+            }),
+            0xf1 => take_int(&mut input, 2),
+            0xf2 => take_int(&mut input, 3),
+            0xf3 => take_int(&mut input, 4),
+            0xf4 => take_int(&mut input, 8),
+            _ => None,
+        };
+        entries.push(entry.ok_or_else(corrupt)?);
+        let backlen = backlen_size(before - input.len());
+        take(&mut input, backlen).ok_or_else(corrupt)?;
+    }
+}
+
+// sorted integers of 2, 4 or 8 bytes, after the width and the count
+pub(super) fn intset(bytes: &[u8]) -> Result<Vec<Vec<u8>>, RdbError> {
+    let corrupt = || RdbError::Corrupt("intset");
+    let mut input = bytes;
+    let width = take(&mut input, 4).ok_or_else(corrupt)?;
+    let width = u32::from_le_bytes(width.try_into().unwrap_or_default()) as usize;
+    let len = take(&mut input, 4).ok_or_else(corrupt)?;
+    let len = u32::from_le_bytes(len.try_into().unwrap_or_default()) as usize;
+    if !matches!(width, 2 | 4 | 8) || input.len() != width * len {
+        return Err(corrupt());
+    }
+    input
+        .chunks(width)
+        .map(|mut chunk| take_int(&mut chunk, width).ok_or_else(corrupt))
+        .collect()
+}
+
+// hashes of redis before 2.6: a count, then each field and value after its length, the value
+// also after a number of unused bytes that follow it
+pub(super) fn zipmap(bytes: &[u8]) -> Result<Vec<Vec<u8>>, RdbError> {
+    let corrupt = || RdbError::Corrupt("zipmap");
+    let mut input = bytes.get(1..).ok_or_else(corrupt)?;
+    let mut entries = Vec::new();
+    loop {
+        let Some(len) = zipmap_len(&mut input).ok_or_else(corrupt)? else {
+            return Ok(entries);
+        };
+        entries.push(take_string(&mut input, len).ok_or_else(corrupt)?);
+        let len = zipmap_len(&mut input).flatten().ok_or_else(corrupt)?;
+        let free = take(&mut input, 1).ok_or_else(corrupt)?[0];
+        entries.push(take_string(&mut input, len).ok_or_else(corrupt)?);
+        take(&mut input, free as usize).ok_or_else(corrupt)?;
+    }
+}
+
+// `Some(None)` at the end of the zipmap
+fn zipmap_len(input: &mut &[u8]) -> Option<Option<usize>> {
+    match take(input, 1)?[0] {
+        END => Some(None),
+        0xfe => {
+            let len = take(input, 4)?;
+            Some(Some(u32::from_le_bytes(len.try_into().ok()?) as usize))
+        }
+        len => Some(Some(len as usize)),
+    }
+}
+
+// how many bytes the backwards length of an entry of `len` bytes takes, 7 bits each
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2_097_150 => 3,
+        2_097_151..=268_435_454 => 4,
+        _ => 5,
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Some(bytes)
+}
+
+fn take_string(input: &mut &[u8], len: usize) -> Option<Vec<u8>> {
+    take(input, len).map(<[u8]>::to_vec)
+}
+
+// a little endian two's complement integer of `width` bytes, as its decimal text
+fn take_int(input: &mut &[u8], width: usize) -> Option<Vec<u8>> {
+    let bytes = take(input, width)?;
+    let mut buf = [0; 8];
+    buf[..width].copy_from_slice(bytes);
+    let shift = 64 - 8 * width as u32;
+    let value = (i64::from_le_bytes(buf) << shift) >> shift;
+    Some(value.to_string().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(entries: &[&str]) -> Vec<Vec<u8>> {
+        entries
+            .iter()
+            .map(|entry| entry.as_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_ziplist() {
+        // "a", "hello", 7, -2, 1000 and -8388608 in each of the encodings
+        let mut bytes = vec![0; 10];
+        bytes.extend_from_slice(b"\x00\x01a");
+        bytes.extend_from_slice(b"\x03\x05hello");
+        bytes.extend_from_slice(b"\x07\xf8");
+        bytes.extend_from_slice(b"\x02\xfe\xfe");
+        bytes.extend_from_slice(b"\x03\xc0\xe8\x03");
+        bytes.extend_from_slice(b"\x04\xf0\x00\x00\x80");
+        bytes.push(END);
+        assert_eq!(
+            ziplist(&bytes).unwrap(),
+            strings(&["a", "hello", "7", "-2", "1000", "-8388608"])
+        );
+        assert!(ziplist(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_listpack() {
+        // "a", 5, -100, 1000 and a 70 byte string, each followed by its backwards length
+        let mut bytes = vec![0; 6];
+        bytes.extend_from_slice(b"\x81a\x02");
+        bytes.extend_from_slice(b"\x05\x01");
+        bytes.extend_from_slice(b"\xdf\x9c\x02");
+        bytes.extend_from_slice(b"\xc3\xe8\x02");
+        bytes.extend_from_slice(b"\xe0\x46");
+        bytes.extend_from_slice(&[b'x'; 70]);
+        bytes.push(72);
+        bytes.extend_from_slice(b"\xf3\x00\x00\x00\x80\x05");
+        bytes.push(END);
+        let long = "x".repeat(70);
+        assert_eq!(
+            listpack(&bytes).unwrap(),
+            strings(&["a", "5", "-100", "1000", &long, "-2147483648"])
+        );
+        assert!(listpack(&bytes[..9]).is_err());
+    }
+
+    #[test]
+    fn test_intset() {
+        let bytes = b"\x02\x00\x00\x00\x03\x00\x00\x00\xff\xff\x01\x00\x00\x01";
+        assert_eq!(intset(bytes).unwrap(), strings(&["-1", "1", "256"]));
+        assert!(intset(&bytes[..12]).is_err());
+        assert!(intset(b"\x03\x00\x00\x00\x00\x00\x00\x00").is_err());
+    }
+
+    #[test]
+    fn test_zipmap() {
+        let bytes = b"\x02\x01a\x03\x01one\x00\x01b\x01\x00x\xff";
+        assert_eq!(zipmap(bytes).unwrap(), strings(&["a", "one", "b", "x"]));
+        assert!(zipmap(&bytes[..6]).is_err());
+    }
+
+    #[test]
+    fn test_backlen_size() {
+        assert_eq!(backlen_size(1), 1);
+        assert_eq!(backlen_size(127), 1);
+        assert_eq!(backlen_size(128), 2);
+        assert_eq!(backlen_size(16383), 3);
+    }
+}
@@ -2,7 +2,6 @@ use std::io::Cursor;
 use std::ops::Deref;
 
 use anyhow::Result;
-use bytes::Buf;
 
 use super::Frame;
 use super::{get_int, get_u8, RespDecode, RespEncode, RespError};
@@ -38,10 +37,6 @@ impl RespDecode for Array {
             let mut inner = Vec::with_capacity(len);
 
             for _ in 0..len {
-                if !buf.has_remaining() {
-                    break;
-                }
-
                 let frame = Frame::decode(buf)?;
                 inner.push(frame);
             }
@@ -94,6 +89,15 @@ mod tests {
         assert_eq!(frame, Array::new(vec![b"foo".into(), b"bar".into(),]));
     }
 
+    #[test]
+    fn test_array_decode_incomplete() {
+        let mut buf = Cursor::new(&b"*2\r\n$3\r\nfoo\r\n"[..]);
+        assert!(matches!(
+            Array::decode(&mut buf),
+            Err(RespError::Incomplete)
+        ));
+    }
+
     #[test]
     fn test_array_encode() {
         let frame = Array::new(vec![b"foo".into(), b"bar".into()]);
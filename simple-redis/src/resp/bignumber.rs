@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
 
@@ -46,6 +47,14 @@ impl RespEncode for BigNumber {
     }
 }
 
+impl Deref for BigNumber {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
 
@@ -52,6 +53,14 @@ impl RespEncode for Boolean {
     }
 }
 
+impl Deref for Boolean {
+    type Target = bool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
 
@@ -6,7 +7,7 @@ use super::{get_decimal, get_line, get_u8, RespDecode, RespEncode, RespError};
 
 #[derive(Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BulkError {
-    inner: Vec<u8>,
+    pub(crate) inner: Vec<u8>,
 }
 
 impl BulkError {
@@ -53,6 +54,14 @@ impl RespEncode for BulkError {
     }
 }
 
+impl Deref for BulkError {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,405 @@
+use std::fmt::Display;
+
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::frame::Frame;
+use super::RespError;
+
+/// Deserializes `T` from a frame, the reverse of `to_frame` and `to_frame_resp2`: maps and the
+/// flattened arrays of RESP2 both fill structs and maps, numbers are also read from the text
+/// of strings and an error reply fails with its message.
+pub fn from_frame<T: DeserializeOwned>(frame: Frame) -> Result<T, RespError> {
+    T::deserialize(FrameDeserializer(frame))
+}
+
+impl de::Error for RespError {
+    fn custom<T: Display>(msg: T) -> Self {
+        RespError::Serde(msg.to_string())
+    }
+}
+
+struct FrameDeserializer(Frame);
+
+impl FrameDeserializer {
+    // the text of a string reply, numbers in RESP2 replies often come as one
+    fn text(&self) -> Option<&str> {
+        match &self.0 {
+            Frame::SimpleString(s) => Some(s.as_str()),
+            Frame::BulkString(s) => std::str::from_utf8(s).ok(),
+            Frame::BigNumber(n) => Some(n.as_str()),
+            _ => None,
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&self) -> Option<T> {
+        self.text().and_then(|text| text.parse().ok())
+    }
+
+    // the key and value pairs of a map, or of an array of alternating keys and values
+    fn into_pairs(self) -> Result<Vec<(Frame, Frame)>, RespError> {
+        match self.0 {
+            Frame::Map(map) => Ok(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()),
+            Frame::Array(array) if array.len() % 2 == 0 => {
+                let mut items = array.inner.into_iter();
+                let mut pairs = Vec::new();
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    pairs.push((key, value));
+                }
+                Ok(pairs)
+            }
+            frame => Err(unexpected(&frame, "a map")),
+        }
+    }
+}
+
+fn unexpected(frame: &Frame, expected: &str) -> RespError {
+    match frame {
+        Frame::SimpleError(error) => RespError::Serde(error.to_string()),
+        Frame::BulkError(error) => RespError::Serde(String::from_utf8_lossy(error).into_owned()),
+        frame => RespError::Serde(format!("expected {}, found {:?}", expected, frame)),
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+                match self.parse() {
+                    Some(n) => visitor.$visit(n),
+                    None => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for FrameDeserializer {
+    type Error = RespError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match self.0 {
+            Frame::SimpleString(s) => visitor.visit_string(s.inner),
+            Frame::BulkString(s) => match String::from_utf8(s.inner) {
+                Ok(s) => visitor.visit_string(s),
+                Err(e) => visitor.visit_byte_buf(e.into_bytes()),
+            },
+            Frame::Integer(i) => visitor.visit_i64(i.inner),
+            Frame::Double(d) => visitor.visit_f64(*d),
+            Frame::Boolean(b) => visitor.visit_bool(*b),
+            Frame::BigNumber(n) => visitor.visit_string(n.to_string()),
+            Frame::Null(_) => visitor.visit_unit(),
+            Frame::Array(array) => visitor.visit_seq(Seq(array.inner.into_iter())),
+            Frame::Push(push) => visitor.visit_seq(Seq(push.inner.into_iter())),
+            Frame::Set(set) => {
+                let items: Vec<Frame> = set.iter().cloned().collect();
+                visitor.visit_seq(Seq(items.into_iter()))
+            }
+            Frame::Map(_) => {
+                let pairs = self.into_pairs()?;
+                visitor.visit_map(Pairs(pairs.into_iter(), None))
+            }
+            frame => Err(unexpected(&frame, "a value")),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match &self.0 {
+            Frame::Integer(i) => visitor.visit_f64(i.inner as f64),
+            _ => match self.parse() {
+                Some(f) => visitor.visit_f64(f),
+                None => self.deserialize_any(visitor),
+            },
+        }
+    }
+
+    // RESP2 replies carry booleans as 0 or 1
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match &self.0 {
+            Frame::Integer(i) if matches!(i.inner, 0 | 1) => visitor.visit_bool(i.inner == 1),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match self.0 {
+            Frame::BulkString(s) => visitor.visit_byte_buf(s.inner),
+            Frame::SimpleString(s) => visitor.visit_byte_buf(s.inner.into_bytes()),
+            frame => FrameDeserializer(frame).deserialize_any(visitor),
+        }
+    }
+
+    // so a Vec<u8> can be read from a bulk string
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match self.0 {
+            Frame::BulkString(s) => {
+                let mut bytes: SeqDeserializer<_, RespError> =
+                    SeqDeserializer::new(s.inner.into_iter());
+                let value = visitor.visit_seq(&mut bytes)?;
+                bytes.end()?;
+                Ok(value)
+            }
+            frame => FrameDeserializer(frame).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        match self.0 {
+            Frame::Null(_) => visitor.visit_none(),
+            frame => visitor.visit_some(FrameDeserializer(frame)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RespError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        let pairs = self.into_pairs()?;
+        visitor.visit_map(Pairs(pairs.into_iter(), None))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RespError> {
+        self.deserialize_map(visitor)
+    }
+
+    // a unit variant is its name, any other a map of the name to its value
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RespError> {
+        if self.text().is_some() {
+            return visitor.visit_enum(Variant(self.0, None));
+        }
+        let mut pairs = self.into_pairs()?;
+        match pairs.pop() {
+            Some((variant, value)) if pairs.is_empty() => {
+                visitor.visit_enum(Variant(variant, Some(value)))
+            }
+            _ => Err(RespError::Serde(
+                "expected a map with a single variant".to_string(),
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RespError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string unit unit_struct tuple tuple_struct identifier
+    }
+}
+
+struct Seq(std::vec::IntoIter<Frame>);
+
+impl<'de> SeqAccess<'de> for Seq {
+    type Error = RespError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, RespError> {
+        self.0
+            .next()
+            .map(|frame| seed.deserialize(FrameDeserializer(frame)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+// the value of the pair whose key was just read waits in the second field
+struct Pairs(std::vec::IntoIter<(Frame, Frame)>, Option<Frame>);
+
+impl<'de> MapAccess<'de> for Pairs {
+    type Error = RespError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, RespError> {
+        let Some((key, value)) = self.0.next() else {
+            return Ok(None);
+        };
+        self.1 = Some(value);
+        seed.deserialize(FrameDeserializer(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, RespError> {
+        let value = self
+            .1
+            .take()
+            .ok_or_else(|| RespError::Serde("map value without a key".to_string()))?;
+        seed.deserialize(FrameDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Variant(Frame, Option<Frame>);
+
+impl<'de> EnumAccess<'de> for Variant {
+    type Error = RespError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), RespError> {
+        let name = FrameDeserializer(self.0.clone())
+            .text()
+            .map(str::to_string)
+            .ok_or_else(|| unexpected(&self.0, "a variant name"))?;
+        let deserializer: StrDeserializer<'_, RespError> = name.as_str().into_deserializer();
+        Ok((seed.deserialize(deserializer)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Variant {
+    type Error = RespError;
+
+    fn unit_variant(self) -> Result<(), RespError> {
+        match self.1 {
+            None | Some(Frame::Null(_)) => Ok(()),
+            Some(frame) => Err(unexpected(&frame, "a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, RespError> {
+        let value = self
+            .1
+            .ok_or_else(|| RespError::Serde("expected a variant with a value".to_string()))?;
+        seed.deserialize(FrameDeserializer(value))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RespError> {
+        match self.1 {
+            Some(value) => de::Deserializer::deserialize_seq(FrameDeserializer(value), visitor),
+            None => Err(RespError::Serde("expected a tuple variant".to_string())),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RespError> {
+        match self.1 {
+            Some(value) => de::Deserializer::deserialize_map(FrameDeserializer(value), visitor),
+            None => Err(RespError::Serde("expected a struct variant".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::resp::{to_frame, to_frame_resp2, Null, SimpleError};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Info {
+        name: String,
+        port: u16,
+        ratio: f64,
+        master: bool,
+        replicas: Vec<String>,
+        owner: Option<String>,
+        role: Role,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Role {
+        Master,
+        Replica(u64),
+        Sentinel { quorum: u8 },
+    }
+
+    #[test]
+    fn test_from_frame_round_trip() {
+        for role in [
+            Role::Master,
+            Role::Replica(42),
+            Role::Sentinel { quorum: 2 },
+        ] {
+            let info = Info {
+                name: "cache".to_string(),
+                port: 6379,
+                ratio: 0.25,
+                master: false,
+                replicas: vec!["a".to_string(), "b".to_string()],
+                owner: Some("ops".to_string()),
+                role,
+            };
+            let frame = to_frame(&info).unwrap();
+            assert_eq!(from_frame::<Info>(frame).unwrap(), info);
+            let frame = to_frame_resp2(&info).unwrap();
+            assert_eq!(from_frame::<Info>(frame).unwrap(), info);
+        }
+    }
+
+    #[test]
+    fn test_from_frame() {
+        assert_eq!(from_frame::<Option<i64>>(Frame::Null(Null)).unwrap(), None);
+        assert_eq!(from_frame::<Option<i64>>(5.into()).unwrap(), Some(5));
+        assert_eq!(from_frame::<u32>(b"12".into()).unwrap(), 12);
+        assert_eq!(from_frame::<f64>(3.into()).unwrap(), 3.0);
+        assert!(from_frame::<bool>(1.into()).unwrap());
+        assert_eq!(
+            from_frame::<Vec<u8>>(b"\xff\x00".into()).unwrap(),
+            vec![0xff, 0]
+        );
+
+        let pairs: Vec<Frame> = vec![b"a".into(), 1.into(), b"b".into(), 2.into()];
+        let map = from_frame::<HashMap<String, i64>>(pairs.into()).unwrap();
+        assert_eq!(
+            map,
+            HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+
+        let error = from_frame::<String>(Frame::SimpleError(SimpleError::new("ERR no")));
+        assert_eq!(error.unwrap_err().to_string(), "Serde error: ERR no");
+        assert!(from_frame::<i64>(b"x".into()).is_err());
+        assert!(from_frame::<Info>(vec![b"name".into()].into()).is_err());
+    }
+}
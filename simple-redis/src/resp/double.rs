@@ -1,4 +1,4 @@
-use std::{hash::Hash, io::Cursor};
+use std::{hash::Hash, io::Cursor, ops::Deref};
 
 use anyhow::Result;
 
@@ -67,6 +67,14 @@ impl RespEncode for Double {
     }
 }
 
+impl Deref for Double {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
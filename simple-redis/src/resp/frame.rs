@@ -1,6 +1,6 @@
 use enum_dispatch::enum_dispatch;
 use std::io::Cursor;
-use crate::resp::{peek_u8, Array, BulkString, Map, Null, RespDecode, RespError, Set, SimpleError, SimpleString};
+use crate::resp::{peek_u8, Array, BulkString, Map, Null, Push, RespDecode, RespError, Set, SimpleError, SimpleString};
 use crate::resp::bignumber::BigNumber;
 use crate::resp::boolean::Boolean;
 use crate::resp::bulk_error::BulkError;
@@ -22,6 +22,7 @@ pub enum Frame {
     BulkError(BulkError),
     Map(Map),
     Set(Set),
+    Push(Push),
 }
 
 impl RespDecode for Frame {
@@ -43,6 +44,7 @@ impl RespDecode for Frame {
             b'!' => BulkError::decode(buf).map(Into::into),
             b'%' => Map::decode(buf).map(Into::into),
             b'~' => Set::decode(buf).map(Into::into),
+            b'>' => Push::decode(buf).map(Into::into),
             _ => Err(RespError::InvalidType(format!(
                 "Invalid prefix for Frame: {:?}",
                 buf.get_ref()
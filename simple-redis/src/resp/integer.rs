@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
 
@@ -42,6 +43,14 @@ impl RespEncode for Integer {
     }
 }
 
+impl Deref for Integer {
+    type Target = i64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,6 @@
-use std::{collections::BTreeMap, io::Cursor};
+use std::{collections::BTreeMap, io::Cursor, ops::Deref};
 
 use anyhow::Result;
-use bytes::Buf;
 
 use super::{get_decimal, get_u8, Frame, RespDecode, RespEncode, RespError};
 
@@ -31,10 +30,6 @@ impl RespDecode for Map {
         let mut inner = BTreeMap::new();
 
         for _ in 0..len {
-            if !buf.has_remaining() {
-                break;
-            }
-
             let key = Frame::decode(buf)?;
             let value = Frame::decode(buf)?;
             inner.insert(key, value);
@@ -60,6 +55,14 @@ impl RespEncode for Map {
     }
 }
 
+impl Deref for Map {
+    type Target = BTreeMap<Frame, Frame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,11 +3,14 @@ mod bignumber;
 mod boolean;
 mod bulk_error;
 mod bulk_string;
+mod de;
 mod double;
 pub mod frame;
 mod integer;
 mod map;
 pub mod null;
+mod push;
+mod ser;
 mod set;
 mod simple_error;
 mod simple_string;
@@ -18,19 +21,22 @@ use bytes::Buf;
 use enum_dispatch::enum_dispatch;
 use thiserror::Error;
 
-use array::Array;
+pub use array::Array;
 pub use bignumber::BigNumber;
 pub use boolean::Boolean;
 pub use bulk_error::BulkError;
-use bulk_string::BulkString;
+pub use bulk_string::BulkString;
+pub use de::from_frame;
 pub use double::Double;
 use frame::Frame;
 pub use integer::Integer;
-use map::Map;
-use null::Null;
-use set::Set;
-use simple_error::SimpleError;
-use simple_string::SimpleString;
+pub use map::Map;
+pub use null::Null;
+pub use push::Push;
+pub use ser::{to_frame, to_frame_resp2};
+pub use set::Set;
+pub use simple_error::SimpleError;
+pub use simple_string::SimpleString;
 
 #[derive(Debug, Error)]
 pub enum RespError {
@@ -51,6 +57,9 @@ pub enum RespError {
 
     #[error("ParseFloat error: {0}")]
     ParseFloatError(#[from] std::num::ParseFloatError),
+
+    #[error("Serde error: {0}")]
+    Serde(String),
 }
 
 pub trait RespDecode: Sized {
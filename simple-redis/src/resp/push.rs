@@ -0,0 +1,85 @@
+use std::io::Cursor;
+use std::ops::Deref;
+
+use anyhow::Result;
+
+use super::Frame;
+use super::{get_decimal, get_u8, RespDecode, RespEncode, RespError};
+
+// out of band data like invalidation messages, the first element names its kind
+#[derive(Debug, Hash, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Push {
+    pub(crate) inner: Vec<Frame>,
+}
+
+impl Push {
+    pub fn new(inner: Vec<Frame>) -> Self {
+        Self { inner }
+    }
+}
+
+impl RespDecode for Push {
+    const PREFIX: u8 = b'>';
+
+    fn decode(buf: &mut Cursor<&[u8]>) -> Result<Self, RespError> {
+        if get_u8(buf)? != Self::PREFIX {
+            return Err(RespError::InvalidType(format!(
+                "Invalid prefix for Push: {:?}",
+                buf.get_ref()
+            )));
+        }
+
+        let len = get_decimal(buf)? as usize;
+        let mut inner = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            inner.push(Frame::decode(buf)?);
+        }
+
+        Ok(Self::new(inner))
+    }
+}
+
+impl RespEncode for Push {
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.push(Self::PREFIX);
+        buf.extend(self.inner.len().to_string().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+
+        for frame in &self.inner {
+            buf.extend(frame.encode());
+        }
+
+        buf
+    }
+}
+
+impl Deref for Push {
+    type Target = Vec<Frame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_decode() {
+        let mut buf = Cursor::new(&b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nfoo\r\n"[..]);
+        let frame = Push::decode(&mut buf).unwrap();
+        assert_eq!(
+            frame,
+            Push::new(vec![b"invalidate".into(), vec![b"foo".into()].into()])
+        );
+    }
+
+    #[test]
+    fn test_push_encode() {
+        let frame = Push::new(vec![b"invalidate".into(), Frame::Null(super::super::Null)]);
+        assert_eq!(frame.encode(), b">2\r\n$10\r\ninvalidate\r\n_\r\n");
+    }
+}
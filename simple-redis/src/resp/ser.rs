@@ -0,0 +1,498 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::ser::{self, Serialize};
+
+use super::frame::Frame;
+use super::{BigNumber, BulkString, Map, Null, RespError};
+
+/// Serializes `value` into a RESP3 frame: structs and maps become maps, sequences arrays,
+/// `None` and `()` null, floats doubles, and strings and bytes bulk strings.
+pub fn to_frame<T: Serialize + ?Sized>(value: &T) -> Result<Frame, RespError> {
+    value.serialize(FrameSerializer { resp2: false })
+}
+
+/// Like `to_frame` for RESP2 connections, which have no maps, booleans or doubles: maps are
+/// flattened into arrays of keys and values, booleans become 0 or 1 and floats bulk strings.
+pub fn to_frame_resp2<T: Serialize + ?Sized>(value: &T) -> Result<Frame, RespError> {
+    value.serialize(FrameSerializer { resp2: true })
+}
+
+impl ser::Error for RespError {
+    fn custom<T: Display>(msg: T) -> Self {
+        RespError::Serde(msg.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FrameSerializer {
+    resp2: bool,
+}
+
+impl FrameSerializer {
+    fn map(&self, entries: Vec<(Frame, Frame)>) -> Frame {
+        if self.resp2 {
+            let array: Vec<Frame> = entries
+                .into_iter()
+                .flat_map(|(key, value)| [key, value])
+                .collect();
+            array.into()
+        } else {
+            Frame::Map(Map::new(entries.into_iter().collect::<BTreeMap<_, _>>()))
+        }
+    }
+
+    // enums are tagged externally like in serde_json, a map of the variant name to its value
+    fn variant(&self, variant: &'static str, value: Frame) -> Frame {
+        self.map(vec![(variant.as_bytes().into(), value)])
+    }
+}
+
+impl ser::Serializer for FrameSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Frame, RespError> {
+        if self.resp2 {
+            Ok((v as i64).into())
+        } else {
+            Ok(v.into())
+        }
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Frame, RespError> {
+        Ok(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Frame, RespError> {
+        self.serialize_i64(v as i64)
+    }
+
+    // past the range of an integer reply the number is sent as a big number, or as text to a
+    // RESP2 connection
+    fn serialize_u64(self, v: u64) -> Result<Frame, RespError> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) if self.resp2 => Ok(v.to_string().as_bytes().into()),
+            Err(_) => Ok(Frame::BigNumber(BigNumber::new(v.to_string()))),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Frame, RespError> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Frame, RespError> {
+        if self.resp2 {
+            Ok(v.to_string().as_bytes().into())
+        } else {
+            Ok(v.into())
+        }
+    }
+
+    fn serialize_char(self, v: char) -> Result<Frame, RespError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Frame, RespError> {
+        Ok(v.as_bytes().into())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Frame, RespError> {
+        Ok(Frame::BulkString(BulkString::new(v)))
+    }
+
+    fn serialize_none(self) -> Result<Frame, RespError> {
+        Ok(Frame::Null(Null))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Frame, RespError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Frame, RespError> {
+        Ok(Frame::Null(Null))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Frame, RespError> {
+        self.serialize_unit()
+    }
+
+    // the variant name alone, like the status replies of redis
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Frame, RespError> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Frame, RespError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Frame, RespError> {
+        let value = value.serialize(self)?;
+        Ok(self.variant(variant, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, RespError> {
+        Ok(SeqSerializer {
+            serializer: self,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, RespError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, RespError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, RespError> {
+        let mut seq = self.serialize_seq(Some(len))?;
+        seq.variant = Some(variant);
+        Ok(seq)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, RespError> {
+        Ok(MapSerializer {
+            serializer: self,
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer, RespError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer, RespError> {
+        let mut map = self.serialize_map(Some(len))?;
+        map.variant = Some(variant);
+        Ok(map)
+    }
+}
+
+struct SeqSerializer {
+    serializer: FrameSerializer,
+    items: Vec<Frame>,
+    variant: Option<&'static str>,
+}
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        self.items.push(value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Frame, RespError> {
+        let array = self.items.into();
+        match self.variant {
+            Some(variant) => Ok(self.serializer.variant(variant, array)),
+            None => Ok(array),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+struct MapSerializer {
+    serializer: FrameSerializer,
+    entries: Vec<(Frame, Frame)>,
+    // a key waiting for its value
+    key: Option<Frame>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), RespError> {
+        let value = value.serialize(self.serializer)?;
+        self.entries.push((key.as_bytes().into(), value));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Frame, RespError> {
+        let map = self.serializer.map(self.entries);
+        match self.variant {
+            Some(variant) => Ok(self.serializer.variant(variant, map)),
+            None => Ok(map),
+        }
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), RespError> {
+        self.key = Some(key.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), RespError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| RespError::Serde("map value without a key".to_string()))?;
+        self.entries.push((key, value.serialize(self.serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), RespError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = Frame;
+    type Error = RespError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), RespError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Frame, RespError> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Info {
+        name: &'static str,
+        port: u16,
+        ratio: f64,
+        master: bool,
+        replicas: Vec<String>,
+        owner: Option<String>,
+        #[serde(with = "serde_bytes_like")]
+        key: Vec<u8>,
+    }
+
+    // serde serializes Vec<u8> as a sequence unless told it is bytes
+    mod serde_bytes_like {
+        pub fn serialize<S: serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+    }
+
+    #[derive(Serialize)]
+    enum Role {
+        Master,
+        Replica { offset: u64 },
+    }
+
+    fn info() -> Info {
+        Info {
+            name: "cache",
+            port: 6379,
+            ratio: 0.5,
+            master: true,
+            replicas: vec!["a".to_string()],
+            owner: None,
+            key: vec![0xff, 0],
+        }
+    }
+
+    #[test]
+    fn test_to_frame() {
+        let Frame::Map(map) = to_frame(&info()).unwrap() else {
+            panic!("Expected Map");
+        };
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.get(&b"name".into()), Some(&b"cache".into()));
+        assert_eq!(map.get(&b"port".into()), Some(&6379.into()));
+        assert_eq!(map.get(&b"ratio".into()), Some(&0.5.into()));
+        assert_eq!(map.get(&b"master".into()), Some(&true.into()));
+        assert_eq!(
+            map.get(&b"replicas".into()),
+            Some(&vec![b"a".into()].into())
+        );
+        assert_eq!(map.get(&b"owner".into()), Some(&Frame::Null(Null)));
+        assert_eq!(map.get(&b"key".into()), Some(&b"\xff\x00".into()));
+
+        assert_eq!(to_frame(&Role::Master).unwrap(), "Master".into());
+        let Frame::Map(map) = to_frame(&Role::Replica { offset: 7 }).unwrap() else {
+            panic!("Expected Map");
+        };
+        let Some(Frame::Map(replica)) = map.get(&b"Replica".into()) else {
+            panic!("Expected Map, got {:?}", map);
+        };
+        assert_eq!(replica.get(&b"offset".into()), Some(&7.into()));
+
+        assert_eq!(
+            to_frame(&u64::MAX).unwrap(),
+            Frame::BigNumber(BigNumber::new(u64::MAX.to_string()))
+        );
+        assert_eq!(
+            to_frame(&(1, "a")).unwrap(),
+            vec![1.into(), b"a".into()].into()
+        );
+    }
+
+    #[test]
+    fn test_to_frame_resp2() {
+        let frame = to_frame_resp2(&info()).unwrap();
+        let expected: Vec<Frame> = vec![
+            b"name".into(),
+            b"cache".into(),
+            b"port".into(),
+            6379.into(),
+            b"ratio".into(),
+            b"0.5".into(),
+            b"master".into(),
+            1.into(),
+            b"replicas".into(),
+            vec![b"a".into()].into(),
+            b"owner".into(),
+            Frame::Null(Null),
+            b"key".into(),
+            b"\xff\x00".into(),
+        ];
+        // fields keep their declaration order once flattened
+        assert_eq!(frame, expected.into());
+        assert_eq!(
+            to_frame_resp2(&u64::MAX).unwrap(),
+            u64::MAX.to_string().as_bytes().into()
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::BTreeSet;
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
-use bytes::Buf;
 
 use super::Frame;
 use super::{get_decimal, get_u8, RespDecode, RespEncode, RespError};
@@ -33,10 +33,6 @@ impl RespDecode for Set {
         let mut inner = BTreeSet::new();
 
         for _ in 0..len {
-            if !buf.has_remaining() {
-                break;
-            }
-
             let frame = Frame::decode(buf)?;
             inner.insert(frame);
         }
@@ -60,6 +56,14 @@ impl RespEncode for Set {
     }
 }
 
+impl Deref for Set {
+    type Target = BTreeSet<Frame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::ops::Deref;
 
 use anyhow::Result;
 
@@ -6,7 +7,7 @@ use super::{get_line, get_u8, RespDecode, RespEncode, RespError};
 
 #[derive(Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SimpleError {
-    inner: String,
+    pub(crate) inner: String,
 }
 
 impl SimpleError {
@@ -44,6 +45,14 @@ impl RespEncode for SimpleError {
     }
 }
 
+impl Deref for SimpleError {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
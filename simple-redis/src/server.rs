@@ -0,0 +1,1087 @@
+use std::fs::Permissions;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::sync::watch;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn, Instrument, Span};
+
+use crate::backend::{Backend, ConnectionKind, ShutdownMode};
+use crate::config::Config;
+use crate::engine::{Engine, Router, ThreadPerCore};
+use crate::network::{
+    serve_metrics, set_keepalive, shutdown_aware_stream_handle, Connection, Tls, TlsConfig,
+};
+
+// how often the expired hash fields nobody reads are looked for
+const ACTIVE_EXPIRE_PERIOD: Duration = Duration::from_millis(100);
+
+pub type SaveHook = Arc<dyn Fn(&Backend) -> Result<()> + Send + Sync>;
+
+pub struct Server;
+
+impl Server {
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::default()
+    }
+}
+
+pub struct ServerBuilder {
+    addr: Option<SocketAddr>,
+    backend: Option<Backend>,
+    config: Option<Config>,
+    span: Span,
+    save: Option<SaveHook>,
+    engine: Engine,
+    tls: Option<(SocketAddr, TlsConfig)>,
+    unix: Option<(PathBuf, Option<u32>)>,
+    metrics: Option<SocketAddr>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self {
+            addr: Some(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 6379))),
+            backend: None,
+            config: None,
+            span: Span::none(),
+            save: None,
+            engine: Engine::default(),
+            tls: None,
+            unix: None,
+            metrics: None,
+        }
+    }
+}
+
+impl ServerBuilder {
+    /// Port 0 binds an ephemeral port, see `ServerHandle::local_addr`.
+    pub fn bind(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.addr = Some(addr.into());
+        self
+    }
+
+    /// Only serves the TLS listener and the unix socket, at least one of them has to be set.
+    pub fn without_tcp(mut self) -> Self {
+        self.addr = None;
+        self
+    }
+
+    /// Also accepts connections on a unix socket at `path`, a stale socket file is replaced.
+    /// `perm` are the permission bits of the socket file, like 0o700.
+    pub fn unix_socket(mut self, path: impl Into<PathBuf>, perm: Option<u32>) -> Self {
+        self.unix = Some((path.into(), perm));
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// With `Engine::ThreadPerCore` the keys of the backend move to the cores while the server
+    /// runs and come back once it stopped.
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Also accepts TLS connections on `addr`, next to the plain listener.
+    pub fn tls(mut self, addr: impl Into<SocketAddr>, config: TlsConfig) -> Self {
+        self.tls = Some((addr.into(), config));
+        self
+    }
+
+    /// Serves `GET /metrics` over http on `addr` for prometheus to scrape.
+    pub fn metrics(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.metrics = Some(addr.into());
+        self
+    }
+
+    /// Called on shutdown unless NOSAVE was requested.
+    pub fn on_save(
+        mut self,
+        save: impl Fn(&Backend) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.save = Some(Arc::new(save));
+        self
+    }
+
+    pub async fn start(self) -> Result<ServerHandle> {
+        let backend = match (self.backend, &self.config) {
+            (Some(backend), Some(config)) => {
+                backend.configure(config);
+                backend
+            }
+            (Some(backend), None) => backend,
+            (None, Some(config)) => Backend::with_config(config),
+            (None, None) => Backend::new(),
+        };
+
+        if self.addr.is_none() && self.tls.is_none() && self.unix.is_none() {
+            anyhow::bail!("the server has no listener");
+        }
+
+        let listener = match self.addr {
+            Some(addr) => Some(TcpListener::bind(addr).await?),
+            None => None,
+        };
+        let local_addr = match &listener {
+            Some(listener) => Some(listener.local_addr()?),
+            None => None,
+        };
+
+        // certificates that can not be loaded fail the start instead of every handshake
+        let tls = match self.tls {
+            Some((addr, config)) => Some(TlsListener {
+                tls: Tls::new(config)?,
+                listener: TcpListener::bind(addr).await?,
+            }),
+            None => None,
+        };
+        let tls_local_addr = match &tls {
+            Some(tls) => Some(tls.listener.local_addr()?),
+            None => None,
+        };
+
+        let unix = match self.unix {
+            Some((path, perm)) => Some(UnixSocket::bind(path, perm)?),
+            None => None,
+        };
+        let unix_socket = unix.as_ref().map(|unix| unix.path.clone());
+
+        let metrics = match self.metrics {
+            Some(addr) => Some(TcpListener::bind(addr).await?),
+            None => None,
+        };
+        let metrics_local_addr = match &metrics {
+            Some(metrics) => Some(metrics.local_addr()?),
+            None => None,
+        };
+
+        let shutdown = backend.shutdown().subscribe();
+        let handle_tls = tls.as_ref().map(|tls| tls.tls.clone());
+        let listeners = Listeners {
+            tcp: listener,
+            tls,
+            unix,
+            metrics,
+        };
+        let task = tokio::spawn(
+            serve(
+                listeners,
+                backend.clone(),
+                shutdown,
+                self.save,
+                self.engine,
+                self.span.clone(),
+            )
+            .instrument(self.span),
+        );
+
+        Ok(ServerHandle {
+            local_addr,
+            tls_local_addr,
+            unix_socket,
+            metrics_local_addr,
+            tls: handle_tls,
+            backend,
+            task,
+        })
+    }
+}
+
+pub struct ServerHandle {
+    local_addr: Option<SocketAddr>,
+    tls_local_addr: Option<SocketAddr>,
+    unix_socket: Option<PathBuf>,
+    metrics_local_addr: Option<SocketAddr>,
+    tls: Option<Tls>,
+    backend: Backend,
+    task: JoinHandle<Result<()>>,
+}
+
+impl ServerHandle {
+    /// Panics when the server was started `without_tcp`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr.expect("the server has no TCP listener")
+    }
+
+    pub fn tls_local_addr(&self) -> Option<SocketAddr> {
+        self.tls_local_addr
+    }
+
+    pub fn unix_socket(&self) -> Option<&Path> {
+        self.unix_socket.as_deref()
+    }
+
+    pub fn metrics_local_addr(&self) -> Option<SocketAddr> {
+        self.metrics_local_addr
+    }
+
+    pub fn backend(&self) -> &Backend {
+        &self.backend
+    }
+
+    /// `Tls::reload` reads the certificate files again, connections accepted afterwards use them.
+    pub fn tls(&self) -> Option<&Tls> {
+        self.tls.as_ref()
+    }
+
+    pub async fn shutdown(self) -> Result<()> {
+        self.shutdown_with(ShutdownMode::Default).await
+    }
+
+    pub async fn shutdown_with(self, mode: ShutdownMode) -> Result<()> {
+        self.backend.shutdown().trigger(mode);
+        self.wait().await
+    }
+
+    // resolves once the server stopped, e.g. after a SHUTDOWN command or a signal
+    pub async fn wait(self) -> Result<()> {
+        self.task.await?
+    }
+}
+
+struct TlsListener {
+    listener: TcpListener,
+    tls: Tls,
+}
+
+struct UnixSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl UnixSocket {
+    fn bind(path: PathBuf, perm: Option<u32>) -> Result<Self> {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        if let Some(perm) = perm {
+            std::fs::set_permissions(&path, Permissions::from_mode(perm))?;
+        }
+
+        Ok(Self { listener, path })
+    }
+}
+
+// the socket file goes away with the listener, so a restart finds the path free
+impl Drop for UnixSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// an accept without its listener never resolves, so the accept loop can always wait on all of them
+struct Listeners {
+    tcp: Option<TcpListener>,
+    tls: Option<TlsListener>,
+    unix: Option<UnixSocket>,
+    metrics: Option<TcpListener>,
+}
+
+impl Listeners {
+    async fn accept_tcp(&self) -> std::io::Result<(TcpStream, SocketAddr)> {
+        match &self.tcp {
+            Some(listener) => listener.accept().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn accept_tls(&self) -> std::io::Result<(TcpStream, SocketAddr, Tls)> {
+        match &self.tls {
+            Some(tls) => {
+                let (stream, raddr) = tls.listener.accept().await?;
+                Ok((stream, raddr, tls.tls.clone()))
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn accept_unix(&self) -> std::io::Result<(UnixStream, &Path)> {
+        match &self.unix {
+            Some(unix) => Ok((unix.listener.accept().await?.0, &unix.path)),
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn accept_metrics(&self) -> std::io::Result<(TcpStream, SocketAddr)> {
+        match &self.metrics {
+            Some(listener) => listener.accept().await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+fn spawn_connection<S>(
+    connections: &mut JoinSet<()>,
+    stream: S,
+    connection: Connection,
+    backend: &Backend,
+    shutdown: &watch::Receiver<Option<ShutdownMode>>,
+    router: Option<Router>,
+    span: &Span,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let backend = backend.clone();
+    let shutdown = shutdown.clone();
+    connections.spawn(
+        async move {
+            if let Err(e) =
+                shutdown_aware_stream_handle(stream, connection, backend, shutdown, router).await
+            {
+                info!("Error: {:?}", e);
+            }
+        }
+        .instrument(span.clone()),
+    );
+}
+
+async fn serve(
+    listeners: Listeners,
+    backend: Backend,
+    mut shutdown: watch::Receiver<Option<ShutdownMode>>,
+    save: Option<SaveHook>,
+    engine: Engine,
+    span: Span,
+) -> Result<()> {
+    let mut connections = JoinSet::new();
+    let mut cores = match engine {
+        Engine::Shared => None,
+        Engine::ThreadPerCore(cores) => Some(ThreadPerCore::start(
+            &backend,
+            cores,
+            shutdown.clone(),
+            span.clone(),
+        )?),
+    };
+
+    if let Some(listener) = &listeners.tcp {
+        info!("Listening on {}", listener.local_addr()?);
+    }
+    if let Some(tls) = &listeners.tls {
+        info!("Listening for TLS on {}", tls.listener.local_addr()?);
+    }
+    if let Some(unix) = &listeners.unix {
+        info!("Listening on {}", unix.path.display());
+    }
+    if let Some(metrics) = &listeners.metrics {
+        info!(
+            "Serving metrics on http://{}/metrics",
+            metrics.local_addr()?
+        );
+    }
+
+    let mut active_expire = tokio::time::interval(ACTIVE_EXPIRE_PERIOD);
+    active_expire.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mode = loop {
+        // the partitions of the thread-per-core engine are reached through its router, only plain
+        // tcp connections are handed to the cores themselves
+        let router = cores.as_ref().map(ThreadPerCore::router);
+
+        tokio::select! {
+            accepted = listeners.accept_tcp() => match accepted {
+                Ok((stream, raddr)) => {
+                    info!("Accepted connection from {}", raddr);
+                    set_keepalive(&stream, &backend);
+                    if let Some(cores) = &mut cores {
+                        if let Err(e) = cores.accept(stream) {
+                            warn!("Failed to hand over connection: {}", e);
+                        }
+                        continue;
+                    }
+                    let connection = Connection::tcp(&stream, ConnectionKind::Tcp);
+                    spawn_connection(&mut connections, stream, connection, &backend, &shutdown, None, &span);
+                }
+                Err(e) => warn!("Failed to accept connection: {}", e),
+            },
+            accepted = listeners.accept_unix() => match accepted {
+                Ok((stream, path)) => {
+                    info!("Accepted connection on {}", path.display());
+                    let connection = Connection::unix(path);
+                    spawn_connection(&mut connections, stream, connection, &backend, &shutdown, router, &span);
+                }
+                Err(e) => warn!("Failed to accept unix socket connection: {}", e),
+            },
+            accepted = listeners.accept_tls() => match accepted {
+                Ok((stream, raddr, tls)) => {
+                    info!("Accepted TLS connection from {}", raddr);
+                    set_keepalive(&stream, &backend);
+                    let backend = backend.clone();
+                    let mut shutdown = shutdown.clone();
+                    let connection = Connection::tcp(&stream, ConnectionKind::Tls);
+                    connections.spawn(
+                        async move {
+                            let stream = tokio::select! {
+                                stream = tls.acceptor().accept(stream) => stream,
+                                _ = shutdown.changed() => return,
+                            };
+                            let result = match stream {
+                                Ok(stream) => shutdown_aware_stream_handle(stream, connection, backend, shutdown, router).await,
+                                Err(e) => Err(e.into()),
+                            };
+                            if let Err(e) = result {
+                                info!("Error: {:?}", e);
+                            }
+                        }
+                        .instrument(span.clone()),
+                    );
+                }
+                Err(e) => warn!("Failed to accept TLS connection: {}", e),
+            },
+            accepted = listeners.accept_metrics() => match accepted {
+                Ok((stream, _)) => {
+                    let backend = backend.clone();
+                    connections.spawn(
+                        async move {
+                            if let Err(e) = serve_metrics(stream, backend).await {
+                                info!("Metrics error: {:?}", e);
+                            }
+                        }
+                        .instrument(span.clone()),
+                    );
+                }
+                Err(e) => warn!("Failed to accept metrics connection: {}", e),
+            },
+            _ = active_expire.tick(), if backend.active_expire() => {
+                backend.active_expire_cycle();
+            }
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            _ = shutdown.changed() => break shutdown.borrow().unwrap_or(ShutdownMode::Default),
+        }
+    };
+
+    drop(listeners);
+    info!("Shutting down, draining {} connections", connections.len());
+    while connections.join_next().await.is_some() {}
+    if let Some(cores) = cores {
+        cores.stop(&backend).await?;
+    }
+
+    match (mode, save) {
+        (ShutdownMode::NoSave, _) => {}
+        (_, Some(save)) => {
+            info!("Saving before shutdown");
+            let result = save(&backend);
+            backend.stats().record_save(result.is_ok());
+            result?;
+        }
+        (ShutdownMode::Save, None) => warn!("SAVE requested but no persistence is configured"),
+        (ShutdownMode::Default, None) => {}
+    }
+
+    info!("Server stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::resp::frame::Frame;
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+    use tokio_rustls::TlsConnector;
+
+    fn ephemeral() -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 0))
+    }
+
+    #[tokio::test]
+    async fn test_server_ephemeral_port_and_shutdown() {
+        let server = Server::builder().bind(ephemeral()).start().await.unwrap();
+        let addr = server.local_addr();
+        assert_ne!(addr.port(), 0);
+
+        let mut client = Client::connect(addr).await.unwrap();
+        client.set("key", "value").await.unwrap();
+
+        server.shutdown().await.unwrap();
+        assert!(client.get("key").await.is_err());
+        assert!(Client::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_server_existing_backend() {
+        let backend = Backend::new();
+        backend.set("key", b"value");
+
+        let server = Server::builder()
+            .bind(ephemeral())
+            .backend(backend.clone())
+            .start()
+            .await
+            .unwrap();
+
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+        server.shutdown().await.unwrap();
+
+        // the backend can be handed to a new server after the first one stopped
+        let server = Server::builder()
+            .bind(ephemeral())
+            .backend(backend)
+            .start()
+            .await
+            .unwrap();
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_save_hook() {
+        let saves = Arc::new(AtomicUsize::new(0));
+
+        for (mode, expected) in [
+            (ShutdownMode::Default, 1),
+            (ShutdownMode::Save, 2),
+            (ShutdownMode::NoSave, 2),
+        ] {
+            let counter = saves.clone();
+            let server = Server::builder()
+                .bind(ephemeral())
+                .on_save(move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                })
+                .start()
+                .await
+                .unwrap();
+
+            server.shutdown_with(mode).await.unwrap();
+            assert_eq!(saves.load(Ordering::SeqCst), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_maxclients() {
+        let config = Config {
+            maxclients: 1,
+            ..Config::default()
+        };
+        let server = Server::builder()
+            .bind(ephemeral())
+            .config(config)
+            .start()
+            .await
+            .unwrap();
+
+        let mut first = Client::connect(server.local_addr()).await.unwrap();
+        first.set("key", "value").await.unwrap();
+        let mut second = Client::connect(server.local_addr()).await.unwrap();
+        let error = second.get("key").await.unwrap_err();
+        assert!(error.to_string().contains("max number of clients reached"));
+        assert_eq!(server.backend().stats().rejected_connections(), 1);
+
+        // the first client keeps working
+        assert_eq!(first.get("key").await.unwrap(), Some(b"value".to_vec()));
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_output_buffer_limit() {
+        let mut config = Config::default();
+        config
+            .client_output_buffer_limits
+            .set("normal 1kb 0 0")
+            .unwrap();
+        let server = Server::builder()
+            .bind(ephemeral())
+            .config(config)
+            .start()
+            .await
+            .unwrap();
+        server.backend().set("small", "value");
+        server.backend().set("big", "x".repeat(4096).as_str());
+
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        assert_eq!(client.get("small").await.unwrap(), Some(b"value".to_vec()));
+        assert!(client.get("big").await.is_err());
+        assert_eq!(server.backend().stats().output_limit_disconnections(), 1);
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_idle_timeout() {
+        let config = Config {
+            timeout: 1,
+            ..Config::default()
+        };
+        let server = Server::builder()
+            .bind(ephemeral())
+            .config(config)
+            .start()
+            .await
+            .unwrap();
+
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        client.set("key", "value").await.unwrap();
+        // the invalidations it is sent meanwhile do not count as activity
+        let mut tracking = Client::connect(server.local_addr()).await.unwrap();
+        for command in [&["HELLO", "3"][..], &["CLIENT", "TRACKING", "ON", "BCAST"]] {
+            let command = command.iter().map(|arg| arg.as_bytes().into()).collect();
+            tracking.execute(command).await.unwrap();
+        }
+        let mut writer = Client::connect(server.local_addr()).await.unwrap();
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            writer.set("other", "value").await.unwrap();
+        }
+        assert!(client.get("key").await.is_err());
+        assert!(tracking.get("key").await.is_err());
+        assert!(writer.get("key").await.is_ok());
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_shutdown_command() {
+        let server = Server::builder().bind(ephemeral()).start().await.unwrap();
+
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        client
+            .execute(vec![b"SHUTDOWN".into(), b"NOSAVE".into()])
+            .await
+            .unwrap();
+
+        server.wait().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_select_is_per_connection() {
+        let server = Server::builder().bind(ephemeral()).start().await.unwrap();
+
+        let mut first = Client::connect(server.local_addr()).await.unwrap();
+        let mut second = Client::connect(server.local_addr()).await.unwrap();
+        first
+            .execute(vec![b"SELECT".into(), b"1".into()])
+            .await
+            .unwrap();
+        first.set("key", "value").await.unwrap();
+
+        assert_eq!(second.get("key").await.unwrap(), None);
+        assert_eq!(first.get("key").await.unwrap(), Some(b"value".to_vec()));
+        server.shutdown().await.unwrap();
+    }
+
+    // both servers share a single thread, MIGRATE awaits the target instead of blocking it
+    #[tokio::test]
+    async fn test_server_migrate() {
+        let source = Server::builder().bind(ephemeral()).start().await.unwrap();
+        let target = Server::builder().bind(ephemeral()).start().await.unwrap();
+        source.backend().set("a", "1");
+        source.backend().hset("b", "field", "value").unwrap();
+        source.backend().set("c", "3");
+        target.backend().set("c", "old");
+
+        let port = target.local_addr().port().to_string();
+        let migrate = |args: &[&str]| -> Vec<Frame> {
+            ["MIGRATE", "127.0.0.1", port.as_str()]
+                .iter()
+                .chain(args)
+                .map(|arg| arg.as_bytes().into())
+                .collect()
+        };
+
+        let mut client = Client::connect(source.local_addr()).await.unwrap();
+        let reply = client
+            .execute(migrate(&["", "1", "1000", "KEYS", "a", "b"]))
+            .await
+            .unwrap();
+        assert_eq!(reply, Frame::from(b"OK"));
+        assert!(!source.backend().contains_key("a"));
+        let moved = target.backend().session();
+        moved.select(1).unwrap();
+        assert_eq!(moved.get_string("a"), Some("1".into()));
+        assert_eq!(moved.hget("b", "field"), Some("value".into()));
+
+        // the target refuses to overwrite unless asked to
+        let error = client
+            .execute(migrate(&["c", "0", "1000"]))
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", error).contains("BUSYKEY"));
+        let reply = client
+            .execute(migrate(&["c", "0", "1000", "COPY", "REPLACE"]))
+            .await
+            .unwrap();
+        assert_eq!(reply, Frame::from(b"OK"));
+        assert_eq!(source.backend().get_string("c"), Some("3".into()));
+        assert_eq!(target.backend().get_string("c"), Some("3".into()));
+
+        // a key the target refuses stays, the one it restored is gone
+        source.backend().set("d", "4");
+        let error = client
+            .execute(migrate(&["", "0", "1000", "KEYS", "c", "d"]))
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", error).contains("BUSYKEY"));
+        assert_eq!(source.backend().get_string("c"), Some("3".into()));
+        assert!(!source.backend().contains_key("d"));
+        assert_eq!(target.backend().get_string("d"), Some("4".into()));
+
+        let reply = client.execute(migrate(&["a", "0", "1000"])).await.unwrap();
+        assert_eq!(reply, Frame::from("NOKEY"));
+
+        source.shutdown().await.unwrap();
+        target.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_thread_per_core() {
+        let backend = Backend::new();
+        backend.set("existing", "value");
+
+        let server = Server::builder()
+            .bind(ephemeral())
+            .backend(backend.clone())
+            .engine(Engine::ThreadPerCore(4))
+            .start()
+            .await
+            .unwrap();
+
+        let mut first = Client::connect(server.local_addr()).await.unwrap();
+        let mut second = Client::connect(server.local_addr()).await.unwrap();
+        assert_eq!(
+            first.get("existing").await.unwrap(),
+            Some(b"value".to_vec())
+        );
+
+        // the keys land on different partitions, MSET and MGET coordinate them
+        let mut mset = vec![b"MSET".into()];
+        for i in 0..16 {
+            mset.push(format!("key{}", i).as_bytes().into());
+            mset.push(format!("{}", i).as_bytes().into());
+        }
+        first.execute(mset).await.unwrap();
+
+        let mut mget = vec![b"MGET".into()];
+        mget.extend((0..16).map(|i| format!("key{}", i).as_bytes().into()));
+        let values: Vec<Frame> = (0..16)
+            .map(|i| format!("{}", i).as_bytes().into())
+            .collect();
+        assert_eq!(second.execute(mget).await.unwrap(), values.into());
+        assert_eq!(
+            second.execute(vec![b"DBSIZE".into()]).await.unwrap(),
+            17.into()
+        );
+
+        // MIGRATE hands a lent key to the target and deletes it on its partition
+        let target = Server::builder().bind(ephemeral()).start().await.unwrap();
+        let port = target.local_addr().port().to_string();
+        let migrate = ["MIGRATE", "127.0.0.1", &port, "key0", "0", "1000"]
+            .iter()
+            .map(|arg| arg.as_bytes().into())
+            .collect();
+        assert_eq!(first.execute(migrate).await.unwrap(), Frame::from(b"OK"));
+        assert_eq!(first.get("key0").await.unwrap(), None);
+        assert_eq!(target.backend().get_string("key0"), Some("0".into()));
+        target.shutdown().await.unwrap();
+
+        second
+            .execute(vec![b"SELECT".into(), b"1".into()])
+            .await
+            .unwrap();
+        assert_eq!(second.get("key0").await.unwrap(), None);
+
+        first.execute(vec![b"FLUSHDB".into()]).await.unwrap();
+        assert_eq!(
+            first.execute(vec![b"DBSIZE".into()]).await.unwrap(),
+            0.into()
+        );
+        first.set("after", "flush").await.unwrap();
+
+        // the keys are back in the backend once the server stopped
+        server.shutdown().await.unwrap();
+        assert_eq!(backend.keys(), vec!["after"]);
+    }
+
+    // a CA, a certificate for localhost and a client certificate, written to `dir`
+    fn write_certs(dir: &Path) -> RootCertStore {
+        std::fs::create_dir_all(dir).unwrap();
+        let write = |name: &str, pem: String| std::fs::write(dir.join(name), pem).unwrap();
+
+        let ca_key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = params.self_signed(&ca_key).unwrap();
+        write("ca.pem", ca.pem());
+
+        for (name, san) in [("server", "localhost"), ("client", "client")] {
+            let key = KeyPair::generate().unwrap();
+            let cert = CertificateParams::new(vec![san.to_string()])
+                .unwrap()
+                .signed_by(&key, &ca, &ca_key)
+                .unwrap();
+            write(&format!("{}.pem", name), cert.pem());
+            write(&format!("{}.key", name), key.serialize_pem());
+        }
+
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.der().clone()).unwrap();
+        roots
+    }
+
+    fn tls_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("simple-redis-{}-{}", name, std::process::id()))
+    }
+
+    fn tls_config(dir: &Path, name: &str) -> TlsConfig {
+        TlsConfig::new(
+            dir.join(format!("{}.pem", name)),
+            dir.join(format!("{}.key", name)),
+        )
+        .ca_cert_file(dir.join("ca.pem"))
+    }
+
+    // a handshake the server rejects may only surface on the first reply
+    async fn rejected(connector: &TlsConnector, addr: SocketAddr) -> bool {
+        match Client::connect_tls(addr, connector, "localhost").await {
+            Ok(mut client) => client.get("key").await.is_err(),
+            Err(_) => true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_tls_client_auth() {
+        let dir = tls_dir("tls-auth");
+        let roots = write_certs(&dir);
+
+        let server = Server::builder()
+            .bind(ephemeral())
+            .tls(ephemeral(), tls_config(&dir, "server"))
+            .start()
+            .await
+            .unwrap();
+        let tls_addr = server.tls_local_addr().unwrap();
+
+        let connector = tls_config(&dir, "client").connector().unwrap();
+        let mut client = Client::connect_tls(tls_addr, &connector, "localhost")
+            .await
+            .unwrap();
+        client.set("key", "value").await.unwrap();
+
+        // both listeners serve the same keys
+        let mut plain = Client::connect(server.local_addr()).await.unwrap();
+        assert_eq!(plain.get("key").await.unwrap(), Some(b"value".to_vec()));
+
+        let anonymous = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        assert!(rejected(&TlsConnector::from(Arc::new(anonymous)), tls_addr).await);
+
+        server.shutdown().await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_tls_reload() {
+        let dir = tls_dir("tls-reload");
+        write_certs(&dir);
+
+        let server = Server::builder()
+            .bind(ephemeral())
+            .tls(ephemeral(), tls_config(&dir, "server"))
+            .start()
+            .await
+            .unwrap();
+        let tls_addr = server.tls_local_addr().unwrap();
+        let old = tls_config(&dir, "client").connector().unwrap();
+        let mut client = Client::connect_tls(tls_addr, &old, "localhost")
+            .await
+            .unwrap();
+
+        // a new CA signs new certificates, the server keeps the old ones until it reloads
+        write_certs(&dir);
+        let new = tls_config(&dir, "client").connector().unwrap();
+        assert!(rejected(&new, tls_addr).await);
+
+        server.tls().unwrap().reload().unwrap();
+        let mut reloaded = Client::connect_tls(tls_addr, &new, "localhost")
+            .await
+            .unwrap();
+        reloaded.set("key", "value").await.unwrap();
+        assert!(rejected(&old, tls_addr).await);
+
+        // established connections are not affected
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+
+        server.shutdown().await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_unix_socket() {
+        let path = std::env::temp_dir().join(format!("simple-redis-{}.sock", std::process::id()));
+        let server = Server::builder()
+            .without_tcp()
+            .unix_socket(&path, Some(0o700))
+            .start()
+            .await
+            .unwrap();
+        assert_eq!(server.unix_socket(), Some(path.as_path()));
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        let mut client = Client::connect_unix(&path).await.unwrap();
+        client.set("key", "value").await.unwrap();
+        assert_eq!(client.get("key").await.unwrap(), Some(b"value".to_vec()));
+
+        let list = client
+            .execute(vec![b"CLIENT".into(), b"LIST".into()])
+            .await
+            .unwrap();
+        let list = match list {
+            Frame::BulkString(list) => String::from_utf8(list.inner).unwrap(),
+            frame => panic!("Expected BulkString, got {:?}", frame),
+        };
+        let expected = format!(
+            "addr={}:0 laddr={}:0 conn=unix ",
+            path.display(),
+            path.display()
+        );
+        assert!(list.contains(&expected), "{}", list);
+        assert!(
+            list.contains(" flags=U db=0 qbuf=0 obl=0 oll=0 omem=0 cmd=client\n"),
+            "{}",
+            list
+        );
+
+        server.shutdown().await.unwrap();
+        assert!(!path.exists());
+    }
+
+    async fn http_get(addr: SocketAddr, path: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_server_metrics() {
+        let server = Server::builder()
+            .bind(ephemeral())
+            .metrics(ephemeral())
+            .start()
+            .await
+            .unwrap();
+        let metrics_addr = server.metrics_local_addr().unwrap();
+
+        let mut client = Client::connect(server.local_addr()).await.unwrap();
+        client.set("key", "value").await.unwrap();
+        client.get("key").await.unwrap();
+        client.get("key").await.unwrap();
+
+        let response = http_get(metrics_addr, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        for line in [
+            "simple_redis_connected_clients{conn=\"tcp\"} 1",
+            "simple_redis_commands_processed_total 3",
+            "simple_redis_command_duration_seconds_count{cmd=\"get\"} 2",
+            "simple_redis_keys{db=\"0\",type=\"string\"} 1",
+            "simple_redis_rdb_changes_since_last_save 1",
+        ] {
+            assert!(response.lines().any(|l| l == line), "{} missing", line);
+        }
+        // SET key value is 33 bytes, each GET key 22
+        assert!(response
+            .lines()
+            .any(|l| l == "simple_redis_net_input_bytes_total 77"));
+
+        let response = http_get(metrics_addr, "/").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_client_tracking() {
+        use crate::resp::Push;
+        use std::time::Duration;
+        use tokio::time::timeout;
+
+        let server = Server::builder().bind(ephemeral()).start().await.unwrap();
+        let command = |args: &[&str]| -> Vec<Frame> {
+            args.iter().map(|arg| arg.as_bytes().into()).collect()
+        };
+
+        let mut reader = Client::connect(server.local_addr()).await.unwrap();
+        reader.execute(command(&["HELLO", "3"])).await.unwrap();
+        reader
+            .execute(command(&["CLIENT", "TRACKING", "ON"]))
+            .await
+            .unwrap();
+        reader.get("key").await.unwrap();
+
+        // invalidations are only redirected to RESP3 connections
+        let mut listener = Client::connect(server.local_addr()).await.unwrap();
+        let id = listener.execute(command(&["CLIENT", "ID"])).await.unwrap();
+        let Frame::Integer(id) = id else {
+            panic!("Expected Integer, got {:?}", id);
+        };
+        let mut tracker = Client::connect(server.local_addr()).await.unwrap();
+        let redirect = id.to_string();
+        let track = command(&["CLIENT", "TRACKING", "ON", "REDIRECT", &redirect]);
+        assert!(tracker.execute(track.clone()).await.is_err());
+        listener.execute(command(&["HELLO", "3"])).await.unwrap();
+        tracker.execute(track).await.unwrap();
+        tracker.get("other").await.unwrap();
+
+        let mut writer = Client::connect(server.local_addr()).await.unwrap();
+        writer.set("key", "value").await.unwrap();
+        writer.set("other", "value").await.unwrap();
+
+        let push = timeout(Duration::from_secs(5), reader.next_push())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            push,
+            Frame::Push(Push::new(vec![
+                b"invalidate".into(),
+                vec![b"key".into()].into()
+            ]))
+        );
+        let message = timeout(Duration::from_secs(5), listener.next_push())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            message,
+            Frame::Push(Push::new(vec![
+                b"invalidate".into(),
+                vec![b"other".into()].into()
+            ]))
+        );
+
+        server.shutdown().await.unwrap();
+    }
+}